@@ -7,26 +7,8 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
     let mut settings:Vec<SettingsItem> = Vec::new();
     // let mut categories = HashMap::new();
 
-    let mut prefix = None;
-
     if let Data::Struct(data) = &ast.data {
 
-        // look for prefix
-        for a in ast.attrs.iter() {
-            if a.path.is_ident("Setting") { 
-                if let Ok(Meta::List(list)) = a.parse_meta() {
-                    for name_value in recurse_meta(list) {
-                        match name_value.lit {
-                            Lit::Str(s) if name_value.path.is_ident("settings_prefix") || name_value.path.is_ident("prefix") => prefix = Some(s.value()),
-                            
-                            _ => panic!("nope"),
-                        }
-                    }
-                }
-                break;
-            }
-        }
-
         // go through settings
         for f in data.fields.iter() {
             let mut setting = SettingsItem::default();
@@ -130,20 +112,22 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
     let struct_name = ast.ident.to_string();
     let mut lines = Vec::new();
     lines.push(format!("impl {struct_name} {{"));
-    lines.push("pub fn get_menu_items(&self, p: Vector2, sender: Arc<SyncSender<()>>) -> Vec<Box<dyn ScrollableItem>> {".to_owned());
+    // items are tagged with their path from the root settings (ie `common_game_settings.autoplay.seed`),
+    // so the same struct can be used as a subsetting more than once
+    lines.push("pub fn get_menu_items(&self, p: Vector2, prefix: String, sender: Arc<SyncSender<()>>) -> Vec<Box<dyn ScrollableItem>> {".to_owned());
     lines.push("let mut list:Vec<Box<dyn ScrollableItem>> = Vec::new();".to_owned());
     lines.push("let font = get_font();".to_owned());
     
     // pulling vals back from the menu
     let mut lines2 = Vec::new();
-    lines2.push("pub fn from_menu(&mut self, list: &ScrollableArea) {".to_owned());
+    lines2.push("pub fn from_menu(&mut self, prefix: String, list: &ScrollableArea) {".to_owned());
 
     macro_rules! thingy {
         ($val2:expr, $setting:expr, $type:ident) => {
             lines2.push(format!("
             // {}
             {{
-                let val = list.get_tagged(\"{}\".to_owned()); // get item from list
+                let val = list.get_tagged(prefix.clone() + \"{}\"); // get item from list
                 let val = val.first().expect(\"error getting tagged\"); // unwrap
                 let val = val.get_value(); // get the value from the item
                 let val = val.downcast_ref::<{}>().expect(&format!(\"error downcasting for {}\"));
@@ -156,7 +140,7 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
             lines2.push(format!("
             // {}
             {{
-                let val = list.get_tagged(\"{}\".to_owned()); // get item from list
+                let val = list.get_tagged(prefix.clone() + \"{}\"); // get item from list
                 let val = val.first().expect(\"error getting tagged\"); // unwrap
                 let val = val.get_value(); // get the value from the item
                 let val = val.downcast_ref::<f64>().expect(&format!(\"error downcasting for {}\"));
@@ -169,7 +153,7 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
             lines2.push(format!("
             // {}
             {{
-                let val = list.get_tagged(\"{}\".to_owned()); // get item from list
+                let val = list.get_tagged(prefix.clone() + \"{}\"); // get item from list
                 let val = val.first().expect(\"error getting tagged\"); // unwrap
                 let val = val.get_value(); // get the value from the item
                 let val = val.downcast_ref::<Option<{}>>().expect(&format!(\"error downcasting for {}\"));
@@ -184,7 +168,7 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
             lines2.push(format!("
             // {}
             {{
-                let val = list.get_tagged(\"{}\".to_owned()); // get item from list
+                let val = list.get_tagged(prefix.clone() + \"{}\"); // get item from list
                 let val = val.first().expect(\"error getting tagged\"); // unwrap
                 let val = val.get_value(); // get the value from the item
                 let val = val.downcast_ref::<Option<{}>>().expect(&format!(\"error downcasting for {}\"));
@@ -199,7 +183,7 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
     for setting in settings {
         let text = setting.setting_text.unwrap_or_default();
         let val = setting.setting_name.clone();
-        let val2 = prefix.clone().map(|s|s+".").unwrap_or_default() + &setting.setting_name;
+        let val2 = setting.setting_name.clone();
         let mut add = true;
 
         if let Some(category) = setting.category {
@@ -290,11 +274,11 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
 
             // 
             SettingsType::SubSetting => {
-                lines.push(format!("list.extend(self.{val}.get_menu_items(p, sender.clone()));"));
+                lines.push(format!("list.extend(self.{val}.get_menu_items(p, prefix.clone() + \"{val}.\", sender.clone()));"));
                 add = false;
 
                 lines2.push(format!("\n// {val2}"));
-                lines2.push(format!("self.{val}.from_menu(list);"));
+                lines2.push(format!("self.{val}.from_menu(prefix.clone() + \"{val}.\", list);"));
             }
 
             // shrug
@@ -303,7 +287,7 @@ pub(crate) fn impl_settings(ast: &syn::DeriveInput) -> proc_macro2::TokenStream
         }
 
         if add {
-            lines.push(format!("i.set_tag(\"{val2}\");"));
+            lines.push(format!("i.set_tag(&(prefix.clone() + \"{val2}\"));"));
 
             lines.push(format!("let c = sender.clone();"));
             lines.push(format!("i.on_change = Arc::new(move|_,_|{{c.send(()).unwrap()}});"));
@@ -9,3 +9,4 @@ mod null_audio;
 
 pub use audio::*;
 pub use audio_manager::*;
+pub use null_audio::*;
@@ -0,0 +1,299 @@
+use crate::prelude::*;
+
+/// how much sideways distance counts against an item when moving in a direction
+const ACROSS_WEIGHT:f64 = 2.0;
+
+/// an input that moves focus around a menu, or acts on whatever has focus
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NavigationInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// tab
+    Next,
+    /// shift+tab
+    Previous,
+    Confirm,
+    Back,
+}
+impl NavigationInput {
+    pub fn from_key(key: Key, mods: &KeyModifiers) -> Option<Self> {
+        match key {
+            Key::Up => Some(Self::Up),
+            Key::Down => Some(Self::Down),
+            Key::Left => Some(Self::Left),
+            Key::Right => Some(Self::Right),
+            Key::Tab if mods.shift => Some(Self::Previous),
+            Key::Tab => Some(Self::Next),
+            Key::Return => Some(Self::Confirm),
+            Key::Escape => Some(Self::Back),
+            _ => None,
+        }
+    }
+
+    /// gamepads use the dpad, A/B and the bumpers.
+    /// anything else (ie a taiko drum) uses its taiko bindings, where kats move focus and dons confirm
+    pub fn from_controller(controller: &Box<dyn Controller>, button: u8) -> Option<Self> {
+        if let Some(button) = controller.map_button(button) {
+            return match button {
+                ControllerButton::DPad_Up => Some(Self::Up),
+                ControllerButton::DPad_Down => Some(Self::Down),
+                ControllerButton::DPad_Left => Some(Self::Left),
+                ControllerButton::DPad_Right => Some(Self::Right),
+                ControllerButton::Right_Bumper => Some(Self::Next),
+                ControllerButton::Left_Bumper => Some(Self::Previous),
+                ControllerButton::A | ControllerButton::Start => Some(Self::Confirm),
+                ControllerButton::B => Some(Self::Back),
+                _ => None,
+            }
+        }
+
+        let taiko = get_settings!().keybinds.get_bindings("taiko");
+        let target = InputTarget::controller(controller, button);
+        if taiko.matches("left_kat", &target) { return Some(Self::Previous) }
+        if taiko.matches("right_kat", &target) { return Some(Self::Next) }
+        if taiko.matches("left_don", &target) || taiko.matches("right_don", &target) { return Some(Self::Confirm) }
+        None
+    }
+
+    /// the key this input comes from, so anything that handles keys can be navigated with a controller
+    pub fn to_key(self) -> (Key, KeyModifiers) {
+        let mut mods = KeyModifiers::default();
+        let key = match self {
+            Self::Up => Key::Up,
+            Self::Down => Key::Down,
+            Self::Left => Key::Left,
+            Self::Right => Key::Right,
+            Self::Next => Key::Tab,
+            Self::Previous => {
+                mods.shift = true;
+                Key::Tab
+            }
+            Self::Confirm => Key::Return,
+            Self::Back => Key::Escape,
+        };
+
+        (key, mods)
+    }
+
+    fn direction(&self) -> Option<Vector2> {
+        match self {
+            Self::Up => Some(Vector2::new(0.0, -1.0)),
+            Self::Down => Some(Vector2::new(0.0, 1.0)),
+            Self::Left => Some(Vector2::new(-1.0, 0.0)),
+            Self::Right => Some(Vector2::new(1.0, 0.0)),
+            _ => None,
+        }
+    }
+}
+
+/// keeps track of which item in a menu has focus, and moves it around with [`NavigationInput`]s.
+/// items are passed as their bounds, so anything with a position and size can be focused.
+/// what confirming does is up to the menu, usually a click on the center of the focused item
+#[derive(Clone, Debug, Default)]
+pub struct FocusRing {
+    index: usize,
+    /// only shown once its been used, so it stays out of the way of mouse users
+    visible: bool,
+}
+impl FocusRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the focused item, if the ring is being used
+    pub fn focused(&self) -> Option<usize> {
+        self.visible.then_some(self.index)
+    }
+    pub fn focus(&mut self, index: usize) {
+        self.index = index;
+        self.visible = true;
+    }
+    /// hide the ring, ie when the mouse is used
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// move focus. returns true if the input was used.
+    /// the first input only shows the ring, so the user can see where they are before anything moves
+    pub fn navigate(&mut self, input: NavigationInput, items: &[Rectangle]) -> bool {
+        if items.is_empty() { return false }
+        if !matches!(input, NavigationInput::Confirm | NavigationInput::Back) && !self.visible {
+            self.visible = true;
+            self.index = self.index.min(items.len() - 1);
+            return true;
+        }
+        self.index = self.index.min(items.len() - 1);
+
+        match input {
+            NavigationInput::Next => self.index = (self.index + 1) % items.len(),
+            NavigationInput::Previous => self.index = (self.index + items.len() - 1) % items.len(),
+            NavigationInput::Confirm | NavigationInput::Back => return false,
+            direction => {
+                let Some(index) = Self::find_in_direction(self.index, direction.direction().unwrap(), items) else { return false };
+                self.index = index;
+            }
+        }
+
+        true
+    }
+
+    /// find the closest item in `direction`, preferring items that are lined up with the current one
+    fn find_in_direction(current: usize, direction: Vector2, items: &[Rectangle]) -> Option<usize> {
+        let from = Self::center(&items[current]);
+
+        items
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != current)
+            .filter_map(|(i, bounds)| {
+                let diff = Self::center(bounds) - from;
+                let along = diff.x * direction.x + diff.y * direction.y;
+                if along <= 0.0 { return None }
+
+                let across = (diff.x * direction.y - diff.y * direction.x).abs();
+                Some((i, along + across * ACROSS_WEIGHT))
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i)
+    }
+
+    fn center(bounds: &Rectangle) -> Vector2 {
+        bounds.pos + bounds.size / 2.0
+    }
+
+    /// where to click to activate the focused item
+    pub fn focused_center(&self, items: &[Rectangle]) -> Option<Vector2> {
+        self.focused().and_then(|i| items.get(i)).map(Self::center)
+    }
+
+    /// get the bounds of the items in a scrollable, to navigate between them
+    pub fn item_bounds(items: &Vec<Box<dyn ScrollableItem>>) -> Vec<Rectangle> {
+        items
+            .iter()
+            .map(|i| Rectangle::bounds_only(i.get_pos(), i.size()))
+            .collect()
+    }
+
+    /// scroll `scroll` so the focused item is fully visible
+    pub fn scroll_into_view(&self, scroll: &mut ScrollableArea) {
+        let Some(item) = self.focused().and_then(|i| scroll.items.get(i)) else { return };
+        let top = item.get_pos().y;
+        let bottom = top + item.size().y;
+
+        let area_top = scroll.get_pos().y;
+        let area_bottom = area_top + scroll.size().y;
+
+        let offset = if bottom > area_bottom {
+            area_bottom - bottom
+        } else if top < area_top {
+            area_top - top
+        } else {
+            return
+        };
+
+        scroll.scroll_pos += offset;
+        scroll.refresh_layout();
+    }
+
+    /// draw the focus indicator around the focused item
+    pub fn draw(&self, items: &[Rectangle], offset: Vector2, depth: f64, list: &mut RenderableCollection) {
+        let Some(bounds) = self.focused().and_then(|i| items.get(i)) else { return };
+
+        list.push(Rectangle::new(
+            Color::TRANSPARENT_WHITE,
+            depth - 1.0,
+            offset + bounds.pos - Vector2::ONE * 3.0,
+            bounds.size + Vector2::ONE * 6.0,
+            Some(Border::new(Color::YELLOW, 2.0))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(x: f64, y: f64) -> Rectangle {
+        Rectangle::bounds_only(Vector2::new(x, y), Vector2::new(50.0, 50.0))
+    }
+
+    /// 0 1
+    /// 2 3
+    fn grid() -> Vec<Rectangle> {
+        vec![item(0.0, 0.0), item(100.0, 0.0), item(0.0, 100.0), item(100.0, 100.0)]
+    }
+
+    fn find(current: usize, input: NavigationInput, items: &[Rectangle]) -> Option<usize> {
+        FocusRing::find_in_direction(current, input.direction().unwrap(), items)
+    }
+
+    #[test]
+    fn moves_around_a_grid() {
+        let items = grid();
+        assert_eq!(find(0, NavigationInput::Right, &items), Some(1));
+        assert_eq!(find(0, NavigationInput::Down, &items), Some(2));
+        assert_eq!(find(3, NavigationInput::Left, &items), Some(2));
+        assert_eq!(find(3, NavigationInput::Up, &items), Some(1));
+    }
+
+    #[test]
+    fn nothing_past_the_edge() {
+        let items = grid();
+        assert_eq!(find(0, NavigationInput::Left, &items), None);
+        assert_eq!(find(0, NavigationInput::Up, &items), None);
+        assert_eq!(find(3, NavigationInput::Right, &items), None);
+        assert_eq!(find(3, NavigationInput::Down, &items), None);
+    }
+
+    #[test]
+    fn prefers_lined_up_items() {
+        // the diagonal item is closer going down, but the one directly below is lined up
+        let items = vec![item(0.0, 0.0), item(150.0, 80.0), item(0.0, 200.0)];
+        assert_eq!(find(0, NavigationInput::Down, &items), Some(2));
+    }
+
+    #[test]
+    fn picks_the_closest_in_line() {
+        let items = vec![item(0.0, 0.0), item(0.0, 200.0), item(0.0, 100.0)];
+        assert_eq!(find(0, NavigationInput::Down, &items), Some(2));
+    }
+
+    #[test]
+    fn first_input_only_shows_the_ring() {
+        let items = grid();
+        let mut ring = FocusRing::new();
+        assert_eq!(ring.focused(), None);
+
+        assert!(ring.navigate(NavigationInput::Down, &items));
+        assert_eq!(ring.focused(), Some(0));
+
+        assert!(ring.navigate(NavigationInput::Down, &items));
+        assert_eq!(ring.focused(), Some(2));
+    }
+
+    #[test]
+    fn next_and_previous_wrap() {
+        let items = grid();
+        let mut ring = FocusRing::new();
+        ring.focus(3);
+
+        assert!(ring.navigate(NavigationInput::Next, &items));
+        assert_eq!(ring.focused(), Some(0));
+        assert!(ring.navigate(NavigationInput::Previous, &items));
+        assert_eq!(ring.focused(), Some(3));
+    }
+
+    #[test]
+    fn confirm_and_back_are_left_to_the_menu() {
+        let items = grid();
+        let mut ring = FocusRing::new();
+        ring.focus(1);
+
+        assert!(!ring.navigate(NavigationInput::Confirm, &items));
+        assert!(!ring.navigate(NavigationInput::Back, &items));
+        assert!(!ring.navigate(NavigationInput::Down, &[]));
+        assert_eq!(ring.focused(), Some(1));
+    }
+}
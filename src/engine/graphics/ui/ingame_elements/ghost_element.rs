@@ -0,0 +1,64 @@
+use crate::prelude::*;
+
+const FONT_SIZE:f64 = 25.0;
+const PADDING:f64 = 4.0;
+const LINE_COUNT:usize = 3;
+const SIZE:Vector2 = Vector2::new(300.0, (FONT_SIZE + PADDING) * LINE_COUNT as f64);
+
+/// shows how the current play compares to the ghost at this point in the map
+pub struct GhostElement {
+    lines: Vec<(String, Color)>,
+}
+impl GhostElement {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new()
+        }
+    }
+}
+
+impl InnerUIElement for GhostElement {
+    fn display_name(&self) -> &'static str { "Ghost Info" }
+
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(Vector2::ZERO, SIZE)
+    }
+
+    fn update(&mut self, manager: &mut IngameManager) {
+        self.lines.clear();
+        let Some(ghost) = &manager.ghost else { return };
+        let ghost_score = ghost.score();
+
+        let score_diff = manager.score.score as i64 - ghost_score.score as i64;
+        let acc_diff = (manager.score.accuracy - ghost_score.accuracy) * 100.0;
+        let sign = if score_diff < 0 { "-" } else { "+" };
+        let color = if score_diff < 0 { Color::RED } else { Color::GREEN };
+
+        self.lines.push((format!("Ghost: {}", ghost.username), Color::BLACK));
+        self.lines.push((format!("{sign}{} / {acc_diff:+.2}%", format_number(score_diff.unsigned_abs())), color));
+        self.lines.push((format!("Ghost combo: {}x", ghost_score.combo), Color::BLACK));
+    }
+
+    fn draw(&mut self, pos_offset: Vector2, scale: Vector2, list: &mut RenderableCollection) {
+        if self.lines.is_empty() { return }
+        const DEPTH:f64 = -1000.0;
+
+        list.push(visibility_bg(
+            pos_offset,
+            SIZE * scale,
+            DEPTH
+        ));
+
+        let font = get_font();
+        for (i, (line, color)) in self.lines.iter().enumerate() {
+            list.push(Text::new(
+                *color,
+                DEPTH - 0.001,
+                pos_offset + Vector2::new(PADDING, (FONT_SIZE + PADDING) * i as f64) * scale,
+                (FONT_SIZE * scale.y) as u32,
+                line.clone(),
+                font.clone()
+            ))
+        }
+    }
+}
@@ -3,12 +3,14 @@ use crate::prelude::*;
 pub struct LeaderboardElement {
     scores: Vec<IngameScore>,
     image: Option<Image>,
+    map: Arc<BeatmapMeta>,
 }
 impl LeaderboardElement {
     pub async fn new() -> Self {
         Self {
             scores: Vec::new(),
             image: SkinManager::get_texture("menu-button-background", true).await,
+            map: Default::default(),
         }
     }
 }
@@ -30,6 +32,7 @@ impl InnerUIElement for LeaderboardElement {
     fn update(&mut self, manager: &mut IngameManager) {
         //TODO: make this better?
         self.scores = manager.all_scores().into_iter().map(|i|i.clone()).collect();
+        self.map = manager.metadata.clone();
     }
 
     fn draw(&mut self, pos_offset:Vector2, scale:Vector2, list: &mut RenderableCollection) {
@@ -44,7 +47,7 @@ impl InnerUIElement for LeaderboardElement {
 
         let mut base_pos = pos_offset;
         for score in self.scores.iter() {
-            let mut l = LeaderboardItem::new(score.clone());
+            let mut l = LeaderboardItem::new(score.clone(), &self.map);
             l.image = self.image.clone();
             l.ui_scale_changed(scale);
 
@@ -1,6 +1,8 @@
 mod combo_element;
+mod ghost_element;
 mod score_element;
 mod elapsed_element;
+mod practice_element;
 mod accuracy_element;
 mod remaining_element;
 mod health_bar_element;
@@ -14,8 +16,10 @@ mod judgement_counter_element;
 
 
 pub use combo_element::*;
+pub use ghost_element::*;
 pub use score_element::*;
 pub use elapsed_element::*;
+pub use practice_element::*;
 pub use accuracy_element::*;
 pub use remaining_element::*;
 pub use health_bar_element::*;
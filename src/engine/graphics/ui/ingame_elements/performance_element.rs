@@ -2,10 +2,13 @@ use crate::prelude::*;
 const PADDING:Vector2 = Vector2::new(3.0, 3.0);
 
 const WHITE_TEXT:bool = true;
+/// font size of the breakdown text under the perf
+const BREAKDOWN_SIZE:f64 = 15.0;
 
 pub struct PerformanceElement {
     perf_image: Option<SkinnedNumber>,
     perf: f32,
+    breakdown: String,
 
     bounds_size: Vector2,
 }
@@ -34,6 +37,7 @@ impl PerformanceElement {
             bounds_size,
             perf_image,
             perf: 0.0,
+            breakdown: String::new(),
         }
     }
 }
@@ -44,17 +48,31 @@ impl InnerUIElement for PerformanceElement {
     fn get_bounds(&self) -> Rectangle {
         Rectangle::bounds_only(
             -self.bounds_size.x_portion() - PADDING,
-            self.bounds_size + PADDING * 2.0
+            self.bounds_size + PADDING * 2.0 + Vector2::with_y(BREAKDOWN_SIZE)
         )
     }
 
     fn update(&mut self, manager: &mut IngameManager) {
-        self.perf = manager.score.performance;
+        self.perf = manager.performance.total;
+        self.breakdown = manager.performance.breakdown_string();
     }
 
     fn draw(&mut self, pos_offset: Vector2, scale: Vector2, list: &mut RenderableCollection) {
         let bounds_x = self.bounds_size.x_portion();
 
+        // breakdown, right aligned under the perf
+        let mut breakdown = Text::new(
+            if WHITE_TEXT { Color::WHITE } else { Color::BLACK },
+            0.0,
+            Vector2::ZERO,
+            (BREAKDOWN_SIZE * scale.y) as u32,
+            self.breakdown.clone(),
+            get_font()
+        );
+        let breakdown_size = breakdown.measure_text();
+        breakdown.pos = pos_offset + Vector2::new(-breakdown_size.x, self.bounds_size.y * scale.y);
+        list.push(breakdown);
+
         if let Some(perf) = &mut self.perf_image {
             perf.number = self.perf as f64;
             perf.scale = scale;
@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+const FONT_SIZE:f64 = 25.0;
+const PADDING:f64 = 4.0;
+const LINE_COUNT:usize = 4;
+const SIZE:Vector2 = Vector2::new(300.0, (FONT_SIZE + PADDING) * LINE_COUNT as f64);
+
+pub struct PracticeElement {
+    lines: Vec<String>,
+}
+impl PracticeElement {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new()
+        }
+    }
+}
+
+impl InnerUIElement for PracticeElement {
+    fn display_name(&self) -> &'static str { "Practice Info" }
+
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(Vector2::ZERO, SIZE)
+    }
+
+    fn update(&mut self, manager: &mut IngameManager) {
+        self.lines.clear();
+        let Some(practice) = &manager.practice else { return };
+
+        let loop_count = if practice.settings.loop_count == 0 { "∞".to_owned() } else { practice.settings.loop_count.to_string() };
+
+        self.lines.push("Practice".to_owned());
+        self.lines.push(format!("Loop {}/{loop_count}", practice.current_loop + 1));
+        self.lines.push(format!("Speed: {:.2}x", manager.game_speed()));
+        if let Some(last) = practice.results.last() {
+            self.lines.push(format!("Last: {:.2}% ({} misses)", last.accuracy * 100.0, last.misses));
+        }
+    }
+
+    fn draw(&mut self, pos_offset: Vector2, scale: Vector2, list: &mut RenderableCollection) {
+        if self.lines.is_empty() { return }
+        const DEPTH:f64 = -1000.0;
+
+        list.push(visibility_bg(
+            pos_offset,
+            Vector2::new(SIZE.x, (FONT_SIZE + PADDING) * self.lines.len() as f64) * scale,
+            DEPTH
+        ));
+
+        let font = get_font();
+        for (i, line) in self.lines.iter().enumerate() {
+            list.push(Text::new(
+                if i == 0 { Color::RED } else { Color::BLACK },
+                DEPTH - 0.001,
+                pos_offset + Vector2::new(PADDING, (FONT_SIZE + PADDING) * i as f64) * scale,
+                (FONT_SIZE * scale.y) as u32,
+                line.clone(),
+                font.clone()
+            ))
+        }
+    }
+}
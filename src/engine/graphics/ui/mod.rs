@@ -3,9 +3,11 @@ mod fps_display;
 mod generic_button;
 mod ingame_elements;
 mod centered_text_helper;
+mod focus_ring;
 
 pub use ui_element::*;
 pub use fps_display::*;
 pub use generic_button::*;
 pub use ingame_elements::*;
-pub use centered_text_helper::*;
\ No newline at end of file
+pub use centered_text_helper::*;
+pub use focus_ring::*;
\ No newline at end of file
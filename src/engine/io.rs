@@ -229,16 +229,23 @@ pub async fn extract_all() {
 
 
 
-pub async fn read_other_game_replay(path: impl AsRef<Path>) -> TatakuResult<Replay> {
+pub async fn read_other_game_replay(path: impl AsRef<Path>) -> TatakuResult<(Replay, ReplayExtras)> {
     let path = path.as_ref();
 
     match path.extension().and_then(|s|s.to_str()) {
 
         // tataku replay
-        Some("ttkr") => Ok(open_database(path.to_str().unwrap())?.read::<Replay>()?),
+        Some("ttkr") => {
+            let mut reader = open_database(path.to_str().unwrap())?;
+            let replay = reader.read::<Replay>()?;
+            Ok((replay, ReplayExtras::read(&mut reader)))
+        }
 
         // osu replay
-        Some("osr") => Ok(convert_osu_replay(path)?),
+        Some("osr") => Ok((convert_osu_replay(path)?, ReplayExtras::default())),
+
+        // quaver replay
+        Some("qr") => convert_quaver_replay(path),
 
         _ => Err(TatakuError::String("Unknown replay file".to_owned()))
     }
@@ -10,33 +10,53 @@ use piston::input::ButtonState;
 use piston::ControllerAxisEvent;
 
 use crate::prelude::*;
+
+/// how far a touch has to move before its a drag instead of a tap
+const TOUCH_DRAG_DISTANCE:f64 = 15.0;
+/// how far a touch has to be dragged to scroll the same amount as one mouse wheel step
+const TOUCH_SCROLL_DISTANCE:f64 = 40.0;
+
 pub struct InputManager {
     pub mouse_pos: Vector2,
     pub scroll_delta: f64,
-    pub mouse_moved: bool,
+    /// when the mouse was last moved, if it moved since the last check
+    mouse_moved: Option<Instant>,
 
     pub mouse_buttons: HashSet<MouseButton>,
-    pub mouse_down: HashSet<(MouseButton, Instant)>,
-    pub mouse_up: HashSet<(MouseButton, Instant)>,
+    /// buttons that were pressed but waiting to be registered, in the order they were pressed
+    pub mouse_down: Vec<(MouseButton, Instant)>,
+    /// buttons that were released but waiting to be registered, in the order they were released
+    pub mouse_up: Vec<(MouseButton, Instant)>,
 
     /// controller names
     pub controller_names: HashMap<u32, Arc<String>>,
     /// index is controller id
     pub controller_buttons: HashMap<u32, HashSet<u8>>,
     /// index is controller id
-    pub controller_down: HashMap<u32, HashSet<u8>>,
+    pub controller_down: HashMap<u32, Vec<(u8, Instant)>>,
     /// index is controller id
-    pub controller_up: HashMap<u32, HashSet<u8>>,
+    pub controller_up: HashMap<u32, Vec<(u8, Instant)>>,
     /// index is controller id
     /// value index is axis id, value value is (changed, value)
     pub controller_axis: HashMap<u32, HashMap<u8, (bool, f64)>>,
+    /// index is controller id, value is when an axis last changed
+    controller_axis_moved: HashMap<u32, Instant>,
+
+    /// touches that are currently down, by touch id
+    pub touches: HashMap<i64, Vector2>,
+    /// touch events waiting to be registered, in the order they happened
+    touch_events: Vec<(TouchInput, Instant)>,
+    /// should the first touch act as the mouse? (taps click, drags scroll)
+    pub touch_as_mouse: bool,
+    /// the touch currently acting as the mouse
+    mouse_touch: Option<MouseTouch>,
 
     /// currently pressed keys
     keys: HashSet<Key>,
-    /// keys that were pressed but waiting to be registered
-    keys_down: HashSet<(Key, Instant)>,
-    /// keys that were released but waiting to be registered
-    keys_up: HashSet<(Key, Instant)>,
+    /// keys that were pressed but waiting to be registered, in the order they were pressed
+    keys_down: Vec<(Key, Instant)>,
+    /// keys that were released but waiting to be registered, in the order they were released
+    keys_up: Vec<(Key, Instant)>,
     
     text_cache: String,
     window_change_focus: Option<bool>,
@@ -51,22 +71,25 @@ pub struct InputManager {
 
 
     pub raw_input: bool,
+
+    /// when the current recording started, and what its recorded so far
+    recording: Option<(Instant, InputRecording)>,
 }
 impl InputManager {
     pub fn new() -> InputManager {
         InputManager {
             mouse_pos: Vector2::ZERO,
             scroll_delta: 0.0,
-            mouse_moved: false,
+            mouse_moved: None,
             register_times: Vec::new(),
 
             mouse_buttons: HashSet::new(),
-            mouse_down: HashSet::new(),
-            mouse_up: HashSet::new(),
+            mouse_down: Vec::new(),
+            mouse_up: Vec::new(),
 
             keys: HashSet::new(),
-            keys_down: HashSet::new(),
-            keys_up:  HashSet::new(),
+            keys_down: Vec::new(),
+            keys_up: Vec::new(),
 
             
             controller_names: HashMap::new(),
@@ -74,6 +97,12 @@ impl InputManager {
             controller_down: HashMap::new(),
             controller_up: HashMap::new(),
             controller_axis: HashMap::new(),
+            controller_axis_moved: HashMap::new(),
+
+            touches: HashMap::new(),
+            touch_events: Vec::new(),
+            touch_as_mouse: true,
+            mouse_touch: None,
 
 
             text_cache: String::new(),
@@ -82,7 +111,8 @@ impl InputManager {
             double_tap_protection: None,
             last_key_press: HashMap::new(),
 
-            raw_input: false
+            raw_input: false,
+            recording: None,
         }
     }
 
@@ -99,11 +129,11 @@ impl InputManager {
         }
 
         if !self.controller_down.contains_key(&id) {
-            self.controller_down.insert(id, HashSet::new());
+            self.controller_down.insert(id, Vec::new());
         }
 
         if !self.controller_up.contains_key(&id) {
-            self.controller_up.insert(id, HashSet::new());
+            self.controller_up.insert(id, Vec::new());
         }
     
         if !self.controller_axis.contains_key(&id) {
@@ -115,7 +145,24 @@ impl InputManager {
         self.double_tap_protection = protection;
     }
 
+    /// start recording every raw event. any recording thats already going is thrown out
+    pub fn start_recording(&mut self, beatmap_hash: String, playmode: String) {
+        self.recording = Some((Instant::now(), InputRecording { beatmap_hash, playmode, events: Vec::new() }));
+    }
+    /// stop recording, and get what was recorded
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take().map(|(_, recording)| recording)
+    }
+    fn record(&mut self, event: RecordedEvent) {
+        let Some((start, recording)) = &mut self.recording else { return };
+        recording.events.push((start.as_millis64(), event));
+    }
+
     pub fn handle_events(&mut self, e:Event) {
+        if let Some(event) = RecordedEvent::from_event(&e) {
+            self.record(event);
+        }
+
         if let Some(button) = e.button_args() {
             match (button.button, button.state) {
                 (Button::Keyboard(key), ButtonState::Press) => {
@@ -133,9 +180,10 @@ impl InputManager {
                     }
 
                     if ok_to_continue {
+                        let now = Instant::now();
                         self.keys.insert(key);
-                        self.keys_down.insert((key, Instant::now()));
-                        self.last_key_press.insert(key, (Instant::now(), false));
+                        self.keys_down.push((key, now));
+                        self.last_key_press.insert(key, (now, false));
                     }
                 }
                 (Button::Keyboard(key), ButtonState::Release) => {
@@ -152,7 +200,7 @@ impl InputManager {
 
                     if ok_to_continue {
                         self.keys.remove(&key);
-                        self.keys_up.insert((key, Instant::now()));
+                        self.keys_up.push((key, Instant::now()));
                         // self.last_key_press.remove(&key);
                     } else {
                         self.last_key_press.remove(&key);
@@ -160,23 +208,18 @@ impl InputManager {
                 }
                 (Button::Mouse(mb), ButtonState::Press) => {
                     self.mouse_buttons.insert(mb);
-                    self.mouse_down.insert((mb, Instant::now()));
+                    self.mouse_down.push((mb, Instant::now()));
                 }
                 (Button::Mouse(mb), ButtonState::Release) => {
                     self.mouse_buttons.remove(&mb);
-                    self.mouse_up.insert((mb, Instant::now()));
+                    self.mouse_up.push((mb, Instant::now()));
                 }
 
                 _ => {}
             }
         }
 
-        e.mouse_cursor(|[x, y]| {
-            let incoming = Vector2::new(x, y);
-            if incoming == self.mouse_pos { return }
-            self.mouse_moved = true;
-            self.mouse_pos = incoming;
-        });
+        e.mouse_cursor(|[x, y]| self.set_mouse_pos(Vector2::new(x, y)));
 
         e.mouse_scroll(|d| { self.scroll_delta += d[1]} );
         if let Some(e) = e.text_args() { self.text_cache += &e; }
@@ -189,6 +232,9 @@ impl InputManager {
     }
 
     pub fn handle_controller_events(&mut self, e:Event, controller_name: String) {
+        if let Event::Input(input, _) = &e {
+            self.record(RecordedEvent::Controller(input.clone(), controller_name.clone()));
+        }
 
         if let Some(axis) = e.controller_axis_args() {
             // debug!("got controller axis: {:?}", axis);
@@ -200,6 +246,7 @@ impl InputManager {
             let map = self.controller_axis.get_mut(&controller_id).unwrap();
             if ![Some(&(true, value)), Some(&(false, value))].contains(&map.get(&id)) {
                 map.insert(id, (true, value));
+                self.controller_axis_moved.insert(controller_id, Instant::now());
             }
         } else if let Some(button) = e.button_args() {
             match (button.button, button.state) {
@@ -207,12 +254,12 @@ impl InputManager {
                     // debug!("press: c: {}, b: {}", cb.id, cb.button);
                     self.verify_controller_index_exists(cb.id, controller_name);
                     self.controller_buttons.get_mut(&cb.id).unwrap().insert(cb.button);
-                    self.controller_down.get_mut(&cb.id).unwrap().insert(cb.button);
+                    self.controller_down.get_mut(&cb.id).unwrap().push((cb.button, Instant::now()));
                 }
                 (Button::Controller(cb), ButtonState::Release) => {
                     // debug!("release: c: {}, b: {}", cb.id, cb.button);
                     self.controller_buttons.get_mut(&cb.id).unwrap().remove(&cb.button);
-                    self.controller_up.get_mut(&cb.id).unwrap().insert(cb.button);
+                    self.controller_up.get_mut(&cb.id).unwrap().push((cb.button, Instant::now()));
                 }
                 _ => {}
             }
@@ -220,6 +267,62 @@ impl InputManager {
 
     }
 
+    /// handle a touch event. touches from the window come through [`GameEvent::Touch`],
+    /// synthetic ones can be injected by calling this directly
+    pub fn handle_touch(&mut self, touch: TouchInput) {
+        self.record(RecordedEvent::Touch(touch.id, [touch.pos.x, touch.pos.y], touch.phase));
+
+        match touch.phase {
+            TouchPhase::Down | TouchPhase::Move => { self.touches.insert(touch.id, touch.pos); }
+            TouchPhase::Up => { self.touches.remove(&touch.id); }
+        }
+        self.touch_events.push((touch, Instant::now()));
+
+        let Some(mut mouse_touch) = self.mouse_touch.take() else {
+            // the first touch to go down while no other touch is acting as the mouse takes over
+            if self.touch_as_mouse && touch.phase == TouchPhase::Down {
+                self.mouse_touch = Some(MouseTouch { id: touch.id, start: touch.pos, last: touch.pos, dragging: false });
+                self.set_mouse_pos(touch.pos);
+            }
+            return;
+        };
+        if mouse_touch.id != touch.id {
+            self.mouse_touch = Some(mouse_touch);
+            return;
+        }
+        // gameplay might have started since the touch went down
+        if !self.touch_as_mouse { return }
+
+        self.set_mouse_pos(touch.pos);
+        match touch.phase {
+            TouchPhase::Down => self.mouse_touch = Some(mouse_touch),
+            TouchPhase::Move => {
+                if touch.pos.distance(mouse_touch.start) > TOUCH_DRAG_DISTANCE {
+                    mouse_touch.dragging = true;
+                }
+                if mouse_touch.dragging {
+                    self.scroll_delta += (touch.pos.y - mouse_touch.last.y) / TOUCH_SCROLL_DISTANCE;
+                }
+
+                mouse_touch.last = touch.pos;
+                self.mouse_touch = Some(mouse_touch);
+            }
+            // a touch that wasnt dragged is a click
+            TouchPhase::Up if !mouse_touch.dragging => {
+                let now = Instant::now();
+                self.mouse_down.push((MouseButton::Left, now));
+                self.mouse_up.push((MouseButton::Left, now));
+            }
+            TouchPhase::Up => {}
+        }
+    }
+
+    fn set_mouse_pos(&mut self, pos: Vector2) {
+        if pos == self.mouse_pos { return }
+        self.mouse_moved = Some(Instant::now());
+        self.mouse_pos = pos;
+    }
+
     /// is the key currently down (not up)
     pub fn key_down(&self, k:Key) -> bool {self.keys.contains(&k)}
     pub fn get_key_mods(&self) -> KeyModifiers {
@@ -231,39 +334,29 @@ impl InputManager {
     }
 
 
-    /// get all keys that were pressed, and clear the pressed list. (will be true when first checked and pressed, false after first check or when key is up)
-    pub fn get_keys_down(&mut self) -> Vec<Key> {
-        let mut down = Vec::new();
-        for (i, time) in &self.keys_down {down.push(i.clone()); self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
-        self.keys_down.clear();
-
-        down
+    /// get all keys that were pressed and when they were pressed, and clear the pressed list. (will be true when first checked and pressed, false after first check or when key is up)
+    pub fn get_keys_down(&mut self) -> Vec<(Key, Instant)> {
+        for (_, time) in &self.keys_down {self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
+        std::mem::take(&mut self.keys_down)
     }
-    pub fn get_keys_up(&mut self) -> Vec<Key> {
-        let mut up = Vec::new();
-        for (i, time) in &self.keys_up {up.push(i.clone()); self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
-        self.keys_up.clear();
-
-        up
+    pub fn get_keys_up(&mut self) -> Vec<(Key, Instant)> {
+        for (_, time) in &self.keys_up {self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
+        std::mem::take(&mut self.keys_up)
     }
 
 
-    /// get all pressed mouse buttons, and reset the pressed array
-    pub fn get_mouse_down(&mut self) -> Vec<MouseButton> {
-        let mut down = Vec::new();
-        for (i, time) in &self.mouse_down {down.push(i.clone()); self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
-        self.mouse_down.clear();
-        down
+    /// get all pressed mouse buttons and when they were pressed, and reset the pressed array
+    pub fn get_mouse_down(&mut self) -> Vec<(MouseButton, Instant)> {
+        for (_, time) in &self.mouse_down {self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
+        std::mem::take(&mut self.mouse_down)
     }
-    pub fn get_mouse_up(&mut self) -> Vec<MouseButton> {
-        let mut up = Vec::new();
-        for (i, time) in &self.mouse_up {up.push(i.clone()); self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
-        self.mouse_up.clear();
-        up
+    pub fn get_mouse_up(&mut self) -> Vec<(MouseButton, Instant)> {
+        for (_, time) in &self.mouse_up {self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
+        std::mem::take(&mut self.mouse_up)
     }
 
-    /// get whether the mouse was moved or not
-    pub fn get_mouse_moved(&mut self) -> bool {
+    /// get when the mouse was last moved, if it was moved since the last check
+    pub fn get_mouse_moved(&mut self) -> Option<Instant> {
         std::mem::take(&mut self.mouse_moved)
     }
     /// get how much the mouse wheel as scrolled (vertically) since the last check
@@ -273,46 +366,51 @@ impl InputManager {
 
 
     /// get all pressed controller buttons, and reset the pressed array
-    /// (controller_id, button_id)
-    pub fn get_controller_down(&mut self) -> Vec<(Box<dyn Controller>, u8)> {
+    /// (controller, button_id, time pressed)
+    pub fn get_controller_down(&mut self) -> Vec<(Box<dyn Controller>, u8, Instant)> {
         let mut down = Vec::new();
         for (c, buttons) in self.controller_down.iter_mut() {
             let name = self.controller_names.get(c).unwrap();
            
-            for b in buttons.iter() {
+            for (b, time) in buttons.drain(..) {
                 let controller = make_controller(*c, name.clone());
-                down.push((controller, *b));
+                down.push((controller, b, time));
             }
-            buttons.clear()
         }
         down
     }
 
     /// get all released controller buttons, and reset the pressed array
-    /// (controller_id, button_id)
-    pub fn get_controller_up(&mut self) -> Vec<(Box<dyn Controller>, u8)> {
+    /// (controller, button_id, time released)
+    pub fn get_controller_up(&mut self) -> Vec<(Box<dyn Controller>, u8, Instant)> {
         let mut up = Vec::new();
         for (c, buttons) in self.controller_up.iter_mut() {
             let name = self.controller_names.get(c).unwrap();
             
-            for b in buttons.iter() {
+            for (b, time) in buttons.drain(..) {
                 let controller = make_controller(*c, name.clone());
-                up.push((controller, *b));
+                up.push((controller, b, time));
             }
-            buttons.clear()
         }
         up
     }
 
+    /// get all touch events and when they happened, and clear the list
+    pub fn get_touch_events(&mut self) -> Vec<(TouchInput, Instant)> {
+        for (_, time) in &self.touch_events {self.register_times.push(time.elapsed().as_secs_f32()*1000.0)}
+        std::mem::take(&mut self.touch_events)
+    }
+
     /// get all controller axes
-    /// (controller, [axis_id, (changed, value)])
-    pub fn get_controller_axis(&mut self) -> Vec<(Box<dyn Controller>, HashMap<u8, (bool, f64)>)> {
+    /// (controller, [axis_id, (changed, value)], time an axis last changed)
+    pub fn get_controller_axis(&mut self) -> Vec<(Box<dyn Controller>, HashMap<u8, (bool, f64)>, Instant)> {
         let mut axis = Vec::new();
 
         for (c, axis_data) in self.controller_axis.iter_mut() {
             let name = self.controller_names.get(c).unwrap();
             let controller = make_controller(*c, name.clone());
-            axis.push((controller, axis_data.clone()));
+            let moved = self.controller_axis_moved.remove(c).unwrap_or_else(Instant::now);
+            axis.push((controller, axis_data.clone(), moved));
 
             // update all the changed to false, since we've now checked them
             for (_, (changed, _)) in axis_data.iter_mut() {
@@ -323,6 +421,14 @@ impl InputManager {
         axis
     }
     
+    /// get where every axis currently is, by controller name
+    pub fn get_axis_values(&self) -> HashMap<Arc<String>, HashMap<u8, f64>> {
+        self.controller_axis.iter().filter_map(|(c, axis_data)| {
+            let name = self.controller_names.get(c)?.clone();
+            Some((name, axis_data.iter().map(|(axis, (_, value))| (*axis, *value)).collect()))
+        }).collect()
+    }
+
     /// gets any text typed since the last check
     pub fn get_text(&mut self) -> String {
         std::mem::take(&mut self.text_cache)
@@ -354,10 +460,110 @@ impl InputManager {
 
 
 
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchPhase {
+    Down,
+    Move,
+    /// lifted or cancelled
+    Up,
+}
+
+/// a finger on the screen. the id stays the same from when it goes down until its lifted
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TouchInput {
+    pub id: i64,
+    pub pos: Vector2,
+    pub phase: TouchPhase,
+}
+impl TouchInput {
+    pub fn new(id: i64, pos: Vector2, phase: TouchPhase) -> Self {
+        Self { id, pos, phase }
+    }
+}
+impl From<piston::TouchArgs> for TouchInput {
+    fn from(args: piston::TouchArgs) -> Self {
+        let [x, y] = args.position();
+        let phase = match args.touch {
+            piston::Touch::Start => TouchPhase::Down,
+            piston::Touch::Move => TouchPhase::Move,
+            piston::Touch::End | piston::Touch::Cancel => TouchPhase::Up,
+        };
+
+        Self::new(args.id, Vector2::new(x, y), phase)
+    }
+}
+
+/// the touch thats acting as the mouse
+#[derive(Copy, Clone, Debug)]
+struct MouseTouch {
+    id: i64,
+    start: Vector2,
+    last: Vector2,
+    /// once a touch has been dragged it scrolls instead of clicking
+    dragging: bool,
+}
+
+/// an axis used as a button, ie a trigger or a stick direction
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AxisConfig {
     pub axis_id: u8,
-    pub threshhold: f64
+    /// the axis counts as pressed once it reaches this value
+    pub threshhold: f64,
+    /// once pressed, how far back past the threshhold the axis has to go to count as released.
+    /// stops a shaky axis sitting right on the threshhold from spamming presses
+    pub deadzone: f64,
+    /// flip the axis, for axes that are pressed by going negative
+    pub inverted: bool,
+}
+impl AxisConfig {
+    pub fn new(axis_id: u8, threshhold: f64, inverted: bool) -> Self {
+        Self {
+            axis_id,
+            threshhold,
+            inverted,
+            ..Self::default()
+        }
+    }
+
+    /// is the axis pressed at this value? `pressed` is whether it was pressed before
+    pub fn check(&self, value: f64, pressed: bool) -> bool {
+        let value = if self.inverted { -value } else { value };
+        if pressed {
+            value >= self.threshhold - self.deadzone
+        } else {
+            value >= self.threshhold
+        }
+    }
+}
+// axes are bound as keybind targets, so they need to be hashable.
+// the values only come from calibration or settings, so comparing their bits is fine
+impl PartialEq for AxisConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.axis_id == other.axis_id
+        && self.inverted == other.inverted
+        && self.threshhold.to_bits() == other.threshhold.to_bits()
+        && self.deadzone.to_bits() == other.deadzone.to_bits()
+    }
+}
+impl Eq for AxisConfig {}
+impl std::hash::Hash for AxisConfig {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.axis_id.hash(state);
+        self.inverted.hash(state);
+        self.threshhold.to_bits().hash(state);
+        self.deadzone.to_bits().hash(state);
+    }
+}
+impl Default for AxisConfig {
+    fn default() -> Self {
+        Self {
+            axis_id: 0,
+            threshhold: 0.5,
+            deadzone: 0.1,
+            inverted: false,
+        }
+    }
 }
 
 
@@ -381,4 +587,110 @@ impl ControllerInputConfig {
             false
         }
     }
+
+    /// get whether this input is pressed after an axis update, or None if the update doesnt include this input's axis
+    pub fn check_axis(&self, axis_data: &HashMap<u8, (bool, f64)>, pressed: bool) -> Option<bool> {
+        let axis = self.axis?;
+        let (changed, value) = axis_data.get(&axis.axis_id)?;
+        if !changed { return None }
+
+        Some(axis.check(*value, pressed))
+    }
+}
+
+/// turn axis updates into presses and releases for any inputs bound to an axis.
+/// `held` keeps track of which axis inputs are currently pressed between updates.
+/// returns (input, pressed) for every input that changed
+pub fn get_axis_presses<T: Copy + Eq + std::hash::Hash>(
+    inputs: &[(T, ControllerInputConfig)], 
+    axis_data: &HashMap<u8, (bool, f64)>, 
+    held: &mut HashSet<T>
+) -> Vec<(T, bool)> {
+    let mut changes = Vec::new();
+
+    for (input, config) in inputs {
+        let was_pressed = held.contains(input);
+        let Some(pressed) = config.check_axis(axis_data, was_pressed) else { continue };
+        if pressed == was_pressed { continue }
+
+        if pressed {
+            held.insert(*input);
+        } else {
+            held.remove(input);
+        }
+        changes.push((*input, pressed));
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(input: &mut InputManager, id: i64, x: f64, y: f64, phase: TouchPhase) {
+        input.handle_touch(TouchInput::new(id, Vector2::new(x, y), phase));
+    }
+    fn clicks(input: &mut InputManager) -> usize {
+        input.get_mouse_up();
+        input.get_mouse_down().len()
+    }
+
+    #[test]
+    fn tap_clicks() {
+        let mut input = InputManager::new();
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Down);
+        assert_eq!(input.mouse_pos, Vector2::new(100.0, 100.0));
+        assert_eq!(clicks(&mut input), 0);
+
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Up);
+        assert_eq!(clicks(&mut input), 1);
+        assert!(input.touches.is_empty());
+    }
+
+    #[test]
+    fn drag_scrolls_instead_of_clicking() {
+        let mut input = InputManager::new();
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Down);
+        touch(&mut input, 1, 100.0, 100.0 + TOUCH_DRAG_DISTANCE + 1.0, TouchPhase::Move);
+        touch(&mut input, 1, 100.0, 100.0 + TOUCH_DRAG_DISTANCE + 1.0 + TOUCH_SCROLL_DISTANCE, TouchPhase::Move);
+        assert!(input.get_scroll_delta() > 1.0);
+
+        touch(&mut input, 1, 100.0, 300.0, TouchPhase::Up);
+        assert_eq!(clicks(&mut input), 0);
+    }
+
+    #[test]
+    fn only_the_first_touch_is_the_mouse() {
+        let mut input = InputManager::new();
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Down);
+        touch(&mut input, 2, 500.0, 500.0, TouchPhase::Down);
+        assert_eq!(input.mouse_pos, Vector2::new(100.0, 100.0));
+        assert_eq!(input.touches.len(), 2);
+
+        // lifting the second touch doesnt click
+        touch(&mut input, 2, 500.0, 500.0, TouchPhase::Up);
+        assert_eq!(clicks(&mut input), 0);
+        assert_eq!(input.touches.len(), 1);
+
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Up);
+        assert_eq!(clicks(&mut input), 1);
+
+        // once the mouse touch is lifted, the next touch takes over
+        touch(&mut input, 3, 300.0, 300.0, TouchPhase::Down);
+        assert_eq!(input.mouse_pos, Vector2::new(300.0, 300.0));
+    }
+
+    #[test]
+    fn touches_dont_act_as_mouse_during_gameplay() {
+        let mut input = InputManager::new();
+        input.touch_as_mouse = false;
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Down);
+        touch(&mut input, 1, 100.0, 100.0, TouchPhase::Up);
+
+        assert_eq!(clicks(&mut input), 0);
+        assert_eq!(input.mouse_pos, Vector2::ZERO);
+        // but still get sent to the gamemode
+        assert_eq!(input.get_touch_events().len(), 2);
+    }
 }
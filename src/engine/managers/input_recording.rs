@@ -0,0 +1,170 @@
+use piston::Event;
+use crate::prelude::*;
+
+/// file extension for input recordings
+pub const INPUT_RECORDING_EXTENSION:&str = "ttir";
+
+lazy_static::lazy_static! {
+    /// tells playback that gameplay has started
+    static ref PLAYBACK_START: tokio::sync::Notify = tokio::sync::Notify::new();
+}
+
+/// an input event as its stored in a recording
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    Window(piston::Input),
+    /// controller event, controller name
+    Controller(piston::Input, String),
+    /// touch id, position, phase
+    Touch(i64, [f64; 2], TouchPhase),
+}
+impl RecordedEvent {
+    pub fn from_event(e: &Event) -> Option<Self> {
+        let Event::Input(input, _) = e else { return None };
+        Some(Self::Window(input.clone()))
+    }
+
+    /// turn this back into the event the game got when it was recorded
+    pub fn into_game_event(self) -> GameEvent {
+        match self {
+            Self::Window(input) => GameEvent::WindowEvent(Event::Input(input, None)),
+            Self::Controller(input, name) => GameEvent::ControllerEvent(Event::Input(input, None), name),
+            Self::Touch(id, [x, y], phase) => GameEvent::Touch(TouchInput::new(id, Vector2::new(x, y), phase)),
+        }
+    }
+}
+
+/// every raw input event during a play and when it happened, so input problems can be reproduced
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// what was being played, so whoever is looking at it knows what to load
+    pub beatmap_hash: String,
+    pub playmode: String,
+
+    /// ms since the recording started, and the event
+    pub events: Vec<(f64, RecordedEvent)>,
+}
+impl InputRecording {
+    pub fn load(path: impl AsRef<Path>) -> TatakuResult<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> TatakuResult<()> {
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+}
+
+/// feeds a recording back into the game through the game event channel
+pub struct InputPlayback;
+impl InputPlayback {
+    /// start feeding `recording` into `sender` once gameplay starts, with the same timing it was recorded with
+    pub fn spawn(recording: InputRecording, sender: tokio::sync::mpsc::Sender<GameEvent>) {
+        tokio::spawn(async move {
+            info!("input playback waiting for gameplay to start (map {}, {})", recording.beatmap_hash, recording.playmode);
+            PLAYBACK_START.notified().await;
+            info!("input playback started, {} events", recording.events.len());
+
+            let start = tokio::time::Instant::now();
+            for (time, event) in recording.events {
+                tokio::time::sleep_until(start + Duration::from_secs_f64(time / 1000.0)).await;
+                if sender.send(event.into_game_event()).await.is_err() { break }
+            }
+
+            info!("input playback done");
+        });
+    }
+
+    /// let playback know gameplay has started
+    pub fn gameplay_started() {
+        PLAYBACK_START.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use piston::{ Input, ButtonArgs, ButtonState, Button };
+
+    fn key_input(key: Key, state: ButtonState) -> Input {
+        Input::Button(ButtonArgs { state, button: Button::Keyboard(key), scancode: None })
+    }
+
+    fn record_some(input: &mut InputManager) -> InputRecording {
+        input.start_recording("map_hash".to_owned(), "taiko".to_owned());
+        input.handle_events(Event::Input(key_input(Key::D, ButtonState::Press), None));
+        input.handle_touch(TouchInput::new(3, Vector2::new(10.0, 20.0), TouchPhase::Down));
+        input.handle_events(Event::Input(key_input(Key::D, ButtonState::Release), None));
+        input.stop_recording().unwrap()
+    }
+
+    #[test]
+    fn records_events_in_order() {
+        let mut input = InputManager::new();
+        let recording = record_some(&mut input);
+
+        assert_eq!(recording.beatmap_hash, "map_hash");
+        assert_eq!(recording.playmode, "taiko");
+        assert_eq!(recording.events.len(), 3);
+        assert!(recording.events.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        assert!(matches!(&recording.events[0].1, RecordedEvent::Window(Input::Button(b)) if b.state == ButtonState::Press));
+        assert!(matches!(&recording.events[1].1, RecordedEvent::Touch(3, [x, y], TouchPhase::Down) if *x == 10.0 && *y == 20.0));
+        assert!(matches!(&recording.events[2].1, RecordedEvent::Window(Input::Button(b)) if b.state == ButtonState::Release));
+    }
+
+    #[test]
+    fn only_records_while_recording() {
+        let mut input = InputManager::new();
+        input.handle_touch(TouchInput::new(1, Vector2::ZERO, TouchPhase::Down));
+        assert!(input.stop_recording().is_none());
+
+        // starting again throws out the old recording
+        input.start_recording("old".to_owned(), "osu".to_owned());
+        input.handle_touch(TouchInput::new(1, Vector2::ZERO, TouchPhase::Up));
+        input.start_recording("new".to_owned(), "osu".to_owned());
+
+        let recording = input.stop_recording().unwrap();
+        assert_eq!(recording.beatmap_hash, "new");
+        assert!(recording.events.is_empty());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let recording = record_some(&mut InputManager::new());
+        let path = std::env::temp_dir().join(format!("tataku_test_recording_{}.{INPUT_RECORDING_EXTENSION}", std::process::id()));
+
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.beatmap_hash, recording.beatmap_hash);
+        assert_eq!(loaded.playmode, recording.playmode);
+        assert_eq!(loaded.events.len(), recording.events.len());
+        for ((time, event), (loaded_time, loaded_event)) in recording.events.iter().zip(loaded.events.iter()) {
+            assert_eq!(time, loaded_time);
+            assert_eq!(format!("{event:?}"), format!("{loaded_event:?}"));
+        }
+    }
+
+    #[test]
+    fn played_back_events_match_what_was_recorded() {
+        let recording = record_some(&mut InputManager::new());
+        let mut playback = InputManager::new();
+
+        for (_, event) in recording.events {
+            match event.into_game_event() {
+                GameEvent::WindowEvent(e) => playback.handle_events(e),
+                GameEvent::Touch(touch) => playback.handle_touch(touch),
+                _ => panic!("unexpected event"),
+            }
+        }
+
+        let touches = playback.get_touch_events();
+        assert_eq!(touches.len(), 1);
+        assert_eq!(touches[0].0, TouchInput::new(3, Vector2::new(10.0, 20.0), TouchPhase::Down));
+        assert!(playback.get_keys_down().iter().any(|(k, _)| *k == Key::D));
+    }
+}
@@ -1,5 +1,6 @@
 mod instant;
 mod input_manager;
+mod input_recording;
 mod volume_control;
 mod cursor_manager;
 mod notification_manager;
@@ -7,6 +8,7 @@ mod notification_manager;
 pub use instant::*;
 pub use volume_control::*;
 pub use input_manager::*;
+pub use input_recording::*;
 pub use cursor_manager::*;
 pub use notification_manager::*;
 
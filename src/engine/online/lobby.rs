@@ -0,0 +1,369 @@
+use crate::prelude::*;
+
+/// lobby packets arent part of tataku-common's [`PacketId`] yet, so they're sent as their own messages.
+/// every lobby message starts with this so it can be told apart from a normal packet.
+///
+/// this is our own extension, the official server doesnt know about it and would drop these messages.
+/// a server that does sends [`LobbyPacket::Server_LobbiesSupported`] after login,
+/// and lobby packets are only sent once it has (see `OnlineManager::lobbies_supported`).
+/// until lobbies are part of tataku-common, the menus for them are only in builds with the `multiplayer_lobbies` feature
+pub const LOBBY_PACKET_MAGIC:&[u8] = b"TTLOBBY";
+
+/// where a player in a lobby is at
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LobbyPlayerState {
+    #[default]
+    NotReady,
+    Ready,
+    /// doesnt have the lobby's map
+    NoMap,
+    Playing,
+    /// finished the map, waiting for everyone else
+    Done,
+}
+impl LobbyPlayerState {
+    pub fn color(&self) -> Color {
+        match self {
+            Self::NotReady => Color::WHITE,
+            Self::Ready => Color::GREEN,
+            Self::NoMap => Color::RED,
+            Self::Playing => Color::YELLOW,
+            Self::Done => Color::BLUE,
+        }
+    }
+}
+impl Display for LobbyPlayerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotReady => write!(f, "Not Ready"),
+            Self::Ready => write!(f, "Ready"),
+            Self::NoMap => write!(f, "No Map"),
+            Self::Playing => write!(f, "Playing"),
+            Self::Done => write!(f, "Done"),
+        }
+    }
+}
+impl Serializable for LobbyPlayerState {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(match sr.read::<u8>()? {
+            1 => Self::Ready,
+            2 => Self::NoMap,
+            3 => Self::Playing,
+            4 => Self::Done,
+            _ => Self::NotReady,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(*self as u8);
+    }
+}
+
+/// the map a lobby is playing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LobbyBeatmap {
+    /// empty if the host hasnt picked a map yet
+    pub hash: String,
+    pub playmode: PlayMode,
+    /// artist - title [version], so players without the map know what to look for
+    pub title: String,
+}
+impl LobbyBeatmap {
+    pub fn new(map: &BeatmapMeta, playmode: PlayMode) -> Self {
+        Self {
+            hash: map.beatmap_hash.clone(),
+            playmode: map.check_mode_override(playmode),
+            title: format!("{} - {} [{}]", map.artist, map.title, map.version),
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        !self.hash.is_empty()
+    }
+}
+impl Serializable for LobbyBeatmap {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            hash: sr.read()?,
+            playmode: sr.read()?,
+            title: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.hash.clone());
+        sw.write(self.playmode.clone());
+        sw.write(self.title.clone());
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LobbyPlayer {
+    pub user_id: u32,
+    pub username: String,
+    pub state: LobbyPlayerState,
+}
+impl Serializable for LobbyPlayer {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            user_id: sr.read()?,
+            username: sr.read()?,
+            state: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.user_id);
+        sw.write(self.username.clone());
+        sw.write(self.state);
+    }
+}
+
+/// what the lobby list shows about a lobby
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LobbyInfo {
+    pub id: u32,
+    pub name: String,
+    pub host: String,
+    pub has_password: bool,
+    pub players: u8,
+    pub playing: bool,
+}
+impl Serializable for LobbyInfo {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            id: sr.read()?,
+            name: sr.read()?,
+            host: sr.read()?,
+            has_password: sr.read()?,
+            players: sr.read()?,
+            playing: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.id);
+        sw.write(self.name.clone());
+        sw.write(self.host.clone());
+        sw.write(self.has_password);
+        sw.write(self.players);
+        sw.write(self.playing);
+    }
+}
+
+/// everything about the lobby we're in. the server sends the whole thing whenever something changes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lobby {
+    pub id: u32,
+    pub name: String,
+    pub host_id: u32,
+    pub players: Vec<LobbyPlayer>,
+
+    pub beatmap: LobbyBeatmap,
+    /// mods everyone plays with, picked by the host
+    pub mods: Vec<String>,
+    pub speed: u16,
+
+    pub playing: bool,
+}
+impl Lobby {
+    pub fn player(&self, user_id: u32) -> Option<&LobbyPlayer> {
+        self.players.iter().find(|p| p.user_id == user_id)
+    }
+
+    pub fn host(&self) -> Option<&LobbyPlayer> {
+        self.player(self.host_id)
+    }
+
+    /// the lobby's mods, as a mod manager
+    pub fn mod_manager(&self) -> ModManager {
+        ModManager::new()
+            .with_mods(self.mods.iter().cloned().collect())
+            .with_speed(self.speed)
+    }
+
+    /// can the host start the match? everyone who has the map has to be ready
+    pub fn can_start(&self) -> bool {
+        self.beatmap.is_set()
+        && !self.playing
+        && self.players
+            .iter()
+            .filter(|p| p.user_id != self.host_id && p.state != LobbyPlayerState::NoMap)
+            .all(|p| p.state == LobbyPlayerState::Ready)
+    }
+}
+impl Serializable for Lobby {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            id: sr.read()?,
+            name: sr.read()?,
+            host_id: sr.read()?,
+            players: sr.read()?,
+            beatmap: sr.read()?,
+            mods: sr.read()?,
+            speed: sr.read()?,
+            playing: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.id);
+        sw.write(self.name.clone());
+        sw.write(self.host_id);
+        sw.write(self.players.clone());
+        sw.write(self.beatmap.clone());
+        sw.write(self.mods.clone());
+        sw.write(self.speed);
+        sw.write(self.playing);
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug)]
+pub enum LobbyPacket {
+    // ===== lobby list =====
+    Client_LobbyList,
+    Server_LobbyList { lobbies: Vec<LobbyInfo> },
+
+    // ===== create/join/leave =====
+    Client_CreateLobby { name: String, password: String },
+    Client_JoinLobby { lobby_id: u32, password: String },
+    Client_LeaveLobby,
+    Server_LobbyJoined { lobby: Lobby },
+    Server_LobbyJoinFailed { reason: String },
+    /// we're no longer in the lobby, ie it was closed
+    Server_LobbyLeft,
+
+    // ===== lobby state =====
+    Client_LobbySetMap { beatmap: LobbyBeatmap },
+    Client_LobbySetMods { mods: Vec<String>, speed: u16 },
+    Client_LobbySetState { state: LobbyPlayerState },
+    Server_LobbyUpdate { lobby: Lobby },
+
+    // ===== match =====
+    /// host wants to start
+    Client_LobbyStart,
+    /// everyone should start the map now
+    Server_LobbyStart,
+    /// current score during the match, or the final score if complete
+    Client_LobbyScore { score: Score, complete: bool },
+    Server_LobbyScore { user_id: u32, score: Score },
+    /// everyone has finished
+    Server_LobbyComplete,
+
+    /// sent after login by servers that know about lobbies
+    Server_LobbiesSupported,
+
+    /// a packet this client doesnt know about
+    Unknown,
+}
+impl LobbyPacket {
+    /// get the bytes to send for this packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = LOBBY_PACKET_MAGIC.to_vec();
+        bytes.extend(SimpleWriter::new().write(self.clone()).done());
+        bytes
+    }
+
+    /// read a lobby message, if thats what `data` is
+    pub fn from_bytes(data: &[u8]) -> Option<SerializationResult<Self>> {
+        let data = data.strip_prefix(LOBBY_PACKET_MAGIC)?;
+        Some(SerializationReader::new(data.to_vec()).read())
+    }
+}
+impl Serializable for LobbyPacket {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(match sr.read::<u8>()? {
+            0 => Self::Client_LobbyList,
+            1 => Self::Server_LobbyList { lobbies: sr.read()? },
+
+            2 => Self::Client_CreateLobby { name: sr.read()?, password: sr.read()? },
+            3 => Self::Client_JoinLobby { lobby_id: sr.read()?, password: sr.read()? },
+            4 => Self::Client_LeaveLobby,
+            5 => Self::Server_LobbyJoined { lobby: sr.read()? },
+            6 => Self::Server_LobbyJoinFailed { reason: sr.read()? },
+            7 => Self::Server_LobbyLeft,
+
+            8 => Self::Client_LobbySetMap { beatmap: sr.read()? },
+            9 => Self::Client_LobbySetMods { mods: sr.read()?, speed: sr.read()? },
+            10 => Self::Client_LobbySetState { state: sr.read()? },
+            11 => Self::Server_LobbyUpdate { lobby: sr.read()? },
+
+            12 => Self::Client_LobbyStart,
+            13 => Self::Server_LobbyStart,
+            14 => Self::Client_LobbyScore { score: sr.read()?, complete: sr.read()? },
+            15 => Self::Server_LobbyScore { user_id: sr.read()?, score: sr.read()? },
+            16 => Self::Server_LobbyComplete,
+
+            17 => Self::Server_LobbiesSupported,
+
+            _ => Self::Unknown,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        match self {
+            Self::Client_LobbyList => sw.write(0u8),
+            Self::Server_LobbyList { lobbies } => {
+                sw.write(1u8);
+                sw.write(lobbies.clone());
+            }
+
+            Self::Client_CreateLobby { name, password } => {
+                sw.write(2u8);
+                sw.write(name.clone());
+                sw.write(password.clone());
+            }
+            Self::Client_JoinLobby { lobby_id, password } => {
+                sw.write(3u8);
+                sw.write(*lobby_id);
+                sw.write(password.clone());
+            }
+            Self::Client_LeaveLobby => sw.write(4u8),
+            Self::Server_LobbyJoined { lobby } => {
+                sw.write(5u8);
+                sw.write(lobby.clone());
+            }
+            Self::Server_LobbyJoinFailed { reason } => {
+                sw.write(6u8);
+                sw.write(reason.clone());
+            }
+            Self::Server_LobbyLeft => sw.write(7u8),
+
+            Self::Client_LobbySetMap { beatmap } => {
+                sw.write(8u8);
+                sw.write(beatmap.clone());
+            }
+            Self::Client_LobbySetMods { mods, speed } => {
+                sw.write(9u8);
+                sw.write(mods.clone());
+                sw.write(*speed);
+            }
+            Self::Client_LobbySetState { state } => {
+                sw.write(10u8);
+                sw.write(*state);
+            }
+            Self::Server_LobbyUpdate { lobby } => {
+                sw.write(11u8);
+                sw.write(lobby.clone());
+            }
+
+            Self::Client_LobbyStart => sw.write(12u8),
+            Self::Server_LobbyStart => sw.write(13u8),
+            Self::Client_LobbyScore { score, complete } => {
+                sw.write(14u8);
+                sw.write(score.clone());
+                sw.write(*complete);
+            }
+            Self::Server_LobbyScore { user_id, score } => {
+                sw.write(15u8);
+                sw.write(*user_id);
+                sw.write(score.clone());
+            }
+            Self::Server_LobbyComplete => sw.write(16u8),
+
+            Self::Server_LobbiesSupported => sw.write(17u8),
+            Self::Unknown => sw.write(u8::MAX),
+        }
+    }
+}
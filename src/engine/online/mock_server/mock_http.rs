@@ -0,0 +1,225 @@
+use tokio::{ sync::Mutex, net::{ TcpListener, TcpStream }, io::{ AsyncReadExt, AsyncWriteExt } };
+
+use crate::prelude::*;
+
+/// a score on the mock server's leaderboards
+#[derive(Clone, Debug)]
+pub struct MockScore {
+    pub score_id: u64,
+    pub score: Score,
+    /// none for scores from the scenario
+    pub replay: Option<Replay>,
+    /// what was submitted next to the replay
+    pub extras: ReplayExtras,
+}
+
+/// same as what the score server sends for leaderboards
+#[derive(Serialize)]
+struct MockLeaderboardScore {
+    score_id: u64,
+    score_hash: Option<String>,
+    score: Score,
+}
+
+struct MockRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct MockResponse {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+impl MockResponse {
+    fn json(value: &impl Serialize) -> Self {
+        Self { status: 200, content_type: "application/json", body: serde_json::to_vec(value).unwrap_or_default() }
+    }
+    fn bytes(body: Vec<u8>) -> Self {
+        Self { status: 200, content_type: "application/octet-stream", body }
+    }
+    fn error(status: u16, message: impl ToString) -> Self {
+        Self { status, content_type: "text/plain", body: message.to_string().into_bytes() }
+    }
+}
+
+/// the score server's http endpoints. this is a really basic http server, but its enough for reqwest
+pub(super) async fn serve_mock_http(listener: TcpListener, state: Arc<Mutex<MockServerState>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_request(stream, state).await {
+                        warn!("[mock] http error: {e}");
+                    }
+                });
+            }
+            Err(e) => warn!("[mock] error accepting http connection: {e}"),
+        }
+    }
+}
+
+async fn handle_request(mut stream: TcpStream, state: Arc<Mutex<MockServerState>>) -> TatakuResult<()> {
+    let Some(request) = read_request(&mut stream).await? else { return Ok(()) };
+    let response = state.lock().await.handle_http(request);
+
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        _ => "Not Found",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream) -> TatakuResult<Option<MockRequest>> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    // read until the end of the headers
+    let header_end = loop {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 { return Ok(None) }
+        data.extend_from_slice(&buf[..read]);
+
+        if let Some(i) = data.windows(4).position(|w| w == b"\r\n\r\n") { break i + 4 }
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_owned();
+    let target = request_line.next().unwrap_or_default();
+
+    let content_length = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, len)| len.trim().parse::<usize>().ok())
+        .unwrap_or_default();
+
+    // and then the body
+    let mut body = data.split_off(header_end);
+    while body.len() < content_length {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 { break }
+        body.extend_from_slice(&buf[..read]);
+    }
+
+    // hashes and playmodes dont need decoding, so this doesnt bother
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect();
+
+    Ok(Some(MockRequest {
+        method,
+        path: path.to_owned(),
+        query,
+        body,
+    }))
+}
+
+// http handling
+impl MockServerState {
+    fn handle_http(&mut self, request: MockRequest) -> MockResponse {
+        match (&*request.method, &*request.path) {
+            ("POST", "/score_submit") => self.submit_score(&request.body),
+            ("GET", "/api/get_scores") => self.get_scores(&request.query),
+            ("GET", "/replay_file") => self.get_replay(&request.query),
+            ("GET", path) if path.starts_with("/download/replay/") => self.get_quaver_replay(&path["/download/replay/".len()..]),
+
+            ("POST", "/lastfm/check") => MockResponse::json(&serde_json::json!({ "auth_url": null })),
+            ("POST", "/lastfm/set_now_playing") => {
+                let Ok(body) = serde_json::from_slice::<serde_json::Value>(&request.body) else { return MockResponse::error(400, "bad json") };
+                let artist = body["artist"].as_str().unwrap_or_default().to_owned();
+                let track = body["track"].as_str().unwrap_or_default().to_owned();
+                self.now_playing.push((artist, track));
+                MockResponse::json(&serde_json::json!({}))
+            }
+
+            (method, path) => MockResponse::error(404, format!("{method} {path} not found")),
+        }
+    }
+
+    fn submit_score(&mut self, body: &[u8]) -> MockResponse {
+        let ScoreSubmitPayload { submit, extras } = match serde_json::from_slice(body) {
+            Ok(payload) => payload,
+            Err(e) => return MockResponse::error(400, e),
+        };
+
+        if let Some(reason) = &self.scenario.reject_scores {
+            return MockResponse::json(&SubmitResponse::NotSubmitted(NotSubmittedReason::InternalError, reason.clone()));
+        }
+        if self.scenario.check_login(&submit.username, &submit.password).is_err() {
+            return MockResponse::json(&SubmitResponse::NotSubmitted(NotSubmittedReason::NoUser, "Bad username or password".to_owned()));
+        }
+        let Some(score) = submit.replay.score_data.clone() else {
+            return MockResponse::json(&SubmitResponse::NotSubmitted(NotSubmittedReason::InternalError, "Replay has no score".to_owned()));
+        };
+
+        let score_id = self.scores.len() as u64 + 1;
+        let placing = self.scores
+            .iter()
+            .filter(|s| s.score.beatmap_hash == score.beatmap_hash && s.score.playmode == score.playmode && s.score.score > score.score)
+            .count() + 1;
+        self.scores.push(MockScore { score_id, score, replay: Some(submit.replay), extras });
+
+        MockResponse::json(&SubmitResponse::Submitted { score_id: score_id as _, placing: placing as _, performance_rating: 0.0 })
+    }
+
+    fn get_scores(&self, query: &HashMap<String, String>) -> MockResponse {
+        let (Some(hash), Some(mode)) = (query.get("hash"), query.get("mode")) else { return MockResponse::error(400, "missing hash or mode") };
+
+        let mut scores = self.scores
+            .iter()
+            .filter(|s| &s.score.beatmap_hash == hash && &s.score.playmode == mode)
+            .map(|s| MockLeaderboardScore {
+                score_id: s.score_id,
+                score_hash: s.replay.as_ref().map(|_| s.score.hash()),
+                score: s.score.clone(),
+            })
+            .collect::<Vec<_>>();
+        scores.sort_by(|a, b| b.score.score.cmp(&a.score.score));
+
+        MockResponse::json(&scores)
+    }
+
+    fn get_replay(&self, query: &HashMap<String, String>) -> MockResponse {
+        let score_id = query.get("score_id").and_then(|id| id.parse::<u64>().ok());
+        let hash = query.get("hash");
+
+        let replay = self.scores
+            .iter()
+            .find(|s| Some(s.score_id) == score_id || Some(&s.score.hash()) == hash)
+            .and_then(|s| Some((s.replay.as_ref()?, &s.extras)));
+
+        match replay {
+            Some((replay, extras)) => MockResponse::bytes(write_tataku_replay(replay, extras)),
+            None => MockResponse::error(404, "replay not found"),
+        }
+    }
+
+    /// quaver's api, for when the settings' quaver url points here
+    fn get_quaver_replay(&self, score_id: &str) -> MockResponse {
+        let replay = score_id.parse::<u32>().ok().and_then(|id| self.quaver_replays.get(&id));
+
+        match replay {
+            Some(bytes) => MockResponse::bytes(bytes.clone()),
+            None => MockResponse::error(404, "replay not found"),
+        }
+    }
+}
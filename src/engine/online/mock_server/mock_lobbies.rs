@@ -0,0 +1,211 @@
+use crate::prelude::*;
+
+pub struct MockLobby {
+    pub lobby: Lobby,
+    pub password: String,
+}
+impl MockLobby {
+    fn info(&self) -> LobbyInfo {
+        LobbyInfo {
+            id: self.lobby.id,
+            name: self.lobby.name.clone(),
+            host: self.lobby.host().map(|p| p.username.clone()).unwrap_or_default(),
+            has_password: !self.password.is_empty(),
+            players: self.lobby.players.len() as u8,
+            playing: self.lobby.playing,
+        }
+    }
+}
+
+// lobby handling
+impl MockServerState {
+    pub(super) fn handle_lobby_packet(&mut self, user_id: u32, packet: LobbyPacket) {
+        match packet {
+            LobbyPacket::Client_LobbyList => {
+                let lobbies = self.lobbies.values().map(MockLobby::info).collect();
+                self.send_lobby(user_id, LobbyPacket::Server_LobbyList { lobbies });
+            }
+
+            LobbyPacket::Client_CreateLobby { name, password } => self.create_lobby(user_id, name, password),
+            LobbyPacket::Client_JoinLobby { lobby_id, password } => self.join_lobby(user_id, lobby_id, password),
+            LobbyPacket::Client_LeaveLobby => self.leave_lobby(user_id),
+
+            LobbyPacket::Client_LobbySetMap { beatmap } => self.edit_lobby(user_id, true, |lobby| {
+                lobby.beatmap = beatmap;
+                // everyone has to ready up again for the new map
+                lobby.players
+                    .iter_mut()
+                    .filter(|p| p.state == LobbyPlayerState::Ready)
+                    .for_each(|p| p.state = LobbyPlayerState::NotReady);
+            }),
+            LobbyPacket::Client_LobbySetMods { mods, speed } => self.edit_lobby(user_id, true, |lobby| {
+                lobby.mods = mods;
+                lobby.speed = speed;
+            }),
+            LobbyPacket::Client_LobbySetState { state } => self.edit_lobby(user_id, false, |lobby| {
+                if let Some(player) = lobby.players.iter_mut().find(|p| p.user_id == user_id) {
+                    player.state = state;
+                }
+            }),
+
+            LobbyPacket::Client_LobbyStart => self.start_match(user_id),
+            LobbyPacket::Client_LobbyScore { score, complete } => self.lobby_score(user_id, score, complete),
+
+            p => warn!("[mock] ignoring lobby packet {p:?}"),
+        }
+    }
+
+    fn send_lobby(&self, user_id: u32, packet: LobbyPacket) {
+        self.send(user_id, packet.to_bytes());
+    }
+
+    /// send `packet` to everyone in the lobby
+    fn lobby_broadcast(&self, lobby_id: u32, packet: LobbyPacket) {
+        let Some(mock) = self.lobbies.get(&lobby_id) else { return };
+        let data = packet.to_bytes();
+        for player in mock.lobby.players.iter() {
+            self.send(player.user_id, data.clone());
+        }
+    }
+
+    fn lobby_updated(&self, lobby_id: u32) {
+        let Some(lobby) = self.lobbies.get(&lobby_id).map(|l| l.lobby.clone()) else { return };
+        self.lobby_broadcast(lobby_id, LobbyPacket::Server_LobbyUpdate { lobby });
+    }
+
+    fn user_lobby(&self, user_id: u32) -> Option<u32> {
+        self.connections.get(&user_id).and_then(|c| c.lobby)
+    }
+
+    fn edit_lobby(&mut self, user_id: u32, host_only: bool, f: impl FnOnce(&mut Lobby)) {
+        let Some(lobby_id) = self.user_lobby(user_id) else { return };
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        if host_only && mock.lobby.host_id != user_id { return }
+
+        f(&mut mock.lobby);
+        self.lobby_updated(lobby_id);
+    }
+
+    fn create_lobby(&mut self, user_id: u32, name: String, password: String) {
+        let Some(username) = self.connections.get(&user_id).map(|c| c.username.clone()) else { return };
+        self.leave_lobby(user_id);
+
+        let id = self.next_lobby_id;
+        self.next_lobby_id += 1;
+
+        let lobby = Lobby {
+            id,
+            name,
+            host_id: user_id,
+            players: vec![LobbyPlayer { user_id, username, state: LobbyPlayerState::NotReady }],
+            speed: ModManager::new().speed,
+            ..Default::default()
+        };
+        self.lobbies.insert(id, MockLobby { lobby: lobby.clone(), password });
+        if let Some(connection) = self.connections.get_mut(&user_id) {
+            connection.lobby = Some(id);
+        }
+
+        self.send_lobby(user_id, LobbyPacket::Server_LobbyJoined { lobby });
+    }
+
+    fn join_lobby(&mut self, user_id: u32, lobby_id: u32, password: String) {
+        let Some(username) = self.connections.get(&user_id).map(|c| c.username.clone()) else { return };
+
+        let reason = match self.lobbies.get(&lobby_id) {
+            None => Some("Lobby doesn't exist"),
+            Some(mock) if mock.password != password => Some("Wrong password"),
+            Some(mock) if mock.lobby.playing => Some("Match in progress"),
+            Some(_) => None,
+        };
+        if let Some(reason) = reason {
+            return self.send_lobby(user_id, LobbyPacket::Server_LobbyJoinFailed { reason: reason.to_owned() });
+        }
+
+        self.leave_lobby(user_id);
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        mock.lobby.players.push(LobbyPlayer { user_id, username, state: LobbyPlayerState::NotReady });
+        let lobby = mock.lobby.clone();
+        if let Some(connection) = self.connections.get_mut(&user_id) {
+            connection.lobby = Some(lobby_id);
+        }
+
+        self.send_lobby(user_id, LobbyPacket::Server_LobbyJoined { lobby });
+        self.lobby_updated(lobby_id);
+    }
+
+    pub(super) fn leave_lobby(&mut self, user_id: u32) {
+        let Some(lobby_id) = self.connections.get_mut(&user_id).and_then(|c| c.lobby.take()) else { return };
+        self.send_lobby(user_id, LobbyPacket::Server_LobbyLeft);
+
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        mock.lobby.players.retain(|p| p.user_id != user_id);
+
+        // close the lobby if its empty, otherwise pass host to the next person
+        let Some(next_host) = mock.lobby.players.first().map(|p| p.user_id) else {
+            self.lobbies.remove(&lobby_id);
+            return;
+        };
+        if mock.lobby.host_id == user_id {
+            mock.lobby.host_id = next_host;
+        }
+
+        self.check_match_complete(lobby_id);
+        self.lobby_updated(lobby_id);
+    }
+
+    fn start_match(&mut self, user_id: u32) {
+        let Some(lobby_id) = self.user_lobby(user_id) else { return };
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        if mock.lobby.host_id != user_id || !mock.lobby.can_start() { return }
+
+        mock.lobby.playing = true;
+        mock.lobby.players
+            .iter_mut()
+            .filter(|p| p.state != LobbyPlayerState::NoMap)
+            .for_each(|p| p.state = LobbyPlayerState::Playing);
+
+        // only players who have the map start
+        let playing = mock.lobby.players
+            .iter()
+            .filter(|p| p.state == LobbyPlayerState::Playing)
+            .map(|p| p.user_id)
+            .collect::<Vec<_>>();
+        let start = LobbyPacket::Server_LobbyStart.to_bytes();
+        for user_id in playing {
+            self.send(user_id, start.clone());
+        }
+        self.lobby_updated(lobby_id);
+    }
+
+    fn lobby_score(&mut self, user_id: u32, score: Score, complete: bool) {
+        let Some(lobby_id) = self.user_lobby(user_id) else { return };
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        if !mock.lobby.playing { return }
+        let Some(player) = mock.lobby.players.iter_mut().find(|p| p.user_id == user_id && p.state == LobbyPlayerState::Playing) else { return };
+
+        if complete {
+            player.state = LobbyPlayerState::Done;
+        }
+
+        self.lobby_broadcast(lobby_id, LobbyPacket::Server_LobbyScore { user_id, score });
+        if complete {
+            self.check_match_complete(lobby_id);
+            self.lobby_updated(lobby_id);
+        }
+    }
+
+    /// end the match if nobody is still playing
+    fn check_match_complete(&mut self, lobby_id: u32) {
+        let Some(mock) = self.lobbies.get_mut(&lobby_id) else { return };
+        if !mock.lobby.playing || mock.lobby.players.iter().any(|p| p.state == LobbyPlayerState::Playing) { return }
+
+        mock.lobby.playing = false;
+        mock.lobby.players
+            .iter_mut()
+            .filter(|p| p.state == LobbyPlayerState::Done)
+            .for_each(|p| p.state = LobbyPlayerState::NotReady);
+
+        self.lobby_broadcast(lobby_id, LobbyPacket::Server_LobbyComplete);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::prelude::*;
+
+/// what the [`MockServer`] does, so tests (or people) can set up a specific situation.
+/// can be loaded from json for `--mock-server`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockScenario {
+    /// accounts that can log in. if empty, anyone can log in with any username and password
+    pub users: Vec<MockUser>,
+    /// users who are always online but never actually connect
+    pub bots: Vec<MockBot>,
+    /// run for every user after they log in. packets from steps only go to that user
+    pub steps: Vec<MockStep>,
+
+    /// scores already on the leaderboards. these have no replays
+    pub scores: Vec<Score>,
+    /// reject every submitted score with this reason
+    pub reject_scores: Option<String>,
+}
+impl MockScenario {
+    pub fn load(path: impl AsRef<Path>) -> TatakuResult<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// check a username and password against [`Self::users`]
+    pub fn check_login(&self, username: &String, password: &String) -> Result<(), LoginStatus> {
+        if self.users.is_empty() { return Ok(()) }
+
+        match self.users.iter().find(|u| &u.username == username) {
+            None => Err(LoginStatus::NoUser),
+            Some(user) if &user.password != password => Err(LoginStatus::BadPassword),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MockUser {
+    pub username: String,
+    /// the password as the client sends it, ie already hashed
+    pub password: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MockBot {
+    /// should be below [`MOCK_FIRST_USER_ID`]
+    pub user_id: u32,
+    pub username: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MockStep {
+    /// wait before doing the next step
+    Wait { ms: u64 },
+    Notification { message: String },
+    /// a chat message. `channel` starts with # for channels, otherwise its a dm
+    Message { sender_id: u32, channel: String, message: String },
+    /// pretend someone came online
+    BotJoined { user_id: u32, username: String },
+    /// pretend someone went offline
+    BotLeft { user_id: u32 },
+}
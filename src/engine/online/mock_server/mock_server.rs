@@ -0,0 +1,366 @@
+use tokio::{ sync::{ Mutex, mpsc }, net::{ TcpListener, TcpStream } };
+use futures_util::{ SinkExt, StreamExt };
+use tokio_tungstenite::{ accept_async, tungstenite::protocol::Message };
+
+use crate::prelude::*;
+use super::mock_http::serve_mock_http;
+
+use PacketId::*;
+
+/// user ids for users who connect start here, so bots can use anything below it
+pub const MOCK_FIRST_USER_ID:u32 = 1000;
+
+/// a local stand-in for the tataku server and score server.
+/// speaks the same websocket protocol (plus lobby packets) and http endpoints the client uses,
+/// so online stuff can be tested without the network
+pub struct MockServer {
+    /// websocket url, use for `server_url`
+    pub server_url: String,
+    /// http url, use for `score_url`
+    pub score_url: String,
+    pub state: Arc<Mutex<MockServerState>>,
+
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+impl MockServer {
+    /// start the server on random local ports
+    pub async fn start(scenario: MockScenario) -> TatakuResult<Self> {
+        Self::start_on("127.0.0.1:0", "127.0.0.1:0", scenario).await
+    }
+
+    pub async fn start_on(ws_addr: &str, http_addr: &str, scenario: MockScenario) -> TatakuResult<Self> {
+        let ws_listener = TcpListener::bind(ws_addr).await?;
+        let http_listener = TcpListener::bind(http_addr).await?;
+        let server_url = format!("ws://{}", ws_listener.local_addr()?);
+        let score_url = format!("http://{}", http_listener.local_addr()?);
+
+        let state = Arc::new(Mutex::new(MockServerState::new(scenario)));
+        let tasks = vec![
+            tokio::spawn(Self::accept_loop(ws_listener, state.clone())),
+            tokio::spawn(serve_mock_http(http_listener, state.clone())),
+        ];
+
+        Ok(Self {
+            server_url,
+            score_url,
+            state,
+            tasks,
+        })
+    }
+
+    async fn accept_loop(listener: TcpListener, state: Arc<Mutex<MockServerState>>) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => { tokio::spawn(Self::handle_connection(stream, state.clone())); }
+                Err(e) => warn!("[mock] error accepting connection: {e}"),
+            }
+        }
+    }
+
+    async fn handle_connection(stream: TcpStream, state: Arc<Mutex<MockServerState>>) {
+        let ws_stream = match accept_async(stream).await {
+            Ok(ws_stream) => ws_stream,
+            Err(e) => return warn!("[mock] websocket handshake failed: {e}"),
+        };
+        let (mut writer, mut reader) = ws_stream.split();
+
+        // everything sent to this connection goes through here, so the state doesnt have to await the socket
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(async move {
+            while let Some(data) = receiver.recv().await {
+                if writer.send(Message::Binary(data)).await.is_err() { break }
+            }
+            let _ = writer.close().await;
+        });
+
+        // the sender moves into the state once the user logs in, so removing the user closes the connection
+        let mut sender = Some(sender);
+        let mut user_id = None;
+        while let Some(message) = reader.next().await {
+            match message {
+                Ok(Message::Binary(data)) => {
+                    if let Err(e) = Self::handle_message(&state, &mut user_id, &mut sender, data).await {
+                        warn!("[mock] error with packet: {e}");
+                    }
+                }
+                Ok(Message::Close(_)) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+
+        if let Some(user_id) = user_id {
+            state.lock().await.disconnect(user_id);
+        }
+    }
+
+    async fn handle_message(state: &Arc<Mutex<MockServerState>>, user_id: &mut Option<u32>, sender: &mut Option<mpsc::UnboundedSender<Vec<u8>>>, data: Vec<u8>) -> TatakuResult<()> {
+        if let Some(packet) = LobbyPacket::from_bytes(&data) {
+            let packet = packet?;
+            if let Some(user_id) = *user_id {
+                state.lock().await.handle_lobby_packet(user_id, packet);
+            }
+            return Ok(());
+        }
+
+        let mut reader = SerializationReader::new(data);
+        while reader.can_read() {
+            let packet:PacketId = reader.read()?;
+
+            // nothing but login is allowed until the user is logged in
+            let Some(id) = *user_id else {
+                if let (Client_UserLogin { username, password, game, .. }, Some(s)) = (packet, sender.take()) {
+                    match Self::login(state, username, password, game, s).await {
+                        Ok(id) => *user_id = Some(id),
+                        Err(s) => *sender = Some(s),
+                    }
+                }
+                continue;
+            };
+
+            let mut state = state.lock().await;
+            match packet {
+                Client_LogOut => {
+                    state.disconnect(id);
+                    *user_id = None;
+                }
+                packet => state.handle_packet(id, packet),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// gives the sender back if the login failed, so they can try again
+    async fn login(state: &Arc<Mutex<MockServerState>>, username: String, password: String, game: String, sender: mpsc::UnboundedSender<Vec<u8>>) -> Result<u32, mpsc::UnboundedSender<Vec<u8>>> {
+        let mut lock = state.lock().await;
+        if let Err(status) = lock.scenario.check_login(&username, &password) {
+            let _ = sender.send(create_packet!(Server_LoginResponse { status, user_id: 0 }));
+            return Err(sender);
+        }
+
+        let user_id = lock.add_connection(username, game, sender);
+        let steps = lock.scenario.steps.clone();
+        drop(lock);
+
+        tokio::spawn(Self::run_steps(state.clone(), user_id, steps));
+        Ok(user_id)
+    }
+
+    async fn run_steps(state: Arc<Mutex<MockServerState>>, user_id: u32, steps: Vec<MockStep>) {
+        for step in steps {
+            let packet = match step {
+                MockStep::Wait { ms } => {
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                    continue;
+                }
+                MockStep::Notification { message } => create_packet!(Server_Notification { message, severity: Severity::Info }),
+                MockStep::Message { sender_id, channel, message } => create_packet!(Server_SendMessage { sender_id, message, channel }),
+                MockStep::BotJoined { user_id, username } => create_packet!(Server_UserJoined { user_id, username, game: "Bot".to_owned() }),
+                MockStep::BotLeft { user_id } => create_packet!(Server_UserLeft { user_id }),
+            };
+
+            let state = state.lock().await;
+            if !state.connections.contains_key(&user_id) { return }
+            state.send(user_id, packet);
+        }
+    }
+}
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.tasks.iter().for_each(|t| t.abort());
+    }
+}
+
+
+pub struct MockConnection {
+    pub user_id: u32,
+    pub username: String,
+    pub game: String,
+    /// who this user is spectating
+    pub spectating: Option<u32>,
+    /// which lobby this user is in
+    pub lobby: Option<u32>,
+
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+/// everything the mock server knows, and a log of what clients did so tests can check it
+pub struct MockServerState {
+    pub scenario: MockScenario,
+    pub connections: HashMap<u32, MockConnection>,
+    next_user_id: u32,
+
+    // ===== logs =====
+    /// every chat message, as (sender_id, channel, message)
+    pub chat_log: Vec<(u32, String, String)>,
+    /// how many spectator frames each user has sent
+    pub spectator_frames: HashMap<u32, usize>,
+    /// last status text for each user
+    pub statuses: HashMap<u32, String>,
+    /// (artist, title) from lastfm now playing requests
+    pub now_playing: Vec<(String, String)>,
+
+    /// leaderboard scores, including the scenario's scores
+    pub scores: Vec<MockScore>,
+    /// .qr files by quaver score id, served like quaver's replay downloads
+    pub quaver_replays: HashMap<u32, Vec<u8>>,
+    pub(super) lobbies: HashMap<u32, MockLobby>,
+    pub(super) next_lobby_id: u32,
+}
+impl MockServerState {
+    pub fn new(scenario: MockScenario) -> Self {
+        let scores = scenario.scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| MockScore { score_id: i as u64 + 1, score: score.clone(), replay: None, extras: ReplayExtras::default() })
+            .collect();
+
+        Self {
+            scenario,
+            connections: HashMap::new(),
+            next_user_id: MOCK_FIRST_USER_ID,
+
+            chat_log: Vec::new(),
+            spectator_frames: HashMap::new(),
+            statuses: HashMap::new(),
+            now_playing: Vec::new(),
+
+            scores,
+            quaver_replays: HashMap::new(),
+            lobbies: HashMap::new(),
+            next_lobby_id: 1,
+        }
+    }
+
+    pub fn find_user(&self, username: &String) -> Option<&MockConnection> {
+        self.connections.values().find(|c| &c.username == username)
+    }
+
+    pub(super) fn send(&self, user_id: u32, data: Vec<u8>) {
+        if let Some(connection) = self.connections.get(&user_id) {
+            let _ = connection.sender.send(data);
+        }
+    }
+    fn broadcast(&self, data: Vec<u8>, except: Option<u32>) {
+        for &user_id in self.connections.keys().filter(|&&id| Some(id) != except) {
+            self.send(user_id, data.clone());
+        }
+    }
+
+    fn add_connection(&mut self, username: String, game: String, sender: mpsc::UnboundedSender<Vec<u8>>) -> u32 {
+        let user_id = self.next_user_id;
+        self.next_user_id += 1;
+
+        let _ = sender.send(create_packet!(Server_LoginResponse { status: LoginStatus::Ok, user_id }));
+        let _ = sender.send(LobbyPacket::Server_LobbiesSupported.to_bytes());
+
+        // tell the new user who's online
+        for bot in self.scenario.bots.iter() {
+            let _ = sender.send(create_packet!(Server_UserJoined { user_id: bot.user_id, username: bot.username.clone(), game: "Bot".to_owned() }));
+        }
+        for connection in self.connections.values() {
+            let _ = sender.send(create_packet!(Server_UserJoined { user_id: connection.user_id, username: connection.username.clone(), game: connection.game.clone() }));
+        }
+
+        // and tell everyone else about the new user
+        self.broadcast(create_packet!(Server_UserJoined { user_id, username: username.clone(), game: game.clone() }), None);
+
+        self.connections.insert(user_id, MockConnection {
+            user_id,
+            username,
+            game,
+            spectating: None,
+            lobby: None,
+            sender,
+        });
+        user_id
+    }
+
+    /// remove a user, closing their connection. also for kicking users to test reconnecting
+    pub fn disconnect(&mut self, user_id: u32) {
+        self.leave_spectator(user_id);
+        self.leave_lobby(user_id);
+
+        // anyone spectating this user cant anymore
+        self.connections
+            .values_mut()
+            .filter(|c| c.spectating == Some(user_id))
+            .for_each(|c| c.spectating = None);
+
+        if self.connections.remove(&user_id).is_some() {
+            self.broadcast(create_packet!(Server_UserLeft { user_id }), None);
+        }
+    }
+
+    fn handle_packet(&mut self, user_id: u32, packet: PacketId) {
+        match packet {
+            Ping => self.send(user_id, create_packet!(Pong)),
+            Pong => {}
+
+            Client_StatusUpdate { action, action_text, mode } => {
+                self.statuses.insert(user_id, action_text.clone());
+                self.broadcast(create_packet!(Server_UserStatusUpdate { user_id, action, action_text, mode }), Some(user_id));
+            }
+
+            Client_SendMessage { channel, message } => self.send_message(user_id, channel, message),
+
+            Client_Spectate { host_id } => self.spectate(user_id, host_id),
+            Client_LeaveSpectator => self.leave_spectator(user_id),
+            Client_SpectatorFrames { frames } => {
+                *self.spectator_frames.entry(user_id).or_default() += frames.len();
+
+                let data = create_packet!(Server_SpectatorFrames { frames });
+                for spectator in self.connections.values().filter(|c| c.spectating == Some(user_id)) {
+                    let _ = spectator.sender.send(data.clone());
+                }
+            }
+
+            p => debug!("[mock] ignoring packet {p:?}"),
+        }
+    }
+
+    fn send_message(&mut self, sender_id: u32, channel: String, message: String) {
+        self.chat_log.push((sender_id, channel.clone(), message.clone()));
+
+        // like the real server, channel messages go to everyone. clients only show the channels they're in
+        if channel.starts_with('#') {
+            return self.broadcast(create_packet!(Server_SendMessage { sender_id, message, channel }), None);
+        }
+
+        // dm, the channel is the other user's username
+        let Some(sender) = self.connections.get(&sender_id).map(|c| c.username.clone()) else { return };
+        if let Some(target) = self.find_user(&channel).map(|c| c.user_id) {
+            self.send(target, create_packet!(Server_SendMessage { sender_id, message: message.clone(), channel: sender }));
+        }
+        self.send(sender_id, create_packet!(Server_SendMessage { sender_id, message, channel }));
+    }
+
+    fn spectate(&mut self, user_id: u32, host_id: u32) {
+        let result = if host_id == user_id {
+            SpectateResult::Error_SpectatingYourself
+        } else if self.scenario.bots.iter().any(|b| b.user_id == host_id) {
+            SpectateResult::Error_SpectatingBot
+        } else if !self.connections.contains_key(&host_id) {
+            SpectateResult::Error_HostOffline
+        } else {
+            SpectateResult::Ok
+        };
+
+        let ok = matches!(result, SpectateResult::Ok);
+        self.send(user_id, create_packet!(Server_SpectateResult { result, host_id }));
+        if !ok { return }
+
+        self.leave_spectator(user_id);
+        let Some(connection) = self.connections.get_mut(&user_id) else { return };
+        connection.spectating = Some(host_id);
+        let username = connection.username.clone();
+
+        self.send(host_id, create_packet!(Server_SpectatorJoined { user_id, username }));
+        self.send(host_id, create_packet!(Server_SpectatorPlayingRequest { user_id }));
+    }
+
+    fn leave_spectator(&mut self, user_id: u32) {
+        let Some(host_id) = self.connections.get_mut(&user_id).and_then(|c| c.spectating.take()) else { return };
+        self.send(host_id, create_packet!(Server_SpectatorLeft { user_id }));
+    }
+}
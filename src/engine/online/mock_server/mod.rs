@@ -0,0 +1,12 @@
+mod mock_http;
+mod mock_server;
+mod mock_lobbies;
+mod mock_scenario;
+
+#[cfg(test)]
+mod tests;
+
+pub use mock_http::*;
+pub use mock_server::*;
+pub use mock_lobbies::*;
+pub use mock_scenario::*;
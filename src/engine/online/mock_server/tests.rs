@@ -0,0 +1,681 @@
+use tokio::{ sync::mpsc, net::TcpStream };
+use futures_util::{ SinkExt, StreamExt, stream::SplitSink };
+use tokio_tungstenite::{ MaybeTlsStream, WebSocketStream, connect_async, tungstenite::protocol::Message };
+
+use crate::prelude::*;
+
+use PacketId::*;
+
+/// how long to wait for something to happen before failing
+const TIMEOUT:Duration = Duration::from_secs(5);
+const CONNECTION_COUNT: usize = 50;
+
+lazy_static::lazy_static! {
+    /// these tests use the global online manager and settings, so only one can run at a time
+    static ref TEST_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// start a mock server, and point the settings and online manager at it
+async fn setup(scenario: MockScenario) -> MockServer {
+    let server = MockServer::start(scenario).await.unwrap();
+    GlobalValueManager::update(Arc::new(Settings {
+        server_url: server.server_url.clone(),
+        score_url: server.score_url.clone(),
+        quaver_url: server.score_url.clone(),
+        username: "user".to_owned(),
+        password: "password".to_owned(),
+        ..Default::default()
+    }));
+    GlobalValueManager::update(Arc::new(ModManager::new()));
+
+    *ONLINE_MANAGER.write().await = OnlineManager::new();
+    server
+}
+
+/// connect the online manager and wait for it to log in
+async fn login() -> u32 {
+    tokio::spawn(OnlineManager::start());
+    wait_for_online(|m| m.user_id != 0).await;
+    ONLINE_MANAGER.read().await.user_id
+}
+
+async fn wait_for_online(check: impl Fn(&OnlineManager) -> bool) {
+    tokio::time::timeout(TIMEOUT, async {
+        while !check(&*ONLINE_MANAGER.read().await) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("timed out waiting for the online manager");
+}
+
+async fn wait_for_server(server: &MockServer, check: impl Fn(&MockServerState) -> bool) {
+    tokio::time::timeout(TIMEOUT, async {
+        while !check(&*server.state.lock().await) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("timed out waiting for the mock server");
+}
+
+fn has_message(manager: &OnlineManager, channel: &ChatChannel, text: &str) -> bool {
+    manager.chat_messages
+        .get(channel)
+        .filter(|messages| messages.iter().any(|m| m.text == text))
+        .is_some()
+}
+
+
+/// a second user, for things that need someone else online
+struct MockClient {
+    user_id: u32,
+    writer: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+    receiver: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+impl MockClient {
+    async fn connect(server: &MockServer, username: &str, password: &str) -> (LoginStatus, Self) {
+        let (ws_stream, _) = connect_async(server.server_url.as_str()).await.unwrap();
+        let (writer, mut reader) = ws_stream.split();
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = reader.next().await {
+                let Message::Binary(data) = message else { continue };
+                if sender.send(data).is_err() { break }
+            }
+        });
+
+        let mut client = Self { user_id: 0, writer, receiver };
+        client.send(create_packet!(Client_UserLogin {
+            protocol_version: 1,
+            game: "Mock".to_owned(),
+            username: username.to_owned(),
+            password: password.to_owned()
+        })).await;
+
+        let (status, user_id) = client.wait_packet(|p| match p {
+            Server_LoginResponse { status, user_id } => Some((status, user_id)),
+            _ => None,
+        }).await;
+        client.user_id = user_id;
+
+        (status, client)
+    }
+
+    async fn login(server: &MockServer, username: &str) -> Self {
+        let (status, client) = Self::connect(server, username, "password").await;
+        assert!(matches!(status, LoginStatus::Ok), "mock client couldnt log in");
+        client
+    }
+
+    async fn send(&mut self, data: Vec<u8>) {
+        self.writer.send(Message::Binary(data)).await.unwrap();
+    }
+
+    async fn recv(&mut self) -> Vec<u8> {
+        tokio::time::timeout(TIMEOUT, self.receiver.recv())
+            .await
+            .expect("timed out waiting for a packet")
+            .expect("connection closed")
+    }
+
+    /// wait for a packet `f` returns something for, skipping everything else
+    async fn wait_packet<T>(&mut self, mut f: impl FnMut(PacketId) -> Option<T>) -> T {
+        loop {
+            let data = self.recv().await;
+            if LobbyPacket::from_bytes(&data).is_some() { continue }
+
+            let mut reader = SerializationReader::new(data);
+            while reader.can_read() {
+                if let Some(t) = f(reader.read().unwrap()) { return t }
+            }
+        }
+    }
+
+    async fn wait_lobby_packet<T>(&mut self, mut f: impl FnMut(LobbyPacket) -> Option<T>) -> T {
+        loop {
+            let data = self.recv().await;
+            let Some(packet) = LobbyPacket::from_bytes(&data) else { continue };
+            if let Some(t) = f(packet.unwrap()) { return t }
+        }
+    }
+}
+
+
+#[tokio::test]
+async fn bad_login() {
+    let scenario = MockScenario {
+        users: vec![MockUser { username: "user".to_owned(), password: "password".to_owned() }],
+        ..Default::default()
+    };
+    let server = MockServer::start(scenario).await.unwrap();
+
+    let (status, _) = MockClient::connect(&server, "user", "wrong").await;
+    assert!(matches!(status, LoginStatus::BadPassword));
+    let (status, _) = MockClient::connect(&server, "nobody", "password").await;
+    assert!(matches!(status, LoginStatus::NoUser));
+    let (status, _) = MockClient::connect(&server, "user", "password").await;
+    assert!(matches!(status, LoginStatus::Ok));
+}
+
+#[tokio::test]
+async fn presence_and_chat() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario {
+        bots: vec![MockBot { user_id: 1, username: "bot".to_owned() }],
+        steps: vec![
+            MockStep::Wait { ms: 50 },
+            MockStep::Message { sender_id: 1, channel: "#osu".to_owned(), message: "not in this channel".to_owned() },
+            MockStep::Message { sender_id: 1, channel: "#general".to_owned(), message: "hello from bot".to_owned() },
+        ],
+        ..Default::default()
+    }).await;
+    let user_id = login().await;
+    let general = ChatChannel::Channel { name: "general".to_owned() };
+
+    // scenario stuff
+    wait_for_online(|m| m.users.contains_key(&1)).await;
+    wait_for_online(|m| has_message(m, &general, "hello from bot")).await;
+    // sent to everyone, but we're not in #osu
+    assert!(!ONLINE_MANAGER.read().await.chat_messages.contains_key(&ChatChannel::Channel { name: "osu".to_owned() }));
+
+    // someone else comes online, and leaves
+    let mut other = MockClient::login(&server, "other").await;
+    let other_id = other.user_id;
+    wait_for_online(|m| m.users.contains_key(&other_id)).await;
+
+    other.send(create_packet!(Client_SendMessage { channel: "user".to_owned(), message: "hello from other".to_owned() })).await;
+    wait_for_online(|m| has_message(m, &ChatChannel::User { username: "other".to_owned() }, "hello from other")).await;
+
+    other.send(create_packet!(Client_LogOut)).await;
+    wait_for_online(|m| !m.users.contains_key(&other_id)).await;
+
+    // our messages get sent back to us
+    OnlineManager::send_message(general.clone(), "hello from user".to_owned());
+    wait_for_online(|m| has_message(m, &general, "hello from user")).await;
+    assert!(server.state.lock().await.chat_log.contains(&(user_id, "#general".to_owned(), "hello from user".to_owned())));
+}
+
+#[tokio::test]
+async fn spectating() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario {
+        bots: vec![MockBot { user_id: 1, username: "bot".to_owned() }],
+        ..Default::default()
+    }).await;
+    let user_id = login().await;
+
+    // cant spectate bots
+    let mut spectator = MockClient::login(&server, "spectator").await;
+    spectator.send(create_packet!(Client_Spectate { host_id: 1 })).await;
+    let result = spectator.wait_packet(|p| match p { Server_SpectateResult { result, .. } => Some(result), _ => None }).await;
+    assert!(matches!(result, SpectateResult::Error_SpectatingBot));
+
+    // someone spectates us
+    spectator.send(create_packet!(Client_Spectate { host_id: user_id })).await;
+    let result = spectator.wait_packet(|p| match p { Server_SpectateResult { result, .. } => Some(result), _ => None }).await;
+    assert!(matches!(result, SpectateResult::Ok));
+
+    let spectator_id = spectator.user_id;
+    wait_for_online(|m| m.spectator_list.iter().any(|(id, _)| *id == spectator_id)).await;
+    wait_for_online(|m| m.spectate_info_pending.contains(&spectator_id)).await;
+
+    OnlineManager::send_spec_frames(vec![(0.0, SpectatorFrameData::Pause), (10.0, SpectatorFrameData::UnPause)], true);
+    let count = spectator.wait_packet(|p| match p { Server_SpectatorFrames { frames } => Some(frames.len()), _ => None }).await;
+    assert_eq!(count, 2);
+
+    spectator.send(create_packet!(Client_LeaveSpectator)).await;
+    wait_for_online(|m| m.spectator_list.is_empty()).await;
+
+    // we spectate someone
+    let mut host = MockClient::login(&server, "host").await;
+    OnlineManager::start_spectating(host.user_id);
+    host.wait_packet(|p| matches!(p, Server_SpectatorJoined { .. }).then_some(())).await;
+
+    host.send(create_packet!(Client_SpectatorFrames { frames: vec![(0.0, SpectatorFrameData::Pause)] })).await;
+    wait_for_online(|m| m.buffered_spectator_frames.len() == 1).await;
+    assert_eq!(server.state.lock().await.spectator_frames.get(&host.user_id), Some(&1));
+
+    // the host's connection drops, we spectate them again once they're back
+    let host_id = host.user_id;
+    server.state.lock().await.disconnect(host_id);
+    wait_for_online(|m| !m.users.contains_key(&host_id)).await;
+
+    let mut host = MockClient::login(&server, "host").await;
+    host.wait_packet(|p| match p {
+        Server_SpectatorJoined { user_id: id, .. } if id == user_id => Some(()),
+        _ => None,
+    }).await;
+}
+
+#[tokio::test]
+async fn score_submit_and_leaderboard() {
+    let _lock = TEST_LOCK.lock().await;
+    let mut existing = Score::new("map_hash".to_owned(), "someone".to_owned(), "osu".to_owned());
+    existing.score = 500;
+    let server = setup(MockScenario { scores: vec![existing], ..Default::default() }).await;
+
+    let mut score = Score::new("map_hash".to_owned(), "user".to_owned(), "osu".to_owned());
+    score.score = 1000;
+    let mut replay = Replay::new();
+    replay.score_data = Some(score.clone());
+
+    let submit = ScoreSubmit {
+        username: "user".to_owned(),
+        password: "password".to_owned(),
+        game: "tataku".to_owned(),
+        replay,
+        map_info: ScoreMapInfo {
+            game: MapGame::Osu,
+            map_hash: score.beatmap_hash.clone(),
+            playmode: score.playmode.clone(),
+        }
+    };
+    let response = reqwest::Client::new()
+        .post(format!("{}/score_submit", server.score_url))
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&submit).unwrap())
+        .send().await.unwrap()
+        .text().await.unwrap();
+    match serde_json::from_str::<SubmitResponse>(&response).unwrap() {
+        SubmitResponse::Submitted { placing, .. } => assert_eq!(placing, 1),
+        _ => panic!("score wasnt submitted"),
+    }
+
+    // leaderboard through the score helper
+    let mut helper = ScoreHelper::new();
+    helper.current_method = ScoreRetreivalMethod::Global;
+    let loader = helper.get_scores(&score.beatmap_hash, &score.playmode).await;
+    tokio::time::timeout(TIMEOUT, async {
+        while !loader.read().await.done {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("timed out waiting for scores");
+
+    let loader = loader.read().await;
+    assert_eq!(loader.scores.len(), 2);
+    assert_eq!(loader.scores[0].score.score, 1000);
+    assert!(matches!(loader.scores[1].replay_location, ReplayLocation::OnlineNotExist));
+
+    // and the replay
+    let ReplayLocation::Online(downloader) = &loader.scores[0].replay_location else { panic!("submitted score has no replay") };
+    let (downloaded, extras) = downloader.get_replay().await.unwrap();
+    assert_eq!(downloaded.score_data.map(|s| s.score), Some(1000));
+    // submitted without any extras
+    assert_eq!(extras, ReplayExtras::default());
+}
+
+#[tokio::test]
+async fn rejected_score() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario { reject_scores: Some("nope".to_owned()), ..Default::default() }).await;
+
+    let mut replay = Replay::new();
+    replay.score_data = Some(Score::new("map_hash".to_owned(), "user".to_owned(), "osu".to_owned()));
+    let submit = ScoreSubmit {
+        username: "user".to_owned(),
+        password: "password".to_owned(),
+        game: "tataku".to_owned(),
+        replay,
+        map_info: ScoreMapInfo { game: MapGame::Osu, map_hash: "map_hash".to_owned(), playmode: "osu".to_owned() }
+    };
+    let response = reqwest::Client::new()
+        .post(format!("{}/score_submit", server.score_url))
+        .body(serde_json::to_string(&submit).unwrap())
+        .send().await.unwrap()
+        .text().await.unwrap();
+
+    match serde_json::from_str::<SubmitResponse>(&response).unwrap() {
+        SubmitResponse::NotSubmitted(_, reason) => assert_eq!(reason, "nope"),
+        _ => panic!("score was submitted"),
+    }
+    assert!(server.state.lock().await.scores.is_empty());
+}
+
+/// add a map to the beatmap manager so scores on it can be submitted.
+/// theres no file for it, so verifying the replay when its queued fails, which only gets logged
+async fn add_map(map_hash: &str) {
+    let map = BeatmapMeta::new("missing.osu".to_owned(), map_hash.to_owned(), BeatmapType::Osu);
+    BEATMAP_MANAGER.write().await.beatmaps_by_hash.insert(map_hash.to_owned(), Arc::new(map));
+}
+
+fn submit_replay(map_hash: &str, score: u64) -> Replay {
+    let mut data = Score::new(map_hash.to_owned(), "user".to_owned(), "osu".to_owned());
+    data.score = score;
+    let mut replay = Replay::new();
+    replay.score_data = Some(data);
+    replay
+}
+
+fn credentials() -> (String, String) {
+    ("user".to_owned(), "password".to_owned())
+}
+
+async fn clear_submissions() {
+    Database::get().await.execute("DELETE FROM score_submissions", []).unwrap();
+}
+
+async fn wait_for_submissions(check: impl Fn((usize, usize, usize)) -> bool) {
+    tokio::time::timeout(TIMEOUT, async {
+        while !check(Database::get_submission_counts().await) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("timed out waiting for the submit queue");
+}
+
+#[tokio::test]
+async fn score_submit_queue_and_retry() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    clear_submissions().await;
+    add_map("map_hash").await;
+
+    // only queued once
+    let replay = submit_replay("map_hash", 1000);
+    let score_hash = replay.score_data.as_ref().unwrap().hash();
+    let extras = ReplayExtras {
+        judgment_preset: JudgmentPreset::EtternaJ4.to_id(&[], &"osu".to_owned()),
+        scoring_system: ScoringSystemKind::ScoreV2,
+    };
+    Database::queue_score_submission(&replay, &extras, Some(true)).await;
+    Database::queue_score_submission(&replay, &extras, Some(true)).await;
+    let pending = Database::get_pending_submissions().await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].status, SubmissionStatus::Pending);
+    assert_eq!(pending[0].extras, extras);
+    assert_eq!(pending[0].verified, Some(true));
+
+    // the score server is down, so it stays in the queue
+    let settings = get_settings!().clone();
+    GlobalValueManager::update(Arc::new(Settings { score_url: "http://127.0.0.1:1".to_owned(), ..settings.clone() }));
+    let attempt = ScoreSubmitHelper::try_submit(&pending[0].replay, &pending[0].extras, credentials()).await;
+    assert!(matches!(attempt, SubmitAttempt::Failed(_)));
+    assert!(attempt.status().is_none());
+
+    // its back, so trying again works
+    GlobalValueManager::update(Arc::new(settings));
+    let status = ScoreSubmitHelper::try_submit(&pending[0].replay, &pending[0].extras, credentials()).await.status();
+    assert_eq!(status, Some(SubmissionStatus::Submitted));
+    Database::set_submission_status(&score_hash, &SubmissionStatus::Submitted).await;
+
+    assert!(Database::get_pending_submissions().await.is_empty());
+    assert_eq!(Database::get_submission_statuses(&"map_hash".to_owned()).await.get(&score_hash), Some(&SubmissionStatus::Submitted));
+    assert_eq!(server.state.lock().await.scores.len(), 1);
+
+    // the judgment preset and scoring system made it to the server, and come back with the replay
+    assert_eq!(server.state.lock().await.scores[0].extras, extras);
+    let (downloaded, downloaded_extras) = TatakuReplayDownloader::new(1, None).get_replay().await.unwrap();
+    assert_eq!(downloaded.score_data.map(|s| s.hash()), Some(score_hash));
+    assert_eq!(downloaded_extras, extras);
+}
+
+#[tokio::test]
+async fn score_submit_statuses_and_requeue() {
+    let _lock = TEST_LOCK.lock().await;
+    let _server = setup(MockScenario { reject_scores: Some("nope".to_owned()), ..Default::default() }).await;
+    clear_submissions().await;
+    add_map("map_hash").await;
+
+    let rejected = submit_replay("map_hash", 100);
+    let failed = submit_replay("missing_map", 200);
+    Database::queue_score_submission(&rejected, &ReplayExtras::default(), None).await;
+    Database::queue_score_submission(&failed, &ReplayExtras::default(), None).await;
+
+    for submission in Database::get_pending_submissions().await {
+        let status = ScoreSubmitHelper::try_submit(&submission.replay, &submission.extras, credentials()).await.status().expect("no response");
+        Database::set_submission_status(&submission.score_hash, &status).await;
+    }
+
+    // the server said no to one, and the other never got there
+    let rejected_hash = rejected.score_data.as_ref().unwrap().hash();
+    let failed_hash = failed.score_data.as_ref().unwrap().hash();
+    assert_eq!(Database::get_submission_statuses(&"map_hash".to_owned()).await.get(&rejected_hash), Some(&SubmissionStatus::Rejected("nope".to_owned())));
+    assert_eq!(Database::get_submission_statuses(&"missing_map".to_owned()).await.get(&failed_hash), Some(&SubmissionStatus::Failed("Map not found locally".to_owned())));
+    assert_eq!(Database::get_submission_counts().await, (0, 1, 1));
+
+    // resubmit all puts both back
+    ScoreSubmitHelper::resubmit_all().await;
+    assert_eq!(Database::get_submission_counts().await, (2, 0, 0));
+}
+
+#[tokio::test]
+async fn score_submit_paused_without_credentials() {
+    let _lock = TEST_LOCK.lock().await;
+    let _server = setup(MockScenario::default()).await;
+    clear_submissions().await;
+    add_map("map_hash").await;
+
+    let settings = get_settings!().clone();
+    GlobalValueManager::update(Arc::new(Settings { username: String::new(), password: String::new(), ..settings.clone() }));
+    tokio::spawn(ScoreSubmitHelper::run_queue());
+    ScoreSubmitHelper::new(submit_replay("map_hash", 1000), ReplayExtras::default()).submit();
+
+    // nothing is tried until theres an account
+    tokio::time::timeout(TIMEOUT, async {
+        while !ScoreSubmitHelper::paused() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }).await.expect("queue didnt pause");
+    wait_for_submissions(|counts| counts == (1, 0, 0)).await;
+    // the replay was checked once when it was queued. the map has no file, so it couldnt be simulated
+    assert_eq!(Database::get_pending_submissions().await[0].verified, None);
+
+    // logged in, so it goes through
+    GlobalValueManager::update(Arc::new(settings));
+    login().await;
+    wait_for_submissions(|counts| counts == (0, 0, 0)).await;
+    assert!(!ScoreSubmitHelper::paused());
+}
+
+#[tokio::test]
+async fn quaver_replay_download() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    server.state.lock().await.quaver_replays.insert(5, include_bytes!("../../../tataku/beatmaps/quaver/test_data/replay.qr").to_vec());
+
+    let score = Score::new("map_hash".to_owned(), "tester".to_owned(), "mania".to_owned());
+    let (replay, extras) = QuaverReplayDownloader::new(score.clone(), 5).get_replay().await.unwrap();
+    assert_eq!(replay.frames.len(), 6);
+    assert_eq!(replay.score_data.map(|s| s.score), Some(900000));
+    // the replay has strict on, which is a preset for us
+    assert_eq!(JudgmentPreset::from_id(extras.judgment_preset.as_ref(), &"mania".to_owned()).0, JudgmentPreset::QuaverStrict);
+
+    assert!(QuaverReplayDownloader::new(score, 6).get_replay().await.is_err());
+}
+
+#[tokio::test]
+async fn lastfm() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+
+    LastFmIntegration::update("title".to_owned(), "artist".to_owned()).await;
+    assert_eq!(server.state.lock().await.now_playing, vec![("artist".to_owned(), "title".to_owned())]);
+}
+
+#[tokio::test]
+async fn lobby_match() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    login().await;
+    wait_for_online(|m| m.lobbies_supported).await;
+
+    OnlineManager::create_lobby("lobby".to_owned(), String::new());
+    wait_for_online(|m| m.lobby.is_some()).await;
+    let lobby_id = ONLINE_MANAGER.read().await.lobby.as_ref().unwrap().id;
+
+    let mut other = MockClient::login(&server, "other").await;
+    other.send(LobbyPacket::Client_JoinLobby { lobby_id, password: "wrong".to_owned() }.to_bytes()).await;
+    other.wait_lobby_packet(|p| matches!(p, LobbyPacket::Server_LobbyJoinFailed { .. }).then_some(())).await;
+    other.send(LobbyPacket::Client_JoinLobby { lobby_id, password: String::new() }.to_bytes()).await;
+    other.wait_lobby_packet(|p| matches!(p, LobbyPacket::Server_LobbyJoined { .. }).then_some(())).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.players.len() == 2).is_some()).await;
+    assert!(ONLINE_MANAGER.read().await.is_lobby_host());
+
+    // pick a map and ready up
+    OnlineManager::lobby_set_map(LobbyBeatmap { hash: "map_hash".to_owned(), playmode: "osu".to_owned(), title: "map".to_owned() });
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.beatmap.is_set()).is_some()).await;
+    other.send(LobbyPacket::Client_LobbySetState { state: LobbyPlayerState::Ready }.to_bytes()).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.can_start()).is_some()).await;
+
+    // play
+    OnlineManager::lobby_start();
+    wait_for_online(|m| m.lobby_start_pending).await;
+    other.wait_lobby_packet(|p| matches!(p, LobbyPacket::Server_LobbyStart).then_some(())).await;
+
+    let mut score = Score::new("map_hash".to_owned(), "user".to_owned(), "osu".to_owned());
+    score.score = 100;
+    OnlineManager::lobby_score(score.clone(), true);
+    score.username = "other".to_owned();
+    score.score = 200;
+    other.send(LobbyPacket::Client_LobbyScore { score, complete: true }.to_bytes()).await;
+
+    wait_for_online(|m| m.lobby_complete && m.lobby_scores.len() == 2).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| !l.playing).is_some()).await;
+}
+
+#[tokio::test]
+async fn lobby_mods_restored() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    login().await;
+    wait_for_online(|m| m.lobbies_supported).await;
+
+    let mods = ModManager::new().with_speed(150);
+    *ModManager::get_mut() = mods.clone();
+
+    let mut host = MockClient::login(&server, "host").await;
+    host.send(LobbyPacket::Client_CreateLobby { name: "lobby".to_owned(), password: String::new() }.to_bytes()).await;
+    let lobby_id = host.wait_lobby_packet(|p| match p { LobbyPacket::Server_LobbyJoined { lobby } => Some(lobby.id), _ => None }).await;
+    OnlineManager::join_lobby(lobby_id, String::new());
+    wait_for_online(|m| m.lobby.is_some()).await;
+
+    // the lobby menu uses the host's mods while we're in the lobby
+    let lobby_mods = ONLINE_MANAGER.read().await.lobby.as_ref().unwrap().mod_manager();
+    *ModManager::get_mut() = lobby_mods;
+    assert!(ModManager::get_cloned() != mods);
+
+    OnlineManager::leave_lobby().await;
+    assert!(ModManager::get_cloned() == mods);
+}
+
+#[tokio::test]
+async fn lobbies_need_server_support() {
+    let _lock = TEST_LOCK.lock().await;
+    let _server = setup(MockScenario::default()).await;
+    login().await;
+    wait_for_online(|m| m.lobbies_supported).await;
+
+    // pretend this is a server that doesnt know about lobbies
+    ONLINE_MANAGER.write().await.lobbies_supported = false;
+    OnlineManager::create_lobby("lobby".to_owned(), String::new());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(ONLINE_MANAGER.read().await.lobby.is_none());
+}
+
+#[tokio::test]
+async fn reconnect() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    tokio::spawn(OnlineManager::run());
+    wait_for_online(|m| m.connection.is_connected()).await;
+    let user_id = ONLINE_MANAGER.read().await.user_id;
+    let general = ChatChannel::Channel { name: "general".to_owned() };
+
+    // someone spectates us
+    let mut watcher = MockClient::login(&server, "watcher").await;
+    watcher.send(create_packet!(Client_Spectate { host_id: user_id })).await;
+    let watcher_id = watcher.user_id;
+    wait_for_online(|m| m.spectator_list.iter().any(|(id, _)| *id == watcher_id)).await;
+
+    // spectate someone, and be in a lobby with someone else
+    let mut host = MockClient::login(&server, "host").await;
+    OnlineManager::start_spectating(host.user_id);
+    host.wait_packet(|p| matches!(p, Server_SpectatorJoined { .. }).then_some(())).await;
+
+    let mut other = MockClient::login(&server, "other").await;
+    other.send(LobbyPacket::Client_CreateLobby { name: "lobby".to_owned(), password: "pass".to_owned() }.to_bytes()).await;
+    wait_for_online(|m| m.lobbies_supported).await;
+    let lobby_id = other.wait_lobby_packet(|p| match p { LobbyPacket::Server_LobbyJoined { lobby } => Some(lobby.id), _ => None }).await;
+    OnlineManager::join_lobby(lobby_id, "pass".to_owned());
+    wait_for_online(|m| m.lobby.is_some()).await;
+
+    // connection drops
+    server.state.lock().await.disconnect(user_id);
+    wait_for_online(|m| !m.connection.is_connected()).await;
+
+    // sent while disconnected, so it should be queued
+    OnlineManager::send_message(general.clone(), "sent while offline".to_owned());
+
+    // back online with everything restored
+    wait_for_online(|m| m.connection.is_connected() && m.user_id != user_id).await;
+    let user_id = ONLINE_MANAGER.read().await.user_id;
+
+    host.wait_packet(|p| match p {
+        Server_SpectatorJoined { user_id: id, .. } if id == user_id => Some(()),
+        _ => None,
+    }).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.player(m.user_id).is_some()).is_some()).await;
+    wait_for_online(|m| has_message(m, &general, "sent while offline")).await;
+    assert!(server.state.lock().await.chat_log.contains(&(user_id, "#general".to_owned(), "sent while offline".to_owned())));
+
+    // still hosting, so whoever is watching gets what we're playing again
+    wait_for_online(|m| m.spectate_info_pending.contains(&watcher_id)).await;
+    assert_eq!(ONLINE_MANAGER.read().await.spectator_list.len(), 1);
+}
+
+#[tokio::test]
+async fn lobby_rejoin_after_match() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+    tokio::spawn(OnlineManager::run());
+    wait_for_online(|m| m.connection.is_connected() && m.lobbies_supported).await;
+    let user_id = ONLINE_MANAGER.read().await.user_id;
+
+    // be in a match with someone else
+    let mut other = MockClient::login(&server, "other").await;
+    other.send(LobbyPacket::Client_CreateLobby { name: "lobby".to_owned(), password: String::new() }.to_bytes()).await;
+    let lobby_id = other.wait_lobby_packet(|p| match p { LobbyPacket::Server_LobbyJoined { lobby } => Some(lobby.id), _ => None }).await;
+    OnlineManager::join_lobby(lobby_id, String::new());
+    wait_for_online(|m| m.lobby.is_some()).await;
+
+    other.send(LobbyPacket::Client_LobbySetMap { beatmap: LobbyBeatmap { hash: "map_hash".to_owned(), playmode: "osu".to_owned(), title: "map".to_owned() } }.to_bytes()).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.beatmap.is_set()).is_some()).await;
+    OnlineManager::lobby_set_state(LobbyPlayerState::Ready);
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.can_start()).is_some()).await;
+    other.send(LobbyPacket::Client_LobbyStart.to_bytes()).await;
+    wait_for_online(|m| m.lobby_start_pending).await;
+
+    // connection drops, and the match is still going when we're back
+    server.state.lock().await.disconnect(user_id);
+    wait_for_online(|m| !m.connection.is_connected()).await;
+    wait_for_online(|m| m.lobby_rejoin_wait.as_ref().filter(|w| w.match_seen).is_some()).await;
+    assert!(ONLINE_MANAGER.read().await.lobby.is_some());
+
+    // match ends, we get back in
+    let score = Score::new("map_hash".to_owned(), "other".to_owned(), "osu".to_owned());
+    other.send(LobbyPacket::Client_LobbyScore { score, complete: true }.to_bytes()).await;
+    wait_for_online(|m| m.lobby.as_ref().filter(|l| l.player(m.user_id).is_some()).is_some()).await;
+}
+
+#[tokio::test]
+async fn connecting_after_login_failed() {
+    let _lock = TEST_LOCK.lock().await;
+    let _server = setup(MockScenario::default()).await;
+    ONLINE_MANAGER.write().await.connection = ConnectionState::LoginFailed;
+
+    // the new attempt is shown, not the old failure. nothing is listening here, so it stays on connecting
+    OnlineManager::connect("ws://127.0.0.1:1".to_owned()).await;
+    assert_eq!(ONLINE_MANAGER.read().await.connection, ConnectionState::Connecting);
+}
+
+#[tokio::test]
+async fn load_test() {
+    let _lock = TEST_LOCK.lock().await;
+    let server = setup(MockScenario::default()).await;
+
+    for i in 0..CONNECTION_COUNT {
+        tokio::spawn(async move {
+            OnlineManager::start().await;
+            trace!("online thread {} stopped", i);
+        });
+    }
+
+    wait_for_server(&server, |s| s.connections.len() == CONNECTION_COUNT).await;
+}
@@ -1,9 +1,13 @@
 mod apis;
+mod lobby;
 mod online_user;
+mod mock_server;
 mod integrations;
 mod online_manager;
 
 pub use apis::*;
+pub use lobby::*;
 pub use online_user::*;
+pub use mock_server::*;
 pub use integrations::*;
 pub use online_manager::*;
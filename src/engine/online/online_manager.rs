@@ -1,3 +1,4 @@
+use rand::Rng;
 use tokio::{ sync::Mutex, net::TcpStream };
 use futures_util::{ SinkExt, StreamExt, stream::SplitSink };
 use tokio_tungstenite::{ MaybeTlsStream, WebSocketStream, connect_async, tungstenite::protocol::Message };
@@ -15,6 +16,19 @@ const EXTRA_ONLINE_LOGGING:bool = true;
 // how many frames do we buffer before sending?
 // higher means less packet spam
 const SPECTATOR_BUFFER_FLUSH_SIZE: usize = 20;
+
+/// ms to wait before the first reconnect attempt. doubles every attempt after that
+const RECONNECT_BASE_DELAY: f32 = 1_000.0;
+/// most ms to wait between reconnect attempts
+const RECONNECT_MAX_DELAY: f32 = 60_000.0;
+/// most packets to keep while disconnected. oldest ones are dropped first
+const MAX_QUEUED_PACKETS: usize = 100;
+/// how many messages to load from the database for each chat channel
+const CHAT_HISTORY_LENGTH: usize = 100;
+/// ms to wait between lobby list checks while waiting for a match to end so we can rejoin its lobby
+const LOBBY_REJOIN_POLL_DELAY: u64 = 2_000;
+/// the channel everyone is in by default
+pub const DEFAULT_CHAT_CHANNEL:&str = "#general";
 type ThreadSafeSelf = Arc<tokio::sync::RwLock<OnlineManager>>;
 
 #[macro_export]
@@ -54,7 +68,9 @@ lazy_static::lazy_static! {
 
 ///
 pub struct OnlineManager {
-    pub connected: bool,
+    pub connection: ConnectionState,
+    /// goes up every time we connect, so things from an old connection know to stop
+    session: u32,
     pub users: HashMap<u32, Arc<Mutex<OnlineUser>>>, // user id is key
     pub discord: Option<Discord>,
 
@@ -62,9 +78,22 @@ pub struct OnlineManager {
 
     /// socket writer
     pub writer: Option<Arc<Mutex<WsWriter>>>,
+    /// packets sent while we weren't connected, sent once we're back
+    queued_packets: parking_lot::Mutex<Vec<Vec<u8>>>,
+    /// last status we sent, so it can be sent again after reconnecting
+    last_status: Option<(UserAction, String, PlayMode)>,
 
     // ====== chat ======
     pub chat_messages: HashMap<ChatChannel, Vec<ChatMessage>>,
+    /// channels we're in. the server sends channel messages to everyone, so this is only used to pick which ones to show.
+    /// since it's kept on our end, it's still the same after reconnecting
+    pub chat_channels: Vec<String>,
+    /// how many messages havent been seen yet in each channel
+    pub chat_unread: HashMap<ChatChannel, usize>,
+    /// channels with an unseen message mentioning us
+    pub chat_mentions: HashSet<ChatChannel>,
+    /// has the chat history been loaded from the database yet
+    chat_history_loaded: bool,
 
     // ====== spectator ======
 
@@ -82,17 +111,39 @@ pub struct OnlineManager {
 
     /// was a spectator request accepted? if so, this will be the user_id
     spectate_pending: u32,
+    /// who we're spectating, so we can spectate them again after reconnecting
+    spectating_host: Option<u32>,
+    /// the username of the host we were spectating when they went offline, so we can spectate them again when they're back
+    spectating_host_offline: Option<String>,
+
+    // ====== multiplayer ======
+    /// did the server say it knows about lobbies? see [`LOBBY_PACKET_MAGIC`]
+    pub lobbies_supported: bool,
+    /// lobbies from the last lobby list request
+    pub lobbies: Vec<LobbyInfo>,
+    /// the lobby this user is in
+    pub lobby: Option<Lobby>,
+    /// latest score for each player in the current match, by user id
+    pub lobby_scores: HashMap<u32, Score>,
+    /// bumped whenever lobby_scores changes, so the ingame leaderboard only rebuilds when it needs to
+    pub lobby_scores_version: usize,
+    /// the server said to start the match, waiting for the lobby menu to start it
+    pub(crate) lobby_start_pending: bool,
+    /// has everyone finished the current match
+    pub lobby_complete: bool,
+    /// password for the lobby we're in, for rejoining after reconnecting
+    lobby_password: String,
+    /// trying to rejoin this lobby after reconnecting
+    lobby_rejoin: Option<u32>,
+    /// the lobby wouldnt let us back in, maybe because its match is still going
+    pub(crate) lobby_rejoin_wait: Option<LobbyRejoinWait>,
+    /// the mods the player had before joining a lobby, put back once they leave
+    mods_before_lobby: Option<ModManager>,
 }
 impl OnlineManager {
     pub fn new() -> OnlineManager {
         let mut messages = HashMap::new();
-        let channel = ChatChannel::Channel{name: "general".to_owned()};
-        messages.insert(channel.clone(), vec![ChatMessage::new(
-            "System".to_owned(),
-            channel,
-            u32::MAX,
-            "this is a test message".to_owned()
-        )]);
+        messages.insert(ChatChannel::from_name(DEFAULT_CHAT_CHANNEL.to_owned()), Vec::new());
 
         let discord = Discord::new();
         if let Err(e) = &discord {
@@ -105,7 +156,10 @@ impl OnlineManager {
             discord: discord.ok(),
             // chat: Chat::new(),
             writer: None,
-            connected: false,
+            connection: ConnectionState::Disconnected,
+            session: 0,
+            queued_packets: parking_lot::Mutex::new(Vec::new()),
+            last_status: None,
             buffered_spectator_frames: Vec::new(),
             last_spectator_frame: Instant::now(),
             spectating: false,
@@ -113,16 +167,66 @@ impl OnlineManager {
             spectator_list: Vec::new(),
             spectate_info_pending: Vec::new(),
             chat_messages: messages,
-            spectate_pending: 0
+            chat_channels: vec![DEFAULT_CHAT_CHANNEL.to_owned()],
+            chat_unread: HashMap::new(),
+            chat_mentions: HashSet::new(),
+            chat_history_loaded: false,
+            spectate_pending: 0,
+            spectating_host: None,
+            spectating_host_offline: None,
+
+            lobbies: Vec::new(),
+            lobbies_supported: false,
+            lobby: None,
+            lobby_scores: HashMap::new(),
+            lobby_scores_version: 0,
+            lobby_start_pending: false,
+            lobby_complete: false,
+            lobby_password: String::new(),
+            lobby_rejoin: None,
+            lobby_rejoin_wait: None,
+            mods_before_lobby: None,
         }
     }
-    pub async fn start() {
-        let s = ONLINE_MANAGER.clone();
+    /// stay connected to the server, reconnecting with backoff whenever the connection drops
+    pub async fn run() {
+        let mut attempt = 0;
+        loop {
+            if Self::start().await { attempt = 0 }
+
+            if ONLINE_MANAGER.read().await.connection == ConnectionState::LoginFailed {
+                // theres no point trying again until the username or password changes
+                Self::wait_for_new_credentials().await;
+                attempt = 0;
+                continue;
+            }
+
+            attempt += 1;
+            ONLINE_MANAGER.write().await.connection = ConnectionState::Reconnecting { attempt };
+            tokio::time::sleep(backoff_delay(attempt)).await;
+        }
+    }
+
+    /// connect once, using the server url from the settings. returns if we logged in
+    pub async fn start() -> bool {
         let url = get_settings!().server_url.clone();
+        Self::connect(url).await
+    }
+
+    /// connect to the server at `url` and handle packets until the connection closes. returns if we logged in
+    pub async fn connect(url: String) -> bool {
+        let s = ONLINE_MANAGER.clone();
+        {
+            let mut s = s.write().await;
+            s.session += 1;
+            if s.connection != ConnectionState::Connected {
+                s.connection = ConnectionState::Connecting;
+            }
+        }
+
         // initialize the connection
         match connect_async(url).await {
             Ok((ws_stream, _)) => {
-                s.write().await.connected = true;
                 let (writer, mut reader) = ws_stream.split();
                 let writer = Arc::new(Mutex::new(writer));
 
@@ -154,36 +258,96 @@ impl OnlineManager {
                             }
                         }
 
-                        Ok(Message::Close(_)) => {
-                            NotificationManager::add_text_notification("Disconnected from server", 5000.0, Color::RED).await;
-                            let mut s = s.write().await;
-                            s.connected = false;
-                            s.writer = None;
-                        }
+                        Ok(Message::Close(_)) => break,
                         Ok(message) => if EXTRA_ONLINE_LOGGING {warn!("Got something else: {:?}", message)},
 
                         Err(oof) => {
                             error!("oof: {}", oof);
-
-                            let mut s = s.write().await;
-                            s.connected = false;
-                            s.writer = None;
-
-                            // reconnect handled by caller
                             break;
                         }
                     }
                 }
+
+                Self::on_disconnect().await
             }
             Err(oof) => {
-                // s.write().await.connected = false;
                 warn!("Could not accept connection: {}", oof);
+                false
             }
         }
     }
 
+    /// the connection closed. returns if we were logged in
+    async fn on_disconnect() -> bool {
+        let mut s = ONLINE_MANAGER.write().await;
+        s.writer = None;
+
+        // the server tells us all of this again once we're back
+        s.users.clear();
+        s.spectate_info_pending.clear();
+        // might not be the same server when we reconnect
+        s.lobbies_supported = false;
+        // spectator_list is kept, they're sent what we're playing again once we're back (see restore_session)
+
+        let was_connected = s.connection == ConnectionState::Connected;
+        if was_connected {
+            s.connection = ConnectionState::Disconnected;
+            NotificationManager::add_text_notification("Disconnected from server, reconnecting...", 5000.0, Color::RED).await;
+        }
+        was_connected
+    }
+
+    async fn wait_for_new_credentials() {
+        let credentials = || {
+            let settings = get_settings!();
+            (settings.server_url.clone(), settings.username.clone(), settings.password.clone())
+        };
+
+        let failed = credentials();
+        while credentials() == failed {
+            tokio::time::sleep(Duration::from_millis(1_000)).await;
+        }
+    }
+
+    /// we just logged in. if this is a reconnect, get back to where we were before the connection dropped
+    async fn restore_session(&mut self) {
+        self.connection = ConnectionState::Connected;
+
+        if let Some((action, action_text, mode)) = self.last_status.clone() {
+            send_packet!(self.writer, create_packet!(Client_StatusUpdate { action, action_text, mode }));
+        }
+        if let Some(host_id) = self.spectating_host {
+            send_packet!(self.writer, create_packet!(Client_Spectate { host_id }));
+        }
+        // we're hosting, anyone still watching needs to know what we're playing again
+        self.spectate_info_pending = self.spectator_list.iter().map(|(user_id, _)| *user_id).collect();
+
+        let queued = std::mem::take(&mut *self.queued_packets.lock());
+        if !queued.is_empty() {
+            debug!("Sending {} queued packets", queued.len());
+        }
+        for data in queued {
+            send_packet!(self.writer, data);
+        }
+    }
+
+    /// send a packet, or if we're not connected, queue it to be sent once we're back
+    async fn send_or_queue(&self, data: Vec<u8>) {
+        if self.connection == ConnectionState::Connected && send_packet!(self.writer, data.clone()) { return }
+
+        let mut queue = self.queued_packets.lock();
+        if queue.len() >= MAX_QUEUED_PACKETS {
+            queue.remove(0);
+        }
+        queue.push(data);
+    }
+
     async fn handle_packet(data:Vec<u8>) -> TatakuResult<()> {
         let s = ONLINE_MANAGER.clone();
+        if let Some(packet) = LobbyPacket::from_bytes(&data) {
+            return OnlineManager::handle_lobby_packet(packet?).await;
+        }
+
         let mut reader = SerializationReader::new(data);
 
         while reader.can_read() {
@@ -197,6 +361,15 @@ impl OnlineManager {
 
                 // login
                 PacketId::Server_LoginResponse { status, user_id } => {
+                    if !matches!(status, LoginStatus::Ok | LoginStatus::UnknownError) {
+                        // wrong username or password, stop trying until they change
+                        let mut s = s.write().await;
+                        s.connection = ConnectionState::LoginFailed;
+                        if let Some(writer) = &s.writer {
+                            let _ = writer.lock().await.close().await;
+                        }
+                    }
+
                     match status {
                         LoginStatus::UnknownError => {
                             trace!("Unknown Error");
@@ -212,10 +385,12 @@ impl OnlineManager {
                         },
                         LoginStatus::Ok => {
                             trace!("Success, got user_id: {}", user_id);
-                            s.write().await.user_id = user_id;
+                            let mut s = s.write().await;
+                            s.user_id = user_id;
+                            s.restore_session().await;
                             NotificationManager::add_text_notification("[Login] Logged in!", 2000.0, Color::GREEN).await;
 
-                            ping_handler()
+                            ping_handler(s.session)
                         },
                     }
                 }
@@ -239,16 +414,30 @@ impl OnlineManager {
                 // ===== user updates =====
                 PacketId::Server_UserJoined { user_id, username, game } => {
                     if EXTRA_ONLINE_LOGGING {debug!("User {} joined (id: {}, game: {})", username, user_id, game)};
-                    let mut user = OnlineUser::new(user_id, username);
+                    let mut user = OnlineUser::new(user_id, username.clone());
                     user.game = game;
-                    s.write().await.users.insert(user_id, Arc::new(Mutex::new(user)));
+
+                    let mut lock = s.write().await;
+                    lock.users.insert(user_id, Arc::new(Mutex::new(user)));
+
+                    // the host we were spectating is back
+                    if lock.spectating_host_offline.as_ref() == Some(&username) {
+                        lock.spectating_host_offline = None;
+                        lock.spectating_host = Some(user_id);
+                        send_packet!(lock.writer, create_packet!(Client_Spectate { host_id: user_id }));
+                    }
                 }
                 PacketId::Server_UserLeft {user_id} => {
                     if EXTRA_ONLINE_LOGGING {debug!("User id {} left", user_id)};
 
                     let mut lock = s.write().await;
                     // remove from online users
-                    lock.users.remove(&user_id);
+                    let user = lock.users.remove(&user_id);
+
+                    // the host we're spectating went offline, keep an eye out for them coming back
+                    if let Some(user) = user.filter(|_| lock.spectating_host == Some(user_id)) {
+                        lock.spectating_host_offline = Some(user.lock().await.username.clone());
+                    }
 
                     // remove from our spec list
                     for (i, &(id, _)) in lock.spectator_list.iter().enumerate() {
@@ -276,19 +465,14 @@ impl OnlineManager {
                 PacketId::Server_SendMessage {sender_id, message, channel}=> {
                     if EXTRA_ONLINE_LOGGING {debug!("Got message: `{}` from user id `{}` in channel `{}`", message, sender_id, channel)};
 
-                    let channel = if channel.starts_with("#") {
-                        ChatChannel::Channel {name: channel.trim_start_matches("#").to_owned()}
-                    } else {
-                        ChatChannel::User {username: channel}
-                    };
-
                     let mut lock = s.write().await;
+                    // not in this channel
+                    if channel.starts_with('#') && !lock.chat_channels.contains(&channel) { continue }
+
+                    let channel = ChatChannel::from_name(channel);
+
                     let sender = lock.find_user_by_id(sender_id).unwrap_or_default().lock().await.username.clone();
-                    let chat_messages = &mut lock.chat_messages;
-                    // if the list doesnt include the channel, add it
-                    if !chat_messages.contains_key(&channel) {
-                        chat_messages.insert(channel.clone(), Vec::new());
-                    }
+                    if Self::is_ignored(&sender) { continue }
 
                     let message = ChatMessage::new(
                         sender,
@@ -298,7 +482,26 @@ impl OnlineManager {
                     );
 
                     // add the message to the channel
-                    chat_messages.get_mut(&channel).unwrap().push(message);
+                    lock.chat_messages.entry(channel.clone()).or_default().push(message.clone());
+
+                    // let the user know about it if it wasnt from them
+                    let mut toast = false;
+                    if sender_id != lock.user_id {
+                        *lock.chat_unread.entry(channel.clone()).or_default() += 1;
+
+                        let mentioned = message.mentions(&get_settings!().username);
+                        if mentioned || matches!(channel, ChatChannel::User { .. }) {
+                            lock.chat_mentions.insert(channel);
+                            toast = !CHAT_OPEN.load(SeqCst);
+                        }
+                    }
+                    drop(lock);
+
+                    if toast {
+                        NotificationManager::add_text_notification(format!("{}: {}", message.sender, message.display_text()), 5000.0, Color::BLUE).await;
+                    }
+                    // dont hold up the rest of the packets waiting on the database
+                    tokio::spawn(async move { Database::save_chat_message(&message).await });
                 }
 
                 
@@ -310,7 +513,12 @@ impl OnlineManager {
                 }
                 // spec join/leave
                 PacketId::Server_SpectatorJoined { user_id, username }=> {
-                    s.write().await.spectator_list.push((user_id, username.clone()));
+                    {
+                        let mut lock = s.write().await;
+                        // still in the list from before we reconnected
+                        if lock.spectator_list.iter().any(|(id, _)| *id == user_id) { continue }
+                        lock.spectator_list.push((user_id, username.clone()));
+                    }
                     NotificationManager::add_text_notification(&format!("{} is now spectating", username), 2000.0, Color::GREEN).await;
                 }
                 PacketId::Server_SpectatorLeft { user_id } => {
@@ -326,7 +534,14 @@ impl OnlineManager {
                 PacketId::Server_SpectateResult {result, host_id} => {
                     trace!("Got spec result {:?}", result);
                     match result {
-                        SpectateResult::Ok => s.write().await.spectate_pending = host_id,
+                        SpectateResult::Ok => {
+                            let mut s = s.write().await;
+                            // already spectating them, so this is us getting back after reconnecting
+                            if s.spectating_host == Some(host_id) { continue }
+
+                            s.spectating_host = Some(host_id);
+                            s.spectate_pending = host_id;
+                        }
                         SpectateResult::Error_SpectatingBot => NotificationManager::add_text_notification("You cannot spectate a bot!", 3000.0, Color::RED).await,
                         SpectateResult::Error_HostOffline => NotificationManager::add_text_notification("Spectate host is offline!", 3000.0, Color::RED).await,
                         SpectateResult::Error_SpectatingYourself => NotificationManager::add_text_notification("You cannot spectate yourself!", 3000.0, Color::RED).await,
@@ -357,9 +572,88 @@ impl OnlineManager {
         Ok(())
     }
 
+    async fn handle_lobby_packet(packet: LobbyPacket) -> TatakuResult<()> {
+        if EXTRA_ONLINE_LOGGING {debug!("Got lobby packet {:?}", packet)};
+        let mut s = ONLINE_MANAGER.write().await;
+
+        match packet {
+            LobbyPacket::Server_LobbiesSupported => {
+                s.lobbies_supported = true;
+
+                // get back into the lobby we were in before reconnecting
+                if let Some(lobby_id) = s.lobby.as_ref().map(|l| l.id) {
+                    s.lobby_rejoin = Some(lobby_id);
+                    s.lobby_rejoin_wait = None;
+                    let password = s.lobby_password.clone();
+                    send_packet!(s.writer, LobbyPacket::Client_JoinLobby { lobby_id, password }.to_bytes());
+                }
+            }
+
+            LobbyPacket::Server_LobbyList { lobbies } => {
+                s.lobbies = lobbies;
+                if let Some(wait) = s.lobby_rejoin_wait.take() {
+                    s.check_lobby_rejoin(wait).await;
+                }
+            }
+
+            LobbyPacket::Server_LobbyJoined { lobby } => {
+                // back in the lobby we were in before reconnecting, keep the match state
+                if s.lobby_rejoin.take() == Some(lobby.id) {
+                    s.lobby = Some(lobby);
+                    return Ok(());
+                }
+
+                NotificationManager::add_text_notification(&format!("Joined lobby {}", lobby.name), 2000.0, Color::GREEN).await;
+                s.mods_before_lobby.get_or_insert_with(ModManager::get_cloned);
+                s.lobby = Some(lobby);
+                s.lobby_scores.clear();
+                s.lobby_scores_version += 1;
+                s.lobby_start_pending = false;
+            }
+            LobbyPacket::Server_LobbyJoinFailed { reason } => {
+                if let Some(lobby_id) = s.lobby_rejoin.take() {
+                    // the match might still be going, check the lobby list to see if its worth waiting for it to end
+                    s.lobby_rejoin_wait = Some(LobbyRejoinWait { lobby_id, reason, match_seen: false });
+                    send_packet!(s.writer, LobbyPacket::Client_LobbyList.to_bytes());
+                    return Ok(());
+                }
+
+                NotificationManager::add_text_notification(&format!("Couldn't join lobby: {reason}"), 3000.0, Color::RED).await;
+            }
+            LobbyPacket::Server_LobbyLeft => {
+                if s.lobby.take().is_some() {
+                    s.restore_lobby_mods();
+                    NotificationManager::add_text_notification("You left the lobby", 2000.0, Color::YELLOW).await;
+                }
+            }
+
+            LobbyPacket::Server_LobbyUpdate { lobby } => {
+                if s.lobby.as_ref().filter(|l| l.id == lobby.id).is_some() {
+                    s.lobby = Some(lobby);
+                }
+            }
+
+            LobbyPacket::Server_LobbyStart => {
+                s.lobby_scores.clear();
+                s.lobby_scores_version += 1;
+                s.lobby_complete = false;
+                s.lobby_start_pending = true;
+            }
+            LobbyPacket::Server_LobbyScore { user_id, score } => {
+                s.lobby_scores.insert(user_id, score);
+                s.lobby_scores_version += 1;
+            }
+            LobbyPacket::Server_LobbyComplete => s.lobby_complete = true,
+
+            LobbyPacket::Unknown => warn!("Got unknown lobby packet"),
+            p => warn!("Got unhandled lobby packet: {:?}", p),
+        }
+
+        Ok(())
+    }
+
     pub fn set_action(action_info: SetAction, incoming_mode: Option<PlayMode>) {
         tokio::spawn(async move {
-            let s = ONLINE_MANAGER.read().await;
             let mode = incoming_mode.clone().unwrap_or(String::new());
 
 
@@ -374,11 +668,16 @@ impl OnlineManager {
             };
 
 
-            send_packet!(s.writer, create_packet!(Client_StatusUpdate { action, action_text: action_text.clone(), mode }));
-            if action == UserAction::Leaving {
-                send_packet!(s.writer, create_packet!(Client_LogOut));
+            {
+                let mut s = ONLINE_MANAGER.write().await;
+                s.last_status = Some((action, action_text.clone(), mode.clone()));
+                send_packet!(s.writer, create_packet!(Client_StatusUpdate { action, action_text: action_text.clone(), mode }));
+                if action == UserAction::Leaving {
+                    send_packet!(s.writer, create_packet!(Client_LogOut));
+                }
             }
 
+            let s = ONLINE_MANAGER.read().await;
 
             if let Some(discord) = &s.discord {
                 discord.change_status(&action_info, incoming_mode).await;
@@ -420,7 +719,7 @@ impl OnlineManager {
                             user_id: *user_id,
                             beatmap_hash: manager.beatmap.hash(),
                             mode: manager.gamemode.playmode(),
-                            mods: manager.score.mods_string_sorted(),
+                            mods: manager.spectator_mods_string(),
                             current_time: manager.time(),
                             speed: manager.current_mods.speed
                         };
@@ -457,6 +756,76 @@ impl OnlineManager {
         }
     }
 
+    /// join a chat channel. `channel` should start with #
+    pub fn join_channel(channel: String) {
+        tokio::spawn(async move {
+            let mut s = ONLINE_MANAGER.write().await;
+            s.chat_messages.entry(ChatChannel::from_name(channel.clone())).or_default();
+            if s.chat_channels.contains(&channel) { return }
+
+            s.chat_channels.push(channel);
+        });
+    }
+
+    /// leave a chat channel, or close a direct message
+    pub fn leave_channel(channel: ChatChannel) {
+        tokio::spawn(async move {
+            let mut s = ONLINE_MANAGER.write().await;
+            s.chat_messages.remove(&channel);
+            s.chat_unread.remove(&channel);
+            s.chat_mentions.remove(&channel);
+
+            let ChatChannel::Channel { .. } = &channel else { return };
+            let name = channel.get_name();
+            s.chat_channels.retain(|c| c != &name);
+        });
+    }
+
+    /// add a message which is only shown to this user, ie for command errors
+    pub fn system_message(channel: ChatChannel, text: String) {
+        tokio::spawn(async move {
+            let message = ChatMessage::new("System".to_owned(), channel.clone(), u32::MAX, text);
+            ONLINE_MANAGER.write().await.chat_messages.entry(channel).or_default().push(message);
+        });
+    }
+
+    /// load the chat history from the database. only does anything the first time its called
+    pub fn load_chat_history() {
+        tokio::spawn(async move {
+            if std::mem::replace(&mut ONLINE_MANAGER.write().await.chat_history_loaded, true) { return }
+
+            let history = Database::get_chat_history(CHAT_HISTORY_LENGTH).await;
+            let mut s = ONLINE_MANAGER.write().await;
+
+            // group it by channel, and put it before anything which came in before it was loaded
+            let mut channels: HashMap<ChatChannel, Vec<ChatMessage>> = HashMap::new();
+            for message in history {
+                channels.entry(message.channel.clone()).or_default().push(message);
+            }
+            for (channel, mut messages) in channels {
+                let current = s.chat_messages.entry(channel).or_default();
+                messages.retain(|m| !current.contains(m));
+                messages.append(current);
+                *current = messages;
+            }
+        });
+    }
+
+    /// should messages from this user be hidden
+    pub fn is_ignored(username: &String) -> bool {
+        get_settings!().ignored_users.iter().any(|u| u.eq_ignore_ascii_case(username))
+    }
+
+    pub fn send_message(channel: ChatChannel, message: String) {
+        tokio::spawn(async move {
+            let s = ONLINE_MANAGER.read().await;
+            s.send_or_queue(create_packet!(Client_SendMessage {
+                channel: channel.get_name(),
+                message
+            })).await;
+        });
+    }
+
     pub fn find_user_by_id(&self, user_id: u32) -> Option<Arc<Mutex<OnlineUser>>> {
         for (&id, user) in self.users.iter() {
             if id == user_id {
@@ -508,6 +877,8 @@ impl OnlineManager {
         tokio::spawn(async move {
             let mut s = s.write().await;
             s.buffered_spectator_frames.clear();
+            s.spectating_host = None;
+            s.spectating_host_offline = None;
             if !s.spectating {return}
             s.spectating = false;
             trace!("Stop speccing");
@@ -521,22 +892,153 @@ impl OnlineManager {
     }
 }
 
+// multiplayer
+impl OnlineManager {
+    fn send_lobby_packet(packet: LobbyPacket) {
+        tokio::spawn(async move {
+            let s = ONLINE_MANAGER.read().await;
+            // no point queueing these for a server that doesnt know what they are
+            if s.connection == ConnectionState::Connected && !s.lobbies_supported { return }
+            s.send_or_queue(packet.to_bytes()).await;
+        });
+    }
+
+    pub fn request_lobbies() {
+        Self::send_lobby_packet(LobbyPacket::Client_LobbyList);
+    }
+    pub fn create_lobby(name: String, password: String) {
+        tokio::spawn(async move {
+            let mut s = ONLINE_MANAGER.write().await;
+            if !s.lobbies_supported { return }
+            s.lobby_password = password.clone();
+            send_packet!(s.writer, LobbyPacket::Client_CreateLobby { name, password }.to_bytes());
+        });
+    }
+    pub fn join_lobby(lobby_id: u32, password: String) {
+        tokio::spawn(async move {
+            let mut s = ONLINE_MANAGER.write().await;
+            if !s.lobbies_supported { return }
+            s.lobby_password = password.clone();
+            send_packet!(s.writer, LobbyPacket::Client_JoinLobby { lobby_id, password }.to_bytes());
+        });
+    }
+    /// leave the current lobby. the lobby is cleared right away, so menus dont see it while the server catches up
+    pub async fn leave_lobby() {
+        let mut s = ONLINE_MANAGER.write().await;
+        if s.lobby.take().is_none() { return }
+        s.lobby_scores.clear();
+        s.lobby_scores_version += 1;
+        s.lobby_start_pending = false;
+        s.restore_lobby_mods();
+
+        if s.lobbies_supported {
+            send_packet!(s.writer, LobbyPacket::Client_LeaveLobby.to_bytes());
+        }
+    }
+
+    /// put back the mods the player had before joining the lobby, since the host's mods were being used
+    fn restore_lobby_mods(&mut self) {
+        if let Some(mods) = self.mods_before_lobby.take() {
+            *ModManager::get_mut() = mods;
+        }
+    }
+
+    /// we couldnt rejoin a lobby, and just got the lobby list. wait for its match to end, or give up
+    async fn check_lobby_rejoin(&mut self, mut wait: LobbyRejoinWait) {
+        let still_in_lobby = self.lobby.as_ref().filter(|l| l.id == wait.lobby_id).is_some();
+        let playing = self.lobbies.iter().find(|l| l.id == wait.lobby_id).map(|l| l.playing);
+
+        match playing {
+            // left the lobby while waiting
+            _ if !still_in_lobby => {}
+
+            // still playing, check again in a bit
+            Some(true) => {
+                wait.match_seen = true;
+                self.lobby_rejoin_wait = Some(wait);
+                tokio::spawn(async {
+                    tokio::time::sleep(Duration::from_millis(LOBBY_REJOIN_POLL_DELAY)).await;
+                    Self::request_lobbies();
+                });
+            }
+
+            // the match is over, try again
+            Some(false) if wait.match_seen => {
+                self.lobby_rejoin = Some(wait.lobby_id);
+                let password = self.lobby_password.clone();
+                send_packet!(self.writer, LobbyPacket::Client_JoinLobby { lobby_id: wait.lobby_id, password }.to_bytes());
+            }
+
+            // the lobby is gone, or it wasnt the match keeping us out
+            _ => {
+                self.lobby = None;
+                self.restore_lobby_mods();
+                NotificationManager::add_text_notification(&format!("Couldn't rejoin lobby: {}", wait.reason), 3000.0, Color::RED).await;
+            }
+        }
+    }
+
+    /// set the lobby's map. only does anything for the host
+    pub fn lobby_set_map(beatmap: LobbyBeatmap) {
+        Self::send_lobby_packet(LobbyPacket::Client_LobbySetMap { beatmap });
+    }
+    /// set the lobby's mods. only does anything for the host
+    pub fn lobby_set_mods(mods: &ModManager) {
+        let mut list = mods.mods.iter().cloned().collect::<Vec<_>>();
+        list.sort();
+        Self::send_lobby_packet(LobbyPacket::Client_LobbySetMods { mods: list, speed: mods.speed });
+    }
+    pub fn lobby_set_state(state: LobbyPlayerState) {
+        Self::send_lobby_packet(LobbyPacket::Client_LobbySetState { state });
+    }
+    pub fn lobby_start() {
+        Self::send_lobby_packet(LobbyPacket::Client_LobbyStart);
+    }
+    /// send our score during a match. `complete` is for the final score
+    pub fn lobby_score(score: Score, complete: bool) {
+        Self::send_lobby_packet(LobbyPacket::Client_LobbyScore { score, complete });
+    }
+
+    /// is this user the host of the lobby they're in
+    pub fn is_lobby_host(&self) -> bool {
+        self.lobby.as_ref().filter(|l| l.host_id == self.user_id).is_some()
+    }
+
+    /// did the server say to start the match? only true once per start
+    pub fn take_lobby_start(&mut self) -> bool {
+        std::mem::take(&mut self.lobby_start_pending)
+    }
+}
+
 
 
 const LOG_PINGS:bool = false;
-fn ping_handler() {
+/// ping the server until the connection for `session` ends
+fn ping_handler(session: u32) {
     tokio::spawn(async move {
         let ping = create_packet!(Ping);
         let duration = std::time::Duration::from_millis(1000);
 
         loop {
             tokio::time::sleep(duration).await;
+
+            let s = ONLINE_MANAGER.read().await;
+            if s.session != session { break }
+
             if LOG_PINGS {trace!("Sending ping")};
-            send_packet!(ONLINE_MANAGER.read().await.writer, ping.clone());
+            send_packet!(s.writer, ping.clone());
         }
     });
 }
 
+/// exponential backoff with jitter, so everyone doesnt reconnect at the same time when the server comes back
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let max = (RECONNECT_BASE_DELAY * 2f32.powi(attempt.clamp(1, 16) as i32 - 1)).min(RECONNECT_MAX_DELAY);
+    // somewhere between half of the max and the max
+    let delay = max * rand::thread_rng().gen_range(0.5..=1.0);
+    Duration::from_secs_f32(delay / 1000.0)
+}
+
 #[allow(unused)]
 pub enum SetAction {
     Idle,
@@ -579,30 +1081,48 @@ impl SetAction {
     }
 }
 
-// tests
-#[allow(unused_imports, dead_code)]
-mod tests {
-    const CONNECTION_COUNT: usize = 50;
-    use crate::prelude::*;
-    
-    #[test]
-    fn test() {
-        let runtime = tokio::runtime::Runtime::new().unwrap();
-        runtime.block_on(async {
-            load_test().await
-        });
-        loop {}
-    }
+/// how the connection to the server is doing
+/// a lobby rejoin the server turned down
+pub(crate) struct LobbyRejoinWait {
+    pub lobby_id: u32,
+    /// why the server wouldnt let us back in
+    pub reason: String,
+    /// has the lobby list shown the lobby's match going. if it never did, the match isnt why we couldnt rejoin
+    pub match_seen: bool,
+}
 
-    async fn load_test() {
-        for i in 0..CONNECTION_COUNT {
-            tokio::spawn(async move {
-                // let thing = super::OnlineManager::new();
-                // let thing = Arc::new(tokio::sync::Mutex::new(thing));
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Disconnected,
+    Connecting,
+    /// connected and logged in
+    Connected,
+    /// the connection dropped, or couldnt be made. waiting to try again
+    Reconnecting { attempt: u32 },
+    /// the server didnt accept the username or password
+    LoginFailed,
+}
+impl ConnectionState {
+    pub fn is_connected(&self) -> bool {
+        *self == Self::Connected
+    }
 
-                super::OnlineManager::start().await;
-                trace!("online thread {} stopped", i);
-            });
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Connected => Color::GREEN,
+            Self::Connecting | Self::Reconnecting { .. } => Color::YELLOW,
+            Self::Disconnected | Self::LoginFailed => Color::RED,
+        }
+    }
+}
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disconnected => write!(f, "Offline"),
+            Self::Connecting => write!(f, "Connecting..."),
+            Self::Connected => write!(f, "Online"),
+            Self::Reconnecting { attempt } => write!(f, "Reconnecting (attempt {attempt})"),
+            Self::LoginFailed => write!(f, "Login failed"),
         }
     }
 }
@@ -4,7 +4,6 @@ use tataku_client_proc_macros::Settings;
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[derive(Settings)]
-#[Setting(prefix="background_game_settings")]
 pub struct BackgroundGameSettings {
     /// whether to have gameplay in the main menu bg or not
     #[serde(alias="enabled")]
@@ -25,6 +24,10 @@ pub struct BackgroundGameSettings {
     pub hitsound_volume: f32,
     /// what mode should be playing?
     pub mode: PlayMode,
+
+    // autoplay, so the background play doesnt look robotic
+    #[Subsetting]
+    pub autoplay: AutoplayProfile,
 }
 impl Default for BackgroundGameSettings {
     fn default() -> Self {
@@ -34,7 +37,13 @@ impl Default for BackgroundGameSettings {
             settings_menu_enabled: true,
             opacity: 0.5,
             hitsound_volume: 0.3,
-            mode: "osu".to_owned()
+            mode: "osu".to_owned(),
+
+            autoplay: AutoplayProfile {
+                deviation: 8.0,
+                cursor_style: AutoplayCursorStyle::Dance,
+                ..Default::default()
+            },
         }
     }
 }
\ No newline at end of file
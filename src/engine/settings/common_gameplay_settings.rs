@@ -4,14 +4,10 @@ use tataku_client_proc_macros::Settings;
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[derive(Settings)]
-#[Setting(prefix="common_game_settings")]
 pub struct CommonGameplaySettings {
-    #[Setting(text="Increase Offset")]
     pub key_offset_up: Key,
-    #[Setting(text="Decrease Offset")]
     pub key_offset_down: Key,
 
-    #[Setting(text="Restart Map Key")]
     pub map_restart_key: Key,
     #[Setting(text="Restart Map Hold Time", min=0, max=1000)]
     pub map_restart_delay: f32,
@@ -51,6 +47,10 @@ pub struct CommonGameplaySettings {
     /// how long should a hit indicator be drawn for?
     #[Setting(text="Hit Indicator Draw Time", min=100, max=500)]
     pub hit_indicator_draw_duration: f32,
+
+    // autoplay mod (useful for testing judgments)
+    #[Subsetting]
+    pub autoplay: AutoplayProfile,
 }
 impl CommonGameplaySettings {
     /// init colors etc
@@ -97,6 +97,9 @@ impl Default for CommonGameplaySettings {
 
             // hit indicators
             hit_indicator_draw_duration: 300.0,
+
+            // autoplay plays perfectly unless changed
+            autoplay: AutoplayProfile::default(),
         }
     }
 }
@@ -0,0 +1,587 @@
+use crate::prelude::*;
+
+/// name of the profile used when a context hasnt had one picked
+pub const DEFAULT_PROFILE:&str = "Default";
+
+lazy_static::lazy_static! {
+    /// every context with actions that can be bound.
+    /// gamemodes declare theirs through [`GameModeInfo::keybinds`], the rest are declared where they're used
+    static ref KEYBIND_CONTEXTS: Vec<KeybindContext> = {
+        let mut list = vec![
+            Game::keybind_context(),
+            IngameManager::keybind_context(),
+            BeatmapSelectMenu::keybind_context(),
+            ScoreMenu::keybind_context(),
+        ];
+
+        for playmode in AVAILABLE_PLAYMODES {
+            let Some(info) = get_gamemode_info(&playmode.to_string()) else { continue };
+            list.extend(info.keybinds());
+        }
+
+        list
+    };
+}
+
+/// something that can be bound to an action
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputTarget {
+    Key(Key),
+    /// a key pressed while holding modifiers, ie Ctrl+P
+    KeyCombo {
+        key: Key,
+        ctrl: bool,
+        alt: bool,
+        shift: bool,
+    },
+    Mouse(MouseButton),
+    Controller {
+        controller: String,
+        button: u8,
+    },
+    /// an axis used as a button, ie a trigger
+    ControllerAxis {
+        controller: String,
+        axis: AxisConfig,
+    },
+}
+impl InputTarget {
+    /// `key` along with whichever modifiers are held
+    pub fn key_with(key: Key, mods: &KeyModifiers) -> Self {
+        if !(mods.ctrl || mods.alt || mods.shift) { return Self::Key(key) }
+        Self::KeyCombo { key, ctrl: mods.ctrl, alt: mods.alt, shift: mods.shift }
+    }
+    pub fn ctrl(key: Key) -> Self {
+        Self::KeyCombo { key, ctrl: true, alt: false, shift: false }
+    }
+    pub fn alt(key: Key) -> Self {
+        Self::KeyCombo { key, ctrl: false, alt: true, shift: false }
+    }
+    pub fn shift(key: Key) -> Self {
+        Self::KeyCombo { key, ctrl: false, alt: false, shift: true }
+    }
+
+    pub fn controller(controller: &Box<dyn Controller>, button: u8) -> Self {
+        Self::Controller { controller: (*controller.get_name()).clone(), button }
+    }
+    pub fn controller_axis(controller: &Box<dyn Controller>, axis: AxisConfig) -> Self {
+        Self::ControllerAxis { controller: (*controller.get_name()).clone(), axis }
+    }
+    /// what a calibrated input is bound as, or None if it was skipped
+    pub fn from_controller_input(controller: &String, input: ControllerInputConfig) -> Option<Self> {
+        match (input.button, input.axis) {
+            (Some(button), _) => Some(Self::Controller { controller: controller.clone(), button }),
+            (None, Some(axis)) => Some(Self::ControllerAxis { controller: controller.clone(), axis }),
+            (None, None) => None,
+        }
+    }
+
+    /// is this a button or axis on `controller`?
+    pub fn is_on_controller(&self, controller: &str) -> bool {
+        match self {
+            Self::Controller { controller: c, .. } | Self::ControllerAxis { controller: c, .. } => c == controller,
+            _ => false,
+        }
+    }
+}
+impl Display for InputTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{key:?}"),
+            Self::KeyCombo { key, ctrl, alt, shift } => {
+                if *ctrl { write!(f, "Ctrl+")? }
+                if *alt { write!(f, "Alt+")? }
+                if *shift { write!(f, "Shift+")? }
+                write!(f, "{key:?}")
+            }
+            Self::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Self::Controller { controller, button } => write!(f, "{controller} Button {button}"),
+            Self::ControllerAxis { controller, axis } => write!(f, "{controller} Axis {}{}", axis.axis_id, if axis.inverted { "-" } else { "+" }),
+        }
+    }
+}
+
+/// an action that can be bound, and what its bound to by default
+#[derive(Clone, Debug)]
+pub struct KeybindAction {
+    pub name: String,
+    pub display_name: String,
+    pub defaults: Vec<InputTarget>,
+}
+impl KeybindAction {
+    pub fn new(name: impl ToString, display_name: impl ToString, defaults: Vec<InputTarget>) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: display_name.to_string(),
+            defaults,
+        }
+    }
+}
+
+/// a group of actions which are checked together, ie all the inputs for a gamemode.
+/// a target should only be bound to one action in a context
+#[derive(Clone, Debug)]
+pub struct KeybindContext {
+    pub name: String,
+    pub display_name: String,
+    pub actions: Vec<KeybindAction>,
+    /// built in profiles the user can switch to
+    pub presets: Vec<(String, KeybindProfile)>,
+}
+impl KeybindContext {
+    pub fn new(name: impl ToString, display_name: impl ToString, actions: Vec<KeybindAction>) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: display_name.to_string(),
+            actions,
+            presets: Vec::new(),
+        }
+    }
+    pub fn with_preset(mut self, name: impl ToString, profile: KeybindProfile) -> Self {
+        self.presets.push((name.to_string(), profile));
+        self
+    }
+
+    /// get all the contexts that have been declared
+    pub fn all() -> &'static Vec<KeybindContext> {
+        &KEYBIND_CONTEXTS
+    }
+    pub fn get(name: &str) -> Option<&'static KeybindContext> {
+        KEYBIND_CONTEXTS.iter().find(|c| c.name == name)
+    }
+
+    fn default_profile(&self) -> KeybindProfile {
+        self.actions
+            .iter()
+            .map(|a| (a.name.clone(), a.defaults.clone()))
+            .collect()
+    }
+}
+
+/// action name to what its bound to
+pub type KeybindProfile = HashMap<String, Vec<InputTarget>>;
+
+/// the resolved bindings for a context
+#[derive(Clone, Debug, Default)]
+pub struct ContextBindings {
+    bindings: KeybindProfile,
+}
+impl ContextBindings {
+    pub fn get(&self, action: &str) -> &[InputTarget] {
+        self.bindings.get(action).map(|b| b.as_slice()).unwrap_or_default()
+    }
+
+    /// is `target` bound to `action`?
+    pub fn matches(&self, action: &str, target: &InputTarget) -> bool {
+        self.get(action).contains(target)
+    }
+
+    /// is `action` bound to `key` with exactly the modifiers in `mods`?
+    /// modifiers have to match, so F5 and Ctrl+F5 can be bound to different actions
+    pub fn key_matches(&self, action: &str, key: Key, mods: &KeyModifiers) -> bool {
+        self.matches(action, &InputTarget::key_with(key, mods))
+    }
+
+    /// get every action `target` is bound to
+    pub fn actions_for<'a>(&'a self, target: &'a InputTarget) -> impl Iterator<Item=&'a String> + 'a {
+        self.bindings
+            .iter()
+            .filter(move |(_, targets)| targets.contains(target))
+            .map(|(action, _)| action)
+    }
+
+    /// is anything on `controller` bound in this context?
+    pub fn has_controller(&self, controller: &str) -> bool {
+        self.bindings.values().flatten().any(|t| t.is_on_controller(controller))
+    }
+
+    /// get the axes on `controller` which are bound, with what `bound` says each one is for.
+    /// these can be turned into presses with [`get_axis_presses`]
+    pub fn axis_inputs<T>(&self, controller: &Box<dyn Controller>, bound: impl Fn(&InputTarget) -> Vec<T>) -> Vec<(T, ControllerInputConfig)> {
+        let name = controller.get_name();
+        let axes = self.bindings
+            .values()
+            .flatten()
+            .filter_map(|t| match t {
+                InputTarget::ControllerAxis { controller, axis } if controller == &*name => Some(*axis),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        axes
+            .into_iter()
+            .flat_map(|axis| bound(&InputTarget::controller_axis(controller, axis)).into_iter().map(move |t| (t, ControllerInputConfig::new(None, Some(axis)))))
+            .collect()
+    }
+
+    /// get every target thats bound to more than one action
+    pub fn conflicts(&self) -> HashSet<InputTarget> {
+        let mut seen = HashSet::new();
+        let mut conflicts = HashSet::new();
+
+        for target in self.bindings.values().flat_map(|t| t.iter().collect::<HashSet<_>>()) {
+            if !seen.insert(target) {
+                conflicts.insert(target.clone());
+            }
+        }
+
+        conflicts
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct KeybindSettings {
+    /// context name to the name of the profile its using
+    pub active_profiles: HashMap<String, String>,
+    /// context name, then profile name to the profile.
+    /// only profiles the user has made or changed are stored, presets come from the context
+    pub profiles: HashMap<String, HashMap<String, KeybindProfile>>,
+
+    /// have the keys from before keybinds existed been moved over yet
+    pub migrated: bool,
+}
+impl KeybindSettings {
+    pub fn active_profile(&self, context: &str) -> &str {
+        self.active_profiles.get(context).map(|p| p.as_str()).unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// get the bindings for `context` in its current profile
+    pub fn get_bindings(&self, context: &str) -> ContextBindings {
+        let Some(info) = KeybindContext::get(context) else { return ContextBindings::default() };
+        let profile_name = self.active_profile(context);
+
+        // anything the profile doesnt set uses the default
+        let mut bindings = info.default_profile();
+        let profile = self.profiles
+            .get(context)
+            .and_then(|p| p.get(profile_name))
+            .or_else(|| info.presets.iter().find(|(n, _)| n == profile_name).map(|(_, p)| p));
+
+        if let Some(profile) = profile {
+            for (action, targets) in profile {
+                if let Some(b) = bindings.get_mut(action) {
+                    *b = targets.clone();
+                }
+            }
+        }
+
+        ContextBindings { bindings }
+    }
+
+    /// bind `action` in the context's current profile
+    pub fn set_binding(&mut self, context: &str, action: &str, targets: Vec<InputTarget>) {
+        let profile_name = self.active_profile(context).to_owned();
+
+        // editing a preset makes a copy of it, so copy what we have now before changing anything
+        let current = self.get_bindings(context).bindings;
+        let profile = self.profiles
+            .entry(context.to_owned())
+            .or_default()
+            .entry(profile_name)
+            .or_insert(current);
+
+        profile.insert(action.to_owned(), targets);
+    }
+
+    /// bind a calibrated controller's inputs to `actions` in the context's current profile.
+    /// anything else on that controller is unbound from those actions, so setting a controller up again replaces its old setup
+    pub fn set_controller_bindings(&mut self, context: &str, controller: &String, actions: &[impl AsRef<str>], inputs: &[ControllerInputConfig]) {
+        let current = self.get_bindings(context);
+
+        for (i, action) in actions.iter().enumerate() {
+            let action = action.as_ref();
+            let mut targets = current.get(action).iter().filter(|t| !t.is_on_controller(controller)).cloned().collect::<Vec<_>>();
+            targets.extend(inputs.get(i).and_then(|input| InputTarget::from_controller_input(controller, *input)));
+            self.set_binding(context, action, targets);
+        }
+    }
+
+    /// every profile that can be picked for `context`, starting with the default
+    pub fn profile_names(&self, context: &str) -> Vec<String> {
+        let mut list = vec![DEFAULT_PROFILE.to_owned()];
+        if let Some(info) = KeybindContext::get(context) {
+            list.extend(info.presets.iter().map(|(n, _)| n.clone()));
+        }
+
+        let mut custom = self.profiles.get(context).map(|p| p.keys().cloned().collect::<Vec<_>>()).unwrap_or_default();
+        custom.sort();
+        list.extend(custom);
+
+        let mut seen = HashSet::new();
+        list.retain(|n| seen.insert(n.clone()));
+        list
+    }
+
+    pub fn set_profile(&mut self, context: &str, name: impl ToString) {
+        self.active_profiles.insert(context.to_owned(), name.to_string());
+    }
+
+    /// make a new profile from the current bindings and switch to it
+    pub fn new_profile(&mut self, context: &str, name: impl ToString) {
+        let name = name.to_string();
+        let current = self.get_bindings(context).bindings;
+        self.profiles.entry(context.to_owned()).or_default().insert(name.clone(), current);
+        self.set_profile(context, name);
+    }
+
+    /// copy the keys from the old per-gamemode settings into the default profiles.
+    /// the old key fields (ie `StandardSettings::left_key`, `ManiaSettings::keys` or `TaikoSettings::controller_config`) are only kept so they can be read here,
+    /// nothing else should use them
+    pub fn migrate(settings: &mut Settings) {
+        if settings.keybinds.migrated { return }
+        settings.keybinds.migrated = true;
+
+        let keys = |list: &[(&str, Key)]| list
+            .iter()
+            .map(|(a, k)| (a.to_string(), vec![InputTarget::Key(*k)]))
+            .collect::<Vec<_>>();
+
+        let mut migrated = vec![
+            ("global".to_owned(), keys(&[
+                ("user_panel", settings.key_user_panel),
+            ])),
+            ("gameplay".to_owned(), keys(&[
+                ("offset_up", settings.common_game_settings.key_offset_up),
+                ("offset_down", settings.common_game_settings.key_offset_down),
+                ("restart", settings.common_game_settings.map_restart_key),
+            ])),
+            ("osu".to_owned(), keys(&[
+                ("left", settings.standard_settings.left_key),
+                ("right", settings.standard_settings.right_key),
+            ])),
+            ("taiko".to_owned(), keys(&[
+                ("left_kat", settings.taiko_settings.left_kat),
+                ("left_don", settings.taiko_settings.left_don),
+                ("right_don", settings.taiko_settings.right_don),
+                ("right_kat", settings.taiko_settings.right_kat),
+            ])),
+        ];
+
+        for (i, keys) in settings.mania_settings.keys.iter().enumerate() {
+            let columns = keys
+                .iter()
+                .enumerate()
+                .map(|(col, k)| (format!("column_{}", col + 1), vec![InputTarget::Key(*k)]))
+                .collect();
+            migrated.push((format!("mania_{}k", i + 1), columns));
+        }
+
+        for (context, bindings) in migrated {
+            for (action, targets) in bindings {
+                // keep the default mouse bindings
+                let mut targets = targets;
+                if let Some(info) = KeybindContext::get(&context) {
+                    if let Some(a) = info.actions.iter().find(|a| a.name == action) {
+                        targets.extend(a.defaults.iter().filter(|t| !matches!(t, InputTarget::Key(_))).cloned());
+                    }
+                }
+
+                settings.keybinds.profiles
+                    .entry(context.clone())
+                    .or_default()
+                    .entry(DEFAULT_PROFILE.to_owned())
+                    .or_default()
+                    .insert(action, targets);
+            }
+        }
+
+        // drums that were set up before controllers could be bound
+        for (controller, config) in settings.taiko_settings.controller_config.clone() {
+            let inputs = [config.left_kat, config.left_don, config.right_don, config.right_kat];
+            settings.keybinds.set_controller_bindings("taiko", &controller, &["left_kat", "left_don", "right_don", "right_kat"], &inputs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(list: &[(&str, Vec<InputTarget>)]) -> ContextBindings {
+        ContextBindings {
+            bindings: list.iter().map(|(a, t)| (a.to_string(), t.clone())).collect()
+        }
+    }
+
+    #[test]
+    fn no_conflicts() {
+        let bindings = bindings(&[
+            ("left", vec![InputTarget::Key(Key::Z), InputTarget::Mouse(MouseButton::Left)]),
+            ("right", vec![InputTarget::Key(Key::X), InputTarget::Mouse(MouseButton::Right)]),
+        ]);
+        assert!(bindings.conflicts().is_empty());
+    }
+
+    #[test]
+    fn finds_conflicts() {
+        let bindings = bindings(&[
+            ("left", vec![InputTarget::Key(Key::Z), InputTarget::Mouse(MouseButton::Left)]),
+            ("right", vec![InputTarget::Key(Key::Z), InputTarget::Key(Key::X)]),
+            ("smoke", vec![InputTarget::Key(Key::X)]),
+        ]);
+        assert_eq!(bindings.conflicts(), HashSet::from([InputTarget::Key(Key::Z), InputTarget::Key(Key::X)]));
+    }
+
+    #[test]
+    fn same_target_twice_in_one_action_isnt_a_conflict() {
+        let bindings = bindings(&[
+            ("left", vec![InputTarget::Key(Key::Z), InputTarget::Key(Key::Z)]),
+        ]);
+        assert!(bindings.conflicts().is_empty());
+    }
+
+    #[test]
+    fn combos_dont_conflict_with_their_key() {
+        let bindings = bindings(&[
+            ("refresh", vec![InputTarget::Key(Key::F5)]),
+            ("full_refresh", vec![InputTarget::ctrl(Key::F5)]),
+        ]);
+        assert!(bindings.conflicts().is_empty());
+
+        let mut mods = KeyModifiers::default();
+        assert!(bindings.key_matches("refresh", Key::F5, &mods));
+        assert!(!bindings.key_matches("full_refresh", Key::F5, &mods));
+
+        mods.ctrl = true;
+        assert!(!bindings.key_matches("refresh", Key::F5, &mods));
+        assert!(bindings.key_matches("full_refresh", Key::F5, &mods));
+    }
+
+    #[test]
+    fn replay_viewer_keys_can_be_rebound() {
+        let mut settings = KeybindSettings::default();
+        let mods = KeyModifiers::default();
+
+        // the replay keys are checked along with the offset keys, so they cant share defaults
+        let gameplay = settings.get_bindings("gameplay");
+        assert!(gameplay.conflicts().is_empty());
+        assert!(gameplay.key_matches("replay_pause", Key::P, &mods));
+
+        settings.set_binding("gameplay", "replay_pause", vec![InputTarget::Key(Key::Space)]);
+        let gameplay = settings.get_bindings("gameplay");
+        assert!(gameplay.key_matches("replay_pause", Key::Space, &mods));
+        assert!(!gameplay.key_matches("replay_pause", Key::P, &mods));
+
+        settings.set_binding("gameplay", "replay_faster", vec![InputTarget::Key(Key::Equals)]);
+        assert_eq!(settings.get_bindings("gameplay").conflicts(), HashSet::from([InputTarget::Key(Key::Equals)]));
+    }
+
+    fn drum_targets(bindings: &ContextBindings, action: &str) -> Vec<InputTarget> {
+        bindings.get(action).iter().filter(|t| t.is_on_controller("Drum")).cloned().collect()
+    }
+    fn button(button: u8) -> InputTarget {
+        InputTarget::Controller { controller: "Drum".to_owned(), button }
+    }
+
+    #[test]
+    fn calibration_replaces_controller_bindings() {
+        let mut settings = KeybindSettings::default();
+        let actions = ["left_kat", "left_don", "right_don", "right_kat"];
+        let inputs = [1, 2, 3, 4].map(|b| ControllerInputConfig::new(Some(b), None));
+        settings.set_controller_bindings("taiko", &"Drum".to_owned(), &actions, &inputs);
+
+        let taiko = settings.get_bindings("taiko");
+        assert!(taiko.has_controller("Drum"));
+        assert_eq!(drum_targets(&taiko, "left_kat"), vec![button(1)]);
+        // keys are left alone
+        assert!(taiko.get("left_kat").contains(&InputTarget::Key(Key::D)));
+
+        // setting it up again replaces the old buttons, and skipped inputs are unbound
+        let axis = AxisConfig::new(5, 0.5, true);
+        let inputs = [
+            ControllerInputConfig::new(Some(2), None),
+            ControllerInputConfig::new(Some(1), None),
+            ControllerInputConfig::default(),
+            ControllerInputConfig::new(None, Some(axis)),
+        ];
+        settings.set_controller_bindings("taiko", &"Drum".to_owned(), &actions, &inputs);
+
+        let taiko = settings.get_bindings("taiko");
+        assert_eq!(drum_targets(&taiko, "left_kat"), vec![button(2)]);
+        assert_eq!(drum_targets(&taiko, "left_don"), vec![button(1)]);
+        assert!(drum_targets(&taiko, "right_don").is_empty());
+        assert_eq!(drum_targets(&taiko, "right_kat"), vec![InputTarget::ControllerAxis { controller: "Drum".to_owned(), axis }]);
+        assert!(taiko.conflicts().is_empty());
+    }
+
+    #[test]
+    fn finds_controller_conflicts() {
+        let axis = InputTarget::ControllerAxis { controller: "Drum".to_owned(), axis: AxisConfig::new(5, 0.5, true) };
+        let bindings = bindings(&[
+            ("left_kat", vec![button(1), axis.clone()]),
+            ("left_don", vec![button(1), button(2)]),
+            ("right_kat", vec![axis.clone()]),
+            // same button on another controller
+            ("right_don", vec![InputTarget::Controller { controller: "Other".to_owned(), button: 2 }]),
+        ]);
+        assert_eq!(bindings.conflicts(), HashSet::from([button(1), axis]));
+    }
+
+    #[test]
+    fn migrate_copies_old_keys() {
+        let mut settings = Settings::default();
+        settings.standard_settings.left_key = Key::A;
+        settings.standard_settings.right_key = Key::S;
+        settings.taiko_settings.left_don = Key::F;
+        settings.common_game_settings.map_restart_key = Key::R;
+
+        KeybindSettings::migrate(&mut settings);
+        assert!(settings.keybinds.migrated);
+
+        let osu = settings.keybinds.get_bindings("osu");
+        assert_eq!(osu.get("left").first(), Some(&InputTarget::Key(Key::A)));
+        assert_eq!(osu.get("right").first(), Some(&InputTarget::Key(Key::S)));
+
+        // the default mouse bindings are kept
+        let taiko = settings.keybinds.get_bindings("taiko");
+        assert_eq!(taiko.get("left_don"), &[InputTarget::Key(Key::F), InputTarget::Mouse(MouseButton::Left)]);
+
+        let gameplay = settings.keybinds.get_bindings("gameplay");
+        assert_eq!(gameplay.get("restart"), &[InputTarget::Key(Key::R)]);
+    }
+
+    #[test]
+    fn migrate_copies_mania_columns() {
+        let mut settings = Settings::default();
+        settings.mania_settings.keys[3] = vec![Key::Q, Key::W, Key::O, Key::P];
+
+        KeybindSettings::migrate(&mut settings);
+
+        let mania = settings.keybinds.get_bindings("mania_4k");
+        assert_eq!(mania.get("column_1").first(), Some(&InputTarget::Key(Key::Q)));
+        assert_eq!(mania.get("column_4").first(), Some(&InputTarget::Key(Key::P)));
+    }
+
+    #[test]
+    fn migrate_copies_taiko_controllers() {
+        let mut settings = Settings::default();
+        settings.taiko_settings.controller_config.insert("Drum".to_owned(), TaikoControllerConfig {
+            left_kat: ControllerInputConfig::new(Some(6), None),
+            left_don: ControllerInputConfig::new(Some(10), None),
+            right_don: ControllerInputConfig::new(Some(11), None),
+            right_kat: ControllerInputConfig::default(),
+        });
+
+        KeybindSettings::migrate(&mut settings);
+
+        let taiko = settings.keybinds.get_bindings("taiko");
+        assert_eq!(drum_targets(&taiko, "left_kat"), vec![button(6)]);
+        assert_eq!(drum_targets(&taiko, "right_don"), vec![button(11)]);
+        assert!(drum_targets(&taiko, "right_kat").is_empty());
+        // and the keys are still there
+        assert_eq!(taiko.get("left_kat").first(), Some(&InputTarget::Key(Key::D)));
+    }
+
+    #[test]
+    fn migrate_only_runs_once() {
+        let mut settings = Settings::default();
+        KeybindSettings::migrate(&mut settings);
+
+        settings.keybinds.set_binding("osu", "left", vec![InputTarget::Key(Key::C)]);
+        settings.standard_settings.left_key = Key::A;
+        KeybindSettings::migrate(&mut settings);
+
+        assert_eq!(settings.keybinds.get_bindings("osu").get("left"), &[InputTarget::Key(Key::C)]);
+    }
+}
@@ -5,7 +5,6 @@ use tataku_client_proc_macros::Settings;
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[derive(Settings)]
-#[Setting(prefix="mania_settings")]
 pub struct ManiaSettings {
     // sv
     pub static_sv: bool,
@@ -27,6 +26,15 @@ pub struct ManiaSettings {
     pub judgement_indicator_offset: f64,
     
     pub use_skin_judgments: bool,
+
+    #[Setting(text="Judgment Windows", dropdown="JudgmentPreset")]
+    pub judgment_preset: JudgmentPreset,
+    /// ms windows used by the custom judgment preset, from marvelous to miss
+    #[Setting(text="Custom Judgment Windows (ms)")]
+    pub custom_windows: String,
+
+    #[Setting(text="Scoring System", dropdown="ScoringSystemKind")]
+    pub scoring_system: ScoringSystemKind,
 }
 impl Default for ManiaSettings {
     fn default() -> Self {
@@ -66,7 +74,11 @@ impl Default for ManiaSettings {
             // other
             judgements_per_column: false,
             judgement_indicator_offset: 200.0,
-            use_skin_judgments: true
+            use_skin_judgments: true,
+
+            judgment_preset: JudgmentPreset::QuaverStandard,
+            custom_windows: "18, 43, 76, 106, 127, 164".to_owned(),
+            scoring_system: ScoringSystemKind::Default,
         }
     }
 }
@@ -1,4 +1,5 @@
 mod settings;
+mod keybinds;
 mod osu_import;
 mod taiko_settings;
 mod catch_settings;
@@ -10,6 +11,7 @@ mod common_gameplay_settings;
 mod background_game_settings;
 
 pub use settings::*;
+pub use keybinds::*;
 pub use osu_import::*;
 pub use taiko_settings::*;
 pub use catch_settings::*;
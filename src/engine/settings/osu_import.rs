@@ -140,39 +140,32 @@ pub async fn load_osu_settings(path: impl AsRef<Path>) -> Result<(), TatakuError
 
     // keys
     macro_rules! key {
-        ($key:expr, $val:ident) => {
+        ($key:expr, $context:expr, $action:expr) => {
             if let Some(v) = data.get(&$key.to_owned()) {
                 if let Some(k) = parse_key(&v) {
-                    settings.$val = k
-                }
-            }
-        };
-        ($key:expr, $val:ident, $sub:ident) => {
-            if let Some(v) = data.get(&$key.to_owned()) {
-                if let Some(k) = parse_key(&v) {
-                    settings.$val.$sub = k
+                    import_key(&mut settings, $context, $action, k)
                 }
             }
         }
     }
     
-    key!("keyOsuLeft", standard_settings, left_key);
-    key!("keyOsuRight", standard_settings, right_key);
-    // key!("keyOsuSmoke", standard_settings, smoke_key);
+    key!("keyOsuLeft", "osu", "left");
+    key!("keyOsuRight", "osu", "right");
+    // key!("keyOsuSmoke", "osu", "smoke");
 
-    key!("keyTaikoOuterLeft", taiko_settings, left_kat);
-    key!("keyTaikoInnerLeft", taiko_settings, left_don);
-    key!("keyTaikoInnerRight", taiko_settings, right_don);
-    key!("keyTaikoOuterRight", taiko_settings, right_kat);
+    key!("keyTaikoOuterLeft", "taiko", "left_kat");
+    key!("keyTaikoInnerLeft", "taiko", "left_don");
+    key!("keyTaikoInnerRight", "taiko", "right_don");
+    key!("keyTaikoOuterRight", "taiko", "right_kat");
 
     // key!("keyPause", pause_key);
     // key!("keySkip", skip_key);
     // key!("keyToggleScoreboard", scoreboard_key);
     // key!("keyToggleChat", chat_key);
     // key!("keyScreenshot", screenshot_key);
-    // key!("keyIncreaseAudioOffset", offset_increase_key);
-    // key!("keyDecreaseAudioOffset", offset_decrease_key);
-    // key!("keyQuickRetry", retry);
+    key!("keyIncreaseAudioOffset", "gameplay", "offset_up");
+    key!("keyDecreaseAudioOffset", "gameplay", "offset_down");
+    key!("keyQuickRetry", "gameplay", "restart");
 
     // key!("keyIncreaseSpeed", mania_settings, sv_increase_key);
     // key!("keyDecreaseSpeed", mania_settings, sv_decrease_key);
@@ -181,7 +174,7 @@ pub async fn load_osu_settings(path: impl AsRef<Path>) -> Result<(), TatakuError
     // key!("keyVolumeIncrease", volume_up_key);
     // key!("keyVolumeDecrease", volume_down_key);
 
-    for c in 1..=10 { 
+    for c in 1..=9 { 
         if let Some(k_str) = string!(format!("ManiaLayouts{}K", c)) {
             let mut keys = Vec::new();
             for s in k_str.split(" "){
@@ -191,13 +184,15 @@ pub async fn load_osu_settings(path: impl AsRef<Path>) -> Result<(), TatakuError
             }
 
             if keys.len() == c {
-                settings.mania_settings.keys[c-1] = keys
+                let context = format!("mania_{c}k");
+                for (col, k) in keys.into_iter().enumerate() {
+                    import_key(&mut settings, &context, &format!("column_{}", col + 1), k);
+                }
             }
         }
         
     }
 
-
     let songs_folder = data.get(&"BeatmapDirectory".to_owned()).cloned().or_else(||Some("Songs".to_owned())).unwrap();
     let songs_folder = path.parent().unwrap().join(&songs_folder).to_string_lossy().to_string();
     if !settings.external_games_folders.contains(&songs_folder) {
@@ -208,6 +203,14 @@ pub async fn load_osu_settings(path: impl AsRef<Path>) -> Result<(), TatakuError
 }
 
 
+/// replace the keys bound to an action, keeping any mouse or controller bindings
+fn import_key(settings: &mut Settings, context: &str, action: &str, key: Key) {
+    let mut targets = settings.keybinds.get_bindings(context).get(action).to_vec();
+    targets.retain(|t| !matches!(t, InputTarget::Key(_)));
+    targets.insert(0, InputTarget::Key(key));
+    settings.keybinds.set_binding(context, action, targets);
+}
+
 fn parse_key(k:&String) -> Option<Key> {
     use Key::*;
 
@@ -49,6 +49,10 @@ pub struct Settings {
     pub osu_password: String,
     #[Setting(text="Osu Api Key", password=true)]
     pub osu_api_key: String,
+
+    // quaver (for replays)
+    #[Setting(text="Quaver Url", category="Quaver Integration")]
+    pub quaver_url: String,
     
     // game settings
     #[Subsetting(category="Osu Settings")]
@@ -67,6 +71,8 @@ pub struct Settings {
     pub last_played_mode: String,
     pub last_score_retreival_method: ScoreRetreivalMethod,
     pub last_sort_by: SortBy,
+    /// users whose chat messages are hidden
+    pub ignored_users: Vec<String>,
     
     #[Setting(text="Gamemode Ripple Override")]
     pub allow_gamemode_cursor_ripple_override: bool,
@@ -116,8 +122,8 @@ pub struct Settings {
     pub theme: SelectedTheme,
 
 
-    // misc keybinds
-    #[Setting(text="User Panel Key", category="Common Keybinds")]
+    // keybinds
+    pub keybinds: KeybindSettings,
     pub key_user_panel: Key,
 
     // double tap protection
@@ -126,6 +132,10 @@ pub struct Settings {
     #[Setting(text="DoubleTap Protection Leniency", min=10.0, max=200.0)]
     pub double_tap_protection_duration: f32,
 
+    // input recording
+    #[Setting(text="Record Raw Input During Plays", category="Input Recording")]
+    pub record_input: bool,
+
 
     // integrations
     #[Setting(text="Last.fm Integration", category="Integration")]
@@ -155,6 +165,9 @@ impl Settings {
 
         // check password hashes
         s.check_hashes();
+
+        // move keys from before keybinds existed
+        KeybindSettings::migrate(&mut s);
         
         GlobalValueManager::update(Arc::new(s.clone()));
         GlobalValueManager::update(Arc::new(WindowSize(s.window_size.into())));
@@ -201,6 +214,9 @@ impl Default for Settings {
             osu_password: String::new(),
             osu_api_key: String::new(),
 
+            // quaver
+            quaver_url: "https://quavergame.com".to_owned(),
+
             // game settings
             logging_settings: LoggingSettings::new(),
             standard_settings: StandardSettings::default(),
@@ -213,6 +229,7 @@ impl Default for Settings {
             last_played_mode: "osu".to_owned(),
             last_score_retreival_method: ScoreRetreivalMethod::Local,
             last_sort_by: SortBy::Title,
+            ignored_users: Vec::new(),
             allow_gamemode_cursor_ripple_override: true,
             beatmap_hitsounds: true,
 
@@ -235,11 +252,15 @@ impl Default for Settings {
             cursor_ripple_final_scale: 1.5,
             
             // keybinds
+            keybinds: KeybindSettings::default(),
             key_user_panel: Key::F8,
 
             // doubletap protection
             enable_double_tap_protection: false,
             double_tap_protection_duration: 80.0,
+
+            // input recording
+            record_input: false,
             
             // integrations
             lastfm_enabled: false,
@@ -4,12 +4,9 @@ use tataku_client_proc_macros::Settings;
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[derive(Settings)]
-#[Setting(prefix="standard_settings")]
 pub struct StandardSettings {
     // input
-    #[Setting(text="Osu Key 1")]
     pub left_key: Key,
-    #[Setting(text="Osu Key 2")]
     pub right_key: Key,
     #[Setting(text="Ignore Mouse Buttons")]
     pub ignore_mouse_buttons: bool,
@@ -27,6 +24,8 @@ pub struct StandardSettings {
     pub combo_colors: Vec<String>,
     #[Setting(text="Show x300s")]
     pub show_300s: bool,
+    #[Setting(text="Show Ghost Cursor")]
+    pub ghost_cursor: bool,
 
     // special effects
     #[Setting(text="Hit Ripples")]
@@ -47,6 +46,12 @@ pub struct StandardSettings {
 
     #[Setting(text="Use Skin Judgments")]
     pub use_skin_judgments: bool,
+
+    #[Setting(text="Judgment Windows", dropdown="JudgmentPreset")]
+    pub judgment_preset: JudgmentPreset,
+    /// ms windows used by the custom judgment preset, for 300, 100, 50 and miss
+    #[Setting(text="Custom Judgment Windows (ms)")]
+    pub custom_windows: String,
 }
 impl StandardSettings {
     pub fn get_playfield(&self) -> (f64, Vector2) {
@@ -69,6 +74,7 @@ impl Default for StandardSettings {
 
             draw_follow_points: true,
             show_300s: true,
+            ghost_cursor: true,
 
             combo_colors: vec![
                 "#FFC000".to_owned(),
@@ -85,7 +91,11 @@ impl Default for StandardSettings {
             approach_combo_color: true,
 
             use_beatmap_combo_colors: true,
-            use_skin_judgments: true
+            use_skin_judgments: true,
+
+            judgment_preset: JudgmentPreset::OsuOd,
+            custom_windows: "43, 106, 127, 164".to_owned(),
         }
     }
 }
+
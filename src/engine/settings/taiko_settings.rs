@@ -7,19 +7,15 @@ use tataku_client_proc_macros::Settings;
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[derive(Settings)]
-#[Setting(prefix="taiko_settings")]
 pub struct TaikoSettings {
     // input
-    #[Setting(text="Left Kat")]
     pub left_kat: Key,
-    #[Setting(text="Left Don")]
     pub left_don: Key,
-    #[Setting(text="Right Don")]
     pub right_don: Key,
-    #[Setting(text="Right Kat")]
     pub right_kat: Key,
     #[Setting(text="Ignore Mouse Buttons")]
     pub ignore_mouse_buttons: bool,
+    /// controller setups from before controllers were bound with the keybinds
     pub controller_config: HashMap<String, TaikoControllerConfig>,
 
     // sv
@@ -62,6 +58,12 @@ pub struct TaikoSettings {
     /// how far above the hit position should hit indicators be?
     #[Setting(text="Hit Judgment Y-Offset", min=0, max=100)]
     pub judgement_indicator_offset: f64,
+
+    #[Setting(text="Judgment Windows", dropdown="JudgmentPreset")]
+    pub judgment_preset: JudgmentPreset,
+    /// ms windows used by the custom judgment preset, for 300, 100 and miss
+    #[Setting(text="Custom Judgment Windows (ms)")]
+    pub custom_windows: String,
 }
 impl TaikoSettings {
     pub async fn init_settings(&mut self) {
@@ -126,7 +128,10 @@ impl Default for TaikoSettings {
             kat_color: Color::new(0.0, 0.0, 1.0, 1.0),
             
             judgement_indicator_offset: 0.0,
-            use_skin_judgments: true
+            use_skin_judgments: true,
+
+            judgment_preset: JudgmentPreset::OsuOd,
+            custom_windows: "43, 106, 164".to_owned(),
         }
     }
 }
@@ -139,27 +144,3 @@ pub struct TaikoControllerConfig {
     pub right_don: ControllerInputConfig,
     pub right_kat: ControllerInputConfig,
 }
-impl TaikoControllerConfig {
-    fn new_default(left_kat:u8, left_don:u8, right_don: u8, right_kat: u8) -> Self {
-        Self {
-            left_kat: ControllerInputConfig::new(Some(left_kat), None),
-            left_don: ControllerInputConfig::new(Some(left_don), None),
-            right_don: ControllerInputConfig::new(Some(right_don), None),
-            right_kat: ControllerInputConfig::new(Some(right_kat), None)
-        }
-    }
-    pub fn defaults(controller_name: Arc<String>) -> Self {
-        match &**controller_name {
-            "Taiko Controller"|"HORI CO.,LTD. Taiko Controller" => Self::new_default(6, 10, 11, 7),
-            "Xbox Controller"|"Microsoft X-Box One S pad"|"Microsoft X-Box One pad"|"Microsoft XBox One X pad" => Self::new_default(13, 12, 0, 1),
-            "Wireless Controller"|"Sony Interactive Entertainment Wireless Controller" => Self::new_default(17, 15, 0, 2),
-
-            _ => Self {
-                left_kat: ControllerInputConfig::new(None, None),
-                left_don: ControllerInputConfig::new(None, None),
-                right_don:ControllerInputConfig::new(None, None),
-                right_kat:ControllerInputConfig::new(None, None),
-            }
-        }
-    }
-}
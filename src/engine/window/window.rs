@@ -201,6 +201,11 @@ impl GameWindow {
                     continue;
                 }
 
+                if let Some(touch) = e.touch_args() {
+                    let _ = GAME_EVENT_SENDER.get().unwrap().try_send(GameEvent::Touch(touch.into()));
+                    continue
+                }
+
                 if let Event::Input(Input::FileDrag(FileDrag::Drop(d)), _) = e {
                     let _ = GAME_EVENT_SENDER.get().unwrap().try_send(GameEvent::DragAndDrop(d));
                     continue
@@ -65,6 +65,7 @@ async fn main() {
 
 
     let mut play_game = true;
+    let mut input_playback = None;
 
     let mut args = std::env::args().map(|s|s.to_string());
     args.next(); // skip the file param
@@ -79,12 +80,31 @@ async fn main() {
                 diff_calc_cli(&mut args).await;
             }
 
+            "--simulate" => {
+                play_game = false;
+                simulate_cli(&mut args).await;
+            }
+
+            // run a local stand-in for the server, for testing online stuff
+            "--mock-server" => {
+                play_game = false;
+                mock_server_cli(&mut args).await;
+            }
+
+            // play the game, feeding it input recorded with the record_input setting
+            "--replay-input" => {
+                let path = args.next().expect("no input recording provided");
+                let recording = InputRecording::load(&path).unwrap_or_else(|e| panic!("error loading input recording '{path}': {e}"));
+                info!("loaded input recording for map {} ({}), play it to start playback", recording.beatmap_hash, recording.playmode);
+                input_playback = Some(recording);
+            }
+
             _ => {}
         }
     }
 
     if play_game {
-        start_game().await;
+        start_game(input_playback).await;
 
         // game.await.ok().expect("error finishing game?");
         info!("byebye!");
@@ -92,11 +112,14 @@ async fn main() {
 
 }
 
-async fn start_game() {
+async fn start_game(input_playback: Option<InputRecording>) {
     let main_thread = tokio::task::LocalSet::new();
 
     let (render_queue_sender, render_queue_receiver) = TripleBuffer::default().split();
     let (game_event_sender, game_event_receiver) = tokio::sync::mpsc::channel(30);
+    if let Some(recording) = input_playback {
+        InputPlayback::spawn(recording, game_event_sender.clone());
+    }
 
     // setup window
     trace!("creating window");
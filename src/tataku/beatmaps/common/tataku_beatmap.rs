@@ -11,4 +11,7 @@ pub trait TatakuBeatmap {
     fn slider_velocity_at(&self, time:f32) -> f32;
     fn beat_length_at(&self, time:f32, allow_multiplier:bool) -> f32;
     fn control_point_at(&self, time:f32) -> TimingPoint;
+
+    /// list of (start, end) break times
+    fn get_breaks(&self) -> Vec<(f32, f32)> { Vec::new() }
 }
\ No newline at end of file
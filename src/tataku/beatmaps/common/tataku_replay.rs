@@ -1,9 +1,79 @@
 use crate::prelude::*;
 
+/// marks the extras written after the replay in a .ttkr file.
+/// older versions stop reading once they have the replay, so they just ignore whatever comes after
+const REPLAY_EXTRAS_MAGIC: &str = "TTKR_EXTRAS";
+
+/// things tataku keeps with a replay which tataku-common's [`Replay`] doesnt have room for.
+/// these are stored as json so fields can be added without breaking older files
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayExtras {
+    /// judgment preset id the replay was played with, see [`JudgmentPreset::to_id`]
+    #[serde(default)]
+    pub judgment_preset: Option<String>,
+    #[serde(default)]
+    pub scoring_system: ScoringSystemKind,
+}
+impl ReplayExtras {
+    pub fn from_score(score: &IngameScore) -> Self {
+        Self {
+            judgment_preset: score.judgment_preset.clone(),
+            scoring_system: score.scoring_system,
+        }
+    }
+
+    /// fill in anything this doesnt have from `other`
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            judgment_preset: self.judgment_preset.or(other.judgment_preset),
+            scoring_system: if self.scoring_system == ScoringSystemKind::Default { other.scoring_system } else { self.scoring_system },
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// write the extras, to go after the replay
+    pub fn write(&self, writer: &mut SerializationWriter) {
+        writer.write(REPLAY_EXTRAS_MAGIC.to_owned());
+        writer.write(self.to_json());
+    }
+
+    /// read the extras after a replay. files from before extras were stored get the defaults
+    pub fn read(reader: &mut SerializationReader) -> Self {
+        if !reader.can_read() { return Self::default() }
+        if reader.read::<String>().ok().as_deref() != Some(REPLAY_EXTRAS_MAGIC) { return Self::default() }
+
+        reader
+            .read::<String>()
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).log_error().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// write a replay and its extras as the contents of a .ttkr file
+pub fn write_tataku_replay(replay: &Replay, extras: &ReplayExtras) -> Vec<u8> {
+    SimpleWriter::new()
+        .write(replay.clone())
+        .write(REPLAY_EXTRAS_MAGIC.to_owned())
+        .write(extras.to_json())
+        .done()
+}
+
+/// read the contents of a .ttkr file
+pub fn read_tataku_replay(bytes: Vec<u8>) -> TatakuResult<(Replay, ReplayExtras)> {
+    let mut reader = SerializationReader::new(bytes);
+    let replay = reader.read()?;
+    Ok((replay, ReplayExtras::read(&mut reader)))
+}
+
 
 #[async_trait]
 pub trait ReplayDownloader: Send + Sync + std::fmt::Debug {
-    async fn get_replay(&self) -> TatakuResult<Replay>;
+    /// download the replay, along with any extras stored with it
+    async fn get_replay(&self) -> TatakuResult<(Replay, ReplayExtras)>;
 }
 
 #[derive(Debug)]
@@ -18,7 +88,7 @@ impl TatakuReplayDownloader {
 
 #[async_trait]
 impl ReplayDownloader for TatakuReplayDownloader {
-    async fn get_replay(&self) -> TatakuResult<Replay> {
+    async fn get_replay(&self) -> TatakuResult<(Replay, ReplayExtras)> {
         let base = get_settings!().score_url.clone();
 
         let url = if let Some(hash) = &self.1 {
@@ -36,7 +106,33 @@ impl ReplayDownloader for TatakuReplayDownloader {
             return Err(TatakuError::String("Downloaded file was empty".to_owned()));
         }
 
-        let replay = SerializationReader::new(bytes.to_vec()).read()?;
-        Ok(replay)
+        read_tataku_replay(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extras_round_trip() {
+        let mut replay = Replay::new();
+        replay.score_data = Some(Score::new("map_hash".to_owned(), "user".to_owned(), "mania".to_owned()));
+        let extras = ReplayExtras {
+            judgment_preset: JudgmentPreset::EtternaJ4.to_id(&[], &"mania".to_owned()),
+            scoring_system: ScoringSystemKind::Wife3,
+        };
+        assert!(extras.judgment_preset.is_some());
+
+        let (read, read_extras) = read_tataku_replay(write_tataku_replay(&replay, &extras)).unwrap();
+        assert_eq!(read.score_data.map(|s| s.hash()), replay.score_data.map(|s| s.hash()));
+        assert_eq!(read_extras, extras);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn replays_without_extras() {
+        let bytes = SimpleWriter::new().write(Replay::new()).done();
+        let (_, extras) = read_tataku_replay(bytes).unwrap();
+        assert_eq!(extras, ReplayExtras::default());
+    }
+}
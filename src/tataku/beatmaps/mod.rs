@@ -143,4 +143,15 @@ impl TatakuBeatmap for Beatmap {
             Beatmap::Stepmania(map) => map.control_point_at(time),
         }
     }
+
+    fn get_breaks(&self) -> Vec<(f32, f32)> {
+        match self {
+            Beatmap::None => Vec::new(),
+            Beatmap::Osu(map) => map.get_breaks(),
+            Beatmap::Quaver(map) => map.get_breaks(),
+            Beatmap::Adofai(map) => map.get_breaks(),
+            Beatmap::UTyping(map) => map.get_breaks(),
+            Beatmap::Stepmania(map) => map.get_breaks(),
+        }
+    }
 }
@@ -3,8 +3,10 @@ mod osu_beatmap;
 pub mod storyboard;
 mod hitobject_defs;
 mod osu_replay_converter;
+mod osu_replay_exporter;
 
 pub use osu_replay::*;
 pub use osu_beatmap::*;
 pub use hitobject_defs::*;
-pub use osu_replay_converter::*;
\ No newline at end of file
+pub use osu_replay_converter::*;
+pub use osu_replay_exporter::*;
\ No newline at end of file
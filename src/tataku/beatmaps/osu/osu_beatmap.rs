@@ -14,6 +14,8 @@ pub struct OsuBeatmap {
     pub holds: Vec<HoldDef>,
 
     pub combo_colors: Vec<Color>,
+    /// (start, end)
+    pub breaks: Vec<(f32, f32)>,
 
     // other metadata 
     pub beatmap_version: u8,
@@ -52,6 +54,7 @@ impl OsuBeatmap {
             holds: Vec::new(),
             timing_points: Vec::new(),
             combo_colors: Vec::new(),
+            breaks: Vec::new(),
 
             beatmap_version: 0,
             slider_multiplier: 1.4,
@@ -139,6 +142,15 @@ impl OsuBeatmap {
                         metadata.image_filename = parent_dir.join(filename).to_str().unwrap().to_owned();
                     
                     }
+
+                    // 2,startTime,endTime
+                    if event_type == "2" || event_type == "Break" {
+                        let start = split.next().and_then(|s|s.trim().parse::<f32>().ok());
+                        let end = split.next().and_then(|s|s.trim().parse::<f32>().ok());
+                        if let (Some(start), Some(end)) = (start, end) {
+                            beatmap.breaks.push((start, end));
+                        }
+                    }
                 }
                 BeatmapSection::TimingPoints => {
                     beatmap.timing_points.push(OsuTimingPoint::from_str(&line));
@@ -420,6 +432,10 @@ impl TatakuBeatmap for OsuBeatmap {
 
         point.into()
     }
+
+    fn get_breaks(&self) -> Vec<(f32, f32)> {
+        self.breaks.clone()
+    }
 }
 
 
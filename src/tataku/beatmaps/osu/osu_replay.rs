@@ -12,7 +12,7 @@ impl OsuReplayDownloader {
 
 #[async_trait]
 impl ReplayDownloader for OsuReplayDownloader {
-    async fn get_replay(&self) -> TatakuResult<Replay> {
+    async fn get_replay(&self) -> TatakuResult<(Replay, ReplayExtras)> {
         let key = get_settings!().osu_api_key.clone();
 
         let url = format!("https://osu.ppy.sh//api/get_replay?k={key}&s={}", self.1);
@@ -35,7 +35,7 @@ impl ReplayDownloader for OsuReplayDownloader {
         if let Some(content) = &data.content {
             let data = base64::decode(content).map_err(|e| TatakuError::String(format!("error decoding osu replay: {e}")))?;
 
-            Ok(OsuReplay::replay_from_score_and_lzma(&self.0, &mut data.as_ref())?)
+            Ok((OsuReplay::replay_from_score_and_lzma(&self.0, &mut data.as_ref())?, ReplayExtras::default()))
         } else {
             Err(TatakuError::String(data.error.unwrap_or("peppy api sucks".to_owned())))
         }
@@ -3,13 +3,16 @@ use crate::prelude::*;
 use std::convert::TryInto;
 
 pub fn convert_osu_replay(filepath: impl AsRef<Path>) -> TatakuResult<Replay> {
-    let osu_replay = read_osu_replay(filepath)?;
+    convert_osu_replay_bytes(&std::fs::read(filepath)?)
+}
+
+/// convert the contents of an osu .osr file
+pub fn convert_osu_replay_bytes(file: &[u8]) -> TatakuResult<Replay> {
+    let osu_replay = read_osu_replay(file)?;
     Ok(osu_replay.get_replay())
 }
 
-fn read_osu_replay(file: impl AsRef<Path>) -> TatakuResult<OsuReplay> {
-    let file = std::fs::read(file)?;
-    let file = file.as_slice();
+fn read_osu_replay(file: &[u8]) -> TatakuResult<OsuReplay> {
     let mut offset = 0;
 
     let game_mode = read_byte(file, &mut offset)?;
@@ -266,7 +269,7 @@ impl OsuReplay {
         }
 
         if self.mods.contains(&OsuMods::DoubleTime) {
-            score.speed = 1.33;
+            score.speed = 1.5;
         } else if self.mods.contains(&OsuMods::HalfTime) {
             score.speed = 0.75;
         }
@@ -275,7 +278,7 @@ impl OsuReplay {
         {
             let mods = score.mods_mut();
             if self.mods.contains(&OsuMods::Easy) { mods.insert("easy".to_owned()); }
-            if self.mods.contains(&OsuMods::HardRock) { mods.insert("hard_rock".to_owned()); }
+            if self.mods.contains(&OsuMods::HardRock) { mods.insert("hardrock".to_owned()); }
             if self.mods.contains(&OsuMods::Autoplay) { mods.insert("autoplay".to_owned()); }
             if self.mods.contains(&OsuMods::NoFail) { mods.insert("no_fail".to_owned()); }
         }
@@ -466,6 +469,44 @@ impl OsuMods {
             _ => Self::None,
         }
     }
+
+    pub fn to_num(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::NoFail => 1,
+            Self::Easy => 2,
+            Self::TouchDevice => 4,
+            Self::Hidden => 8,
+            Self::HardRock => 16,
+            Self::SuddenDeath => 32,
+            Self::DoubleTime => 64,
+            Self::Relax => 128,
+            Self::HalfTime => 256,
+            Self::Nightcore => 512,
+            Self::Flashlight => 1024,
+            Self::Autoplay => 2048,
+            Self::SpunOut => 4096,
+            Self::Relax2 => 8192,
+            Self::Perfect => 16384,
+            Self::Key4 => 32768,
+            Self::Key5 => 65536,
+            Self::Key6 => 131072,
+            Self::Key7 => 262144,
+            Self::Key8 => 524288,
+            Self::KeyMod => 1015808,
+            Self::FadeIn => 1048576,
+            Self::Random => 2097152,
+            Self::LastMod => 4194304,
+            Self::TargetPractice => 8388608,
+            Self::Key9 => 16777216,
+            Self::Coop => 33554432,
+            Self::Key1 => 67108864,
+            Self::Key3 => 134217728,
+            Self::Key2 => 268435456,
+            Self::ScoreV2 => 536870912,
+            Self::Mirror => 1073741824,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -536,3 +577,58 @@ fn get_mania_key(i: u32) -> KeyPress {
         _ => KeyPress::Unknown,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn osu_replay() -> OsuReplay {
+        OsuReplay {
+            game_mode: "osu".to_owned(),
+            game_version: 20230326,
+            map_hash: "map".to_owned(),
+            username: "player".to_owned(),
+            replay_hash: String::new(),
+            x300: 2,
+            x100: 1,
+            x50: 0,
+            geki: 0,
+            katu: 0,
+            miss: 0,
+            score: 1000,
+            max_combo: 3,
+            perfect: true,
+            mods: Vec::new(),
+            health: Vec::new(),
+            timestamp: 0,
+            score_id: 0,
+            replay_frames: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn imported_scores_get_simulated_timings() {
+        let replay = osu_replay().get_replay();
+        let mut score = IngameScore::new(replay.score_data.unwrap(), false, false);
+        // osu replays only have key presses
+        assert!(score.hit_stats().is_none());
+
+        let mut simulated = HitTimings::new();
+        simulated.add(1000.0, -10.0, "x300");
+        simulated.add(2000.0, 10.0, "x300");
+        simulated.add(3000.0, 60.0, "x100");
+        assert!(score.fill_missing_timings(simulated));
+
+        let stats = score.hit_stats().expect("score should have stats once its filled");
+        assert_eq!(stats.early_count, 1);
+        assert_eq!(stats.late_count, 2);
+        assert_eq!(score.timings.judgment_of(&score.timings.timings[2]), "x100");
+
+        // timings the score already has are kept
+        let mut other = HitTimings::new();
+        other.add(1000.0, 0.0, "x300");
+        assert!(!score.fill_missing_timings(other));
+        assert_eq!(score.timings.timings.len(), 3);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::prelude::*;
+
+/// version written to exported replays. anything after 20130319 expects an rng seed frame at the end
+const OSU_GAME_VERSION: u32 = 20230326;
+/// how often the life bar graph is sampled (ms)
+const HEALTH_SAMPLE_INTERVAL: f32 = 2000.0;
+/// taiko replays only store presses, but osu needs the key to be released at some point
+const TAIKO_PRESS_LENGTH: f32 = 30.0;
+/// ticks between 0001-01-01 (what .net uses) and the unix epoch
+const UNIX_EPOCH_TICKS: u64 = 621355968000000000;
+
+/// convert a replay and its score to an osu .osr file
+pub async fn export_osu_replay(replay: &Replay, extras: &ReplayExtras, score: &Score, beatmap: &BeatmapMeta) -> TatakuResult<Vec<u8>> {
+    osu_game_mode(&score.playmode)?;
+
+    // osu identifies maps by the md5 of the .osu file, which is only what our hash is for osu maps
+    if beatmap.beatmap_type != BeatmapType::Osu {
+        return Err(TatakuError::String("Only replays on osu maps can be exported to osu".to_owned()));
+    }
+    let map_hash = get_file_hash(&beatmap.file_path)?;
+
+    // the life bar isnt stored with the score, so play the replay back to get it
+    let mut replay_with_score = replay.clone();
+    replay_with_score.score_data = Some(score.clone());
+    let health = match ReplaySimulator::simulate_with_health(&replay_with_score, extras, beatmap, HEALTH_SAMPLE_INTERVAL).await {
+        Ok((_, health)) => health.iter().map(|(time, ratio)| format!("{}|{ratio}", *time as u32)).collect::<Vec<_>>().join(","),
+        Err(e) => {
+            warn!("error getting health for replay export, life bar will be empty: {e}");
+            String::new()
+        }
+    };
+
+    write_osu_replay(replay, score, &map_hash, &health)
+}
+
+fn osu_game_mode(playmode: &PlayMode) -> TatakuResult<u8> {
+    match &**playmode {
+        "osu" => Ok(0),
+        "taiko" => Ok(1),
+        "mania" => Ok(3),
+        other => Err(TatakuError::String(format!("{} replays cant be exported to osu", gamemode_display_name(&other.to_owned())))),
+    }
+}
+
+/// write the .osr file. `health` is osu's `time|ratio` life bar string
+fn write_osu_replay(replay: &Replay, score: &Score, map_hash: &str, health: &str) -> TatakuResult<Vec<u8>> {
+    let game_mode = osu_game_mode(&score.playmode)?;
+
+    let judgment = |key: &str| score.judgments.get(key).copied().unwrap_or_default();
+    let (x300, x100, x50, geki, katu, miss) = match game_mode {
+        // osu counts the big note judgments as part of the regular ones
+        1 => (
+            judgment("x300").saturating_add(judgment("xgeki")),
+            judgment("x100").saturating_add(judgment("xkatu")),
+            0,
+            judgment("xgeki"),
+            judgment("xkatu"),
+            judgment("xmiss"),
+        ),
+        _ => (judgment("x300"), judgment("x100"), judgment("x50"), judgment("xgeki"), judgment("xkatu"), judgment("xmiss")),
+    };
+
+    let frames = OsuFrameWriter::convert(&score.playmode, &replay.frames);
+    let mut frame_data = Vec::new();
+    lzma_rs::lzma_compress_with_options(
+        &mut frames.as_bytes(),
+        &mut frame_data,
+        &lzma_rs::compress::Options { unpacked_size: lzma_rs::compress::UnpackedSize::WriteToHeader(Some(frames.len() as u64)) }
+    )?;
+
+    let mut bytes = Vec::new();
+    write_byte(&mut bytes, game_mode);
+    write_int(&mut bytes, OSU_GAME_VERSION);
+    write_string(&mut bytes, map_hash);
+    write_string(&mut bytes, &score.username);
+    write_string(&mut bytes, &md5(score.hash()));
+
+    write_short(&mut bytes, x300);
+    write_short(&mut bytes, x100);
+    write_short(&mut bytes, x50);
+    write_short(&mut bytes, geki);
+    write_short(&mut bytes, katu);
+    write_short(&mut bytes, miss);
+
+    write_int(&mut bytes, score.score.min(u32::MAX as u64) as u32);
+    write_short(&mut bytes, score.max_combo);
+    write_byte(&mut bytes, (miss == 0) as u8);
+    write_int(&mut bytes, get_osu_mods(score)?);
+    write_string(&mut bytes, health);
+    write_long(&mut bytes, score.time * 10_000_000 + UNIX_EPOCH_TICKS);
+
+    write_int(&mut bytes, frame_data.len() as u32);
+    bytes.extend(frame_data);
+
+    // online score id, which we dont have
+    write_long(&mut bytes, 0);
+
+    Ok(bytes)
+}
+
+fn get_osu_mods(score: &Score) -> TatakuResult<u32> {
+    let score_mods = score.mods();
+    let mut mods = Vec::new();
+
+    for (name, osu_mod) in [
+        ("no_fail", OsuMods::NoFail),
+        ("easy", OsuMods::Easy),
+        ("hardrock", OsuMods::HardRock),
+        ("sudden_death", OsuMods::SuddenDeath),
+        ("perfect", OsuMods::Perfect),
+        ("autoplay", OsuMods::Autoplay),
+        ("relax", OsuMods::Relax),
+    ] {
+        if score_mods.contains(name) { mods.push(osu_mod) }
+    }
+
+    // osu only has the two speeds, anything else would play back wrong
+    let is_speed = |speed: f32| (score.speed - speed).abs() < 0.001;
+    if is_speed(1.5) {
+        mods.push(OsuMods::DoubleTime);
+    } else if is_speed(0.75) {
+        mods.push(OsuMods::HalfTime);
+    } else if !is_speed(1.0) {
+        return Err(TatakuError::String(format!("osu has no mod for {:.2}x speed", score.speed)));
+    }
+
+    Ok(mods.iter().fold(0, |flags, m| flags | m.to_num()))
+}
+
+
+/// turns tataku replay frames into osu's `time delta|x|y|keys` frame string
+struct OsuFrameWriter {
+    /// (time, x, y, keys)
+    frames: Vec<(f32, f32, f32, u32)>,
+    x: f32,
+    y: f32,
+    keys: u32,
+    /// mania stores the keys in the x pos
+    mania: bool,
+}
+impl OsuFrameWriter {
+    fn convert(playmode: &String, tataku_frames: &Vec<(f32, ReplayFrame)>) -> String {
+        let mut writer = Self { frames: Vec::new(), x: 0.0, y: 0.0, keys: 0, mania: playmode == "mania" };
+        let taiko = playmode == "taiko";
+        // time of the last taiko press, whose keys still need releasing
+        let mut pending_release: Option<f32> = None;
+
+        for (time, frame) in tataku_frames.iter() {
+            let time = *time;
+
+            // release taiko keys once we're past the frame they were pressed on
+            if let Some(press_time) = pending_release.filter(|t| t.round() < time.round()) {
+                writer.keys = 0;
+                if press_time + TAIKO_PRESS_LENGTH < time {
+                    writer.push(press_time + TAIKO_PRESS_LENGTH);
+                }
+                pending_release = None;
+            }
+
+            match frame {
+                ReplayFrame::MousePos(x, y) => {
+                    writer.x = *x;
+                    writer.y = *y;
+                }
+                ReplayFrame::Press(key) => writer.keys |= get_key_flags(playmode, *key),
+                ReplayFrame::Release(key) => writer.keys &= !get_key_flags(playmode, *key),
+            }
+            writer.push(time);
+
+            if taiko && writer.keys != 0 {
+                pending_release = Some(time);
+            }
+        }
+        if let Some(press_time) = pending_release {
+            writer.keys = 0;
+            writer.push(press_time + TAIKO_PRESS_LENGTH);
+        }
+
+        // osu always starts replays with these two frames
+        let mut output = "0|256|-500|0,-1|256|-500|0,".to_owned();
+        let mut last_time = -1;
+        for (time, x, y, keys) in writer.frames {
+            let time = time.round() as i64;
+            output += &format!("{}|{x}|{y}|{keys},", time - last_time);
+            last_time = time;
+        }
+
+        // rng seed, which we dont have
+        output += "-12345|0|0|0";
+        output
+    }
+
+    /// add a frame with the current state. if theres already a frame at this time, it gets replaced
+    fn push(&mut self, time: f32) {
+        let frame = if self.mania {
+            (time, self.keys as f32, 0.0, 0)
+        } else {
+            (time, self.x, self.y, self.keys)
+        };
+
+        match self.frames.last_mut() {
+            Some(last) if last.0.round() == time.round() => *last = (last.0, frame.1, frame.2, frame.3),
+            _ => self.frames.push(frame),
+        }
+    }
+}
+
+/// get the osu key flags for a key. for mania, these end up in the x pos
+fn get_key_flags(playmode: &String, key: KeyPress) -> u32 {
+    match (&**playmode, key) {
+        // osu sets the mouse button flag along with the key flag
+        ("osu", KeyPress::LeftMouse) => 1,
+        ("osu", KeyPress::RightMouse) => 2,
+        ("osu", KeyPress::Left) => 1 | 4,
+        ("osu", KeyPress::Right) => 2 | 8,
+        ("osu", KeyPress::Dash) => 16,
+
+        ("taiko", KeyPress::LeftDon) => 1,
+        ("taiko", KeyPress::LeftKat) => 2,
+        ("taiko", KeyPress::RightDon) => 4,
+        ("taiko", KeyPress::RightKat) => 8,
+
+        ("mania", KeyPress::Mania1) => 1 << 0,
+        ("mania", KeyPress::Mania2) => 1 << 1,
+        ("mania", KeyPress::Mania3) => 1 << 2,
+        ("mania", KeyPress::Mania4) => 1 << 3,
+        ("mania", KeyPress::Mania5) => 1 << 4,
+        ("mania", KeyPress::Mania6) => 1 << 5,
+        ("mania", KeyPress::Mania7) => 1 << 6,
+        ("mania", KeyPress::Mania8) => 1 << 7,
+        ("mania", KeyPress::Mania9) => 1 << 8,
+
+        _ => 0,
+    }
+}
+
+
+fn write_byte(bytes: &mut Vec<u8>, b: u8) {
+    bytes.push(b);
+}
+fn write_short(bytes: &mut Vec<u8>, n: u16) {
+    bytes.extend(n.to_le_bytes());
+}
+fn write_int(bytes: &mut Vec<u8>, n: u32) {
+    bytes.extend(n.to_le_bytes());
+}
+fn write_long(bytes: &mut Vec<u8>, n: u64) {
+    bytes.extend(n.to_le_bytes());
+}
+
+fn write_string(bytes: &mut Vec<u8>, s: &str) {
+    if s.is_empty() {
+        bytes.push(0x00);
+        return;
+    }
+
+    bytes.push(0x0b);
+    write_uleb128(bytes, s.len() as u128);
+    bytes.extend(s.as_bytes());
+}
+
+fn write_uleb128(bytes: &mut Vec<u8>, mut n: u128) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+
+        if n == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_converter() {
+        let mut replay = Replay::new();
+        replay.frames = vec![
+            (1000.0, ReplayFrame::Press(KeyPress::Mania1)),
+            (1050.0, ReplayFrame::Press(KeyPress::Mania2)),
+            (1100.0, ReplayFrame::Release(KeyPress::Mania1)),
+            (1150.0, ReplayFrame::Release(KeyPress::Mania2)),
+        ];
+
+        let mut score = Score::new("map".to_owned(), "player".to_owned(), "mania".to_owned());
+        score.score = 123456;
+        score.max_combo = 2;
+        score.speed = 1.5;
+        for (key, count) in [("xgeki", 1), ("x300", 1), ("xkatu", 0), ("x100", 0), ("x50", 0), ("xmiss", 0)] {
+            score.judgments.insert(key.to_owned(), count);
+        }
+        score.mods_mut().insert("no_fail".to_owned());
+        score.mods_mut().insert("hardrock".to_owned());
+
+        let bytes = write_osu_replay(&replay, &score, "osu map hash", "0|1,2000|0.5").unwrap();
+        let converted = convert_osu_replay_bytes(&bytes).unwrap();
+        assert_eq!(converted.frames, replay.frames);
+
+        let converted_score = converted.score_data.unwrap();
+        assert_eq!(converted_score.beatmap_hash, "osu map hash");
+        assert_eq!(converted_score.username, score.username);
+        assert_eq!(converted_score.playmode, score.playmode);
+        assert_eq!(converted_score.score, score.score);
+        assert_eq!(converted_score.max_combo, score.max_combo);
+        assert_eq!(converted_score.speed, score.speed);
+        assert_eq!(converted_score.judgments, score.judgments);
+        assert_eq!(converted_score.mods(), score.mods());
+    }
+}
@@ -1,5 +1,7 @@
 mod quaver_replay;
 mod quaver_beatmap;
+mod quaver_replay_converter;
 
 pub use quaver_replay::*;
-pub use quaver_beatmap::*;
\ No newline at end of file
+pub use quaver_beatmap::*;
+pub use quaver_replay_converter::*;
\ No newline at end of file
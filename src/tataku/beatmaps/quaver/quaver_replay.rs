@@ -12,21 +12,19 @@ impl QuaverReplayDownloader {
 
 #[async_trait]
 impl ReplayDownloader for QuaverReplayDownloader {
-    async fn get_replay(&self) -> TatakuResult<Replay> {
-        Err(TatakuError::String("Not Implemented".to_owned()))
-        // https://quavergame.com/download/replay/48727123
+    async fn get_replay(&self) -> TatakuResult<(Replay, ReplayExtras)> {
+        let base = get_settings!().quaver_url.clone();
+        let url = format!("{base}/download/replay/{}", self.1);
 
-        // let url = format!("https://quavergame.com/download/replay/{}", self.1);
-
-        // // this should be a .qr file, but cloudflare is breaking everything right now
-        // let bytes = reqwest::get(url).await?.bytes().await?;
+        // this should be a .qr file
+        let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
         
-        // // check if the received data 
-        // if bytes.len() == 0 {
-        //     return Err(TatakuError::String("Downloaded file was empty".to_owned()));
-        // }
+        // check if the received data 
+        if bytes.len() == 0 {
+            return Err(TatakuError::String("Downloaded file was empty".to_owned()));
+        }
 
-        // // TODO! parse replay
-        // Ok(replay)
+        let replay = read_quaver_replay(&bytes)?;
+        Ok((replay.get_replay(), replay.get_extras()))
     }
 }
\ No newline at end of file
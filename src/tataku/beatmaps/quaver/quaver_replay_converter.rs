@@ -0,0 +1,374 @@
+use crate::prelude::*;
+use std::convert::TryInto;
+
+pub fn convert_quaver_replay(filepath: impl AsRef<Path>) -> TatakuResult<(Replay, ReplayExtras)> {
+    let bytes = std::fs::read(filepath)?;
+    let replay = read_quaver_replay(&bytes)?;
+    Ok((replay.get_replay(), replay.get_extras()))
+}
+
+/// parse a .qr file
+pub fn read_quaver_replay(file: &[u8]) -> TatakuResult<QuaverReplay> {
+    let mut offset = 0;
+
+    let quaver_version = read_string(file, &mut offset)?;
+    let map_hash = read_string(file, &mut offset)?;
+    let _replay_hash = read_string(file, &mut offset)?;
+    let username = read_string(file, &mut offset)?;
+    // the date string is broken in older replays, so quaver uses the time played instead
+    let _date = read_string(file, &mut offset)?;
+    let time_played = read_long(file, &mut offset)?;
+
+    let game_mode = read_int(file, &mut offset)?;
+    // mirror is the 31st bit, so the mods had to be widened to an int64 at some point
+    let mods = if quaver_version == "None" {
+        read_int(file, &mut offset)? as u64
+    } else {
+        read_long(file, &mut offset)?
+    };
+
+    let score = read_int(file, &mut offset)?;
+    let accuracy = f32::from_bits(read_int(file, &mut offset)?);
+    let max_combo = read_int(file, &mut offset)?;
+
+    let count_marv = read_int(file, &mut offset)?;
+    let count_perf = read_int(file, &mut offset)?;
+    let count_great = read_int(file, &mut offset)?;
+    let count_good = read_int(file, &mut offset)?;
+    let count_okay = read_int(file, &mut offset)?;
+    let count_miss = read_int(file, &mut offset)?;
+    let _pause_count = read_int(file, &mut offset)?;
+
+    // the randomize seed was added in 0.0.1
+    if quaver_version != "None" {
+        let _randomize_seed = read_int(file, &mut offset)?;
+    }
+
+    // the rest of the file is the lzma stream
+    let frames = parse_lzma_stream(&mut &file[offset..])?;
+
+    Ok(QuaverReplay {
+        map_hash,
+        username,
+        time_played,
+        key_count: if game_mode == 2 { 7 } else { 4 },
+        mods,
+        score,
+        accuracy,
+        max_combo,
+        count_marv,
+        count_perf,
+        count_great,
+        count_good,
+        count_okay,
+        count_miss,
+        frames,
+    })
+}
+
+fn parse_lzma_stream(lzma: &mut impl std::io::BufRead) -> TatakuResult<Vec<(i32, u32)>> {
+    let mut decompressed = Vec::new();
+    if let Err(e) = lzma_rs::lzma_decompress(lzma, &mut decompressed) {
+        return Err(TatakuError::String(format!("Error decompressing replay data: {e}")))
+    }
+    let replay_str = String::from_utf8_lossy(&decompressed);
+
+    // frames are `time|keys`, where time is absolute and keys is a bitfield of the pressed lanes
+    let mut frames = Vec::new();
+    for i in replay_str.split(",") {
+        if i.len() == 0 { continue }
+        let mut split = i.split("|");
+
+        macro_rules! parse {
+            ($seg:expr) => {{
+                let str = split.next().ok_or(TatakuError::String(format!("missing {} segment in replay string", $seg)))?;
+                str.trim().parse().map_err(|e| TatakuError::String(format!("{e}")))?
+            }};
+        }
+
+        let time: i32 = parse!("time");
+        let keys: u32 = parse!("keys");
+        frames.push((time, keys));
+    }
+
+    Ok(frames)
+}
+
+
+fn read_int(bytes: &[u8], offset: &mut usize) -> TatakuResult<u32> {
+    if *offset + 4 > bytes.len() { return Err(TatakuError::String(format!("buffer overflow"))); }
+
+    let val = u32::from_le_bytes(bytes[*offset..(*offset + 4)].try_into().unwrap());
+    *offset += 4;
+    Ok(val)
+}
+fn read_long(bytes: &[u8], offset: &mut usize) -> TatakuResult<u64> {
+    if *offset + 8 > bytes.len() { return Err(TatakuError::String(format!("buffer overflow"))); }
+
+    let val = u64::from_le_bytes(bytes[*offset..(*offset + 8)].try_into().unwrap());
+    *offset += 8;
+    Ok(val)
+}
+
+/// c# BinaryReader string, a 7-bit encoded length followed by the utf8 bytes
+fn read_string(bytes: &[u8], offset: &mut usize) -> TatakuResult<String> {
+    let mut len = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*offset).ok_or(TatakuError::String(format!("buffer overflow")))?;
+        *offset += 1;
+
+        len |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 { break }
+        shift += 7;
+    }
+
+    if *offset + len > bytes.len() { return Err(TatakuError::String(format!("buffer overflow"))); }
+    let string = String::from_utf8(bytes[*offset..(*offset + len)].to_vec()).map_err(|e| format!("error parsing string: {e}"))?;
+    *offset += len;
+    Ok(string)
+}
+
+
+#[derive(Clone, Debug)]
+pub struct QuaverReplay {
+    map_hash: String,
+    username: String,
+    /// unix ms
+    time_played: u64,
+    key_count: u8,
+
+    mods: u64,
+    score: u32,
+    accuracy: f32,
+    max_combo: u32,
+
+    count_marv: u32,
+    count_perf: u32,
+    count_great: u32,
+    count_good: u32,
+    count_okay: u32,
+    count_miss: u32,
+
+    /// (time, keys)
+    frames: Vec<(i32, u32)>,
+}
+impl QuaverReplay {
+    pub fn get_score(&self) -> Score {
+        let mut score = Score::new(self.map_hash.clone(), self.username.clone(), "mania".to_owned());
+        score.score = self.score as u64;
+        score.max_combo = self.max_combo as u16;
+        score.time = self.time_played / 1000;
+        score.accuracy = self.accuracy as f64 / 100.0;
+
+        for (key, count) in [
+            ("xgeki", self.count_marv),
+            ("x300", self.count_perf),
+            ("xkatu", self.count_great),
+            ("x100", self.count_good),
+            ("x50", self.count_okay),
+            ("xmiss", self.count_miss),
+        ] {
+            score.judgments.insert(key.to_owned(), count as u16);
+        }
+
+        let mods = QuaverMods::to_mod_manager(self.mods);
+        score.speed = mods.get_speed();
+        *score.mods_mut() = mods.mods;
+
+        score
+    }
+
+    /// strict and chill are mods in quaver, but theyre judgment presets for us, so they go with the replay instead of the score
+    pub fn get_extras(&self) -> ReplayExtras {
+        ReplayExtras {
+            judgment_preset: QuaverMods::to_mod_manager(self.mods).judgment_preset,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_replay(&self) -> Replay {
+        let mut replay = Replay::new();
+        replay.score_data = Some(self.get_score());
+
+        let mut pressed = 0u32;
+        for (time, keys) in self.frames.iter() {
+            let time = *time as f32;
+
+            for lane in 0..self.key_count as u32 {
+                let bit = 1 << lane;
+                let key = get_mania_key(lane);
+
+                if keys & bit > 0 && pressed & bit == 0 {
+                    replay.frames.push((time, ReplayFrame::Press(key)));
+                } else if keys & bit == 0 && pressed & bit > 0 {
+                    replay.frames.push((time, ReplayFrame::Release(key)));
+                }
+            }
+
+            pressed = *keys;
+        }
+
+        replay
+    }
+}
+
+/// quaver's ModIdentifier bitflags, from https://github.com/Quaver/Quaver.API/blob/master/Quaver.API/Enums/ModIdentifier.cs
+pub struct QuaverMods;
+impl QuaverMods {
+    const STRICT: u64 = 1 << 16;
+    const CHILL: u64 = 1 << 17;
+    const AUTOPLAY: u64 = 1 << 19;
+    const NO_FAIL: u64 = 1 << 21;
+
+    /// speed mods, and the rate they set
+    const SPEEDS: &[(u64, f32)] = &[
+        (1 << 1, 0.5),
+        (1 << 2, 0.6),
+        (1 << 3, 0.7),
+        (1 << 4, 0.8),
+        (1 << 5, 0.9),
+        (1 << 6, 1.1),
+        (1 << 7, 1.2),
+        (1 << 8, 1.3),
+        (1 << 9, 1.4),
+        (1 << 10, 1.5),
+        (1 << 11, 1.6),
+        (1 << 12, 1.7),
+        (1 << 13, 1.8),
+        (1 << 14, 1.9),
+        (1 << 15, 2.0),
+        (1 << 24, 0.55),
+        (1 << 25, 0.65),
+        (1 << 26, 0.75),
+        (1 << 27, 0.85),
+        (1 << 28, 0.95),
+        (1 << 33, 1.05),
+        (1 << 34, 1.15),
+        (1 << 35, 1.25),
+        (1 << 36, 1.35),
+        (1 << 37, 1.45),
+        (1 << 38, 1.55),
+        (1 << 39, 1.65),
+        (1 << 40, 1.75),
+        (1 << 41, 1.85),
+        (1 << 42, 1.95),
+    ];
+
+    /// convert quaver's mod flags to our mods. anything we dont have an equivalent for is dropped
+    pub fn to_mod_manager(flags: u64) -> ModManager {
+        let mut mods = ModManager::new();
+        let playmode = "mania".to_owned();
+
+        if let Some((_, speed)) = Self::SPEEDS.iter().find(|(flag, _)| flags & flag > 0) {
+            mods.set_speed(*speed);
+        }
+
+        if flags & Self::NO_FAIL > 0 { mods.add_mod(NoFail.name()); }
+        if flags & Self::AUTOPLAY > 0 { mods.add_mod(Autoplay.name()); }
+
+        // strict and chill change the judgment windows
+        let preset = if flags & Self::STRICT > 0 {
+            Some(JudgmentPreset::QuaverStrict)
+        } else if flags & Self::CHILL > 0 {
+            Some(JudgmentPreset::QuaverRelaxed)
+        } else {
+            None
+        };
+        mods.judgment_preset = preset.and_then(|p| p.to_id(&[], &playmode));
+
+        mods
+    }
+}
+
+fn get_mania_key(lane: u32) -> KeyPress {
+    match lane {
+        0 => KeyPress::Mania1,
+        1 => KeyPress::Mania2,
+        2 => KeyPress::Mania3,
+        3 => KeyPress::Mania4,
+        4 => KeyPress::Mania5,
+        5 => KeyPress::Mania6,
+        6 => KeyPress::Mania7,
+        7 => KeyPress::Mania8,
+        8 => KeyPress::Mania9,
+        _ => KeyPress::Unknown,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a 4k replay at 1.5x with strict and no fail. frames are `0|0,100|1,150|0,200|3,260|2,300|0`
+    const TEST_REPLAY: &[u8] = include_bytes!("test_data/replay.qr");
+
+    #[test]
+    fn parses_header() {
+        let replay = read_quaver_replay(TEST_REPLAY).unwrap();
+        let score = replay.get_score();
+
+        assert_eq!(score.beatmap_hash, "map_hash");
+        assert_eq!(score.username, "tester");
+        assert_eq!(score.playmode, "mania");
+        assert_eq!(score.time, 1600000000);
+        assert_eq!(score.score, 900000);
+        assert_eq!(score.max_combo, 10);
+        assert!((score.accuracy - 0.985).abs() < 0.0001);
+
+        for (key, count) in [("xgeki", 5), ("x300", 2), ("xkatu", 1), ("x100", 1), ("x50", 0), ("xmiss", 1)] {
+            assert_eq!(score.judgments.get(key), Some(&count), "{key}");
+        }
+    }
+
+    #[test]
+    fn parses_frames() {
+        let replay = read_quaver_replay(TEST_REPLAY).unwrap().get_replay();
+
+        assert_eq!(replay.frames, vec![
+            (100.0, ReplayFrame::Press(KeyPress::Mania1)),
+            (150.0, ReplayFrame::Release(KeyPress::Mania1)),
+            (200.0, ReplayFrame::Press(KeyPress::Mania1)),
+            (200.0, ReplayFrame::Press(KeyPress::Mania2)),
+            (260.0, ReplayFrame::Release(KeyPress::Mania1)),
+            (300.0, ReplayFrame::Release(KeyPress::Mania2)),
+        ]);
+    }
+
+    #[test]
+    fn maps_replay_mods() {
+        let replay = read_quaver_replay(TEST_REPLAY).unwrap();
+        let score = replay.get_score();
+        assert_eq!(score.speed, 1.5);
+        assert!(score.mods().contains(NoFail.name()));
+
+        // the preset goes with the replay, not in the score's mods
+        assert!(!score.mods().iter().any(|m| JudgmentPreset::is_preset_mod(m)));
+        let extras = replay.get_extras();
+        assert_eq!(JudgmentPreset::from_id(extras.judgment_preset.as_ref(), &"mania".to_owned()).0, JudgmentPreset::QuaverStrict);
+    }
+
+    #[test]
+    fn maps_mod_flags() {
+        let mods = QuaverMods::to_mod_manager(0);
+        assert_eq!(mods.get_speed(), 1.0);
+        assert!(mods.mods.is_empty());
+        assert_eq!(mods.judgment_preset, None);
+
+        // the 0.05 rates are in the upper bits
+        assert_eq!(QuaverMods::to_mod_manager(1 << 35).get_speed(), 1.25);
+        assert_eq!(QuaverMods::to_mod_manager(1 << 2).get_speed(), 0.6);
+
+        let mods = QuaverMods::to_mod_manager(QuaverMods::CHILL | QuaverMods::AUTOPLAY);
+        assert!(mods.has_autoplay());
+        assert_eq!(JudgmentPreset::from_id(mods.judgment_preset.as_ref(), &"mania".to_owned()).0, JudgmentPreset::QuaverRelaxed);
+        // the preset isnt a mod, so it doesnt change the score hash
+        assert!(!mods.mods.iter().any(|m| JudgmentPreset::is_preset_mod(m)));
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        assert!(read_quaver_replay(&TEST_REPLAY[..40]).is_err());
+    }
+}
@@ -51,19 +51,20 @@ pub async fn diff_calc_cli(args: &mut impl Iterator<Item = String>) {
 
         // make sure everythings updated
         for mode in AVAILABLE_PLAYMODES {
-            do_diffcalc(mode.to_owned().to_owned()).await;
+            do_diffcalc(mode.to_owned().to_owned(), &ModManager::default()).await;
         }
 
-        let mut mods = ModManager::default();
-        mods.set_speed(score.speed);
+        let mods = ModManager::default()
+            .with_speed_f32(score.speed)
+            .with_mods(score.mods());
+        do_diffcalc(score.playmode.clone(), &mods).await;
 
-        let diff = get_diff(&map, &score.playmode, &mods).unwrap_or_default();
+        let diff = get_diff_attributes(&map, &score.playmode, &mods).unwrap_or_default();
+        println!("got diff: {:.2} ({})", diff.diff, diff.skills_string());
 
         // calc the performance
-        let info = get_gamemode_info(&score.playmode).unwrap();
-        let perf_fn = info.get_perf_calc();
-        let perf = (perf_fn)(diff, score.accuracy as f32);
-        println!("got perf: {perf}");
+        let perf = calc_perf_with_diff(&score, &map, &diff);
+        println!("got perf: {:.2} ({})", perf.total, perf.breakdown_string());
         return;
     }
 
@@ -113,8 +114,8 @@ pub async fn diff_calc_cli(args: &mut impl Iterator<Item = String>) {
             for mut mods in mod_mutations.clone() {
                 mods.set_speed(speed as f32 / 100.0);
 
-                let diff = calc.calc(&mods).await.unwrap_or(0.0).normal_or(0.0);
-                data.add(&map, mods.speed as u32, diff, playmode.clone());
+                let diff = calc.calc(&mods).await.unwrap_or_default();
+                data.add(&map, mods.speed as u32, diff.diff, playmode.clone());
             }
         }
 
@@ -139,7 +140,7 @@ pub async fn diff_calc_cli(args: &mut impl Iterator<Item = String>) {
 
         // make sure everythings updated
         for mode in AVAILABLE_PLAYMODES {
-            do_diffcalc(mode.to_owned().to_owned()).await;
+            do_diffcalc(mode.to_owned().to_owned(), &ModManager::default()).await;
         }
 
         // do the thing
@@ -153,7 +154,7 @@ pub async fn diff_calc_cli(args: &mut impl Iterator<Item = String>) {
 
         for (a, b) in &*diffs {
             if let Some(map) = manager.get_by_hash(&a.map_hash) {
-                data.add(&map, a.mods.speed as u32, *b, a.playmode.clone());
+                data.add(&map, a.mods.speed as u32, b.diff, a.playmode.clone());
             }
         }
     }
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+/// default port for the mock websocket server. the http server uses the port after it
+const DEFAULT_MOCK_PORT:u16 = 7270;
+
+pub async fn mock_server_cli(args: &mut impl Iterator<Item = String>) {
+    let args = MockServerArgs::from_args(args);
+
+    let scenario = match &args.scenario {
+        Some(path) => MockScenario::load(path).unwrap_or_else(|e| panic!("error loading scenario '{path}': {e}")),
+        None => MockScenario::default(),
+    };
+
+    let port = args.port.unwrap_or(DEFAULT_MOCK_PORT);
+    let server = MockServer::start_on(&format!("127.0.0.1:{port}"), &format!("127.0.0.1:{}", port + 1), scenario)
+        .await
+        .expect("error starting mock server");
+
+    println!("mock server running");
+    println!("set server_url to {}", server.server_url);
+    println!("set score_url to {}", server.score_url);
+    println!("press ctrl+c to stop");
+
+    tokio::signal::ctrl_c().await.expect("error waiting for ctrl+c");
+}
+
+#[derive(Default)]
+pub struct MockServerArgs {
+    pub scenario: Option<String>,
+    pub port: Option<u16>,
+}
+impl MockServerArgs {
+    pub fn from_args(args: &mut impl Iterator<Item = String>) -> Self {
+        let mut data = Self::default();
+
+        while let Some(other_arg) = args.next() {
+            match &*other_arg {
+                "--port" => data.port = args.next().and_then(|p| p.parse().ok()),
+                _ if data.scenario.is_none() => data.scenario = Some(other_arg),
+                _ => {}
+            }
+        }
+
+        data
+    }
+}
@@ -1,3 +1,7 @@
+mod simulate;
 mod diff_calc;
+mod mock_server;
 
-pub use diff_calc::*;
\ No newline at end of file
+pub use simulate::*;
+pub use diff_calc::*;
+pub use mock_server::*;
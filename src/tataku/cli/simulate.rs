@@ -0,0 +1,60 @@
+use crate::prelude::*;
+
+
+// entry point for this command
+pub async fn simulate_cli(args: &mut impl Iterator<Item = String>) {
+    let args = SimulateArgs::from_args(args);
+    let replay_path = args.replay_file.expect("no replay file provided");
+
+    let (replay, extras) = read_other_game_replay(&replay_path).await.expect("error reading replay file provided");
+    let score = replay.score_data.clone().expect("This replay has no score data");
+
+    // load the map, by hash or path if provided, otherwise by the score's map hash
+    let map_hash_or_path = args.map.unwrap_or_else(|| score.beatmap_hash.clone());
+    let map = match Database::get_all_beatmaps().await.into_iter().find(|m| m.beatmap_hash == map_hash_or_path) {
+        Some(map) => map,
+        None => match Beatmap::load_multiple(&map_hash_or_path) {
+            Ok(maps) => maps.get(0).map(|m|m.get_beatmap_meta()).expect("no beatmap?"),
+            Err(e) => panic!("error loading beatmap '{}': {}", map_hash_or_path, e),
+        }
+    };
+
+    info!("simulating replay...");
+    let verification = ReplaySimulator::verify(&replay, &extras, &map).await.expect("error simulating replay");
+    let simulated = &verification.simulated;
+
+    println!("score: {}", simulated.score);
+    println!("accuracy: {:.2}%", calc_acc(simulated) * 100.0);
+    println!("max combo: {}x", simulated.max_combo);
+    println!("judgments: {}", simulated.judgment_string());
+
+    if verification.matches {
+        println!("replay matches its score");
+    } else {
+        println!("replay does not match its score (max combo {}x)", verification.claimed.max_combo);
+        for (judgment, claimed, simulated) in verification.mismatched_judgments {
+            println!("  {judgment}: claimed {claimed}, got {simulated}");
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SimulateArgs {
+    pub replay_file: Option<String>,
+    pub map: Option<String>,
+}
+impl SimulateArgs {
+    pub fn from_args(args: &mut impl Iterator<Item = String>) -> Self {
+        let mut data = Self::default();
+
+        while let Some(other_arg) = args.next() {
+            match &*other_arg {
+                "--map" => data.map = args.next(),
+                _ if data.replay_file.is_none() => data.replay_file = Some(other_arg),
+                _ => {}
+            }
+        }
+
+        data
+    }
+}
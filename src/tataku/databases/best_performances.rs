@@ -0,0 +1,83 @@
+/**
+ * This is used to store the best performance score for each map, per playmode
+ */
+
+use crate::prelude::*;
+
+#[derive(Clone, Debug, Default)]
+pub struct BestPerformance {
+    pub map_hash: String,
+    pub playmode: PlayMode,
+    pub score_hash: String,
+    pub performance: f32,
+    /// diff calc version the performance was calculated with
+    pub diff_calc_version: u32,
+}
+impl BestPerformance {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            map_hash: row.get("map_hash")?,
+            playmode: row.get("playmode")?,
+            score_hash: row.get("score_hash")?,
+            performance: row.get("performance")?,
+            diff_calc_version: row.get("diff_calc_version")?,
+        })
+    }
+}
+
+impl Database {
+    /// get the best performances for a playmode, sorted by performance (highest first)
+    pub async fn get_best_performances(playmode:&PlayMode) -> Vec<BestPerformance> {
+        let db = Self::get().await;
+
+        let query = format!("SELECT * FROM best_performances WHERE playmode='{playmode}' ORDER BY performance DESC");
+        let mut s = db.prepare(&query).unwrap();
+        let res = s.query_map([], BestPerformance::from_row);
+
+        if let Ok(rows) = res {
+            rows.filter_map(|r|r.ok()).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn save_best_performance(best: &BestPerformance) {
+        let BestPerformance { map_hash, playmode, score_hash, performance, diff_calc_version } = best;
+
+        Self::add_query(DatabaseQuery::InsertOrUpdate { 
+            sql: format!("INSERT INTO best_performances (map_hash, playmode, score_hash, performance, diff_calc_version) VALUES ('{map_hash}', '{playmode}', '{score_hash}', {performance}, {diff_calc_version})"), 
+            table_name: "best_performances".to_owned(), 
+            operation: "INSERT".to_owned(), 
+            sql_if_failed: Some(format!("UPDATE best_performances SET score_hash='{score_hash}', performance={performance}, diff_calc_version={diff_calc_version} WHERE map_hash='{map_hash}' AND playmode='{playmode}'")), 
+            operation_if_failed: Some("UPDATE".to_owned()) 
+        });
+    }
+
+    /// the diff calc version the best performances for a playmode were last recalculated with.
+    /// None if they've never been calculated
+    pub async fn get_best_performance_version(playmode:&PlayMode) -> Option<u32> {
+        let db = Self::get().await;
+        db.query_row(
+            "SELECT diff_calc_version FROM best_performance_versions WHERE playmode=?1",
+            [playmode],
+            |row| row.get(0)
+        ).ok()
+    }
+
+    pub async fn set_best_performance_version(playmode:&PlayMode, version: u32) {
+        let db = Self::get().await;
+        if let Err(e) = db.execute(
+            "INSERT OR REPLACE INTO best_performance_versions (playmode, diff_calc_version) VALUES (?1, ?2)",
+            rusqlite::params![playmode, version]
+        ) {
+            error!("error saving best performance version: {e}");
+        }
+    }
+
+    pub async fn clear_best_performances(playmode:&PlayMode) {
+        let db = Self::get().await;
+        if let Err(e) = db.execute(&format!("DELETE FROM best_performances WHERE playmode='{playmode}'"), []) {
+            error!("error clearing best performances: {e}");
+        }
+    }
+}
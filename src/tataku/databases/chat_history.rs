@@ -0,0 +1,77 @@
+/**
+ * Chat messages, so channels have their history when the game is opened again
+ */
+
+use crate::prelude::*;
+
+/// how many messages to keep for each channel. older ones are deleted as new ones come in
+const MAX_MESSAGES_PER_CHANNEL: usize = 1000;
+
+impl Database {
+    pub async fn save_chat_message(message: &ChatMessage) {
+        let channel = message.channel.get_name();
+        let db = Self::get().await;
+        if let Err(e) = db.execute(
+            "INSERT INTO chat_history (channel, sender, sender_id, text, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![channel, message.sender, message.sender_id, message.text, message.timestamp as i64],
+        ) {
+            error!("error saving chat message: {e}");
+        }
+
+        if let Err(e) = db.execute(
+            "DELETE FROM chat_history WHERE channel=?1 AND id NOT IN (
+                SELECT id FROM chat_history WHERE channel=?1 ORDER BY timestamp DESC, id DESC LIMIT ?2
+            )",
+            rusqlite::params![channel, MAX_MESSAGES_PER_CHANNEL as i64],
+        ) {
+            error!("error trimming chat history: {e}");
+        }
+    }
+
+    /// get the latest `limit` messages from every channel, oldest first
+    pub async fn get_chat_history(limit: usize) -> Vec<ChatMessage> {
+        let db = Self::get().await;
+        let mut s = db.prepare(
+            "SELECT * FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY channel ORDER BY timestamp DESC) AS n FROM chat_history
+            ) WHERE n <= ?1 ORDER BY timestamp ASC"
+        ).unwrap();
+        let res = s.query_map([limit as i64], |row| Ok(ChatMessage {
+            channel: ChatChannel::from_name(row.get("channel")?),
+            sender: row.get("sender")?,
+            sender_id: row.get("sender_id")?,
+            text: row.get("text")?,
+            timestamp: row.get::<_, i64>("timestamp")? as u64,
+        }));
+
+        if let Ok(rows) = res {
+            rows.filter_map(|r| r.ok()).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn history_is_capped_per_channel() {
+        let capped = ChatChannel::Channel { name: "capped".to_owned() };
+        let other = ChatChannel::Channel { name: "not_capped".to_owned() };
+
+        Database::save_chat_message(&ChatMessage::new("user".to_owned(), other.clone(), 1, "kept".to_owned())).await;
+        for i in 0..MAX_MESSAGES_PER_CHANNEL + 10 {
+            Database::save_chat_message(&ChatMessage::new("user".to_owned(), capped.clone(), 1, i.to_string())).await;
+        }
+
+        let history = Database::get_chat_history(usize::MAX / 2).await;
+        let capped_history = history.iter().filter(|m| m.channel == capped).collect::<Vec<_>>();
+        assert_eq!(capped_history.len(), MAX_MESSAGES_PER_CHANNEL);
+        // the oldest ones went
+        assert!(capped_history.iter().all(|m| m.text != "9"));
+        assert!(capped_history.iter().any(|m| m.text == "10"));
+        assert!(history.iter().any(|m| m.channel == other && m.text == "kept"));
+    }
+}
@@ -21,6 +21,9 @@ const MIGRATIONS:&[(&str, &[(&str, &str)])] = &[
         ("mods_string", "TEXT"),
         ("judgments", "TEXT"),
         ("time", "INTEGER"),
+        ("hit_timings", "BLOB"),
+        ("judgment_preset", "TEXT"),
+        ("scoring_system", "TEXT"),
     ]),
     ("beatmaps", &[
         ("bpm_min", "INTEGER"),
@@ -34,6 +37,9 @@ const MIGRATIONS:&[(&str, &[(&str, &str)])] = &[
     ]),
     ("beatmap_preferences", &[
         ("audio_offset", "REAL")
+    ]),
+    ("score_submissions", &[
+        ("verified", "BOOL"),
     ])
 ];
 
@@ -76,7 +82,9 @@ impl Database {
     // }
 
     fn new() -> Arc<Self> {
-        let connection = Connection::open("tataku.db").unwrap();
+        // tests get a database of their own, so they dont touch the real one
+        let path = if cfg!(test) { ":memory:" } else { "tataku.db" };
+        let connection = Connection::open(path).unwrap();
         
         // scores table
         connection.execute(
@@ -159,6 +167,52 @@ impl Database {
             )", [])
         .expect("error creating db table");
 
+        // best performance per map table
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS best_performances (
+                map_hash TEXT,
+                playmode TEXT,
+                score_hash TEXT,
+                performance REAL,
+                diff_calc_version INTEGER,
+
+                PRIMARY KEY (map_hash, playmode)
+            )", [])
+        .expect("error creating db table");
+
+        // which diff calc version each playmode's best performances were last fully calculated with
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS best_performance_versions (
+                playmode TEXT PRIMARY KEY,
+                diff_calc_version INTEGER
+            )", [])
+        .expect("error creating db table");
+
+        // score submit queue table
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS score_submissions (
+                score_hash TEXT PRIMARY KEY,
+                map_hash TEXT,
+                replay BLOB,
+                status INTEGER,
+                reason TEXT,
+                time INTEGER,
+                verified BOOL
+            )", [])
+        .expect("error creating db table");
+
+        // chat history table
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS chat_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel TEXT,
+                sender TEXT,
+                sender_id INTEGER,
+                text TEXT,
+                timestamp INTEGER
+            )", [])
+        .expect("error creating db table");
+
         // ui element things table
         connection.execute(
             "CREATE TABLE IF NOT EXISTS ui_elements (
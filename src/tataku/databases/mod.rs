@@ -5,6 +5,9 @@ mod ui_element_info;
 mod ignore_beatmaps;
 mod beatmap_collections;
 mod beatmap_preferences;
+mod best_performances;
+mod score_submissions;
+mod chat_history;
 // mod difficulty_database;
 
 pub use database::*;
@@ -13,6 +16,8 @@ pub use score_database::*;
 pub use ui_element_info::*;
 pub use beatmap_collections::*;
 pub use beatmap_preferences::*;
+pub use best_performances::*;
+pub use score_submissions::*;
 // pub use difficulty_database::*;
 
 
@@ -2,71 +2,52 @@ use crate::prelude::*;
 use crate::REPLAYS_DIR;
 
 impl Database {
-    pub async fn get_scores(hash:&String, playmode:PlayMode) -> Vec<Score> {
+    pub async fn get_scores(hash:&String, playmode:PlayMode) -> Vec<IngameScore> {
         let db = Self::get().await;
         let mut s = db.prepare(&format!("SELECT * FROM scores WHERE map_hash='{}' AND playmode='{}'", hash, playmode)).unwrap();
 
         s.query_map([], |r| {
-            let _score_hash:String = r.get("score_hash")?;
-
-            let mut mods_string:Option<String> = r.get("mods_string").ok();
-            if let Some(str) = &mods_string {
-                if str.is_empty() {
-                    mods_string = None;
-                }
-            }
-
-            let mut judgments = HashMap::new();
-
-            // string will be key:val|key:val
-            let judgment_str = r
-                .get::<&str, String>("judgments")
+            let timings = r
+                .get::<&str, Vec<u8>>("hit_timings")
                 .ok()
-                .and_then(|s| if s.is_empty() {None} else {Some(s)});
-            if let Some(judgment_string) = judgment_str {
-                judgments = Score::judgments_from_string(&judgment_string);
-            } else { // no judgments, load legacy values
-                for key in [
-                    "x50",
-                    "x100",
-                    "x300",
-                    "xmiss",
-                    "xgeki",
-                    "xkatu"
-                ] {
-                    let val = r.get(key).unwrap_or_default();
-                    judgments.insert(key.to_owned(), val);
-                }
-            }
-
-            let mut score = Score::default();
-            score.version = r.get("version").unwrap_or(1); // v1 didnt include version in the table
-            score.username = r.get("username")?;
-            score.playmode = r.get("playmode")?;
-            score.time = r.get("time").unwrap_or(0);
-            score.score = r.get("score")?;
-            score.combo = r.get("combo")?;
-            score.max_combo = r.get("max_combo")?;
-            score.accuracy = r.get("accuracy").unwrap_or_default();
-            score.beatmap_hash = r.get("map_hash")?;
-            score.speed = r.get("speed").unwrap_or(1.0);
-            score.hit_timings = Vec::new();
-            score.judgments = judgments;
-            
-            if let Some(mods_string) = mods_string {
-                // old mods format, json
-                if mods_string.contains("{") {
-                    *score.mods_mut() = Score::mods_from_old_string(mods_string);
-                } else {
-                    *score.mods_mut() = Score::mods_from_string(mods_string);
+                .and_then(|b| HitTimings::from_bytes(b).ok())
+                .unwrap_or_default();
+
+            let score = score_from_row(r)?;
+            // scores from before the preset was stored separately have it in their mods
+            let judgment_preset = r
+                .get::<&str, Option<String>>("judgment_preset")?
+                .or_else(|| score.mods().into_iter().find(|m| JudgmentPreset::is_preset_mod(m)));
+            // same for the scoring system
+            let scoring_system = match r.get::<&str, Option<String>>("scoring_system")? {
+                Some(id) => ScoringSystemKind::from_id(Some(&id)),
+                None => score.mods().iter().find_map(|m| ScoringSystemKind::from_mod(m)).unwrap_or_default(),
+            };
+
+            Ok(IngameScore::new(score, false, false)
+                .with_timings(timings)
+                .with_judgment_preset(judgment_preset)
+                .with_scoring_system(scoring_system)
+            )
+        })
+            .unwrap()
+            // .filter_map(|m|m.ok())
+            .filter_map(|m| {
+                if let Err(e) = &m {
+                    error!("score error: {}", e);
                 }
-            }
+                m.ok()
+            })
+            .collect::<Vec<IngameScore>>()
+    }
 
+    /// get every local score for a playmode
+    pub async fn get_scores_for_playmode(playmode:&PlayMode) -> Vec<Score> {
+        let db = Self::get().await;
+        let mut s = db.prepare(&format!("SELECT * FROM scores WHERE playmode='{}'", playmode)).unwrap();
 
-            Ok(score)
-        })
+        s.query_map([], score_from_row)
             .unwrap()
-            // .filter_map(|m|m.ok())
             .filter_map(|m| {
                 if let Err(e) = &m {
                     error!("score error: {}", e);
@@ -77,7 +58,7 @@ impl Database {
     }
 
 
-    pub async fn save_score(s:&Score) {
+    pub async fn save_score(s:&IngameScore) {
         trace!("saving score");
 
         let db = Self::get().await;
@@ -91,7 +72,11 @@ impl Database {
                 speed, 
                 version,
                 mods_string,
-                judgments
+                judgments,
+                hit_timings,
+                accuracy,
+                judgment_preset,
+                scoring_system
             ) VALUES (
                 '{}', '{}',
                 '{}', '{}', {},
@@ -101,7 +86,11 @@ impl Database {
                 {},
                 {},
                 '{}',
-                '{}'
+                '{}',
+                X'{}',
+                {},
+                {},
+                {}
             )", 
             s.beatmap_hash, s.hash(),
             s.username, s.playmode, s.time,
@@ -111,7 +100,11 @@ impl Database {
             s.speed,
             s.version,
             s.mods_string_sorted(),
-            s.judgment_string()
+            s.judgment_string(),
+            to_hex(&s.timings.to_bytes()),
+            s.accuracy,
+            s.judgment_preset.as_ref().map(|p| format!("'{p}'")).unwrap_or("NULL".to_owned()),
+            s.scoring_system.to_id().map(|k| format!("'{k}'")).unwrap_or("NULL".to_owned())
         );
 
         match db.prepare(&sql) {
@@ -126,18 +119,106 @@ impl Database {
         
     }
 
+    /// get the judgment preset and scoring system a local score was set with
+    pub async fn get_score_extras(score_hash:&String) -> ReplayExtras {
+        let db = Self::get().await;
+        db.query_row(
+            "SELECT judgment_preset, scoring_system FROM scores WHERE score_hash=?1",
+            [score_hash],
+            |row| Ok(ReplayExtras {
+                judgment_preset: row.get::<_, Option<String>>(0)?,
+                scoring_system: ScoringSystemKind::from_id(row.get::<_, Option<String>>(1)?.as_ref()),
+            })
+        ).unwrap_or_default()
+    }
+
+    /// add hit timings to a score which was saved without them (ie, from before timings were stored)
+    pub async fn add_missing_hit_timings(score_hash:&String, timings:&HitTimings) {
+        if timings.is_empty() { return }
+
+        let db = Self::get().await;
+        let sql = format!("UPDATE scores SET hit_timings=X'{}' WHERE score_hash='{score_hash}' AND hit_timings IS NULL", to_hex(&timings.to_bytes()));
+        if let Err(e) = db.execute(&sql, []) {
+            error!("error adding hit timings: {e}")
+        }
+    }
+
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn score_from_row(r: &rusqlite::Row) -> rusqlite::Result<Score> {
+    let _score_hash:String = r.get("score_hash")?;
+
+    let mut mods_string:Option<String> = r.get("mods_string").ok();
+    if let Some(str) = &mods_string {
+        if str.is_empty() {
+            mods_string = None;
+        }
+    }
+
+    let mut judgments = HashMap::new();
+
+    // string will be key:val|key:val
+    let judgment_str = r
+        .get::<&str, String>("judgments")
+        .ok()
+        .and_then(|s| if s.is_empty() {None} else {Some(s)});
+    if let Some(judgment_string) = judgment_str {
+        judgments = Score::judgments_from_string(&judgment_string);
+    } else { // no judgments, load legacy values
+        for key in [
+            "x50",
+            "x100",
+            "x300",
+            "xmiss",
+            "xgeki",
+            "xkatu"
+        ] {
+            let val = r.get(key).unwrap_or_default();
+            judgments.insert(key.to_owned(), val);
+        }
+    }
+
+    let mut score = Score::default();
+    score.version = r.get("version").unwrap_or(1); // v1 didnt include version in the table
+    score.username = r.get("username")?;
+    score.playmode = r.get("playmode")?;
+    score.time = r.get("time").unwrap_or(0);
+    score.score = r.get("score")?;
+    score.combo = r.get("combo")?;
+    score.max_combo = r.get("max_combo")?;
+    score.accuracy = r.get("accuracy").unwrap_or_default();
+    score.beatmap_hash = r.get("map_hash")?;
+    score.speed = r.get("speed").unwrap_or(1.0);
+    score.hit_timings = Vec::new();
+    score.judgments = judgments;
+    
+    if let Some(mods_string) = mods_string {
+        // old mods format, json
+        if mods_string.contains("{") {
+            *score.mods_mut() = Score::mods_from_old_string(mods_string);
+        } else {
+            *score.mods_mut() = Score::mods_from_string(mods_string);
+        }
+    }
+
+    Ok(score)
 }
 
 /// returns the path of the replay
-pub fn save_replay(r:&Replay, s:&Score) -> TatakuResult<String> {
+pub fn save_replay(r:&Replay, s:&IngameScore) -> TatakuResult<String> {
     // make sure the replay has score data set
     let mut r = r.clone();
     if r.score_data.is_none() {
-        r.score_data = Some(s.clone());
+        r.score_data = Some(s.score.clone());
     }
 
     let mut writer = SerializationWriter::new();
     writer.write(r.clone());
+    ReplayExtras::from_score(s).write(&mut writer);
 
     let hash = s.hash();
     let actual_hash = format!("{:x}", md5::compute(hash));
@@ -147,11 +228,20 @@ pub fn save_replay(r:&Replay, s:&Score) -> TatakuResult<String> {
     Ok(filename)
 }
 
+/// save an input recording next to the replay for the same score
+pub fn save_input_recording(recording: &InputRecording, s:&Score) -> TatakuResult<String> {
+    let actual_hash = format!("{:x}", md5::compute(s.hash()));
+    let filename = format!("{}/{}.{}", REPLAYS_DIR, actual_hash, INPUT_RECORDING_EXTENSION);
+    recording.save(&filename)?;
+    Ok(filename)
+}
+
 pub fn get_local_replay(score_hash:String) -> TatakuResult<Replay> {
     let actual_hash = format!("{:x}", md5::compute(score_hash));
     let fullpath = format!("{}/{}.ttkr", REPLAYS_DIR, actual_hash);
     // info!("loading replay: {fullpath}");
     
+    // the extras are stored with the score too, so theres no need to read them here
     let mut reader = open_database(&fullpath)?;
     Ok(reader.read()?)
 }
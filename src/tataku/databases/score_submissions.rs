@@ -0,0 +1,144 @@
+/**
+ * Scores waiting to be submitted, so they can be retried when the server is reachable again
+ */
+
+use crate::prelude::*;
+
+/// where a local score is at with submitting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmissionStatus {
+    /// hasnt made it to the server yet
+    Pending,
+    Submitted,
+    /// the server refused it, with the reason why
+    Rejected(String),
+    /// it couldnt be sent from here (ie the map isnt installed), with the reason why.
+    /// the server never saw it, so this isnt a rejection
+    Failed(String),
+}
+impl SubmissionStatus {
+    fn from_row(status: u8, reason: Option<String>) -> Self {
+        match status {
+            1 => Self::Submitted,
+            2 => Self::Rejected(reason.unwrap_or_default()),
+            3 => Self::Failed(reason.unwrap_or_default()),
+            _ => Self::Pending,
+        }
+    }
+    fn as_u8(&self) -> u8 {
+        match self {
+            Self::Pending => 0,
+            Self::Submitted => 1,
+            Self::Rejected(_) => 2,
+            Self::Failed(_) => 3,
+        }
+    }
+}
+impl From<&SubmitResponse> for SubmissionStatus {
+    fn from(response: &SubmitResponse) -> Self {
+        match response {
+            SubmitResponse::Submitted { .. } => Self::Submitted,
+            SubmitResponse::NotSubmitted(_, reason) => Self::Rejected(reason.clone()),
+        }
+    }
+}
+
+/// a queued score and its replay
+#[derive(Clone)]
+pub struct ScoreSubmission {
+    pub score_hash: String,
+    pub replay: Replay,
+    pub extras: ReplayExtras,
+    pub status: SubmissionStatus,
+    /// did the replay reproduce its score when it was queued? None if it couldnt be checked
+    pub verified: Option<bool>,
+}
+impl Database {
+    /// add a replay to the submit queue. does nothing if its already queued
+    pub async fn queue_score_submission(replay: &Replay, extras: &ReplayExtras, verified: Option<bool>) {
+        let Some(score) = &replay.score_data else { return };
+        let bytes = write_tataku_replay(replay, extras);
+
+        let db = Self::get().await;
+        if let Err(e) = db.execute(
+            "INSERT OR IGNORE INTO score_submissions (score_hash, map_hash, replay, status, reason, time, verified) VALUES (?1, ?2, ?3, 0, NULL, ?4, ?5)",
+            rusqlite::params![score.hash(), score.beatmap_hash, bytes, score.time as i64, verified],
+        ) {
+            error!("error queueing score submission: {e}");
+        }
+    }
+
+    /// get the scores which still need submitting, oldest first
+    pub async fn get_pending_submissions() -> Vec<ScoreSubmission> {
+        let db = Self::get().await;
+        let mut s = db.prepare("SELECT * FROM score_submissions WHERE status=0 ORDER BY time ASC").unwrap();
+        let res = s.query_map([], |row| Ok((
+            row.get::<_, String>("score_hash")?,
+            row.get::<_, Vec<u8>>("replay")?,
+            SubmissionStatus::from_row(row.get("status")?, row.get("reason")?),
+            row.get::<_, Option<bool>>("verified")?
+        )));
+
+        let Ok(rows) = res else { return Vec::new() };
+        rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(score_hash, bytes, status, verified)| match read_tataku_replay(bytes) {
+                Ok((replay, extras)) => Some(ScoreSubmission { score_hash, replay, extras, status, verified }),
+                Err(e) => {
+                    error!("error reading queued replay for {score_hash}: {e:?}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// get the submission status for every queued score on a map, by score hash
+    pub async fn get_submission_statuses(map_hash: &String) -> HashMap<String, SubmissionStatus> {
+        let db = Self::get().await;
+        let mut s = db.prepare("SELECT score_hash, status, reason FROM score_submissions WHERE map_hash=?1").unwrap();
+        let res = s.query_map([map_hash], |row| Ok((
+            row.get::<_, String>("score_hash")?,
+            SubmissionStatus::from_row(row.get("status")?, row.get("reason")?),
+            row.get::<_, Option<bool>>("verified")?
+        )));
+
+        if let Ok(rows) = res {
+            rows.filter_map(|r| r.ok()).collect()
+        } else {
+            HashMap::new()
+        }
+    }
+
+    /// how many scores are (pending, rejected, failed)
+    pub async fn get_submission_counts() -> (usize, usize, usize) {
+        let db = Self::get().await;
+        let count = |status: u8| db
+            .query_row("SELECT COUNT(*) FROM score_submissions WHERE status=?1", [status], |row| row.get::<_, i64>(0))
+            .unwrap_or_default() as usize;
+
+        (count(0), count(2), count(3))
+    }
+
+    pub async fn set_submission_status(score_hash: &String, status: &SubmissionStatus) {
+        let reason = match status {
+            SubmissionStatus::Rejected(reason) | SubmissionStatus::Failed(reason) => Some(reason.clone()),
+            _ => None,
+        };
+
+        let db = Self::get().await;
+        if let Err(e) = db.execute(
+            "UPDATE score_submissions SET status=?1, reason=?2 WHERE score_hash=?3",
+            rusqlite::params![status.as_u8(), reason, score_hash],
+        ) {
+            error!("error updating score submission: {e}");
+        }
+    }
+
+    /// put every score which didnt get submitted back in the queue, whether the server rejected it or it couldnt be sent
+    pub async fn requeue_unsubmitted_submissions() {
+        let db = Self::get().await;
+        if let Err(e) = db.execute("UPDATE score_submissions SET status=0, reason=NULL WHERE status IN (2, 3)", []) {
+            error!("error requeueing score submissions: {e}");
+        }
+    }
+}
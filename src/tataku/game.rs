@@ -89,13 +89,10 @@ impl Game {
     }
 
     pub async fn init(&mut self) {
-        // online loop
-        tokio::spawn(async move {
-            loop {
-                OnlineManager::start().await;
-                tokio::time::sleep(Duration::from_millis(1_000)).await;
-            }
-        });
+        // online loop, reconnects by itself
+        tokio::spawn(OnlineManager::run());
+        // submits scores set while offline once we're back
+        tokio::spawn(ScoreSubmitHelper::run_queue());
 
         // make sure we have a value in the mod manager global store
         GlobalValueManager::update(Arc::new(ModManager::new()));
@@ -176,6 +173,7 @@ impl Game {
                 match e {
                     GameEvent::WindowEvent(e) => self.input_manager.handle_events(e),
                     GameEvent::ControllerEvent(e, name) => self.input_manager.handle_controller_events(e, name),
+                    GameEvent::Touch(touch) => self.input_manager.handle_touch(touch),
                     
                     GameEvent::DragAndDrop(path) => self.handle_file_drop(path).await,
                     GameEvent::WindowClosed => { 
@@ -266,6 +264,13 @@ impl Game {
         warn!("stopping game");
     }
 
+    /// actions checked everywhere
+    pub fn keybind_context() -> KeybindContext {
+        KeybindContext::new("global", "General", vec![
+            KeybindAction::new("user_panel", "Toggle User Panel", vec![InputTarget::Key(Key::F8)]),
+        ])
+    }
+
     async fn update(&mut self, _delta:f64) {
         let elapsed = self.game_start.elapsed().as_millis() as u64;
         // update the cursor
@@ -303,14 +308,20 @@ impl Game {
 
         // read input events
         let mouse_pos = self.input_manager.mouse_pos;
-        let mut mouse_down = self.input_manager.get_mouse_down();
-        let mut mouse_up = self.input_manager.get_mouse_up();
-        let mouse_moved = self.input_manager.get_mouse_moved();
+        // gameplay needs to know when the input actually happened, everything else just needs what happened
+        let mouse_down_timed = self.input_manager.get_mouse_down();
+        let mouse_up_timed = self.input_manager.get_mouse_up();
+        let mut mouse_down: Vec<MouseButton> = mouse_down_timed.iter().map(|(b, _)| *b).collect();
+        let mut mouse_up: Vec<MouseButton> = mouse_up_timed.iter().map(|(b, _)| *b).collect();
+        let mouse_moved_at = self.input_manager.get_mouse_moved();
+        let mouse_moved = mouse_moved_at.is_some();
         // TODO: do we want this here or only in menus?
         let mut scroll_delta = self.input_manager.get_scroll_delta() * self.settings.scroll_sensitivity;
 
-        let mut keys_down = self.input_manager.get_keys_down();
-        let mut keys_up = self.input_manager.get_keys_up();
+        let keys_down_timed = self.input_manager.get_keys_down();
+        let keys_up_timed = self.input_manager.get_keys_up();
+        let mut keys_down: Vec<Key> = keys_down_timed.iter().map(|(k, _)| *k).collect();
+        let mut keys_up: Vec<Key> = keys_up_timed.iter().map(|(k, _)| *k).collect();
         let mods = self.input_manager.get_key_mods();
         let mut text = self.input_manager.get_text();
         let window_focus_changed = self.input_manager.get_changed_focus();
@@ -319,8 +330,12 @@ impl Game {
         let mut controller_up = self.input_manager.get_controller_up();
         let controller_axis = self.input_manager.get_controller_axis();
 
+        // outside of gameplay the first touch is used as the mouse instead
+        let touches = self.input_manager.get_touch_events();
+        self.input_manager.touch_as_mouse = !matches!(current_state, GameState::Ingame(_));
+
         let mut controller_pause = false;
-        for (c, b) in controller_down.iter() {
+        for (c, b, _) in controller_down.iter() {
             if Some(crate::prelude::ControllerButton::Start) == c.map_button(*b) {
                 controller_pause = true;
                 break;
@@ -345,7 +360,7 @@ impl Game {
         self.volume_controller.on_key_press(&mut keys_down, mods).await;
         
         // check user panel
-        if keys_down.contains(&self.settings.key_user_panel) {
+        if keys_down.iter().any(|k| self.settings.keybinds.get_bindings("global").matches("user_panel", &InputTarget::Key(*k))) {
             let mut user_panel_exists = false;
             let mut chat_exists = false;
             for i in self.dialogs.iter() {
@@ -504,10 +519,17 @@ impl Game {
             async_retain!(keys_down, k, !d.on_key_press(k, &mods, self).await);
             async_retain!(keys_up, k, !d.on_key_release(k,  &mods, self).await);
 
-            async_retain!(controller_down, k, !d.on_controller_press(&k.0, k.1).await);
+            // anything the dialog doesnt handle itself is used to move focus around
+            async_retain!(controller_down, k, !(d.on_controller_press(&k.0, k.1).await || match NavigationInput::from_controller(&k.0, k.1) {
+                Some(input) => {
+                    let (key, mods) = input.to_key();
+                    d.on_key_press(&key, &mods, self).await
+                }
+                None => false
+            }));
             async_retain!(controller_up, k, !d.on_controller_release(&k.0, k.1).await);
 
-            for (c, b) in controller_axis.iter() {
+            for (c, b, _) in controller_axis.iter() {
                 d.on_controller_axis(c, b).await;
             }
 
@@ -569,32 +591,44 @@ impl Game {
 
                     // inputs
                     // mouse
-                    if mouse_moved {manager.mouse_move(mouse_pos).await}
-                    for btn in mouse_down {manager.mouse_down(btn).await}
-                    for btn in mouse_up {manager.mouse_up(btn).await}
+                    if let Some(at) = mouse_moved_at {manager.mouse_move(mouse_pos, at).await}
+                    // anything a dialog or notification used up was removed from the untimed lists
+                    for (btn, at) in mouse_down_timed.into_iter().filter(|(b, _)| mouse_down.contains(b)) {manager.mouse_down(btn, at).await}
+                    for (btn, at) in mouse_up_timed.into_iter().filter(|(b, _)| mouse_up.contains(b)) {manager.mouse_up(btn, at).await}
                     if scroll_delta != 0.0 {manager.mouse_scroll(scroll_delta).await}
 
                     // kb
-                    for k in keys_down.iter() {manager.key_down(*k, mods).await}
-                    for k in keys_up.iter() {manager.key_up(*k).await}
+                    for (k, at) in keys_down_timed.into_iter().filter(|(k, _)| keys_down.contains(k)) {manager.key_down(k, mods, at).await}
+                    for (k, at) in keys_up_timed.into_iter().filter(|(k, _)| keys_up.contains(k)) {manager.key_up(k, at).await}
                     if text.len() > 0 {
                         manager.on_text(&text, &mods).await
                     }
 
                     // controller
-                    for (c, b) in controller_down {
-                        manager.controller_press(&c, b).await;
+                    for (c, b, at) in controller_down {
+                        manager.controller_press(&c, b, at).await;
+                    }
+                    for (c, b, at) in controller_up {
+                        manager.controller_release(&c, b, at).await;
                     }
-                    for (c, b) in controller_up {
-                        manager.controller_release(&c, b).await;
+                    for (c, b, at) in controller_axis {
+                        manager.controller_axis(&c, b, at).await;
                     }
-                    for (c, b) in controller_axis {
-                        manager.controller_axis(&c, b).await;
+
+                    // touch
+                    for (touch, at) in touches {
+                        manager.touch(touch, at).await;
                     }
 
 
                     // update, then check if complete
                     manager.update().await;
+                    manager.update_ghost().await;
+
+                    // a restart is a new play, so it gets a new recording
+                    if std::mem::take(&mut manager.restarted) && self.input_manager.stop_recording().is_some() {
+                        self.input_manager.start_recording(manager.metadata.beatmap_hash.clone(), manager.gamemode.playmode());
+                    }
                     if manager.completed {
                         self.ingame_complete(manager).await;
                     }
@@ -635,13 +669,19 @@ impl Game {
 
 
                 // controller
-                for (c, b) in controller_down {
-                    menu.controller_down(self, &c, b).await;
+                for (c, b, _) in controller_down {
+                    if menu.controller_down(self, &c, b).await { continue }
+
+                    // otherwise use it to navigate the menu
+                    if let Some(input) = NavigationInput::from_controller(&c, b) {
+                        let (key, mods) = input.to_key();
+                        menu.on_key_press(key, self, mods).await;
+                    }
                 }
-                for (c, b) in controller_up {
+                for (c, b, _) in controller_up {
                     menu.controller_up(self, &c, b).await;
                 }
-                for (c, ad) in controller_axis {
+                for (c, ad, _) in controller_axis {
                     menu.controller_axis(self, &c, ad).await;
                 }
 
@@ -710,6 +750,20 @@ impl Game {
                 match &mut self.queued_state {
                     GameState::Ingame(manager) => {
                         manager.start().await;
+
+                        // coming back from the pause menu continues the same play, unless it was retried
+                        let unpausing = match &current_state {
+                            GameState::InMenu(menu) => menu.lock().await.get_name() == "pause",
+                            _ => false,
+                        };
+                        let restarted = std::mem::take(&mut manager.restarted);
+                        if (!unpausing || restarted) && !manager.replaying && !manager.menu_background {
+                            if self.settings.record_input {
+                                self.input_manager.start_recording(manager.metadata.beatmap_hash.clone(), manager.gamemode.playmode());
+                            }
+                            InputPlayback::gameplay_started();
+                        }
+
                         let m = manager.metadata.clone();
                         let start_time = manager.start_time;
 
@@ -935,6 +989,11 @@ impl Game {
         self.dialogs.push(dialog)
     }
 
+    /// where every controller axis currently is, by controller name
+    pub fn controller_axis_values(&self) -> HashMap<Arc<String>, HashMap<u8, f64>> {
+        self.input_manager.get_axis_values()
+    }
+
     pub async fn handle_file_drop(&mut self, path: PathBuf) {
         let path = path.as_path();
         let filename = path.file_name();
@@ -959,10 +1018,10 @@ impl Game {
                     }
                 }
 
-                // tataku | osu replay
-                "ttkr" | "osr" => {
+                // tataku | osu | quaver replay
+                "ttkr" | "osr" | "qr" => {
                     match read_other_game_replay(path).await {
-                        Ok(replay) => self.try_open_replay(replay).await,
+                        Ok((replay, extras)) => self.try_open_replay(replay, extras).await,
                         Err(e) => NotificationManager::add_error_notification("Error opening replay", e).await,
                     }
                 }
@@ -978,7 +1037,7 @@ impl Game {
         }
     }
 
-    pub async fn try_open_replay(&mut self, replay: Replay) {
+    pub async fn try_open_replay(&mut self, replay: Replay, extras: ReplayExtras) {
         let Some(score) = &replay.score_data else {
             NotificationManager::add_text_notification("Replay does not contain score data (too old?)", 5_000.0, Color::RED).await;
             return;
@@ -994,44 +1053,72 @@ impl Game {
         manager.set_current_beatmap(self, &map, true).await;
 
         // move to a score menu with this as the score
-        let score = IngameScore::new(score.clone(), false, false);
+        let score = IngameScore::new(score.clone(), false, false)
+            .with_judgment_preset(extras.judgment_preset)
+            .with_scoring_system(extras.scoring_system);
         let mut menu = ScoreMenu::new(&score, map, false);
+        menu.simulate_missing_timings(replay.clone(), extras);
         menu.replay = Some(replay);
         self.queued_state = GameState::InMenu(Arc::new(Mutex::new(menu)));
     }
 
 
+    /// stop recording input and save it next to the replay for `score`, returning where it was saved
+    async fn finish_input_recording(&mut self, score: &Score) -> Option<String> {
+        let recording = self.input_manager.stop_recording()?;
+        match save_input_recording(&recording, score) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                NotificationManager::add_error_notification("error saving input recording", e).await;
+                None
+            }
+        }
+    }
+
     pub async fn ingame_complete(&mut self, manager: &mut IngameManager) {
         trace!("beatmap complete");
         manager.on_complete();
         manager.score.time = chrono::Utc::now().timestamp() as u64;
 
-        if manager.failed {
+        // a failed multiplayer match still goes to the lobby's results
+        if manager.failed && !manager.multiplayer {
             trace!("player failed");
+            self.finish_input_recording(&manager.score).await;
             let manager2 = std::mem::take(manager);
             self.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(PauseMenu::new(manager2, true).await))));
             
         } else {
             let mut score = manager.score.clone();
-            score.accuracy = get_gamemode_info(&score.playmode).unwrap().calc_acc(&score);
+            // the scoring system might calculate acc differently from the gamemode
+            score.accuracy = manager.scoring.as_ref().map(|s| s.accuracy()).unwrap_or_else(|| calc_acc(&score));
 
             let mut replay = manager.replay.clone();
             replay.score_data = Some(score.score.clone());
+            let input_recording = self.finish_input_recording(&score).await;
 
+            if manager.multiplayer {
+                OnlineManager::lobby_score(score.score.clone(), true);
+            }
 
             let mut score_submit = None;
-            if manager.should_save_score() {
+            if manager.should_save_score() && !manager.failed {
                 // save score
                 Database::save_score(&score).await;
+                RatingHelper::score_set(&score, &manager.metadata).await;
                 match save_replay(&replay, &score) {
                     Ok(_)=> trace!("replay saved ok"),
                     Err(e) => NotificationManager::add_error_notification("error saving replay", e).await,
                 }
 
                 // submit score
-                let submit = ScoreSubmitHelper::new(replay.clone(), &self.settings);
+                let submit = ScoreSubmitHelper::new(replay.clone(), ReplayExtras::from_score(&score));
                 submit.clone().submit();
                 score_submit = Some(submit);
+            } else if manager.replaying {
+                // scores saved before hit timings were stored can get them from watching the replay
+                if let Some(original) = &manager.replay.score_data {
+                    Database::add_missing_hit_timings(&original.hash(), &score.timings).await;
+                }
             }
 
             // used to indicate user stopped watching a replay
@@ -1040,11 +1127,22 @@ impl Game {
                 let menu = self.menus.get("beatmap").unwrap();
                 let menu = menu.clone();
                 self.queue_state_change(GameState::InMenu(menu));
+            } else if manager.multiplayer {
+                // show everyone's scores
+                let menu = LobbyResultsMenu::new(manager.metadata.clone()).await;
+                self.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(menu))));
             } else {
                 // show score menu
                 let mut menu = ScoreMenu::new(&score, manager.metadata.clone(), true);
                 menu.replay = Some(replay.clone());
+                menu.input_recording = input_recording;
                 menu.score_submit = score_submit;
+
+                // practice replays jump around, so they cant be watched properly
+                if let Some(practice) = &manager.practice {
+                    menu.replay = None;
+                    menu.add_practice_results(&practice.results);
+                }
                 self.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(menu))));
             }
 
@@ -1068,12 +1166,12 @@ impl Game {
 pub enum GameState {
     None, // use this as the inital game mode, but be sure to change it after
     Closing,
+    /// multiplayer matches are ingame too, with [`IngameManager::multiplayer`] set
     Ingame(IngameManager),
     InMenu(Arc<Mutex<dyn ControllerInputMenu<Game>>>),
 
     #[allow(dead_code)]
     Spectating(SpectatorManager), // frames awaiting replay, state, beatmap
-    // Multiplaying(MultiplayerState), // wink wink nudge nudge (dont hold your breath)
 }
 impl Default for GameState {
     fn default() -> Self {
@@ -1087,5 +1185,6 @@ pub enum GameEvent {
     WindowEvent(piston::Event),
     DragAndDrop(PathBuf),
     /// controller event, controller name
-    ControllerEvent(piston::Event, String)
+    ControllerEvent(piston::Event, String),
+    Touch(TouchInput),
 }
@@ -0,0 +1,169 @@
+use crate::prelude::*;
+use tataku_client_proc_macros::Settings;
+use rand::{ Rng, SeedableRng, rngs::StdRng };
+
+/// how the osu autoplay cursor moves between notes
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AutoplayCursorStyle {
+    /// straight line at a constant speed
+    Linear,
+    /// straight line, slowing down near the notes
+    Eased,
+    /// swings around in half circles between notes
+    Dance,
+}
+impl AutoplayCursorStyle {
+    /// get the cursor pos between two notes. `index` is used to alternate which way the dance curves
+    pub fn pos_at(&self, start: Vector2, end: Vector2, progress: f64, index: usize) -> Vector2 {
+        let progress = progress.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => Vector2::lerp(start, end, progress),
+            Self::Eased => {
+                // smoothstep
+                let eased = progress * progress * (3.0 - 2.0 * progress);
+                Vector2::lerp(start, end, eased)
+            }
+            Self::Dance => {
+                let center = (start + end) / 2.0;
+                let radius = start - center;
+                let direction = if index % 2 == 0 { 1.0 } else { -1.0 };
+
+                // rotate the start around the center, which ends up at the end after half a turn
+                let angle = std::f64::consts::PI * progress * direction;
+                let (sin, cos) = angle.sin_cos();
+                center + Vector2::new(radius.x * cos - radius.y * sin, radius.x * sin + radius.y * cos)
+            }
+        }
+    }
+}
+impl Default for AutoplayCursorStyle {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+impl Dropdownable for AutoplayCursorStyle {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::Linear,
+            Self::Eased,
+            Self::Dance,
+        ]
+    }
+
+    fn display_text(&self) -> String {
+        match self {
+            Self::Linear => "Linear",
+            Self::Eased => "Eased",
+            Self::Dance => "Cursor Dance",
+        }.to_owned()
+    }
+
+    fn from_string(s:String) -> Self {
+        Self::variants().into_iter().find(|c| c.display_text() == s).unwrap_or_default()
+    }
+}
+
+
+/// how autoplay should play. the default profile plays perfectly
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+#[derive(Settings)]
+pub struct AutoplayProfile {
+    /// average hit offset (ms), negative is early
+    #[Setting(text="Autoplay Mean Offset", min=-50.0, max=50.0)]
+    pub mean_offset: f32,
+    /// standard deviation of the hit offset (ms)
+    #[Setting(text="Autoplay Offset Deviation", min=0.0, max=50.0)]
+    pub deviation: f32,
+    /// 0.0-1.0 chance to not hit a note at all
+    #[Setting(text="Autoplay Miss Chance", min=0.0, max=0.5)]
+    pub miss_chance: f32,
+    /// osu only
+    #[Setting(text="Autoplay Cursor Style", dropdown="AutoplayCursorStyle")]
+    pub cursor_style: AutoplayCursorStyle,
+    /// same seed, same map, same play
+    #[Setting(text="Autoplay Seed", min=0, max=1000)]
+    pub seed: u64,
+}
+
+/// turns an [`AutoplayProfile`] into hit offsets
+pub struct HumanizedAutoplay {
+    pub profile: AutoplayProfile,
+    rng: StdRng,
+}
+impl HumanizedAutoplay {
+    pub fn new(profile: AutoplayProfile) -> Self {
+        let rng = StdRng::seed_from_u64(profile.seed);
+        Self { profile, rng }
+    }
+
+    /// get the offset to hit the next note at, or None if it should be missed
+    pub fn next_hit(&mut self) -> Option<f32> {
+        if self.profile.miss_chance > 0.0 && self.rng.gen::<f32>() < self.profile.miss_chance {
+            return None;
+        }
+        if self.profile.deviation <= 0.0 { return Some(self.profile.mean_offset) }
+
+        // box-muller, rand doesnt come with a normal distribution
+        let u1 = self.rng.gen::<f32>().max(f32::EPSILON);
+        let u2 = self.rng.gen::<f32>();
+        let normal = (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos();
+
+        Some(self.profile.mean_offset + normal * self.profile.deviation)
+    }
+
+    /// get the hit offsets for a list of notes, in order
+    pub fn plan(&mut self, count: usize) -> Vec<Option<f32>> {
+        (0..count).map(|_| self.next_hit()).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(seed: u64) -> AutoplayProfile {
+        AutoplayProfile {
+            mean_offset: -5.0,
+            deviation: 12.0,
+            miss_chance: 0.1,
+            cursor_style: AutoplayCursorStyle::Linear,
+            seed,
+        }
+    }
+
+    #[test]
+    fn same_seed_same_plan() {
+        let a = HumanizedAutoplay::new(profile(1234)).plan(500);
+        let b = HumanizedAutoplay::new(profile(1234)).plan(500);
+        assert_eq!(a, b);
+
+        // and a new seed is a different play
+        let c = HumanizedAutoplay::new(profile(4321)).plan(500);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn plan_matches_hits_in_order() {
+        // plan is only a shortcut for calling next_hit for each note, so both ways have to give the same play
+        let planned = HumanizedAutoplay::new(profile(99)).plan(200);
+        let mut humanizer = HumanizedAutoplay::new(profile(99));
+        let one_by_one = (0..200).map(|_| humanizer.next_hit()).collect::<Vec<_>>();
+        assert_eq!(planned, one_by_one);
+    }
+
+    #[test]
+    fn default_profile_is_perfect() {
+        let plan = HumanizedAutoplay::new(AutoplayProfile::default()).plan(100);
+        assert!(plan.iter().all(|offset| *offset == Some(0.0)));
+    }
+
+    #[test]
+    fn misses_follow_the_chance() {
+        let plan = HumanizedAutoplay::new(AutoplayProfile { miss_chance: 0.25, seed: 7, ..Default::default() }).plan(10_000);
+        let misses = plan.iter().filter(|offset| offset.is_none()).count() as f32 / plan.len() as f32;
+        assert!((misses - 0.25).abs() < 0.02, "miss rate was {misses}");
+    }
+}
@@ -11,20 +11,40 @@ pub trait GameModeInfo {
 
     
     fn calc_acc(&self, score: &Score) -> f64;
-    fn get_perf_calc(&self) -> PerformanceCalc {
-        Box::new(|diff, acc| {
-            let perf = diff * (acc / 0.99).powi(6);
-            #[cfg(feature="debug_perf_rating")]
-            println!("diff:{diff}, acc: {acc} = perf {perf}");
-            perf
-        })
+    /// calculate the performance of a score, split into its parts
+    fn calc_perf(&self, info: PerformanceCalcInfo) -> PerformanceInfo {
+        let acc_mult = info.acc_multiplier(0.99);
+        let miss_mult = info.miss_multiplier(0.98);
+
+        let perf = PerformanceInfo::new()
+            .with_part("Strain", info.diff * 0.8 * acc_mult * miss_mult)
+            .with_part("Acc", info.diff * 0.2 * acc_mult.powi(2));
+
+        #[cfg(feature="debug_perf_rating")]
+        println!("diff:{}, acc: {} = perf {perf:?}", info.diff, info.score.accuracy);
+        perf
     }
+    /// bump this whenever the diff calc or perf calc changes, so cached diffs and best performances get recalculated
+    fn diff_calc_version(&self) -> u32 { 2 }
+    /// how much the play at `index` in the top plays (sorted highest first) counts towards the player's rating
+    fn rating_weight(&self, index: usize) -> f32 { 0.95f32.powi(index as i32) }
+
     fn get_mods(&self) -> Vec<GameplayModGroup> { Vec::new() }
+    /// actions this gamemode lets the user bind
+    fn keybinds(&self) -> Vec<KeybindContext> { Vec::new() }
     fn get_stat_groups(&self) -> Vec<StatGroup> { Vec::new() }
     fn get_judgments(&self) -> Box<dyn crate::prelude::HitJudgments>;
+    /// create a scoring system for this gamemode, None if the gamemode doesnt support it
+    fn create_scoring_system(&self, _kind: ScoringSystemKind) -> Option<Box<dyn ScoringSystem>> { None }
+    /// get the health model for this map.
+    /// by default this uses the preset matching the map's source game
+    fn get_health_helper(&self, map: &BeatmapMeta, mods: &ModManager) -> HealthHelper {
+        HealthHelper::new(HealthPreset::from_beatmap_type(map.beatmap_type), map.get_hp(mods))
+    }
     fn get_diff_string(&self, info: &BeatmapMetaWithDiff, mods: &ModManager) -> String;
     
-    async fn create_game(&self, beatmap: &Beatmap) -> TatakuResult<Box<dyn GameMode>>;
+    /// headless skips loading anything only needed for drawing (ie, for replay simulation)
+    async fn create_game(&self, beatmap: &Beatmap, headless: bool) -> TatakuResult<Box<dyn GameMode>>;
     async fn create_diffcalc(&self, map: &BeatmapMeta) -> TatakuResult<Box<dyn DiffCalc>>;
 
     fn stats_from_groups(&self, _data: &HashMap<String, HashMap<String, Vec<f32>>>) -> Vec<MenuStatsInfo> { Vec::new() }
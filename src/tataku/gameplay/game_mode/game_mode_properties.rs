@@ -11,6 +11,8 @@ pub trait GameModeProperties: Send + Sync {
     
     /// what ms does this map end?
     fn end_time(&self) -> f32;
+    /// how many judgments will a full play of this map have? 0 if unknown
+    fn max_judgments(&self) -> usize { 0 }
 
     /// what key presses are valid, as well as what they should be named as
     /// used for the key counter
@@ -67,7 +67,9 @@ impl GameplayStats {
 pub fn default_stat_groups() -> Vec<StatGroup>{
     vec![
         StatGroup::new("variance", "Variance")
-            .with_stat(HitVarianceStat)
+            .with_stat(HitVarianceStat),
+        StatGroup::new("health", "Health")
+            .with_stat(HealthStat),
     ]
 }
 
@@ -111,6 +113,18 @@ pub fn default_stats_from_groups(data: &HashMap<String, HashMap<String, Vec<f32>
         }
     }
 
+    if let Some(health) = data.get(&"health".to_owned()) {
+        if let Some(health_values) = health.get(&HealthStat.name().to_owned()) {
+            let values:Vec<f32> = health_values.iter().map(|h| h * 100.0).collect();
+            let min = values.iter().fold(100.0f32, |a, &b| a.min(b));
+
+            info.push(MenuStatsInfo::new("Health", GraphType::Line, vec![
+                MenuStatsEntry::new_list("Health", values, Color::GREEN, true, false, ConcatMethod::Mean),
+                MenuStatsEntry::new_f32("Lowest", min, Color::RED, false, true),
+            ]))
+        }
+    }
+
     info
 }
 
@@ -9,6 +9,9 @@ pub trait GameplayMod: Send + Sync {
     
     fn score_multiplier(&self) -> f32 { 1.0 }
     fn removes(&self) -> &'static [&'static str] { &[] }
+
+    /// does this mod change the map's star rating? if so, it will be part of the diff cache key
+    fn affects_difficulty(&self) -> bool { false }
 }
 
 pub struct GameplayModGroup {
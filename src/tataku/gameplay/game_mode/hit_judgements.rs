@@ -3,6 +3,8 @@ use crate::prelude::*;
 pub trait HitJudgments: Send + Sync {
     /// list of all judgments (in display order)
     fn variants(&self) -> Vec<Box<dyn HitJudgments>>;
+    /// every judgment, including ones which arent displayed (ie slider dots)
+    fn all_variants(&self) -> Vec<Box<dyn HitJudgments>> { self.variants() }
 
     /// how much health should be gained/lost for this judgment
     fn get_health(&self) -> f32;
@@ -0,0 +1,217 @@
+use crate::prelude::*;
+
+/// prefix for a preset's id.
+///
+/// the preset is stored separately from the score's mods, so it doesnt change the score's hash or what mods the score counts as having.
+/// spectator frames and older replays only have a mod list though, so the id is put there and moved back out with [`JudgmentPreset::take_from_mods`]
+const JUDGMENT_MOD_PREFIX: &str = "judgment_";
+const CUSTOM_MOD_PREFIX: &str = "judgment_custom_";
+
+/// which hit windows to judge with
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum JudgmentPreset {
+    /// windows based on the map's od
+    OsuOd,
+    QuaverStandard,
+    QuaverStrict,
+    QuaverRelaxed,
+    EtternaJ4,
+    EtternaJ5,
+    EtternaJ6,
+    EtternaJ7,
+    EtternaJ8,
+    EtternaJ9,
+    /// windows from the gamemode's custom judgment windows setting
+    Custom,
+}
+impl JudgmentPreset {
+    /// the preset used when a score doesnt have one (ie, scores set before presets existed)
+    pub fn default_for_playmode(playmode: &PlayMode) -> Self {
+        match &**playmode {
+            "mania" => Self::QuaverStandard,
+            _ => Self::OsuOd,
+        }
+    }
+
+    /// the preset and custom windows selected in the settings for this playmode
+    pub fn from_settings(playmode: &PlayMode) -> (Self, Vec<f32>) {
+        let settings = get_settings!();
+        match &**playmode {
+            "osu" => (settings.standard_settings.judgment_preset, Self::parse_windows(&settings.standard_settings.custom_windows)),
+            "taiko" => (settings.taiko_settings.judgment_preset, Self::parse_windows(&settings.taiko_settings.custom_windows)),
+            "mania" => (settings.mania_settings.judgment_preset, Self::parse_windows(&settings.mania_settings.custom_windows)),
+            _ => (Self::default_for_playmode(playmode), Vec::new()),
+        }
+    }
+
+    /// parse custom windows from the settings, ie "18, 43, 76"
+    pub fn parse_windows(text: &str) -> Vec<f32> {
+        text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter_map(|w| w.parse().ok())
+            .collect()
+    }
+
+    /// get the preset and custom windows from an id, or the playmode's default if there isnt one
+    pub fn from_id(id: Option<&String>, playmode: &PlayMode) -> (Self, Vec<f32>) {
+        id
+            .and_then(|id| Self::parse_id(id))
+            .unwrap_or_else(|| (Self::default_for_playmode(playmode), Vec::new()))
+    }
+
+    fn parse_id(id: &str) -> Option<(Self, Vec<f32>)> {
+        if let Some(windows) = id.strip_prefix(CUSTOM_MOD_PREFIX) {
+            let windows = windows.split('_').filter_map(|w| w.parse().ok()).collect();
+            return Some((Self::Custom, windows));
+        }
+
+        let name = id.strip_prefix(JUDGMENT_MOD_PREFIX)?;
+        Self::variants().into_iter().find(|p| p.mod_name() == name).map(|p| (p, Vec::new()))
+    }
+
+    /// is this mod actually a judgment preset?
+    pub fn is_preset_mod(m: &str) -> bool {
+        m.starts_with(JUDGMENT_MOD_PREFIX)
+    }
+
+    /// get the id to store for this preset, or None if this is the playmode's default
+    ///
+    /// custom windows are rounded to the nearest ms, so a replay uses the exact same windows as the play did
+    pub fn to_id(&self, custom_windows: &[f32], playmode: &PlayMode) -> Option<String> {
+        match self {
+            Self::Custom => Some(format!("{CUSTOM_MOD_PREFIX}{}", custom_windows.iter().map(|w| (w.round() as u32).to_string()).collect::<Vec<_>>().join("_"))),
+            p if *p == Self::default_for_playmode(playmode) => None,
+            p => Some(format!("{JUDGMENT_MOD_PREFIX}{}", p.mod_name())),
+        }
+    }
+
+    /// set the preset to the one selected in the settings
+    pub fn apply_from_settings(mods: &mut ModManager, playmode: &PlayMode) {
+        let (preset, custom) = Self::from_settings(playmode);
+        mods.judgment_preset = preset.to_id(&custom, playmode);
+    }
+
+    /// move a preset out of the mod list (from spectator frames, replays from other games, or scores from before presets were stored separately)
+    pub fn take_from_mods(mods: &mut ModManager) {
+        let Some(id) = mods.mods.iter().find(|m| Self::is_preset_mod(m)).cloned() else { return };
+        mods.mods.retain(|m| !Self::is_preset_mod(m));
+        mods.judgment_preset = Some(id);
+    }
+
+    fn mod_name(&self) -> &'static str {
+        match self {
+            Self::OsuOd => "osu_od",
+            Self::QuaverStandard => "quaver_standard",
+            Self::QuaverStrict => "quaver_strict",
+            Self::QuaverRelaxed => "quaver_relaxed",
+            Self::EtternaJ4 => "etterna_j4",
+            Self::EtternaJ5 => "etterna_j5",
+            Self::EtternaJ6 => "etterna_j6",
+            Self::EtternaJ7 => "etterna_j7",
+            Self::EtternaJ8 => "etterna_j8",
+            Self::EtternaJ9 => "etterna_j9",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// name for leaderboards and other places where space is tight
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Self::OsuOd => "OD",
+            Self::QuaverStandard => "QS",
+            Self::QuaverStrict => "QX",
+            Self::QuaverRelaxed => "QR",
+            Self::EtternaJ4 => "J4",
+            Self::EtternaJ5 => "J5",
+            Self::EtternaJ6 => "J6",
+            Self::EtternaJ7 => "J7",
+            Self::EtternaJ8 => "J8",
+            Self::EtternaJ9 => "J9",
+            Self::Custom => "CJ",
+        }
+    }
+
+    /// get the end of each window in ms, from tightest to miss.
+    ///
+    /// `judgments` is which of mania's 6 windows (marvelous, perfect, great, good, okay/bad, miss) the gamemode judges with,
+    /// ie osu only uses perfect, good, okay and miss. custom windows are already per-gamemode, so they're used as-is.
+    ///
+    /// [`Self::OsuOd`] gives osu!mania's od windows here, other modes should use their own od windows instead.
+    /// returns None if the custom windows are invalid
+    pub fn windows(&self, od: f32, custom_windows: &[f32], judgments: &[usize]) -> Option<Vec<f32>> {
+        let etterna = |scale: f32| {
+            let boo = 180.0 * scale;
+            vec![22.5 * scale, 45.0 * scale, 90.0 * scale, 135.0 * scale, boo, boo.max(180.0)]
+        };
+
+        let windows = match self {
+            // osu!mania's windows
+            Self::OsuOd => vec![16.0, 64.0 - 3.0 * od, 97.0 - 3.0 * od, 127.0 - 3.0 * od, 151.0 - 3.0 * od, 188.0 - 3.0 * od],
+
+            Self::QuaverStandard => vec![18.0, 43.0, 76.0, 106.0, 127.0, 164.0],
+            Self::QuaverStrict => vec![16.0, 35.0, 60.0, 76.0, 96.0, 134.0],
+            Self::QuaverRelaxed => vec![20.0, 50.0, 90.0, 126.0, 150.0, 180.0],
+
+            Self::EtternaJ4 => etterna(1.0),
+            Self::EtternaJ5 => etterna(0.84),
+            Self::EtternaJ6 => etterna(0.66),
+            Self::EtternaJ7 => etterna(0.5),
+            Self::EtternaJ8 => etterna(0.33),
+            Self::EtternaJ9 => etterna(0.2),
+
+            Self::Custom => {
+                let valid = custom_windows.len() == judgments.len()
+                    && custom_windows.first().map(|w| *w > 0.0).unwrap_or_default()
+                    && custom_windows.windows(2).all(|w| w[0] <= w[1]);
+                if !valid { return None }
+
+                return Some(custom_windows.to_vec())
+            }
+        };
+
+        Some(judgments.iter().map(|i| windows[*i]).collect())
+    }
+}
+impl Default for JudgmentPreset {
+    fn default() -> Self {
+        Self::QuaverStandard
+    }
+}
+
+impl Dropdownable for JudgmentPreset {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::OsuOd,
+            Self::QuaverStandard,
+            Self::QuaverStrict,
+            Self::QuaverRelaxed,
+            Self::EtternaJ4,
+            Self::EtternaJ5,
+            Self::EtternaJ6,
+            Self::EtternaJ7,
+            Self::EtternaJ8,
+            Self::EtternaJ9,
+            Self::Custom,
+        ]
+    }
+
+    fn display_text(&self) -> String {
+        match self {
+            Self::OsuOd => "osu! (OD)",
+            Self::QuaverStandard => "Quaver Standard",
+            Self::QuaverStrict => "Quaver Strict",
+            Self::QuaverRelaxed => "Quaver Relaxed",
+            Self::EtternaJ4 => "Etterna J4",
+            Self::EtternaJ5 => "Etterna J5",
+            Self::EtternaJ6 => "Etterna J6",
+            Self::EtternaJ7 => "Etterna J7",
+            Self::EtternaJ8 => "Etterna J8",
+            Self::EtternaJ9 => "Etterna J9",
+            Self::Custom => "Custom",
+        }.to_owned()
+    }
+
+    fn from_string(s:String) -> Self {
+        Self::variants().into_iter().find(|p| p.display_text() == s).unwrap_or_default()
+    }
+}
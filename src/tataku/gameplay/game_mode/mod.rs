@@ -8,6 +8,10 @@ mod game_mode_stat;
 mod game_mode_input;
 mod default_game_mode;
 mod game_mode_properties;
+mod performance_info;
+mod judgment_preset;
+mod scoring_system;
+mod autoplay_profile;
 
 pub use hitsound::*;
 pub use game_mode::*;
@@ -18,4 +22,8 @@ pub use game_mode_info::*;
 pub use game_mode_stat::*;
 pub use game_mode_input::*;
 pub use default_game_mode::*;
-pub use game_mode_properties::*;
\ No newline at end of file
+pub use game_mode_properties::*;
+pub use performance_info::*;
+pub use judgment_preset::*;
+pub use scoring_system::*;
+pub use autoplay_profile::*;
\ No newline at end of file
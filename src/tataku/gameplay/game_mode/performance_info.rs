@@ -0,0 +1,204 @@
+use crate::prelude::*;
+
+/// performance of a score, split into the parts which make it up
+#[derive(Clone, Debug, Default)]
+pub struct PerformanceInfo {
+    pub total: f32,
+    /// (display name, value) for each part of the total, ie aim, speed, acc
+    pub parts: Vec<(&'static str, f32)>,
+}
+impl PerformanceInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add a part to the breakdown, which is also added to the total
+    pub fn with_part(mut self, name: &'static str, value: f32) -> Self {
+        let value = value.normal_or(0.0);
+        self.total += value;
+        self.parts.push((name, value));
+        self
+    }
+
+    /// breakdown as a single line, ie "aim: 12.34pr, acc: 5.67pr"
+    pub fn breakdown_string(&self) -> String {
+        self.parts
+            .iter()
+            .map(|(name, value)| format!("{name}: {value:.2}pr"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+
+/// difficulty of a map, along with the difficulty of each skill the diff calc measured
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DifficultyAttributes {
+    /// overall diff of the map, this is what song select shows
+    pub diff: f32,
+    /// (name, diff) for each skill, ie aim and speed for osu
+    pub skills: Vec<(String, f32)>,
+}
+impl DifficultyAttributes {
+    pub fn new(diff: f32) -> Self {
+        Self { diff: diff.normal_or(0.0), skills: Vec::new() }
+    }
+
+    pub fn with_skill(mut self, name: impl ToString, diff: f32) -> Self {
+        self.skills.push((name.to_string(), diff.normal_or(0.0)));
+        self
+    }
+
+    /// diff of a skill, or 0 if the diff calc doesnt measure it
+    pub fn skill(&self, name: &str) -> f32 {
+        self.skills.iter().find(|(n, _)| n == name).map(|(_, d)| *d).unwrap_or_default()
+    }
+
+    /// skills as a single line, ie "aim: 1.23, speed: 4.56"
+    pub fn skills_string(&self) -> String {
+        self.skills
+            .iter()
+            .map(|(name, diff)| format!("{name}: {diff:.2}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+impl Serializable for DifficultyAttributes {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        let diff = sr.read()?;
+        let names:Vec<String> = sr.read()?;
+        let diffs:Vec<f32> = sr.read()?;
+
+        Ok(Self {
+            diff,
+            skills: names.into_iter().zip(diffs).collect(),
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.diff);
+        sw.write(self.skills.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>());
+        sw.write(self.skills.iter().map(|(_, d)| *d).collect::<Vec<_>>());
+    }
+}
+
+
+/// everything needed to calculate the performance of a score
+pub struct PerformanceCalcInfo<'a> {
+    pub score: &'a Score,
+    pub map: &'a BeatmapMeta,
+    /// overall diff of the map with the score's mods and speed
+    pub diff: f32,
+    /// diff of each skill of the map with the score's mods and speed
+    pub attributes: &'a DifficultyAttributes,
+}
+impl<'a> PerformanceCalcInfo<'a> {
+    pub fn new(score: &'a Score, map: &'a BeatmapMeta, attributes: &'a DifficultyAttributes) -> Self {
+        Self { score, map, diff: attributes.diff, attributes }
+    }
+
+    /// how the score's acc compares to the target acc.
+    /// this is the old acc-only perf curve, so scores with only acc keep roughly the same perf
+    pub fn acc_multiplier(&self, target_acc: f64) -> f32 {
+        (calc_acc(self.score) / target_acc).powi(6) as f32
+    }
+
+    /// how many of the score's judgments affect the combo in a way `check` accepts.
+    /// some judgments share a name (ie slider end misses are counted as misses), so each name is only counted once
+    fn count_judgments(&self, check: impl Fn(&AffectsCombo) -> bool) -> u32 {
+        let Some(info) = get_gamemode_info(&self.score.playmode) else { return 0 };
+
+        info.get_judgments()
+            .all_variants()
+            .iter()
+            .filter(|j| check(&j.affects_combo()))
+            .map(|j| j.as_str_internal())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|j| self.score.judgments.get(j).copy_or_default() as u32)
+            .sum()
+    }
+
+    /// how many combo-breaking judgments the score has
+    pub fn misses(&self) -> u32 {
+        self.count_judgments(|c| matches!(c, AffectsCombo::Reset))
+    }
+
+    /// multiplier for misses, each one takes off a bit more
+    pub fn miss_multiplier(&self, per_miss: f32) -> f32 {
+        per_miss.powi(self.misses() as i32)
+    }
+
+    /// how much of the map was played in one combo (0.0-1.0).
+    /// only judgments which add to or break the combo are counted, so a full combo is always 1.0
+    pub fn combo_ratio(&self) -> f32 {
+        let combo_hits = self.count_judgments(|c| !matches!(c, AffectsCombo::Ignore));
+        if combo_hits == 0 { return 0.0 }
+
+        (self.score.max_combo as f32 / combo_hits as f32).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_add_up_to_the_total() {
+        let perf = PerformanceInfo::new()
+            .with_part("Aim", 10.0)
+            .with_part("Speed", 5.5)
+            .with_part("Acc", f32::NAN);
+
+        assert_eq!(perf.total, 15.5);
+        assert_eq!(perf.breakdown_string(), "Aim: 10.00pr, Speed: 5.50pr, Acc: 0.00pr");
+    }
+
+    #[test]
+    fn difficulty_attributes_round_trip() {
+        let attributes = DifficultyAttributes::new(4.2)
+            .with_skill("Aim", 3.1)
+            .with_skill("Speed", 1.7);
+
+        let bytes = SimpleWriter::new().write(attributes.clone()).done();
+        let read:DifficultyAttributes = SerializationReader::new(bytes).read().unwrap();
+
+        assert_eq!(read, attributes);
+        assert_eq!(read.skill("Speed"), 1.7);
+        assert_eq!(read.skill("Colour"), 0.0);
+    }
+
+    fn score(playmode: &str, max_combo: u16, judgments: &[(&str, u16)]) -> Score {
+        let mut score = Score::new("map_hash".to_owned(), "user".to_owned(), playmode.to_owned());
+        score.max_combo = max_combo;
+        score.judgments = judgments.iter().map(|(j, n)| (j.to_string(), *n)).collect();
+        score
+    }
+
+    #[test]
+    fn full_combo_ratio() {
+        let map = BeatmapMeta::default();
+        let attributes = DifficultyAttributes::default();
+
+        // slider dots and spinner points dont add to the combo
+        let osu = score("osu", 120, &[("x300", 95), ("x100", 5), ("slider_end", 20), ("slider_dot", 50), ("spinner_point", 30)]);
+        let info = PerformanceCalcInfo::new(&osu, &map, &attributes);
+        assert_eq!(info.combo_ratio(), 1.0);
+        assert_eq!(info.misses(), 0);
+
+        // neither do gekis, katus, drumroll ticks or spinner hits
+        let taiko = score("taiko", 100, &[("x300", 80), ("x100", 20), ("xgeki", 10), ("xkatu", 2), ("slider_point", 40), ("spinner_point", 15)]);
+        assert_eq!(PerformanceCalcInfo::new(&taiko, &map, &attributes).combo_ratio(), 1.0);
+    }
+
+    #[test]
+    fn broken_combo_ratio() {
+        let map = BeatmapMeta::default();
+        let attributes = DifficultyAttributes::default();
+
+        let osu = score("osu", 60, &[("x300", 99), ("xmiss", 1), ("slider_end", 20), ("slider_dot", 50)]);
+        let info = PerformanceCalcInfo::new(&osu, &map, &attributes);
+        assert_eq!(info.combo_ratio(), 0.5);
+        assert_eq!(info.misses(), 1);
+    }
+}
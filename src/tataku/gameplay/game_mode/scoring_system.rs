@@ -0,0 +1,169 @@
+use crate::prelude::*;
+
+/// prefix for a scoring system's id in a mod list.
+///
+/// the scoring system is stored separately from the score's mods, so it doesnt change the score's hash or what mods the score counts as having.
+/// spectator frames only have a mod list though, so the id is put there and moved back out with [`ScoringSystemKind::take_from_mods`]
+const SCORING_MOD_PREFIX: &str = "scoring_";
+
+/// a single judgment fed to a scoring system
+#[derive(Copy, Clone, Debug)]
+pub struct ScoringHit<'a> {
+    /// internal name of the judgment
+    pub judgment: &'a str,
+    /// how far off the hit was (ms), None if unknown (ie misses, or rescoring a score without hit timings)
+    pub offset: Option<f32>,
+}
+
+/// turns a stream of judgments into a score and accuracy
+pub trait ScoringSystem: Send + Sync {
+    /// start a new play. max_judgments is 0 if the gamemode doesnt know how many judgments the map has
+    fn reset(&mut self, max_judgments: usize);
+    fn add_hit(&mut self, hit: ScoringHit);
+
+    fn score(&self) -> u64;
+    /// 0.0-1.0
+    fn accuracy(&self) -> f64;
+}
+
+
+/// which scoring system to use. Default uses the judgments' own scores and the gamemode's accuracy calc
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ScoringSystemKind {
+    Default,
+    ScoreV1,
+    ScoreV2,
+    Quaver,
+    Wife3,
+}
+impl ScoringSystemKind {
+    /// the scoring system selected in the settings for this playmode
+    pub fn from_settings(playmode: &PlayMode) -> Self {
+        match &**playmode {
+            "mania" => get_settings!().mania_settings.scoring_system,
+            _ => Self::Default,
+        }
+    }
+
+    /// get the scoring system from its id, or the default if there isnt one
+    pub fn from_id(id: Option<&String>) -> Self {
+        id
+            .and_then(|id| Self::variants().into_iter().find(|k| k.id() == id))
+            .unwrap_or_default()
+    }
+
+    /// get the id to store for this scoring system, or None if this is the default
+    pub fn to_id(&self) -> Option<String> {
+        if *self == Self::Default { return None }
+        Some(self.id().to_owned())
+    }
+
+    /// get the scoring system from a mod, if it is one
+    pub fn from_mod(m: &str) -> Option<Self> {
+        let id = m.strip_prefix(SCORING_MOD_PREFIX)?.to_owned();
+        Some(Self::from_id(Some(&id)))
+    }
+
+    /// the mod to put in a mod list for this scoring system, or None if this is the default
+    pub fn to_mod(&self) -> Option<String> {
+        self.to_id().map(|id| format!("{SCORING_MOD_PREFIX}{id}"))
+    }
+
+    /// set the scoring system to the one selected in the settings
+    pub fn apply_from_settings(mods: &mut ModManager, playmode: &PlayMode) {
+        mods.scoring_system = Self::from_settings(playmode);
+    }
+
+    /// move a scoring system out of the mod list (from spectator frames, or scores from before the scoring system was stored separately)
+    pub fn take_from_mods(mods: &mut ModManager) {
+        let Some(kind) = mods.mods.iter().find_map(|m| Self::from_mod(m)) else { return };
+        mods.mods.retain(|m| Self::from_mod(m).is_none());
+        mods.scoring_system = kind;
+    }
+
+    /// create the scoring system for a playmode, None if this is the default or the playmode doesnt support it
+    pub fn create(&self, playmode: &PlayMode) -> Option<Box<dyn ScoringSystem>> {
+        if *self == Self::Default { return None }
+        get_gamemode_info(playmode)?.create_scoring_system(*self)
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::ScoreV1 => "v1",
+            Self::ScoreV2 => "v2",
+            Self::Quaver => "quaver",
+            Self::Wife3 => "wife3",
+        }
+    }
+
+    /// name for leaderboards and other places where space is tight
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Self::Default => "",
+            Self::ScoreV1 => "V1",
+            Self::ScoreV2 => "V2",
+            Self::Quaver => "QV",
+            Self::Wife3 => "W3",
+        }
+    }
+
+    /// recalculate the score and accuracy of a score with this scoring system.
+    ///
+    /// hits with timings are fed in the order they happened, and any judgments without timings (ie misses) are added after,
+    /// so systems which depend on the order of hits (combo, bonus) will only be close for old scores
+    pub fn rescore(&self, score: &IngameScore) -> Option<(u64, f64)> {
+        let mut system = self.create(&score.playmode)?;
+
+        let mut remaining = score.judgments.clone();
+        let max_judgments = remaining.values().map(|c| *c as usize).sum();
+        system.reset(max_judgments);
+
+        for timing in score.timings.timings.iter() {
+            let judgment = score.timings.judgment_of(timing);
+            if let Some(count) = remaining.get_mut(judgment) {
+                *count = count.saturating_sub(1);
+            }
+            system.add_hit(ScoringHit { judgment, offset: Some(timing.offset) });
+        }
+
+        for (judgment, count) in remaining.iter() {
+            for _ in 0..*count {
+                system.add_hit(ScoringHit { judgment, offset: None });
+            }
+        }
+
+        Some((system.score(), system.accuracy()))
+    }
+}
+impl Default for ScoringSystemKind {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl Dropdownable for ScoringSystemKind {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::Default,
+            Self::ScoreV1,
+            Self::ScoreV2,
+            Self::Quaver,
+            Self::Wife3,
+        ]
+    }
+
+    fn display_text(&self) -> String {
+        match self {
+            Self::Default => "Tataku",
+            Self::ScoreV1 => "osu! ScoreV1",
+            Self::ScoreV2 => "osu! ScoreV2",
+            Self::Quaver => "Quaver",
+            Self::Wife3 => "Etterna Wife3",
+        }.to_owned()
+    }
+
+    fn from_string(s:String) -> Self {
+        Self::variants().into_iter().find(|k| k.display_text() == s).unwrap_or_default()
+    }
+}
@@ -1,26 +1,79 @@
-#![allow(dead_code)]
+use crate::prelude::*;
 
-#[derive(Clone, Default)]
+/// how much health a full bar is
+const MAX_HEALTH:f32 = 100.0;
+
+/// what style of health should be used
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HealthPreset {
+    /// gains and losses are scaled by the map's hp, and health drains between objects
+    Osu,
+    /// fixed gains and losses, no passive drain
+    Quaver,
+}
+impl HealthPreset {
+    /// get the preset which matches how the map's source game handles health
+    pub fn from_beatmap_type(beatmap_type: BeatmapType) -> Self {
+        match beatmap_type {
+            BeatmapType::Quaver | BeatmapType::Stepmania => Self::Quaver,
+            _ => Self::Osu,
+        }
+    }
+}
+
+
+#[derive(Clone)]
 pub struct HealthHelper {
+    pub preset: HealthPreset,
     max_health: f32,
     current_health: f32,
 
-    // pub damage_amount: f32,
-    // pub heal_amount: f32,
+    /// multiplier for judgments which give health
+    gain_multiplier: f32,
+    /// multiplier for judgments which take health
+    loss_multiplier: f32,
+
+    /// how much health is lost per ms while draining
+    drain_rate: f32,
+    /// last time drain was checked
+    last_drain_time: Option<f32>,
+    /// has the first judgment been added? (drain should only happen after the first object)
+    has_judgment: bool,
 }
 impl HealthHelper {
-    pub fn new(_hp_val: Option<f32>) -> Self {
-        let max_health = 80.0;
+    pub fn new(preset: HealthPreset, hp: f32) -> Self {
+        // maps without an hp value get a middle-of-the-road value
+        let hp = if hp < 0.0 { 5.0 } else { hp.clamp(0.0, 10.0) };
+
+        let (gain_multiplier, loss_multiplier, drain_rate) = match preset {
+            HealthPreset::Osu => (
+                map_difficulty(hp, 1.5, 1.0, 0.6),
+                map_difficulty(hp, 0.5, 1.0, 2.0),
+                map_difficulty(hp, 0.5, 2.5, 6.0) / 1000.0,
+            ),
+            // judgment values are based on osu's, so scale them down to roughly match quaver's
+            HealthPreset::Quaver => (0.2, 0.6, 0.0),
+        };
 
         Self {
-            max_health,
-            current_health: max_health,
+            preset,
+            max_health: MAX_HEALTH,
+            current_health: MAX_HEALTH,
 
-            // damage_amount: 10.0,
-            // heal_amount: 3.0
+            gain_multiplier,
+            loss_multiplier,
+            drain_rate,
+            last_drain_time: None,
+            has_judgment: false,
         }
     }
 
+    /// disable passive drain (ie for modes where osu doesnt drain, like taiko)
+    pub fn without_drain(mut self) -> Self {
+        self.drain_rate = 0.0;
+        self
+    }
+
     pub fn is_dead(&self) -> bool {
         self.current_health <= 0.0
     }
@@ -31,6 +84,8 @@ impl HealthHelper {
 
     pub fn reset(&mut self) {
         self.current_health = self.max_health;
+        self.last_drain_time = None;
+        self.has_judgment = false;
     }
 
     #[inline]
@@ -39,22 +94,40 @@ impl HealthHelper {
         if self.current_health > self.max_health { self.current_health = self.max_health }
     }
 
+    /// apply a judgment's health value, scaled by the map's hp
     pub fn do_health(&mut self, value:f32) {
-        self.current_health += value;
+        self.has_judgment = true;
+
+        if value > 0.0 {
+            self.current_health += value * self.gain_multiplier;
+        } else {
+            self.current_health += value * self.loss_multiplier;
+        }
+        self.validate_health();
+    }
+
+    /// drain health for the time passed since the last update.
+    /// if draining is false (ie, in a break), time still passes but no health is taken
+    pub fn update(&mut self, time: f32, draining: bool) {
+        let last_time = self.last_drain_time.replace(time).unwrap_or(time);
+        if !draining || !self.has_judgment || self.drain_rate == 0.0 { return }
+
+        // time went backwards (ie, a time jump), dont heal the player
+        let elapsed = (time - last_time).max(0.0);
+        self.current_health -= elapsed * self.drain_rate;
         self.validate_health();
     }
+}
+impl Default for HealthHelper {
+    fn default() -> Self {
+        Self::new(HealthPreset::Osu, 5.0)
+    }
+}
 
 
-    // pub fn take_damage(&mut self) {
-    //     self.current_health -= self.damage_amount;
-    //     self.validate_health();
-    // }
-    // pub fn give_life(&mut self) {
-    //     self.current_health += self.heal_amount;
-    //     self.validate_health();
-    // }
-    // pub fn give_extra_life(&mut self) {
-    //     self.current_health += self.heal_amount * 1.5;
-    //     self.validate_health();
-    // }
+/// health ratio (0.0-1.0) after each judgment
+pub struct HealthStat;
+impl GameModeStat for HealthStat {
+    fn name(&self) -> &'static str { "health" }
+    fn display_name(&self) -> &'static str { "Health" }
 }
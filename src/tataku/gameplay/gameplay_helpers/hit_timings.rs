@@ -0,0 +1,286 @@
+use crate::prelude::*;
+
+/// a single hit
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HitTiming {
+    /// time of the note that was hit (ms)
+    pub note_time: f32,
+    /// how far off the hit was (ms, negative is early)
+    pub offset: f32,
+    /// index into the judgment names list
+    judgment: u8,
+}
+impl Serializable for HitTiming {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            note_time: sr.read()?,
+            offset: sr.read()?,
+            judgment: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.note_time);
+        sw.write(self.offset);
+        sw.write(self.judgment);
+    }
+}
+
+
+/// every hit in a score.
+/// judgment names are only stored once, so this stays small enough to keep with the score
+#[derive(Clone, Debug, Default)]
+pub struct HitTimings {
+    judgments: Vec<String>,
+    pub timings: Vec<HitTiming>,
+}
+impl HitTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add a hit at song time `time`. the offset is stored as what the player actually hit at, not the song time offset
+    pub fn add_hit(&mut self, time: f32, note_time: f32, speed: f32, judgment: &str) {
+        self.add(note_time, (time - note_time) / speed, judgment)
+    }
+
+    pub fn add(&mut self, note_time: f32, offset: f32, judgment: &str) {
+        let index = match self.judgments.iter().position(|j| j == judgment) {
+            Some(index) => index,
+            None => {
+                self.judgments.push(judgment.to_owned());
+                self.judgments.len() - 1
+            }
+        };
+
+        self.timings.push(HitTiming {
+            note_time,
+            offset,
+            judgment: index as u8,
+        });
+    }
+
+    /// get the name of the judgment a hit got
+    pub fn judgment_of(&self, timing: &HitTiming) -> &str {
+        self.judgments.get(timing.judgment as usize).map(|j| j.as_str()).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.judgments.clear();
+        self.timings.clear();
+    }
+
+    pub fn stats(&self) -> HitTimingStats {
+        HitTimingStats::new(self)
+    }
+
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        SimpleWriter::new().write(self.clone()).done()
+    }
+    pub fn from_bytes(bytes: Vec<u8>) -> TatakuResult<Self> {
+        Ok(SerializationReader::new(bytes).read()?)
+    }
+}
+impl Serializable for HitTimings {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        Ok(Self {
+            judgments: sr.read()?,
+            timings: sr.read()?,
+        })
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.judgments.clone());
+        sw.write(self.timings.clone());
+    }
+}
+
+
+/// find which window a hit at song time `time` lands in, with how far off it was (ms, negative is early).
+/// windows are in real time, so the offset is adjusted for the game speed
+pub fn find_hit_window<HJ>(windows: &[(HJ, Range<f32>)], time: f32, note_time: f32, speed: f32) -> Option<(&HJ, f32)> {
+    let offset = (time - note_time) / speed;
+    windows
+        .iter()
+        .find(|(_, window)| window.contains(&offset.abs()))
+        .map(|(hj, _)| (hj, offset))
+}
+
+
+/// stats derived from a score's hit timings
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HitTimingStats {
+    /// standard deviation of the offsets * 10
+    pub unstable_rate: f32,
+    /// average offset (ms)
+    pub mean: f32,
+
+    /// how many hits were early
+    pub early_count: u32,
+    /// average offset of early hits (ms)
+    pub early_mean: f32,
+    /// how many hits were late
+    pub late_count: u32,
+    /// average offset of late hits (ms)
+    pub late_mean: f32,
+}
+impl HitTimingStats {
+    pub fn new(timings: &HitTimings) -> Self {
+        let offsets = timings.timings.iter().map(|t| t.offset).collect::<Vec<_>>();
+        if offsets.is_empty() { return Self::default() }
+
+        let count = offsets.len() as f32;
+        let mean = offsets.iter().sum::<f32>() / count;
+        let variance = offsets.iter().map(|o| (o - mean).powi(2)).sum::<f32>() / count;
+
+        let early = offsets.iter().filter(|o| **o < 0.0).collect::<Vec<_>>();
+        let late = offsets.iter().filter(|o| **o > 0.0).collect::<Vec<_>>();
+        let avg = |list: &Vec<&f32>| if list.is_empty() { 0.0 } else { list.iter().map(|o| **o).sum::<f32>() / list.len() as f32 };
+
+        Self {
+            unstable_rate: variance.sqrt() * 10.0,
+            mean,
+            early_count: early.len() as u32,
+            early_mean: avg(&early),
+            late_count: late.len() as u32,
+            late_mean: avg(&late),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// osu windows for od 5
+    fn osu_windows() -> Vec<(OsuHitJudgments, Range<f32>)> {
+        vec![
+            (OsuHitJudgments::X300, 0.0..50.0),
+            (OsuHitJudgments::X100, 50.0..100.0),
+            (OsuHitJudgments::X50, 100.0..150.0),
+            (OsuHitJudgments::Miss, 150.0..175.0),
+        ]
+    }
+
+    /// find the window for a hit, and add it to the timings like the ingame manager does
+    fn hit(timings: &mut HitTimings, time: f32, note_time: f32, speed: f32) -> Option<OsuHitJudgments> {
+        let windows = osu_windows();
+        let (hj, _) = find_hit_window(&windows, time, note_time, speed)?;
+        timings.add_hit(time, note_time, speed, hj.as_str_internal());
+        Some(*hj)
+    }
+
+    #[test]
+    fn judgments_early_and_late() {
+        let mut timings = HitTimings::new();
+        let cases = [
+            (1000.0, OsuHitJudgments::X300),
+            (1030.0, OsuHitJudgments::X300),
+            (970.0, OsuHitJudgments::X300),
+            (1075.0, OsuHitJudgments::X100),
+            (925.0, OsuHitJudgments::X100),
+            (1120.0, OsuHitJudgments::X50),
+            (880.0, OsuHitJudgments::X50),
+            (1160.0, OsuHitJudgments::Miss),
+            (840.0, OsuHitJudgments::Miss),
+        ];
+
+        for (time, expected) in cases {
+            assert_eq!(hit(&mut timings, time, 1000.0, 1.0), Some(expected), "hit at {time}");
+        }
+
+        assert_eq!(timings.timings.len(), cases.len());
+        for ((time, expected), timing) in cases.iter().zip(timings.timings.iter()) {
+            assert_eq!(timing.note_time, 1000.0);
+            assert_eq!(timing.offset, time - 1000.0);
+            assert_eq!(timings.judgment_of(timing), expected.as_str_internal());
+        }
+    }
+
+    #[test]
+    fn window_edges() {
+        let windows = osu_windows();
+        // the start of a window is inclusive, the end isnt
+        assert_eq!(find_hit_window(&windows, 1050.0, 1000.0, 1.0).map(|(hj, _)| *hj), Some(OsuHitJudgments::X100));
+        assert_eq!(find_hit_window(&windows, 950.0, 1000.0, 1.0).map(|(hj, _)| *hj), Some(OsuHitJudgments::X100));
+        assert_eq!(find_hit_window(&windows, 1150.0, 1000.0, 1.0).map(|(hj, _)| *hj), Some(OsuHitJudgments::Miss));
+    }
+
+    #[test]
+    fn outside_windows_is_not_a_hit() {
+        let mut timings = HitTimings::new();
+        assert_eq!(hit(&mut timings, 1175.0, 1000.0, 1.0), None);
+        assert_eq!(hit(&mut timings, 500.0, 1000.0, 1.0), None);
+        assert!(timings.is_empty());
+    }
+
+    #[test]
+    fn speed_adjusts_offsets() {
+        let mut timings = HitTimings::new();
+
+        // 60ms of song time at 1.5x is 40ms of real time, which is a 300
+        assert_eq!(hit(&mut timings, 1060.0, 1000.0, 1.5), Some(OsuHitJudgments::X300));
+        // 60ms of song time at 0.75x is 80ms of real time, which is a 100
+        assert_eq!(hit(&mut timings, 940.0, 1000.0, 0.75), Some(OsuHitJudgments::X100));
+
+        assert_eq!(timings.timings[0].offset, 40.0);
+        assert_eq!(timings.timings[1].offset, -80.0);
+    }
+
+    #[test]
+    fn judgment_names_are_stored_once() {
+        let mut timings = HitTimings::new();
+        for time in [1000.0, 1010.0, 1075.0, 990.0] {
+            hit(&mut timings, time, 1000.0, 1.0);
+        }
+
+        assert_eq!(timings.judgments, vec![
+            OsuHitJudgments::X300.as_str_internal().to_owned(),
+            OsuHitJudgments::X100.as_str_internal().to_owned(),
+        ]);
+        assert_eq!(timings.judgment_of(&timings.timings[3]), OsuHitJudgments::X300.as_str_internal());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut timings = HitTimings::new();
+        for time in [1000.0, 1075.0, 880.0, 1160.0] {
+            hit(&mut timings, time, 1000.0, 1.0);
+        }
+
+        let read = HitTimings::from_bytes(timings.to_bytes()).unwrap();
+        assert_eq!(read.judgments, timings.judgments);
+        assert_eq!(read.timings.len(), timings.timings.len());
+        for (a, b) in read.timings.iter().zip(timings.timings.iter()) {
+            assert_eq!((a.note_time, a.offset), (b.note_time, b.offset));
+            assert_eq!(read.judgment_of(a), timings.judgment_of(b));
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let mut timings = HitTimings::new();
+        for offset in [-20.0, -10.0, 0.0, 10.0, 40.0] {
+            timings.add(1000.0, offset, "x300");
+        }
+
+        let stats = timings.stats();
+        assert_eq!(stats.mean, 4.0);
+        assert_eq!(stats.early_count, 2);
+        assert_eq!(stats.early_mean, -15.0);
+        assert_eq!(stats.late_count, 2);
+        assert_eq!(stats.late_mean, 25.0);
+        // variance of the offsets is 424
+        assert!((stats.unstable_rate - 424.0f32.sqrt() * 10.0).abs() < 0.01);
+
+        let empty = HitTimings::new().stats();
+        assert_eq!(empty.unstable_rate, 0.0);
+        assert_eq!(empty.early_count + empty.late_count, 0);
+    }
+}
@@ -15,6 +15,16 @@ pub struct IngameScore {
 
     // snapshots
     pub stats: GameplayStats,
+
+    /// every hit in this score. named this way so it doesnt get mixed up with score.hit_timings
+    pub timings: HitTimings,
+
+    /// judgment preset id the score was set with, see [`JudgmentPreset::to_id`].
+    /// this isnt in the score's mods so it doesnt change the score's hash
+    pub judgment_preset: Option<String>,
+
+    /// scoring system the score was set with. like the preset, this isnt in the score's mods
+    pub scoring_system: ScoringSystemKind,
 }
 impl IngameScore {
     pub fn new(score: Score, is_current: bool, is_previous: bool) -> Self {
@@ -24,22 +34,66 @@ impl IngameScore {
             is_previous,
             replay_location: ReplayLocation::Local,
             stats: GameplayStats::default(),
+            timings: HitTimings::new(),
+            judgment_preset: None,
+            scoring_system: ScoringSystemKind::Default,
+        }
+    }
+
+    pub fn with_timings(mut self, timings: HitTimings) -> Self {
+        self.timings = timings;
+        self
+    }
+    /// use `timings` if this score doesnt have any (ie it was imported from an osu replay, which only has key presses).
+    /// returns if they were used
+    pub fn fill_missing_timings(&mut self, timings: HitTimings) -> bool {
+        if !self.timings.is_empty() { return false }
+        self.timings = timings;
+        true
+    }
+    pub fn with_judgment_preset(mut self, judgment_preset: Option<String>) -> Self {
+        self.judgment_preset = judgment_preset;
+        self
+    }
+    pub fn with_scoring_system(mut self, scoring_system: ScoringSystemKind) -> Self {
+        self.scoring_system = scoring_system;
+        self
+    }
+
+    /// stats from this score's hit timings, None if it doesnt have any
+    pub fn hit_stats(&self) -> Option<HitTimingStats> {
+        if self.timings.is_empty() { None } else { Some(self.timings.stats()) }
+    }
+
+    /// the judgment preset and custom windows this score was set with
+    pub fn get_judgment_preset(&self) -> (JudgmentPreset, Vec<f32>) {
+        JudgmentPreset::from_id(self.judgment_preset.as_ref(), &self.playmode)
+    }
+
+    /// the score's accuracy. scoring systems can depend on more than the judgment counts,
+    /// so scores set with one use the acc that was stored with the score
+    pub fn calc_acc(&self) -> f64 {
+        if self.scoring_system != ScoringSystemKind::Default {
+            return self.accuracy.normal_or(1.0);
         }
+        calc_acc(&self.score)
     }
 
-    pub async fn get_replay(&self) -> Option<Replay> {
+    /// get this score's replay, and the extras it was played with
+    pub async fn get_replay(&self) -> Option<(Replay, ReplayExtras)> {
         info!("downloading: {:#?}", self.replay_location);
 
         match &self.replay_location {
             ReplayLocation::Local => {
                 match get_local_replay_for_score(&self.score) {
-                    Ok(replay) => return Some(replay),
+                    Ok(replay) => return Some((replay, ReplayExtras::from_score(self))),
                     Err(e) => NotificationManager::add_error_notification("Error loading replay", e).await,
                 }
             }
             ReplayLocation::Online(downloader) => {
                 match downloader.get_replay().await {
-                    Ok(replay) => return Some(replay),
+                    // other games' leaderboards can have the preset without it being in the replay
+                    Ok((replay, extras)) => return Some((replay, extras.or(ReplayExtras::from_score(self)))),
                     Err(e) => NotificationManager::add_error_notification("Error reading replay", e).await,
                 }
             }
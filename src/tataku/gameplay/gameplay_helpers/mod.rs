@@ -1,13 +1,23 @@
 mod key_counter;
 mod ingame_score;
 mod health_helper;
+mod hit_timings;
 mod hit_indicator;
+mod practice_helper;
+mod replay_ghost;
+mod replay_simulator;
+mod replay_viewer;
 mod hitsound_manager;
 mod judgement_image_helper;
 
 pub use key_counter::*;
 pub use ingame_score::*;
 pub use health_helper::*;
+pub use hit_timings::*;
 pub use hit_indicator::*;
+pub use practice_helper::*;
+pub use replay_ghost::*;
+pub use replay_simulator::*;
+pub use replay_viewer::*;
 pub use hitsound_manager::*;
 pub use judgement_image_helper::*;
\ No newline at end of file
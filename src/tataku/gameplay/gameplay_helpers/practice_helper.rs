@@ -0,0 +1,113 @@
+use crate::prelude::*;
+
+/// how long before the A point should playback resume when looping
+pub const PRACTICE_REWIND_LEAD:f32 = 2000.0;
+
+#[derive(Clone, Debug)]
+pub struct PracticeSettings {
+    /// A point (ms)
+    pub start: f32,
+    /// B point (ms)
+    pub end: f32,
+    /// how many loops to play before finishing. 0 means loop forever
+    pub loop_count: u32,
+    /// how much to increase the speed by after each clean loop (ie 0.05 for 5%)
+    pub rate_step: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct PracticeLoopResult {
+    /// 1 based loop number
+    pub loop_num: u32,
+    /// speed the loop was played at
+    pub speed: f32,
+    pub accuracy: f64,
+    /// number of combo-breaking judgments
+    pub misses: u32,
+}
+impl PracticeLoopResult {
+    /// was this loop played without breaking combo?
+    pub fn is_clean(&self) -> bool { self.misses == 0 }
+}
+
+
+/// keeps track of a practice session's loops
+pub struct PracticeHelper {
+    pub settings: PracticeSettings,
+    /// 0 based index of the loop currently being played
+    pub current_loop: u32,
+    /// results of each finished loop
+    pub results: Vec<PracticeLoopResult>,
+
+    /// score when the current loop started, used to get the judgments for only this loop
+    loop_start_score: Option<Score>,
+}
+impl PracticeHelper {
+    pub fn new(settings: PracticeSettings) -> Self {
+        Self {
+            settings,
+            current_loop: 0,
+            results: Vec::new(),
+            loop_start_score: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.current_loop = 0;
+        self.results.clear();
+        self.loop_start_score = None;
+    }
+
+    /// where should playback start from for each loop?
+    pub fn rewind_time(&self) -> f32 {
+        (self.settings.start - PRACTICE_REWIND_LEAD).max(0.0)
+    }
+
+    /// is the current loop the last one?
+    pub fn is_last_loop(&self) -> bool {
+        self.settings.loop_count > 0 && self.current_loop + 1 >= self.settings.loop_count
+    }
+
+    /// called when the A point is passed
+    pub fn loop_started(&mut self, score: &Score) {
+        if self.loop_start_score.is_none() {
+            self.loop_start_score = Some(score.clone());
+        }
+    }
+
+    /// called when the B point is passed, returns the result for the loop that was just played
+    pub fn loop_finished(&mut self, score: &Score, combo_breaks: &Vec<&'static str>) -> PracticeLoopResult {
+        // get the judgments only for this loop
+        let mut loop_score = score.clone();
+        if let Some(start) = std::mem::take(&mut self.loop_start_score) {
+            for (k, v) in loop_score.judgments.iter_mut() {
+                *v -= start.judgments.get(k).copy_or_default();
+            }
+        }
+
+        let misses = combo_breaks
+            .iter()
+            .map(|j| loop_score.judgments.get(*j).copy_or_default() as u32)
+            .sum();
+
+        let result = PracticeLoopResult {
+            loop_num: self.current_loop + 1,
+            speed: score.speed,
+            accuracy: calc_acc(&loop_score),
+            misses,
+        };
+
+        self.results.push(result.clone());
+        self.current_loop += 1;
+        result
+    }
+
+    /// get the speed the next loop should be played at
+    pub fn next_speed(&self, current_speed: f32, last: &PracticeLoopResult) -> f32 {
+        if last.is_clean() && self.settings.rate_step > 0.0 {
+            (current_speed + self.settings.rate_step).clamp(0.05, 10.0)
+        } else {
+            current_speed
+        }
+    }
+}
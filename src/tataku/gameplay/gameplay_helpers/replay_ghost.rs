@@ -0,0 +1,55 @@
+use crate::prelude::*;
+
+/// a replay being played alongside the current play, to race against
+pub struct ReplayGhost {
+    pub username: String,
+    simulator: ReplaySimulator,
+    replay: Replay,
+
+    /// time of the last update, to tell when the map was restarted
+    last_time: f32,
+    /// index of the next frame to check for cursor movement
+    frame_index: usize,
+    /// latest cursor position, in osu playfield coords
+    pub cursor_pos: Option<Vector2>,
+}
+impl ReplayGhost {
+    pub async fn new(replay: Replay, extras: ReplayExtras, map: Arc<BeatmapMeta>) -> TatakuResult<Self> {
+        let username = replay.score_data.as_ref().map(|s| s.username.clone()).unwrap_or_default();
+        let simulator = ReplaySimulator::new(&replay, &extras, &map).await?;
+
+        Ok(Self {
+            username,
+            simulator,
+            replay,
+            last_time: f32::NEG_INFINITY,
+            frame_index: 0,
+            cursor_pos: None,
+        })
+    }
+
+    /// run the ghost up to `time`. if time went backwards (ie, a restart), the ghost starts over
+    pub async fn update(&mut self, time: f32) {
+        if time < self.last_time {
+            self.simulator.restart().await;
+            self.frame_index = 0;
+            self.cursor_pos = None;
+        }
+        self.last_time = time;
+
+        self.simulator.advance_to(time).await;
+
+        while let Some((frame_time, frame)) = self.replay.frames.get(self.frame_index) {
+            if *frame_time > time { break }
+            if let ReplayFrame::MousePos(x, y) = frame {
+                self.cursor_pos = Some(Vector2::new(*x as f64, *y as f64));
+            }
+            self.frame_index += 1;
+        }
+    }
+
+    /// the ghost's score at the current time
+    pub fn score(&self) -> &IngameScore {
+        self.simulator.score()
+    }
+}
@@ -0,0 +1,125 @@
+use crate::prelude::*;
+
+/// how far the virtual clock moves each update (ms)
+const SIMULATION_STEP: f32 = 1.0;
+/// how long after the map ends to keep simulating, so any notes still waiting to be missed get missed
+const SIMULATION_END_PADDING: f32 = 1000.0;
+
+/// plays a replay without a window or audio, to get the score it produces
+pub struct ReplaySimulator {
+    manager: Box<IngameManager>,
+    time: f32,
+}
+impl ReplaySimulator {
+    /// set up a simulation of a replay on a map. nothing happens until [`Self::advance_to`] is called
+    pub async fn new(replay: &Replay, extras: &ReplayExtras, map: &BeatmapMeta) -> TatakuResult<Self> {
+        let score = replay.score_data.as_ref().ok_or(TatakuError::String("replay has no score data".to_owned()))?;
+
+        let beatmap = Beatmap::from_metadata(map)?;
+        let playmode = beatmap.playmode(score.playmode.clone());
+        let info = get_gamemode_info(&playmode).ok_or_else(|| TatakuError::GameMode(GameModeError::UnknownGameMode))?;
+        let gamemode = info.create_game(&beatmap, true).await?;
+
+        let mut manager = IngameManager::new_headless(beatmap, gamemode).await;
+        manager.simulated_time = Some(0.0);
+        // replays saved before the extras were stored dont have them, so get them from the local score if there is one
+        let extras = extras.clone().or(Database::get_score_extras(&score.hash()).await);
+        manager.set_replay(replay.clone(), &extras);
+
+        let mut sim = Self {
+            manager: Box::new(manager),
+            time: 0.0,
+        };
+        sim.restart().await;
+        Ok(sim)
+    }
+
+    /// go back to the start of the map, without having to load it again
+    pub async fn restart(&mut self) {
+        self.manager.reset().await;
+        self.manager.lead_in_time = 0.0;
+        self.manager.started = true;
+        self.time = 0.0;
+    }
+
+    /// run the simulation up to `time`
+    pub async fn advance_to(&mut self, time: f32) {
+        while self.time <= time && !self.manager.completed {
+            self.manager.simulated_time = Some(self.time);
+            self.manager.update().await;
+            self.time += SIMULATION_STEP;
+        }
+    }
+
+    /// the score so far
+    pub fn score(&self) -> &IngameScore {
+        &self.manager.score
+    }
+    pub fn end_time(&self) -> f32 {
+        self.manager.end_time
+    }
+
+    /// simulate a replay on a map, and get the resulting score
+    pub async fn simulate(replay: &Replay, extras: &ReplayExtras, map: &BeatmapMeta) -> TatakuResult<IngameScore> {
+        Ok(Self::run(replay, extras, map, None).await?.0)
+    }
+
+    /// simulate a replay, also sampling the health ratio every `interval` ms as (time, ratio)
+    pub async fn simulate_with_health(replay: &Replay, extras: &ReplayExtras, map: &BeatmapMeta, interval: f32) -> TatakuResult<(IngameScore, Vec<(f32, f32)>)> {
+        Self::run(replay, extras, map, Some(interval)).await
+    }
+
+    async fn run(replay: &Replay, extras: &ReplayExtras, map: &BeatmapMeta, health_interval: Option<f32>) -> TatakuResult<(IngameScore, Vec<(f32, f32)>)> {
+        let mut sim = Self::new(replay, extras, map).await?;
+        let end_time = sim.end_time() + SIMULATION_END_PADDING;
+
+        let mut health = Vec::new();
+        match health_interval {
+            Some(interval) => {
+                let mut time = 0.0;
+                while time <= sim.end_time() && !sim.manager.completed {
+                    sim.advance_to(time).await;
+                    health.push((time, sim.manager.health.get_ratio()));
+                    time += interval;
+                }
+                sim.advance_to(end_time).await;
+            }
+            None => sim.advance_to(end_time).await,
+        }
+
+        Ok((sim.manager.score, health))
+    }
+
+    /// check that a replay reproduces the judgments and combo its score claims
+    pub async fn verify(replay: &Replay, extras: &ReplayExtras, map: &BeatmapMeta) -> TatakuResult<ReplayVerification> {
+        let claimed = replay.score_data.clone().ok_or(TatakuError::String("replay has no score data".to_owned()))?;
+        let simulated = Self::simulate(replay, extras, map).await?;
+
+        let mut mismatched_judgments = Vec::new();
+        for key in claimed.judgments.keys().chain(simulated.judgments.keys()).collect::<HashSet<_>>() {
+            let claimed_count = claimed.judgments.get(key).copied().unwrap_or_default();
+            let simulated_count = simulated.judgments.get(key).copied().unwrap_or_default();
+            if claimed_count != simulated_count {
+                mismatched_judgments.push((key.clone(), claimed_count, simulated_count));
+            }
+        }
+
+        Ok(ReplayVerification {
+            matches: mismatched_judgments.is_empty() && claimed.max_combo == simulated.max_combo,
+            mismatched_judgments,
+            claimed,
+            simulated: simulated.score,
+        })
+    }
+}
+
+/// result of checking a replay against its score
+#[derive(Clone)]
+pub struct ReplayVerification {
+    /// do the judgments and max combo match?
+    pub matches: bool,
+    /// judgment, claimed count, simulated count
+    pub mismatched_judgments: Vec<(String, u16, u16)>,
+    pub claimed: Score,
+    pub simulated: Score,
+}
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+/// playback rates the viewer can step through
+const RATES: &[f32] = &[0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 2.0];
+/// how far the seek keys jump (ms)
+pub const REPLAY_SEEK_STEP: f32 = 5000.0;
+/// the most the virtual clock moves each update while fast-forwarding to a seek point (ms).
+/// the clock also stops on every replay frame, so inputs are still judged at their own time
+pub const REPLAY_SEEK_RESOLUTION: f32 = 100.0;
+
+const SEEKBAR_HEIGHT: f64 = 10.0;
+const SEEKBAR_PADDING: f64 = 20.0;
+const KEY_SIZE: Vector2 = Vector2::new(40.0, 40.0);
+const DEPTH: f64 = -1000.0;
+
+/// playback controls for watching a replay
+pub struct ReplayViewer {
+    pub paused: bool,
+    rate_index: usize,
+    pub show_key_overlay: bool,
+
+    /// needed for seekbar clicks, since mouse_down doesnt get a position
+    pub mouse_pos: Vector2,
+}
+impl ReplayViewer {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            rate_index: RATES.iter().position(|r| *r == 1.0).unwrap(),
+            show_key_overlay: true,
+            mouse_pos: Vector2::ZERO,
+        }
+    }
+
+    pub fn rate(&self) -> f32 {
+        RATES[self.rate_index]
+    }
+    pub fn faster(&mut self) {
+        self.rate_index = (self.rate_index + 1).min(RATES.len() - 1);
+    }
+    pub fn slower(&mut self) {
+        self.rate_index = self.rate_index.saturating_sub(1);
+    }
+
+    fn seekbar_bounds(window_size: Vector2) -> (Vector2, Vector2) {
+        let pos = Vector2::new(SEEKBAR_PADDING, window_size.y - SEEKBAR_PADDING - SEEKBAR_HEIGHT);
+        let size = Vector2::new(window_size.x - SEEKBAR_PADDING * 2.0, SEEKBAR_HEIGHT);
+        (pos, size)
+    }
+
+    /// get the time the seekbar would seek to if it was clicked at the current mouse pos
+    pub fn seekbar_time(&self, window_size: Vector2, end_time: f32) -> Option<f32> {
+        let (pos, size) = Self::seekbar_bounds(window_size);
+
+        // a bit of leniency on the y, the bar is pretty thin
+        let pos = pos - Vector2::with_y(SEEKBAR_HEIGHT);
+        let size = size + Vector2::with_y(SEEKBAR_HEIGHT * 2.0);
+        if !Rectangle::bounds_only(pos, size).contains(self.mouse_pos) { return None }
+
+        Some(((self.mouse_pos.x - pos.x) / size.x) as f32 * end_time)
+    }
+
+    pub fn draw(&self, time: f32, end_time: f32, window_size: Vector2, key_counter: &KeyCounter, list: &mut RenderableCollection) {
+        let font = get_font();
+
+        // seekbar
+        let (pos, size) = Self::seekbar_bounds(window_size);
+        let progress = (time / end_time).clamp(0.0, 1.0) as f64;
+        list.push(Rectangle::new(Color::BLACK.alpha(0.6), DEPTH, pos, size, Some(Border::new(Color::WHITE, 1.0))));
+        list.push(Rectangle::new(Color::WHITE, DEPTH - 0.001, pos, Vector2::new(size.x * progress, size.y), None));
+
+        // status text
+        let status = format!(
+            "{}{:.2}x | P: pause, .: step, Left/Right: seek, -/=: speed, K: keys",
+            if self.paused { "Paused | " } else { "" },
+            self.rate()
+        );
+        list.push(Text::new(Color::WHITE, DEPTH, pos - Vector2::with_y(25.0), 20, status, font.clone()));
+
+        // key overlay
+        if !self.show_key_overlay { return }
+        let mut key_pos = Vector2::new(window_size.x - SEEKBAR_PADDING - KEY_SIZE.x, window_size.y / 2.0 - KEY_SIZE.y * key_counter.key_order.len() as f64 / 2.0);
+        for key in key_counter.key_order.iter() {
+            let Some(info) = key_counter.keys.get(key) else { continue };
+            let color = if info.held { Color::YELLOW } else { Color::BLACK.alpha(0.6) };
+
+            list.push(Rectangle::new(color, DEPTH, key_pos, KEY_SIZE, Some(Border::new(Color::WHITE, 1.0))));
+            list.push(Text::new(Color::WHITE, DEPTH - 0.001, key_pos + Vector2::new(5.0, 5.0), 15, info.label.clone(), font.clone()));
+            key_pos.y += KEY_SIZE.y + 5.0;
+        }
+    }
+}
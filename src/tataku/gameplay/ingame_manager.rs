@@ -10,15 +10,20 @@ pub const DURATION_HEIGHT:f64 = 35.0;
 
 /// ms between spectator score sync packets
 const SPECTATOR_SCORE_SYNC_INTERVAL:f32 = 1000.0;
+/// ms between multiplayer score sync packets
+const LOBBY_SCORE_SYNC_INTERVAL:f32 = 1000.0;
 
 
 // bc im lazy
 macro_rules! add_timing {
-    ($self:ident, $time:expr, $note_time:expr) => {{
+    ($self:ident, $time:expr, $note_time:expr, $judgment:expr) => {{
         let diff = $time - $note_time;
         $self.add_stat(HitVarianceStat, diff);
         // $self.score.hit_timings.push(diff);
         $self.hitbar_timings.push(($time, diff));
+
+        let speed = $self.game_speed();
+        $self.score.timings.add_hit($time, $note_time, speed, $judgment.as_str_internal());
     }}
 }
 
@@ -32,6 +37,8 @@ pub struct IngameManager {
     pub score: IngameScore,
     pub replay: Replay,
     pub score_multiplier: f32,
+    /// None if the judgments' own scores are used
+    pub scoring: Option<Box<dyn ScoringSystem>>,
 
     pub health: HealthHelper,
     pub judgment_type: Box<dyn HitJudgments>,
@@ -46,6 +53,8 @@ pub struct IngameManager {
     pub replaying: bool,
     pub failed: bool,
     pub failed_time: f32,
+    /// set when the play is restarted, until the game has started a new input recording for it
+    pub restarted: bool,
 
     /// has something about the ui been changed? 
     /// this will make the play unrankable and should not be saved
@@ -60,6 +69,9 @@ pub struct IngameManager {
 
     /// is this playing in the background of the main menu?
     pub menu_background: bool,
+
+    /// practice session info, if this is a practice session
+    pub practice: Option<PracticeHelper>,
     pub end_time: f32,
 
     pub lead_in_time: f32,
@@ -68,6 +80,8 @@ pub struct IngameManager {
     pub timing_points: Vec<TimingPoint>,
     pub timing_point_index: usize,
     pub song: Arc<dyn AudioInstance>,
+    /// if set, this is used as the current time instead of the song's position (for headless replay simulation)
+    pub simulated_time: Option<f32>,
 
     pub hitsound_manager: HitsoundManager,
 
@@ -82,8 +96,14 @@ pub struct IngameManager {
 
     /// if in replay mode, what replay frame are we at?
     replay_frame: u64,
+    /// playback controls, if watching a replay
+    pub replay_viewer: Option<ReplayViewer>,
+    /// replay being raced against, if any
+    pub ghost: Option<ReplayGhost>,
 
     pub common_game_settings: Arc<CommonGameplaySettings>,
+    /// bindings for the gameplay actions (restart, offset)
+    keybinds: ContextBindings,
     settings: SettingsHelper,
     window_size: Arc<WindowSize>,
 
@@ -93,6 +113,13 @@ pub struct IngameManager {
     last_spectator_score_sync: f32,
     pub spectator_cache: Vec<(u32, String)>,
 
+    /// is this a multiplayer match? the leaderboard shows the other players in the lobby
+    pub multiplayer: bool,
+    /// when was the last lobby score sync packet sent?
+    last_lobby_score_sync: f32,
+    /// which version of the lobby scores is in score_list
+    lobby_scores_version: Option<usize>,
+
     /// what should the game do on start?
     /// mainly a helper for spectator
     pub on_start: Box<dyn FnOnce(&mut Self) + Send + Sync>,
@@ -105,7 +132,9 @@ pub struct IngameManager {
 
     restart_key_hold_start: Option<Instant>,
 
-    map_diff: f32,
+    map_diff: DifficultyAttributes,
+    /// breakdown of the current score's performance
+    pub performance: PerformanceInfo,
 
     // used for discord rich presence
     pub start_time: i64,
@@ -114,6 +143,16 @@ pub struct IngameManager {
 
 impl IngameManager {
     pub async fn new(beatmap: Beatmap, gamemode: Box<dyn GameMode>) -> Self {
+        Self::create(beatmap, gamemode, false).await
+    }
+
+    /// create a manager which only runs the gameplay logic.
+    /// the leaderboard isnt loaded, and theres no song or hitsounds
+    pub async fn new_headless(beatmap: Beatmap, gamemode: Box<dyn GameMode>) -> Self {
+        Self::create(beatmap, gamemode, true).await
+    }
+
+    async fn create(beatmap: Beatmap, gamemode: Box<dyn GameMode>, headless: bool) -> Self {
         let playmode = gamemode.playmode();
         let metadata = beatmap.get_beatmap_meta();
 
@@ -126,19 +165,21 @@ impl IngameManager {
 
         let mut current_mods = ModManager::get_cloned();
         if current_mods.get_speed() == 0.0 { current_mods.set_speed(1.0); }
+        JudgmentPreset::apply_from_settings(&mut current_mods, &playmode);
+        ScoringSystemKind::apply_from_settings(&mut current_mods, &playmode);
         let current_mods = Arc::new(current_mods);
 
         let common_game_settings = Arc::new(settings.common_game_settings.clone().init());
+        let keybinds = settings.keybinds.get_bindings("gameplay");
 
         let mut score =  Score::new(beatmap.hash().clone(), settings.username.clone(), playmode.clone());
         score.speed = current_mods.get_speed();
 
 
-        let health = HealthHelper::new(Some(metadata.hp));
-        let score_loader = Some(SCORE_HELPER.read().await.get_scores(&metadata.beatmap_hash, &playmode).await);
+        let score_loader = if headless { None } else { Some(SCORE_HELPER.read().await.get_scores(&metadata.beatmap_hash, &playmode).await) };
         let key_counter = KeyCounter::new(gamemode.get_possible_keys().into_iter().map(|a| (a.0, a.1.to_owned())).collect());
 
-        let song = AudioManager::get_song().await.unwrap_or(AudioManager::empty_stream()); // temp until we get the audio file path
+        let song: Arc<dyn AudioInstance> = if headless { Arc::new(NullAudioInstance) } else { AudioManager::get_song().await.unwrap_or(AudioManager::empty_stream()) }; // temp until we get the audio file path
 
         let font = get_font();
         let center_text_helper = CenteredTextHelper::new(CENTER_TEXT_DRAW_TIME, -20.0, font.clone()).await;
@@ -153,9 +194,12 @@ impl IngameManager {
         };
 
         let mut hitsound_manager = HitsoundManager::new(audio_playmode_prefix);
-        hitsound_manager.init(&metadata).await;
+        if !headless { hitsound_manager.init(&metadata).await; }
 
         let gamemode_info = get_gamemode_info(&score.playmode).unwrap();
+        let health = gamemode_info.get_health_helper(&metadata, &current_mods);
+
+        let events = beatmap.get_breaks().into_iter().map(|(start, end)| InGameEvent::Break { start, end }).collect();
 
         Self {
             metadata,
@@ -164,6 +208,7 @@ impl IngameManager {
             current_mods,
             health,
             key_counter,
+            events,
 
             lead_in_timer: Instant::now(),
             judgment_type: gamemode_info.get_judgments(),
@@ -182,6 +227,7 @@ impl IngameManager {
             beatmap_preferences,
 
             common_game_settings,
+            keybinds,
             skin_helper: CurrentSkinHelper::new(),
 
             gamemode,
@@ -283,6 +329,20 @@ impl IngameManager {
             RemainingElement::new().await
         ).await);
 
+        // practice info
+        self.ui_elements.push(UIElement::new(
+            &get_name("practice_info"),
+            Vector2::new(0.0, self.window_size.y * (2.0/3.0)),
+            PracticeElement::new()
+        ).await);
+
+        // ghost info
+        self.ui_elements.push(UIElement::new(
+            &get_name("ghost_info"),
+            Vector2::new(self.window_size.x - 300.0, self.window_size.y / 3.0),
+            GhostElement::new()
+        ).await);
+
 
 
         // anything in the gamemode itself
@@ -290,6 +350,8 @@ impl IngameManager {
     }
 
     pub async fn apply_mods(&mut self, mut mods: ModManager) {
+        JudgmentPreset::take_from_mods(&mut mods);
+        ScoringSystemKind::take_from_mods(&mut mods);
         if self.menu_background {
             mods.add_mod(Autoplay.name());
         }
@@ -312,8 +374,8 @@ impl IngameManager {
             self.pending_time_jump = None;
         }
 
-        // check map restart
-        if let Some(press_time) = self.restart_key_hold_start {
+        // check map restart. everyone in a multiplayer match plays together, so it cant be restarted
+        if let Some(press_time) = self.restart_key_hold_start.filter(|_| !self.multiplayer) {
             if press_time.as_millis() >= self.common_game_settings.map_restart_delay {
                 self.reset().await;
                 return;
@@ -359,15 +421,22 @@ impl IngameManager {
         if self.lead_in_time > 0.0 {
             let elapsed = self.lead_in_timer.elapsed().as_micros() as f32 / 1000.0;
             self.lead_in_timer = Instant::now();
-            self.lead_in_time -= elapsed * self.game_speed();
+            self.lead_in_time -= elapsed * self.playback_rate();
 
             if self.lead_in_time <= 0.0 {
                 self.song.set_position(-self.lead_in_time);
                 self.song.set_volume(self.settings.get_music_vol());
-                self.song.set_rate(self.game_speed());
+                self.song.set_rate(self.playback_rate());
                 self.song.play(true);
                 
                 self.lead_in_time = 0.0;
+
+                // practice starts just before the A point
+                if let Some(time) = self.practice.as_ref().map(|p| p.rewind_time()) {
+                    if time > 0.0 {
+                        self.jump_to_time(time, true);
+                    }
+                }
             }
         }
         let time = self.time();
@@ -384,6 +453,8 @@ impl IngameManager {
             if loader.done {
                 self.score_list = loader.scores.iter().map(|s| { let mut s = s.clone(); s.is_previous = s.username == self.score.username; s }).collect();
                 self.score_loader = None;
+                // the lobby scores need to be put back in
+                self.lobby_scores_version = None;
             }
         }
 
@@ -415,14 +486,25 @@ impl IngameManager {
         // update gamemode
         gamemode.update(self, time).await;
 
+        // check practice loop
+        self.check_practice_loop(time).await;
+
+        // drain health
+        let draining = !(self.menu_background || self.failed || self.completed) && self.lead_in_time <= 0.0 && time <= self.end_time && !self.in_break();
+        self.health.update(time, draining);
+        if self.health.is_dead() {
+            self.fail()
+        }
+
         if self.song.is_stopped() {
             trace!("Song over, saying map is complete");
             self.completed = true;
         }
 
         // update score stuff now that gamemode has been updated
-        self.score.accuracy = calc_acc(&self.score);
-        self.score.performance = perfcalc_for_playmode(&self.gamemode.playmode())(self.map_diff, self.score.accuracy as f32);
+        self.score.accuracy = self.scoring.as_ref().map(|s| s.accuracy()).unwrap_or_else(|| calc_acc(&self.score));
+        self.performance = calc_perf_with_diff(&self.score, &self.metadata, &self.map_diff);
+        self.score.performance = self.performance.total;
         // self.score.take_snapshot(time, self.health.get_ratio());
 
         // do fail things
@@ -464,6 +546,10 @@ impl IngameManager {
             self.outgoing_spectator_frame((time, SpectatorFrameData::ScoreSync {score: self.score.score.clone()}))
         }
 
+        if self.multiplayer {
+            self.update_lobby_scores(time);
+        }
+
         // put it back
         self.gamemode = gamemode;
     }
@@ -503,6 +589,11 @@ impl IngameManager {
             indicator.draw(time, list);
         }
 
+        // draw replay controls
+        if let Some(viewer) = &self.replay_viewer {
+            viewer.draw(time, self.end_time, self.window_size.0, &self.key_counter, list);
+        }
+
     }
 }
 
@@ -512,6 +603,7 @@ impl IngameManager {
     // have a hitsound manager trait and hitsound_type trait, and have this pass the hitsound trait to a fn to get a sound, then play it
     // essentially the same thing as judgments
     pub async fn play_note_sound(&mut self, hitsounds: &Vec<Hitsound>) {
+        if self.simulated_time.is_some() { return }
         // let timing_point = self.beatmap.control_point_at(note_time);
 
 
@@ -524,16 +616,26 @@ impl IngameManager {
 
     /// add judgment, affects health and score, but not hit timings
     pub async fn add_judgment<HJ:HitJudgments>(&mut self, judgment: &HJ) {
+        self.add_judgment_with_offset(judgment, None).await
+    }
+
+    /// add judgment, with how far off the hit was (ms) for scoring systems which use it
+    async fn add_judgment_with_offset<HJ:HitJudgments>(&mut self, judgment: &HJ, offset: Option<f32>) {
         // increment judgment, if applicable
         if let Some(count) = self.score.judgments.get_mut(judgment.as_str_internal()) {
             *count += 1;
         }
 
         // do score 
-        let combo_mult = (self.score.combo as f32 * self.score_multiplier).floor() as u16;
-        match judgment.get_score(combo_mult) {
-            score @ i32::MIN..=0 => self.score.score.score -= score.abs() as u64,
-            score @ 1.. => self.score.score.score += score as u64,
+        if let Some(scoring) = &mut self.scoring {
+            scoring.add_hit(ScoringHit { judgment: judgment.as_str_internal(), offset });
+            self.score.score.score = scoring.score();
+        } else {
+            let combo_mult = (self.score.combo as f32 * self.score_multiplier).floor() as u16;
+            match judgment.get_score(combo_mult) {
+                score @ i32::MIN..=0 => self.score.score.score -= score.abs() as u64,
+                score @ 1.. => self.score.score.score += score as u64,
+            }
         }
 
         // do combo
@@ -549,6 +651,8 @@ impl IngameManager {
         // do health
         self.health.do_health(judgment.get_health());
 
+        self.add_stat(HealthStat, self.health.get_ratio());
+
         // check health
         if self.health.is_dead() {
             self.fail()
@@ -567,18 +671,12 @@ impl IngameManager {
 
     /// check and add to hit timings if found
     pub async fn check_judgment<'a, HJ:HitJudgments>(&mut self, windows: &'a Vec<(HJ, Range<f32>)>, time: f32, note_time: f32) -> Option<&'a HJ> {
-        let diff = (time - note_time).abs() / self.game_speed();
-        for (hj, window) in windows.iter() {
-            if window.contains(&diff) {
-                self.add_judgment(hj).await;
-                add_timing!(self, time, note_time);
-
-                // return the hit judgment we got
-                return Some(hj)
-            }
-        }
+        let (hj, offset) = find_hit_window(windows, time, note_time, self.game_speed())?;
+        self.add_judgment_with_offset(hj, Some(offset)).await;
+        add_timing!(self, time, note_time, hj);
 
-        None
+        // return the hit judgment we got
+        Some(hj)
     }
     
     pub async fn check_judgment_condition<
@@ -586,26 +684,17 @@ impl IngameManager {
         HJ:HitJudgments,
         F:Fn() -> bool,
     >(&mut self, windows: &'a Vec<(HJ, Range<f32>)>, time: f32, note_time: f32, cond: F, if_bad: &'a HJ) -> Option<&'a HJ> {
-        let diff = (time - note_time).abs() / self.game_speed();
-        for (hj, window) in windows.iter() {
-            if window.contains(&diff) {
-                let is_okay = cond();
-                if is_okay {
-                    self.add_judgment(hj).await;
-                    add_timing!(self, time, note_time);
-                    // return the hit judgment we got
-                    return Some(hj)
-                } else {
-                    self.add_judgment(if_bad).await;
-                    // return the hit judgment we got
-                    return Some(if_bad)
-                }
-
-            }
+        let (hj, offset) = find_hit_window(windows, time, note_time, self.game_speed())?;
+        if cond() {
+            self.add_judgment_with_offset(hj, Some(offset)).await;
+            add_timing!(self, time, note_time, hj);
+            // return the hit judgment we got
+            Some(hj)
+        } else {
+            self.add_judgment(if_bad).await;
+            // return the hit judgment we got
+            Some(if_bad)
         }
-
-        // info!("no judgment");
-        None
     }
 
 
@@ -638,13 +727,23 @@ impl IngameManager {
     }
 
     pub fn time(&self) -> f32 {
+        if let Some(time) = self.simulated_time { return time }
         let t = self.song.get_position();
 
         t - (self.lead_in_time + self.beatmap_preferences.audio_offset + self.settings.global_offset)
     }
 
+    /// get the map time an input captured at `at` happened at.
+    /// inputs are only processed once per update, so using the current time would be off by up to a whole update
+    pub fn time_at(&self, at: Instant) -> f32 {
+        let time = self.time();
+        if self.simulated_time.is_some() { return time }
+
+        time - at.elapsed().as_secs_f32() * 1000.0 * self.playback_rate()
+    }
+
     pub fn should_save_score(&self) -> bool {
-        let should = !(self.replaying || self.current_mods.has_autoplay() || self.ui_changed);
+        let should = !(self.replaying || self.current_mods.has_autoplay() || self.ui_changed || self.practice.is_some());
         should
     }
 
@@ -662,6 +761,20 @@ impl IngameManager {
         }
     }
 
+    /// how autoplay should play, the background game has its own profile
+    pub fn autoplay_profile(&self) -> AutoplayProfile {
+        if self.menu_background {
+            self.settings.background_game_settings.autoplay
+        } else {
+            self.common_game_settings.autoplay
+        }
+    }
+
+    /// speed the song is actually played at, which includes the replay viewer's rate
+    pub fn playback_rate(&self) -> f32 {
+        self.game_speed() * self.replay_viewer.as_ref().map(|v| v.rate()).unwrap_or(1.0)
+    }
+
 
     pub fn current_timing_point(&self) -> &TimingPoint {
         &self.timing_points[self.timing_point_index]
@@ -721,7 +834,7 @@ impl IngameManager {
                 self.outgoing_spectator_frame((0.0, SpectatorFrameData::Play {
                     beatmap_hash: self.beatmap.hash(),
                     mode: self.gamemode.playmode(),
-                    mods: self.score.mods_string_sorted(),
+                    mods: self.spectator_mods_string(),
                     speed: self.current_mods.speed
                 }));
                 
@@ -738,7 +851,7 @@ impl IngameManager {
             } else {
                 self.song.set_position(0.0);
                 self.song.pause();
-                self.song.set_rate(self.current_mods.get_speed());
+                self.song.set_rate(self.playback_rate());
                 
                 self.lead_in_timer = Instant::now();
                 self.lead_in_time = LEAD_IN_TIME;
@@ -781,17 +894,23 @@ impl IngameManager {
     }
     pub async fn reset(&mut self) {
         self.gamemode.reset(&self.beatmap).await;
+        
+        // mods might have changed, so get a new health helper
+        if let Some(info) = get_gamemode_info(&self.gamemode.playmode()) {
+            self.health = info.get_health_helper(&self.metadata, &self.current_mods);
+        }
         self.health.reset();
         self.key_counter.reset();
         self.hitbar_timings.clear();
         self.judgement_indicators.clear();
         self.restart_key_hold_start = None;
 
-        if self.menu_background {
-            self.gamemode.apply_mods(self.current_mods.clone()).await;
-        } else {
+        // make sure the gamemode is using our mods (replays and spectating can have a different judgment preset from our settings)
+        self.gamemode.apply_mods(self.current_mods.clone()).await;
+
+        if !self.menu_background {
             // reset song
-            self.song.set_rate(self.game_speed());
+            self.song.set_rate(self.playback_rate());
             self.song.set_position(0.0);
             self.song.pause();
         }
@@ -799,9 +918,10 @@ impl IngameManager {
         self.completed = false;
         self.started = false;
         self.failed = false;
+        self.restarted = true;
         self.lead_in_time = LEAD_IN_TIME / self.current_mods.get_speed();
         self.lead_in_timer = Instant::now();
-        self.map_diff = get_diff(&self.beatmap.get_beatmap_meta(), &self.gamemode.playmode(), &self.current_mods).unwrap_or_default();
+        self.map_diff = get_diff_attributes(&self.beatmap.get_beatmap_meta(), &self.gamemode.playmode(), &self.current_mods).unwrap_or_default();
         
         self.score = IngameScore::new(Score::new(self.beatmap.hash(), self.settings.username.clone(), self.gamemode.playmode()), true, false);
         self.score.speed = self.current_mods.get_speed();
@@ -823,12 +943,24 @@ impl IngameManager {
                     false
                 }
             });
+
+            self.score.judgment_preset = self.current_mods.judgment_preset.clone();
+            self.score.scoring_system = self.current_mods.scoring_system;
+
+            self.scoring = self.current_mods.scoring_system.create(&playmode);
+            if let Some(scoring) = &mut self.scoring {
+                scoring.reset(self.gamemode.max_judgments());
+            }
         }
 
 
 
         self.replay_frame = 0;
         self.timing_point_index = 0;
+
+        if let Some(practice) = &mut self.practice {
+            practice.reset();
+        }
         
         if !self.replaying {
             // only reset the replay if we arent replaying
@@ -847,14 +979,14 @@ impl IngameManager {
 
     }
     pub fn fail(&mut self) {
-        if self.failed || self.current_mods.has_nofail() || self.current_mods.has_autoplay() || self.menu_background { return }
+        if self.failed || self.current_mods.has_nofail() || self.current_mods.has_autoplay() || self.menu_background || self.practice.is_some() { return }
         self.failed = true;
         self.failed_time = self.time();
     }
 
     pub async fn combo_break(&mut self) {
         // play hitsound
-        if self.score.combo >= 20 && !self.menu_background {
+        if self.score.combo >= 20 && !self.menu_background && self.simulated_time.is_none() {
             let combobreak = Hitsound::new_simple("combobreak");
             // index of 1 because we want to try beatmap sounds
             self.hitsound_manager.play_sound_single(&combobreak, None, self.settings.get_effect_vol());
@@ -900,7 +1032,25 @@ impl IngameManager {
 
 // Input Handlers
 impl IngameManager {
-    pub async fn key_down(&mut self, key:piston::Key, mods: ayyeve_piston_ui::menu::KeyModifiers) {
+    /// actions checked while playing any gamemode
+    pub fn keybind_context() -> KeybindContext {
+        KeybindContext::new("gameplay", "Gameplay", vec![
+            KeybindAction::new("restart", "Restart Map", vec![InputTarget::Key(Key::Backquote)]),
+            KeybindAction::new("offset_up", "Increase Offset", vec![InputTarget::Key(Key::Equals)]),
+            KeybindAction::new("offset_down", "Decrease Offset", vec![InputTarget::Key(Key::Minus)]),
+
+            // replay viewer
+            KeybindAction::new("replay_pause", "Pause Replay", vec![InputTarget::Key(Key::P)]),
+            KeybindAction::new("replay_step", "Step Replay Frame", vec![InputTarget::Key(Key::Period)]),
+            KeybindAction::new("replay_seek_back", "Seek Replay Back", vec![InputTarget::Key(Key::Left)]),
+            KeybindAction::new("replay_seek_forward", "Seek Replay Forward", vec![InputTarget::Key(Key::Right)]),
+            KeybindAction::new("replay_slower", "Slow Down Replay", vec![InputTarget::Key(Key::LeftBracket)]),
+            KeybindAction::new("replay_faster", "Speed Up Replay", vec![InputTarget::Key(Key::RightBracket)]),
+            KeybindAction::new("replay_key_overlay", "Toggle Replay Key Overlay", vec![InputTarget::Key(Key::K)]),
+        ])
+    }
+
+    pub async fn key_down(&mut self, key:piston::Key, mods: ayyeve_piston_ui::menu::KeyModifiers, at: Instant) {
         if (self.replaying || self.current_mods.has_autoplay()) && !self.menu_background {
             // check replay-only keys
             if key == piston::Key::Escape {
@@ -908,10 +1058,28 @@ impl IngameManager {
                 self.completed = true;
                 return;
             }
+
+            if self.replay_viewer.is_some() && self.lead_in_time <= 0.0 {
+                let time = self.time();
+                let keybinds = self.keybinds.clone();
+
+                if keybinds.key_matches("replay_pause", key, &mods) { self.toggle_replay_pause() }
+                if keybinds.key_matches("replay_step", key, &mods) { self.step_replay_frame() }
+                if keybinds.key_matches("replay_seek_back", key, &mods) { self.seek_replay(time - REPLAY_SEEK_STEP).await }
+                if keybinds.key_matches("replay_seek_forward", key, &mods) { self.seek_replay(time + REPLAY_SEEK_STEP).await }
+                if keybinds.key_matches("replay_slower", key, &mods) { self.change_replay_rate(false) }
+                if keybinds.key_matches("replay_faster", key, &mods) { self.change_replay_rate(true) }
+                if keybinds.key_matches("replay_key_overlay", key, &mods) {
+                    if let Some(viewer) = &mut self.replay_viewer {
+                        viewer.show_key_overlay = !viewer.show_key_overlay;
+                    }
+                }
+            }
         }
 
         // check map restart key
-        if key == self.common_game_settings.map_restart_key {
+        let target = InputTarget::Key(key);
+        if self.keybinds.matches("restart", &target) {
             self.restart_key_hold_start = Some(Instant::now());
             return;
         }
@@ -972,34 +1140,25 @@ impl IngameManager {
         }
 
         // check for offset changing keys
-        if mods.shift {
-            let mut t = 0.0;
-            if key == self.common_game_settings.key_offset_up { t = 5.0 }
-            if key == self.common_game_settings.key_offset_down { t = -5.0 }
+        self.check_offset_keys(&target, mods.shift).await;
 
-            if t != 0.0 {
-                self.increment_global_offset(t).await;
-            }
-        } else {
-            if key == self.common_game_settings.key_offset_up { self.increment_offset(5.0).await; }
-            if key == self.common_game_settings.key_offset_down { self.increment_offset(-5.0).await; }
-        }
-        
 
-        gamemode.key_down(key, self).await;
+        let time = self.time_at(at);
+        gamemode.key_down(key, time, self).await;
         self.gamemode = gamemode;
     }
-    pub async fn key_up(&mut self, key:piston::Key) {
+    pub async fn key_up(&mut self, key:piston::Key, at: Instant) {
         if self.failed { return }
         
         // check map restart key
-        if key == self.common_game_settings.map_restart_key {
+        if self.keybinds.matches("restart", &InputTarget::Key(key)) {
             self.restart_key_hold_start = None;
             return;
         }
 
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.key_up(key, self).await;
+        gamemode.key_up(key, time, self).await;
         self.gamemode = gamemode;
     }
     pub async fn on_text(&mut self, text:&String, mods: &ayyeve_piston_ui::menu::KeyModifiers) {
@@ -1010,37 +1169,64 @@ impl IngameManager {
     }
     
     
-    pub async fn mouse_move(&mut self, pos:Vector2) {
+    pub async fn mouse_move(&mut self, pos:Vector2, at: Instant) {
         if let Some(ui_editor) = &mut self.ui_editor {
             ui_editor.on_mouse_move(&pos, &mut ()).await;
         }
+        if let Some(viewer) = &mut self.replay_viewer {
+            viewer.mouse_pos = pos;
+        }
 
         if self.failed { return }
 
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.mouse_move(pos, self).await;
+        gamemode.mouse_move(pos, time, self).await;
         self.gamemode = gamemode;
     }
-    pub async fn mouse_down(&mut self, btn:piston::MouseButton) {
+    pub async fn mouse_down(&mut self, btn:piston::MouseButton, at: Instant) {
         if let Some(ui_editor) = &mut self.ui_editor {
             ui_editor.on_mouse_down(&Vector2::ZERO, &btn, &KeyModifiers::default(), &mut ()).await;
             return
         }
 
         if self.failed {return}
+
+        let target = InputTarget::Mouse(btn);
+        if self.keybinds.matches("restart", &target) {
+            self.restart_key_hold_start = Some(Instant::now());
+            return;
+        }
+        self.check_offset_keys(&target, false).await;
+
+        // seekbar click
+        if btn == piston::MouseButton::Left && self.lead_in_time <= 0.0 {
+            if let Some(time) = self.replay_viewer.as_ref().and_then(|v| v.seekbar_time(self.window_size.0, self.end_time)) {
+                self.seek_replay(time).await;
+                return;
+            }
+        }
+
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.mouse_down(btn, self).await;
+        gamemode.mouse_down(btn, time, self).await;
         self.gamemode = gamemode;
     }
-    pub async fn mouse_up(&mut self, btn:piston::MouseButton) {
+    pub async fn mouse_up(&mut self, btn:piston::MouseButton, at: Instant) {
         if let Some(ui_editor) = &mut self.ui_editor {
             ui_editor.on_mouse_up(&Vector2::ZERO, &btn, &KeyModifiers::default(), &mut ()).await;
             return
         }
 
         if self.failed {return}
+        if self.keybinds.matches("restart", &InputTarget::Mouse(btn)) {
+            self.restart_key_hold_start = None;
+            return;
+        }
+
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.mouse_up(btn, self).await;
+        gamemode.mouse_up(btn, time, self).await;
         self.gamemode = gamemode;
     }
     pub async fn mouse_scroll(&mut self, delta:f64) {
@@ -1054,26 +1240,67 @@ impl IngameManager {
         self.gamemode = gamemode;
     }
 
+    pub async fn touch(&mut self, touch:TouchInput, at: Instant) {
+        if self.failed {return}
+
+        let time = self.time_at(at);
+        let mut gamemode = std::mem::take(&mut self.gamemode);
+        gamemode.touch(touch, time, self).await;
+        self.gamemode = gamemode;
+    }
+
 
-    pub async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8) {
+    pub async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, at: Instant) {
         if self.failed {return}
+
+        let target = InputTarget::controller(c, btn);
+        if self.keybinds.matches("restart", &target) {
+            self.restart_key_hold_start = Some(Instant::now());
+            return;
+        }
+        self.check_offset_keys(&target, false).await;
+
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.controller_press(c, btn, self).await;
+        gamemode.controller_press(c, btn, time, self).await;
         self.gamemode = gamemode;
     }
-    pub async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8) {
+    pub async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, at: Instant) {
         if self.failed {return}
+        if self.keybinds.matches("restart", &InputTarget::controller(c, btn)) {
+            self.restart_key_hold_start = None;
+            return;
+        }
+
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.controller_release(c, btn, self).await;
+        gamemode.controller_release(c, btn, time, self).await;
         self.gamemode = gamemode;
     }
-    pub async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>) {
+    pub async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>, at: Instant) {
         if self.failed {return}
+
+        let time = self.time_at(at);
         let mut gamemode = std::mem::take(&mut self.gamemode);
-        gamemode.controller_axis(c, axis_data, self).await;
+        gamemode.controller_axis(c, axis_data, time, self).await;
         self.gamemode = gamemode;
     }
 
+    /// change the offset if `target` is bound to an offset action.
+    /// if `global` is set, the global offset is changed instead of the map's
+    async fn check_offset_keys(&mut self, target: &InputTarget, global: bool) {
+        let mut t = 0.0;
+        if self.keybinds.matches("offset_up", target) { t = 5.0 }
+        if self.keybinds.matches("offset_down", target) { t = -5.0 }
+        if t == 0.0 { return }
+
+        if global {
+            self.increment_global_offset(t).await;
+        } else {
+            self.increment_offset(t).await;
+        }
+    }
+
     pub fn window_focus_lost(&mut self, got_focus: bool) {
         // info!("window focus changed");
         if got_focus {
@@ -1088,20 +1315,108 @@ impl IngameManager {
 
 // other misc stuff that isnt touched often and i just wanted it out of the way
 impl IngameManager {
-    pub fn set_replay(&mut self, replay: Replay) {
+    pub fn set_replay(&mut self, replay: Replay, extras: &ReplayExtras) {
         self.replaying = true;
         self.replay = replay;
+        self.replay_viewer = Some(ReplayViewer::new());
 
         // load speed from score
         if let Some(score) = &self.replay.score_data {
             let mut mods = ModManager::new();
             mods.mods = score.mods();
             mods.set_speed(score.speed);
+            JudgmentPreset::take_from_mods(&mut mods);
+            ScoringSystemKind::take_from_mods(&mut mods);
+
+            // these are only in the replay's own mod list for old scores, so the extras win
+            if extras.judgment_preset.is_some() {
+                mods.judgment_preset = extras.judgment_preset.clone();
+            }
+            if extras.scoring_system != ScoringSystemKind::Default {
+                mods.scoring_system = extras.scoring_system;
+            }
+
             self.current_mods = Arc::new(mods);
             *self.score.mods_mut() = self.current_mods.mods.clone();
         }
     }
+
+    /// the score's mods for spectator frames, which also carry the judgment preset and scoring system
+    pub fn spectator_mods_string(&self) -> String {
+        let mut score = self.score.score.clone();
+        if let Some(preset) = &self.current_mods.judgment_preset {
+            score.mods_mut().insert(preset.clone());
+        }
+        if let Some(scoring) = self.current_mods.scoring_system.to_mod() {
+            score.mods_mut().insert(scoring);
+        }
+        score.mods_string_sorted()
+    }
     
+    /// jump to a time in the replay.
+    /// judgments cant be undone, so this replays the inputs up to the target time without audio to rebuild the game state
+    pub async fn seek_replay(&mut self, time: f32) {
+        let Some(viewer) = &self.replay_viewer else { return };
+        let paused = viewer.paused;
+        let time = time.clamp(0.0, self.end_time);
+
+        // going forward can continue from where we are, going back has to start over
+        let mut current = self.time();
+        if time < current {
+            self.reset().await;
+            self.lead_in_time = 0.0;
+            self.started = true;
+            current = 0.0;
+        }
+
+        // dont play the song while we fast-forward
+        let song = std::mem::replace(&mut self.song, Arc::new(NullAudioInstance));
+        while current < time && !self.completed {
+            let next_frame = self.replay.frames.get(self.replay_frame as usize).map(|(frame_time, _)| *frame_time).filter(|t| *t > current);
+            current = (current + REPLAY_SEEK_RESOLUTION).min(time).min(next_frame.unwrap_or(f32::MAX));
+            self.simulated_time = Some(current);
+            self.update().await;
+        }
+        self.simulated_time = None;
+        self.song = song;
+
+        self.song.set_position(time + self.beatmap_preferences.audio_offset + self.settings.global_offset);
+        self.song.set_rate(self.playback_rate());
+        if !paused {
+            self.song.play(false);
+        }
+    }
+
+    fn toggle_replay_pause(&mut self) {
+        let Some(viewer) = &mut self.replay_viewer else { return };
+        viewer.paused = !viewer.paused;
+
+        if viewer.paused {
+            self.song.pause();
+        } else {
+            self.song.play(false);
+        }
+    }
+
+    /// while paused, move forward to the next replay frame
+    fn step_replay_frame(&mut self) {
+        if !self.replay_viewer.as_ref().map(|v| v.paused).unwrap_or_default() { return }
+        let Some((frame_time, _)) = self.replay.frames.get(self.replay_frame as usize) else { return };
+
+        self.song.set_position(frame_time + self.beatmap_preferences.audio_offset + self.settings.global_offset);
+    }
+
+    fn change_replay_rate(&mut self, faster: bool) {
+        let Some(viewer) = &mut self.replay_viewer else { return };
+        if faster {
+            viewer.faster();
+        } else {
+            viewer.slower();
+        }
+
+        self.song.set_rate(self.playback_rate());
+    }
+
     pub async fn increment_offset(&mut self, delta:f32) {
         let time = self.time();
         self.beatmap_preferences.audio_offset += delta;
@@ -1161,8 +1476,109 @@ impl IngameManager {
 
 }
 
+// Ghost Stuff
+impl IngameManager {
+    /// race against a replay. nothing from the ghost's play is saved
+    pub async fn set_ghost(&mut self, replay: Replay, extras: ReplayExtras) {
+        match ReplayGhost::new(replay, extras, self.metadata.clone()).await {
+            Ok(ghost) => self.ghost = Some(ghost),
+            Err(e) => NotificationManager::add_error_notification("Error loading ghost", e).await,
+        }
+    }
+
+    /// catch the ghost up to the current time.
+    /// this cant be done in update, since the ghost runs its own manager's update
+    pub async fn update_ghost(&mut self) {
+        let time = self.time();
+        let Some(ghost) = &mut self.ghost else { return };
+        ghost.update(time).await;
+    }
+}
+
+// Practice Stuff
+impl IngameManager {
+    pub fn set_practice(&mut self, settings: PracticeSettings) {
+        self.practice = Some(PracticeHelper::new(settings));
+    }
+
+    async fn check_practice_loop(&mut self, time: f32) {
+        let Some(mut practice) = std::mem::take(&mut self.practice) else { return };
+
+        if time >= practice.settings.start {
+            practice.loop_started(&self.score);
+        }
+
+        if time >= practice.settings.end {
+            let combo_breaks:Vec<&'static str> = self.judgment_type
+                .variants()
+                .iter()
+                .filter(|j| matches!(j.affects_combo(), AffectsCombo::Reset))
+                .map(|j| j.as_str_internal())
+                .collect();
+
+            let result = practice.loop_finished(&self.score, &combo_breaks);
+            self.center_text_helper.set_value(format!(
+                "Loop {}: {:.2}% ({} misses) @ {:.2}x", 
+                result.loop_num, 
+                result.accuracy * 100.0, 
+                result.misses, 
+                result.speed
+            ), time);
+
+            if practice.is_last_loop() {
+                self.completed = true;
+            } else {
+                let speed = practice.next_speed(self.game_speed(), &result);
+                if speed != self.game_speed() {
+                    self.set_practice_speed(speed).await;
+                }
+
+                self.health.reset();
+                self.score.combo = 0;
+                self.jump_to_time(practice.rewind_time(), true);
+            }
+        }
+
+        self.practice = Some(practice);
+    }
+
+    async fn set_practice_speed(&mut self, speed: f32) {
+        let mut mods = self.current_mods.as_ref().clone();
+        mods.set_speed(speed);
+        self.current_mods = Arc::new(mods);
+        self.gamemode.apply_mods(self.current_mods.clone()).await;
+
+        self.song.set_rate(speed);
+        self.score.speed = speed;
+    }
+}
+
 // Spectator Stuff
 impl IngameManager {
+    /// send our score to the lobby, and show everyone else's on the leaderboard
+    fn update_lobby_scores(&mut self, time: f32) {
+        if self.last_lobby_score_sync + LOBBY_SCORE_SYNC_INTERVAL <= time {
+            self.last_lobby_score_sync = time;
+            OnlineManager::lobby_score(self.score.score.clone(), false);
+        }
+
+        let Ok(manager) = ONLINE_MANAGER.try_read() else { return };
+        if self.lobby_scores_version == Some(manager.lobby_scores_version) { return }
+        self.lobby_scores_version = Some(manager.lobby_scores_version);
+
+        self.score_list = manager.lobby_scores
+            .iter()
+            .filter(|(user_id, _)| **user_id != manager.user_id)
+            .map(|(_, score)| IngameScore::new(score.clone(), false, false))
+            .collect();
+    }
+
+    /// add a frame to the replay being recorded.
+    /// inputs arent handled in the order they happened (ie, mouse moves are handled before key presses), so the frame is put in place by its time
+    pub fn record_replay_frame(&mut self, time: f32, frame: ReplayFrame) {
+        insert_frame_sorted(&mut self.replay.frames, time, frame);
+    }
+
     pub fn outgoing_spectator_frame(&mut self, frame: SpectatorFrame) {
         if self.menu_background || self.replaying { return }
         OnlineManager::send_spec_frames(vec![frame], false)
@@ -1179,6 +1595,7 @@ impl Default for IngameManager {
     fn default() -> Self {
         Self { 
             song: AudioManager::empty_stream(),
+            simulated_time: None,
             judgement_indicators: Vec::new(),
             hitsound_manager: HitsoundManager::new(String::new()),
 
@@ -1191,11 +1608,13 @@ impl Default for IngameManager {
             current_mods: Default::default(),
             score: IngameScore::new(Default::default(), true, false),
             score_multiplier: 1.0,
+            scoring: None,
             replay: Default::default(),
             started: Default::default(),
             completed: Default::default(),
             replaying: Default::default(),
             menu_background: Default::default(),
+            practice: None,
             end_time: Default::default(),
             lead_in_time: Default::default(),
             lead_in_timer: Instant::now(),
@@ -1204,11 +1623,17 @@ impl Default for IngameManager {
             center_text_helper: Default::default(),
             hitbar_timings: Default::default(),
             replay_frame: Default::default(),
+            replay_viewer: None,
+            ghost: None,
             spectator_cache: Default::default(),
             last_spectator_score_sync: 0.0,
+            multiplayer: false,
+            last_lobby_score_sync: 0.0,
+            lobby_scores_version: None,
             on_start: Box::new(|_|{}),
 
             common_game_settings: Default::default(),
+            keybinds: Default::default(),
 
             score_list: Vec::new(),
             score_loader: None,
@@ -1221,6 +1646,7 @@ impl Default for IngameManager {
             key_counter: KeyCounter::default(),
 
             ui_changed: false,
+            restarted: false,
 
             judgment_type: Box::new(DefaultHitJudgments::None),
 
@@ -1230,7 +1656,8 @@ impl Default for IngameManager {
             skin_helper: CurrentSkinHelper::new(),
 
             restart_key_hold_start: None,
-            map_diff: 0.0,
+            map_diff: DifficultyAttributes::default(),
+            performance: PerformanceInfo::default(),
             start_time: 0,
             pause_start: None,
         }
@@ -1238,6 +1665,48 @@ impl Default for IngameManager {
 }
 
 
+/// insert a frame after any frames at or before its time
+fn insert_frame_sorted(frames: &mut Vec<(f32, ReplayFrame)>, time: f32, frame: ReplayFrame) {
+    // frames almost always go at the end, so look from there
+    let index = frames.iter().rposition(|(frame_time, _)| *frame_time <= time).map(|i| i + 1).unwrap_or(0);
+    frames.insert(index, (time, frame));
+}
+
+
 pub enum InGameEvent {
     Break {start: f32, end: f32}
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_stay_in_time_order() {
+        let mut frames = Vec::new();
+
+        // an update's worth of inputs, handled by type rather than by when they happened
+        insert_frame_sorted(&mut frames, 10.0, ReplayFrame::MousePos(1.0, 1.0));
+        insert_frame_sorted(&mut frames, 14.0, ReplayFrame::MousePos(2.0, 2.0));
+        insert_frame_sorted(&mut frames, 12.0, ReplayFrame::Press(KeyPress::Left));
+        insert_frame_sorted(&mut frames, 8.0, ReplayFrame::Press(KeyPress::Right));
+        insert_frame_sorted(&mut frames, 16.0, ReplayFrame::Release(KeyPress::Left));
+
+        let times = frames.iter().map(|(time, _)| *time).collect::<Vec<_>>();
+        assert_eq!(times, vec![8.0, 10.0, 12.0, 14.0, 16.0]);
+        assert!(matches!(frames[2].1, ReplayFrame::Press(KeyPress::Left)));
+    }
+
+    #[test]
+    fn same_time_frames_keep_their_order() {
+        let mut frames = Vec::new();
+        insert_frame_sorted(&mut frames, 5.0, ReplayFrame::Press(KeyPress::Left));
+        insert_frame_sorted(&mut frames, 5.0, ReplayFrame::Release(KeyPress::Left));
+        insert_frame_sorted(&mut frames, 5.0, ReplayFrame::Press(KeyPress::Right));
+
+        assert!(matches!(frames[0].1, ReplayFrame::Press(KeyPress::Left)));
+        assert!(matches!(frames[1].1, ReplayFrame::Release(KeyPress::Left)));
+        assert!(matches!(frames[2].1, ReplayFrame::Press(KeyPress::Right)));
+    }
+}
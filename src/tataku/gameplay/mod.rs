@@ -14,7 +14,25 @@ use crate::prelude::*;
 #[async_trait]
 pub trait DiffCalc: Send + Sync {
     async fn new(g: &BeatmapMeta) -> TatakuResult<Self> where Self:Sized;
-    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<f32>;
+    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<DifficultyAttributes>;
+}
+
+/// combine the strain of each section of a map into one diff.
+/// the hardest sections count the most, each following section counts a bit less
+pub fn weighted_strain(mut strains: Vec<f32>) -> f32 {
+    const PERCENT: f32 = 0.99;
+
+    // Sort by descending
+    strains.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut difficulty = 0.0;
+    let mut weight = 1.0;
+    for x in strains {
+        difficulty += x * weight;
+        weight *= PERCENT;
+    }
+
+    difficulty / ((1.0 - weight) / (1.0 - PERCENT))
 }
 
 
@@ -25,6 +43,12 @@ pub fn calc_acc(score: &Score) -> f64 {
         .normal_or(1.0)
 }
 
+pub fn diff_calc_version(playmode: &String) -> u32 {
+    get_gamemode_info(playmode)
+        .map(|i|i.diff_calc_version())
+        .unwrap_or_default()
+}
+
 pub fn gamemode_display_name(playmode: &String) -> &str {
     get_gamemode_info(playmode)
         .map(|i|i.display_name())
@@ -38,22 +62,29 @@ pub async fn manager_from_playmode(playmode: PlayMode, beatmap: &BeatmapMeta) ->
     let info = get_gamemode_info(&playmode)
         .ok_or_else(||TatakuError::GameMode(GameModeError::UnknownGameMode))?;
 
-    let gamemode = info.create_game(&beatmap).await?;
+    let gamemode = info.create_game(&beatmap, false).await?;
 
     Ok(IngameManager::new(beatmap, gamemode).await)
 }
 
 
-pub fn perfcalc_for_playmode(playmode: &String) -> PerformanceCalc {{
-    get_gamemode_info(&playmode)
-        .map(|i|i.get_perf_calc())
-        .unwrap_or(Box::new(|diff, acc| {
-            let perf = diff * (acc / 0.99).powi(6);
-            #[cfg(feature="debug_perf_rating")]
-            println!("diff:{diff}, acc: {acc} = perf {perf}");
-            perf
-        }))
-}}
+/// calculate the performance of a score with a known diff
+pub fn calc_perf_with_diff(score: &Score, map: &BeatmapMeta, diff: &DifficultyAttributes) -> PerformanceInfo {
+    get_gamemode_info(&score.playmode)
+        .map(|i|i.calc_perf(PerformanceCalcInfo::new(score, map, diff)))
+        .unwrap_or_default()
+}
+
+/// calculate the performance of a score, using the cached diff for the score's mods and speed.
+/// returns None if the diff hasnt been calculated yet
+pub fn calc_perf(score: &Score, map: &BeatmapMeta) -> Option<PerformanceInfo> {
+    let mods = ModManager::default()
+        .with_speed_f32(score.speed)
+        .with_mods(score.mods());
+
+    let diff = get_diff_attributes(map, &score.playmode, &mods)?;
+    Some(calc_perf_with_diff(score, map, &diff))
+}
 
 
 pub async fn calc_diff(map: &BeatmapMeta, mode_override: String) -> TatakuResult<Box<dyn DiffCalc>> {{
@@ -42,7 +42,7 @@ impl DiffCalc for ManiaDifficultyCalculator {
         })
     }
 
-    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<f32> {
+    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<DifficultyAttributes> {
         // let strain = self.strain(mods)?;
         let note_density = self.note_density(mods)?;
         let mut diff = Vec::new();
@@ -62,22 +62,7 @@ impl DiffCalc for ManiaDifficultyCalculator {
             // }
         }
         
-        // let count = diff.len() as f32;
-
-        let mut difficulty = 0.0;
-        let mut weight = 1.0;
-
-        const PERCENT: f32 = 0.99;
-
-        // Sort by descending
-        diff.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
-
-        for x in diff {
-            difficulty += x * weight;
-            weight *= PERCENT;
-        }
-
-        difficulty /= (1.0 - weight) / (1.0 - PERCENT);
+        let difficulty = weighted_strain(diff);
 
         /*
         // TEMP: for writing to csv, nicer graphs
@@ -180,7 +165,10 @@ impl DiffCalc for ManiaDifficultyCalculator {
         }
         */
 
-        Ok(difficulty)
+        // density is the only thing measured for mania so far, so its the only skill
+        Ok(DifficultyAttributes::new(difficulty)
+            .with_skill("Strain", difficulty)
+        )
     }
 }
 
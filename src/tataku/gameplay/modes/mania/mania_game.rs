@@ -38,6 +38,11 @@ pub struct ManiaGame {
     playfield: Arc<ManiaPlayfield>,
 
     game_settings: Arc<ManiaSettings>,
+    keybinds: ContextBindings,
+    /// which controller axes are currently pressed
+    controller_axis_held: HashSet<KeyPress>,
+    /// touch id to the column its holding
+    touch_keys: HashMap<i64, KeyPress>,
 
     mania_skin_settings: Option<Arc<ManiaSkinSettings>>,
     map_preferences: BeatmapPlaymodePreferences,
@@ -58,6 +63,64 @@ impl ManiaGame {
         }
     }
 
+    fn get_keybinds(settings: &Settings, column_count: u8) -> ContextBindings {
+        settings.keybinds.get_bindings(&format!("mania_{column_count}k"))
+    }
+
+    /// get the column keys an input is bound to
+    fn bound_keys(&self, target: &InputTarget) -> Vec<KeyPress> {
+        let base_key = KeyPress::Mania1 as u8;
+        self.keybinds
+            .actions_for(target)
+            .filter_map(|action| action.strip_prefix("column_")?.parse::<u8>().ok())
+            .filter(|col| (1..=self.column_count).contains(col))
+            .map(|col| (base_key + col - 1).into())
+            .collect()
+    }
+
+    /// get the key for the column lane at `x`. touches past the edges count as the outer columns
+    fn touch_column(playfield: &ManiaPlayfield, column_count: u8, x: f64) -> KeyPress {
+        let stride = playfield.column_width + playfield.column_spacing;
+        let col = ((x - playfield.col_pos(0)) / stride).floor().clamp(0.0, (column_count - 1) as f64) as u8;
+        (KeyPress::Mania1 as u8 + col).into()
+    }
+
+    /// what a touch does to the columns, given the column its over now
+    fn touch_frames(touch_keys: &mut HashMap<i64, KeyPress>, touch: TouchInput, column: KeyPress) -> Vec<ReplayFrame> {
+        let old = touch_keys.remove(&touch.id);
+        // a touch that started before gameplay did isnt holding anything
+        if touch.phase == TouchPhase::Move && old.is_none() { return Vec::new() }
+
+        let new = (touch.phase != TouchPhase::Up).then_some(column);
+        if let Some(key) = new { touch_keys.insert(touch.id, key); }
+        if old == new { return Vec::new() }
+
+        old.map(ReplayFrame::Release).into_iter().chain(new.map(ReplayFrame::Press)).collect()
+    }
+
+    fn get_hit_windows(preset: JudgmentPreset, custom_windows: &[f32], od: f32) -> Vec<(ManiaHitJudgments, Range<f32>)> {
+        // mania judges with all the windows
+        const JUDGMENTS: &[usize] = &[0, 1, 2, 3, 4, 5];
+        let windows = preset.windows(od, custom_windows, JUDGMENTS).unwrap_or_else(|| {
+            warn!("invalid custom judgment windows {custom_windows:?}, using quaver standard");
+            JudgmentPreset::QuaverStandard.windows(od, custom_windows, JUDGMENTS).unwrap()
+        });
+
+        [
+            ManiaHitJudgments::Marvelous,
+            ManiaHitJudgments::Perfect,
+            ManiaHitJudgments::Great,
+            ManiaHitJudgments::Good,
+            ManiaHitJudgments::Okay,
+            ManiaHitJudgments::Miss,
+        ]
+            .into_iter()
+            .zip(windows.iter())
+            .enumerate()
+            .map(|(i, (judge, end))| (judge, if i == 0 { 0.0 } else { windows[i - 1] }..*end))
+            .collect()
+    }
+
     fn next_note(&mut self, col:usize) {
         (*self.column_indices.get_mut(col).unwrap()) += 1;
     }
@@ -273,15 +336,8 @@ impl GameMode for ManiaGame {
         let map_preferences = Database::get_beatmap_mode_prefs(&metadata.beatmap_hash, &"mania".to_owned()).await;
         
         // windows
-        let hit_windows = vec![
-            (ManiaHitJudgments::Marvelous, 0.0..18.0),
-            (ManiaHitJudgments::Perfect, 18.0..43.0),
-            (ManiaHitJudgments::Great, 43.0..76.0),
-            (ManiaHitJudgments::Good, 76.0..106.0),
-            (ManiaHitJudgments::Okay, 106.0..127.0),
-            (ManiaHitJudgments::Miss, 127.0..164.0),
-        ];
-
+        let (preset, custom_windows) = JudgmentPreset::from_settings(&"mania".to_owned());
+        let hit_windows = Self::get_hit_windows(preset, &custom_windows, metadata.od);
         let miss_window = hit_windows.last().unwrap().1.end;
 
         const DEFAULT_SNAP: Color = Color::SILVER;
@@ -370,12 +426,15 @@ impl GameMode for ManiaGame {
 
                     sv_mult: map_preferences.scroll_speed as f64,
                     column_count,
+                    keybinds: Self::get_keybinds(&get_settings!(), column_count),
 
                     auto_helper,
                     playfield,
                     mania_skin_settings,
                     map_preferences,
                     game_settings: Arc::new(game_settings),
+                    controller_axis_held: HashSet::new(),
+                    touch_keys: HashMap::new(),
                     key_images_up: HashMap::new(),
                     key_images_down: HashMap::new(),
                 };
@@ -487,12 +546,15 @@ impl GameMode for ManiaGame {
 
                     sv_mult: map_preferences.scroll_speed as f64,
                     column_count,
+                    keybinds: Self::get_keybinds(&get_settings!(), column_count),
 
                     auto_helper,
                     playfield,
                     mania_skin_settings,
                     map_preferences,
                     game_settings: Arc::new(game_settings),
+                    controller_axis_held: HashSet::new(),
+                    touch_keys: HashMap::new(),
                     
                     key_images_up:HashMap::new(),
                     key_images_down:HashMap::new(),
@@ -570,12 +632,15 @@ impl GameMode for ManiaGame {
 
                     sv_mult: map_preferences.scroll_speed as f64,
                     column_count,
+                    keybinds: Self::get_keybinds(&get_settings!(), column_count),
 
                     auto_helper,
                     playfield,
                     mania_skin_settings,
                     map_preferences,
                     game_settings: Arc::new(game_settings),
+                    controller_axis_held: HashSet::new(),
+                    touch_keys: HashMap::new(),
                     
                     key_images_up:HashMap::new(),
                     key_images_down:HashMap::new(),
@@ -645,7 +710,7 @@ impl GameMode for ManiaGame {
 
     async fn handle_replay_frame(&mut self, frame:ReplayFrame, time:f32, manager:&mut IngameManager) {
         if !manager.replaying {
-            manager.replay.frames.push((time, frame));
+            manager.record_replay_frame(time, frame);
             manager.outgoing_spectator_frame((time, SpectatorFrameData::ReplayFrame{frame}));
         }
 
@@ -753,7 +818,7 @@ impl GameMode for ManiaGame {
     async fn update(&mut self, manager:&mut IngameManager, time: f32) {
         if manager.current_mods.has_autoplay() {
             let mut frames = Vec::new();
-            self.auto_helper.update(&self.columns, &mut self.column_indices, time, &mut frames);
+            self.auto_helper.update(&self.columns, &mut self.column_indices, time, &manager.autoplay_profile(), &mut frames);
             for frame in frames {
                 self.handle_replay_frame(frame, time, manager).await
             }
@@ -872,6 +937,9 @@ impl GameMode for ManiaGame {
     }
 
     async fn reset(&mut self, beatmap:&Beatmap) {
+        // new helper so humanized autoplay plays the same way again
+        self.auto_helper = ManiaAutoHelper::new();
+
         for col in self.columns.iter_mut() {
             for note in col.iter_mut() {
                 note.reset().await;
@@ -957,8 +1025,29 @@ impl GameMode for ManiaGame {
         self.apply_new_playfield(Arc::new(playfield));
     }
 
+    async fn time_jump(&mut self, new_time: f32) {
+        for (col, notes) in self.columns.iter_mut().enumerate() {
+            let mut index = None;
+            for (i, note) in notes.iter_mut().enumerate() {
+                note.reset().await;
+                if index.is_none() && note.time() >= new_time {
+                    index = Some(i);
+                }
+            }
+
+            self.column_indices[col] = index.unwrap_or(notes.len());
+            self.column_states[col] = false;
+        }
+
+        for t in self.timing_bars.iter_mut() {
+            t.reset();
+        }
+    }
+
     
-    async fn force_update_settings(&mut self, _settings: &Settings) {}
+    async fn force_update_settings(&mut self, settings: &Settings) {
+        self.keybinds = Self::get_keybinds(settings, self.column_count);
+    }
     
     async fn reload_skin(&mut self) {
         // reload skin settings
@@ -979,8 +1068,10 @@ impl GameMode for ManiaGame {
         self.load_col_images().await;
     }
 
-    async fn apply_mods(&mut self, _mods: Arc<ModManager>) {
-
+    async fn apply_mods(&mut self, mods: Arc<ModManager>) {
+        let (preset, custom_windows) = JudgmentPreset::from_id(mods.judgment_preset.as_ref(), &self.playmode());
+        self.hit_windows = Self::get_hit_windows(preset, &custom_windows, self.map_meta.od);
+        self.miss_window = self.hit_windows.last().unwrap().1.end;
     }
 }
 
@@ -988,7 +1079,7 @@ impl GameMode for ManiaGame {
 #[async_trait]
 impl GameModeInput for ManiaGame {
 
-    async fn key_down(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_down(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         // check sv change keys
         if key == Key::F4 || key == Key::F3 {
             if key == Key::F4 {
@@ -1009,45 +1100,91 @@ impl GameModeInput for ManiaGame {
         }
 
 
-        let settings = get_settings!();
-        let mut game_key = KeyPress::RightDon;
-
-        let keys = &settings.mania_settings.keys[(self.column_count-1) as usize];
-        let base_key = KeyPress::Mania1 as u8;
-        for col in 0..self.column_count as usize {
-            let k = keys[col];
-            if k == key {
-                game_key = ((col + base_key as usize) as u8).into();
-                break;
-            }
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
-        if game_key == KeyPress::RightDon {return}
-        let time = manager.time();
-        self.handle_replay_frame(ReplayFrame::Press(game_key), time, manager).await;
     }
     
-    async fn key_up(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_up(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         // dont accept key input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        let settings = get_settings!();
-        let mut game_key = KeyPress::RightDon;
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
+        }
+    }
 
-        let keys = &settings.mania_settings.keys[(self.column_count-1) as usize];
-        let base_key = KeyPress::Mania1 as u8;
-        for col in 0..self.column_count as usize {
-            let k = keys[col];
-            if k == key {
-                game_key = ((col + base_key as usize) as u8).into();
-                break;
-            }
+
+    async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
+        // dont accept controller input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+
+        for key in self.bound_keys(&InputTarget::controller(c, btn)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
+        }
+    }
+
+    async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
+        // dont accept controller input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+
+        for key in self.bound_keys(&InputTarget::controller(c, btn)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
+        }
+    }
+
+    async fn mouse_down(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
+        // dont accept mouse input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
+        }
+    }
+
+    async fn mouse_up(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
+        // dont accept mouse input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
         }
-        if game_key == KeyPress::RightDon {return}
-        let time = manager.time();
 
-        self.handle_replay_frame(ReplayFrame::Release(game_key), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
+        }
+    }
+
+    async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>, time:f32, manager:&mut IngameManager) {
+        // dont accept controller input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+
+        let inputs = self.keybinds.axis_inputs(c, |target| self.bound_keys(target));
+        for (key, pressed) in get_axis_presses(&inputs, &axis_data, &mut self.controller_axis_held) {
+            let frame = if pressed { ReplayFrame::Press(key) } else { ReplayFrame::Release(key) };
+            self.handle_replay_frame(frame, time, manager).await;
+        }
+    }
+
+    /// each column is a touch lane. sliding into another lane lets go of the old column and presses the new one
+    async fn touch(&mut self, touch:TouchInput, time:f32, manager:&mut IngameManager) {
+        // dont accept touch input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+
+        let column = Self::touch_column(&self.playfield, self.column_count, touch.pos.x);
+        for frame in Self::touch_frames(&mut self.touch_keys, touch, column) {
+            self.handle_replay_frame(frame, time, manager).await;
+        }
     }
 
 }
@@ -1057,6 +1194,14 @@ impl GameModeProperties for ManiaGame {
     fn playmode(&self) -> PlayMode { "mania".to_owned() }
 
     fn end_time(&self) -> f32 { self.end_time }
+    /// holds get a judgment for the press and the release
+    fn max_judgments(&self) -> usize {
+        self.columns
+            .iter()
+            .flatten()
+            .map(|n| if n.note_type() == NoteType::Hold { 2 } else { 1 })
+            .sum()
+    }
     
     fn get_possible_keys(&self) -> Vec<(KeyPress, &str)> {
         let mut list = Vec::new();
@@ -1133,11 +1278,14 @@ impl Drop for ManiaGame {
 // TODO: document whatever the hell is happening here
 struct ManiaAutoHelper {
     states: Vec<AutoplayColumnState>,
+    /// hit offset for each note in each column, None if the note should be missed
+    offsets: Vec<Vec<Option<f32>>>,
 }
 impl ManiaAutoHelper {
     fn new() -> Self {
         Self {
             states: Vec::new(),
+            offsets: Vec::new(),
         }
     }
 
@@ -1146,12 +1294,16 @@ impl ManiaAutoHelper {
         ((col + base_key as usize) as u8).into()
     }
 
-    fn update(&mut self, columns: &Vec<Vec<Box<dyn ManiaHitObject>>>, column_indices: &mut Vec<usize>, time: f32, list: &mut Vec<ReplayFrame>) {
+    fn update(&mut self, columns: &Vec<Vec<Box<dyn ManiaHitObject>>>, column_indices: &mut Vec<usize>, time: f32, profile: &AutoplayProfile, list: &mut Vec<ReplayFrame>) {
         if self.states.len() != columns.len() {
             let new_len = columns.len();
             self.states.resize(new_len, AutoplayColumnState::default());
             // self.notes_hit.resize(new_len, Vec::new());
         }
+        if self.offsets.len() != columns.len() {
+            let mut humanizer = HumanizedAutoplay::new(*profile);
+            self.offsets = columns.iter().map(|c| humanizer.plan(c.len())).collect();
+        }
 
         for c in 0..columns.len() {
             let state = &mut self.states[c];
@@ -1174,7 +1326,10 @@ impl ManiaAutoHelper {
 
             if column_indices[c] >= columns[c].len() { continue }
             let note = &columns[c][column_indices[c]];
-            if time >= note.time() && !note.was_hit() {
+
+            // planned miss
+            let Some(offset) = self.offsets[c].get(column_indices[c]).copied().flatten() else { continue };
+            if time >= note.time() + offset && !note.was_hit() {
                 // if the key is already down, dont press it again
                 // if timer.0 == note.end_time(15.0) && 
                 if state.pressed { continue }
@@ -1183,9 +1338,9 @@ impl ManiaAutoHelper {
                 list.push(ReplayFrame::Press(Self::get_keypress(c)));
                 state.pressed = true;
                 if note.note_type() == NoteType::Hold {
-                    state.release_time = note.end_time(0.0);
+                    state.release_time = note.end_time(0.0) + offset;
                 } else {
-                    state.release_time = note.end_time(50.0);
+                    state.release_time = note.end_time(50.0) + offset;
                 }
             }
         }
@@ -1270,3 +1425,80 @@ impl Deref for ManiaPlayfield {
         &self.settings
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playfield() -> ManiaPlayfield {
+        ManiaPlayfield::new(ManiaPlayfieldSettings::default(), Vector2::new(1280.0, 720.0), 4)
+    }
+
+    /// the middle of a column
+    fn column_x(playfield: &ManiaPlayfield, col: u8) -> f64 {
+        playfield.col_pos(col) + playfield.column_width / 2.0
+    }
+
+    fn send(keys: &mut HashMap<i64, KeyPress>, id: i64, x: f64, phase: TouchPhase) -> Vec<ReplayFrame> {
+        let column = ManiaGame::touch_column(&playfield(), 4, x);
+        ManiaGame::touch_frames(keys, TouchInput::new(id, Vector2::new(x, 0.0), phase), column)
+    }
+
+    #[test]
+    fn touch_columns() {
+        let playfield = playfield();
+        for col in 0..4 {
+            let key = ManiaGame::touch_column(&playfield, 4, column_x(&playfield, col));
+            assert_eq!(key, (KeyPress::Mania1 as u8 + col).into());
+        }
+
+        // past the edges
+        assert_eq!(ManiaGame::touch_column(&playfield, 4, -1000.0), KeyPress::Mania1);
+        assert_eq!(ManiaGame::touch_column(&playfield, 4, 10000.0), KeyPress::Mania4);
+    }
+
+    #[test]
+    fn sliding_changes_column() {
+        let playfield = playfield();
+        let mut keys = HashMap::new();
+
+        let frames = send(&mut keys, 1, column_x(&playfield, 0), TouchPhase::Down);
+        assert!(matches!(frames[..], [ReplayFrame::Press(KeyPress::Mania1)]));
+
+        // moving inside the same column does nothing
+        let frames = send(&mut keys, 1, column_x(&playfield, 0) + 1.0, TouchPhase::Move);
+        assert!(frames.is_empty());
+
+        let frames = send(&mut keys, 1, column_x(&playfield, 1), TouchPhase::Move);
+        assert!(matches!(frames[..], [ReplayFrame::Release(KeyPress::Mania1), ReplayFrame::Press(KeyPress::Mania2)]));
+
+        let frames = send(&mut keys, 1, column_x(&playfield, 1), TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::Release(KeyPress::Mania2)]));
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn touches_release_separately() {
+        let playfield = playfield();
+        let mut keys = HashMap::new();
+        send(&mut keys, 1, column_x(&playfield, 0), TouchPhase::Down);
+        send(&mut keys, 2, column_x(&playfield, 3), TouchPhase::Down);
+
+        let frames = send(&mut keys, 2, column_x(&playfield, 3), TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::Release(KeyPress::Mania4)]));
+        assert_eq!(keys.get(&1), Some(&KeyPress::Mania1));
+
+        let frames = send(&mut keys, 1, column_x(&playfield, 0), TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::Release(KeyPress::Mania1)]));
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn touches_from_before_gameplay_are_ignored() {
+        let playfield = playfield();
+        let mut keys = HashMap::new();
+        assert!(send(&mut keys, 1, column_x(&playfield, 2), TouchPhase::Move).is_empty());
+        assert!(send(&mut keys, 1, column_x(&playfield, 2), TouchPhase::Up).is_empty());
+        assert!(keys.is_empty());
+    }
+}
@@ -9,9 +9,9 @@ impl GameModeInfo for ManiaGameInfo {
 
     /// from https://wiki.quavergame.com/docs/gameplay#accuracy
     fn calc_acc(&self, score: &Score) -> f64 {
-        let marv = score.judgments.get("geki").copy_or_default() as f64;
+        let marv = score.judgments.get("xgeki").copy_or_default() as f64;
         let perf = score.judgments.get("x300").copy_or_default() as f64;
-        let great = score.judgments.get("katu").copy_or_default() as f64;
+        let great = score.judgments.get("xkatu").copy_or_default() as f64;
         let good = score.judgments.get("x100").copy_or_default() as f64;
         let okay  = score.judgments.get("x50").copy_or_default() as f64;
         let miss = score.judgments.get("xmiss").copy_or_default() as f64;
@@ -42,8 +42,31 @@ impl GameModeInfo for ManiaGameInfo {
     }
 
 
-    fn get_perf_calc(&self) -> PerformanceCalc where Self:Sized {
-        Box::new(|diff, acc| diff * (acc / 0.98).powi(6))
+    /// misses are already punished by quaver's acc, so theres no separate miss penalty
+    fn calc_perf(&self, info: PerformanceCalcInfo) -> PerformanceInfo {
+        let acc_mult = info.acc_multiplier(0.98);
+
+        PerformanceInfo::new()
+            .with_part("Strain", info.attributes.skill("Strain") * 0.7 * acc_mult)
+            .with_part("Acc", info.diff * 0.3 * acc_mult.powi(2))
+    }
+
+    /// mania players tend to have lots of similar plays, so the top plays should matter more
+    fn rating_weight(&self, index: usize) -> f32 { 0.9f32.powi(index as i32) }
+
+    fn keybinds(&self) -> Vec<KeybindContext> {
+        ManiaSettings::default().keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, keys)| {
+                let actions = keys
+                    .into_iter()
+                    .enumerate()
+                    .map(|(col, key)| KeybindAction::new(format!("column_{}", col + 1), format!("Column {}", col + 1), vec![InputTarget::Key(key)]))
+                    .collect();
+                KeybindContext::new(format!("mania_{}k", i + 1), format!("Mania {}K", i + 1), actions)
+            })
+            .collect()
     }
 
     fn get_diff_string(&self, info: &BeatmapMetaWithDiff, mods: &ModManager) -> String {
@@ -80,8 +103,17 @@ impl GameModeInfo for ManiaGameInfo {
     fn get_judgments(&self) -> Box<dyn crate::prelude::HitJudgments> {
         Box::new(super::ManiaHitJudgments::Miss)
     }
-    async fn create_game(&self, beatmap: &Beatmap) -> TatakuResult<Box<dyn GameMode>> {
-        let game = ManiaGame::new(beatmap, false).await?;
+    fn create_scoring_system(&self, kind: ScoringSystemKind) -> Option<Box<dyn ScoringSystem>> {
+        match kind {
+            ScoringSystemKind::Default => None,
+            ScoringSystemKind::ScoreV1 => Some(Box::new(super::ManiaScoreV1::default())),
+            ScoringSystemKind::ScoreV2 => Some(Box::new(super::ManiaScoreV2::default())),
+            ScoringSystemKind::Quaver => Some(Box::new(super::QuaverScoring::default())),
+            ScoringSystemKind::Wife3 => Some(Box::new(super::Wife3Scoring::default())),
+        }
+    }
+    async fn create_game(&self, beatmap: &Beatmap, headless: bool) -> TatakuResult<Box<dyn GameMode>> {
+        let game = ManiaGame::new(beatmap, headless).await?;
         Ok(Box::new(game))
     }
     async fn create_diffcalc(&self, map: &BeatmapMeta) -> TatakuResult<Box<dyn DiffCalc>> {
@@ -0,0 +1,448 @@
+use crate::prelude::*;
+use super::ManiaHitJudgments;
+
+const MAX_SCORE: f64 = 1_000_000.0;
+
+fn judgment_from_str(s: &str) -> ManiaHitJudgments {
+    match s {
+        "xgeki" => ManiaHitJudgments::Marvelous,
+        "x300" => ManiaHitJudgments::Perfect,
+        "xkatu" => ManiaHitJudgments::Great,
+        "x100" => ManiaHitJudgments::Good,
+        "x50" => ManiaHitJudgments::Okay,
+        _ => ManiaHitJudgments::Miss,
+    }
+}
+
+/// osu!mania's 320/300/200/100/50/0 hit values
+fn osu_hit_value(j: ManiaHitJudgments) -> f64 {
+    match j {
+        ManiaHitJudgments::Marvelous => 320.0,
+        ManiaHitJudgments::Perfect => 300.0,
+        ManiaHitJudgments::Great => 200.0,
+        ManiaHitJudgments::Good => 100.0,
+        ManiaHitJudgments::Okay => 50.0,
+        ManiaHitJudgments::Miss => 0.0,
+    }
+}
+
+
+/// osu!mania's classic score, from https://osu.ppy.sh/wiki/en/Gameplay/Score/ScoreV1/osu%21mania
+#[derive(Default)]
+pub struct ManiaScoreV1 {
+    max_judgments: usize,
+    score: f64,
+    bonus: f64,
+    hit_value_total: f64,
+    hit_count: usize,
+}
+impl ScoringSystem for ManiaScoreV1 {
+    fn reset(&mut self, max_judgments: usize) {
+        *self = Self { max_judgments, bonus: 100.0, ..Default::default() }
+    }
+
+    fn add_hit(&mut self, hit: ScoringHit) {
+        let j = judgment_from_str(hit.judgment);
+        let (bonus_value, bonus_change) = match j {
+            ManiaHitJudgments::Marvelous => (32.0, 2.0),
+            ManiaHitJudgments::Perfect => (32.0, 1.0),
+            ManiaHitJudgments::Great => (16.0, -8.0),
+            ManiaHitJudgments::Good => (8.0, -24.0),
+            ManiaHitJudgments::Okay => (4.0, -44.0),
+            ManiaHitJudgments::Miss => (0.0, -100.0),
+        };
+        self.bonus = (self.bonus + bonus_change).clamp(0.0, 100.0);
+
+        let per_note = MAX_SCORE * 0.5 / self.max_judgments.max(1) as f64;
+        self.score += per_note * osu_hit_value(j) / 320.0;
+        self.score += per_note * bonus_value * self.bonus.sqrt() / 320.0;
+
+        // v1 acc treats marvelous the same as perfect
+        self.hit_value_total += osu_hit_value(j).min(300.0);
+        self.hit_count += 1;
+    }
+
+    fn score(&self) -> u64 { self.score.round() as u64 }
+    fn accuracy(&self) -> f64 {
+        if self.hit_count == 0 { return 1.0 }
+        self.hit_value_total / (300.0 * self.hit_count as f64)
+    }
+}
+
+
+/// osu!mania's scorev2, where marvelous is worth more than perfect for acc.
+/// score is 99% accuracy and 1% combo
+#[derive(Default)]
+pub struct ManiaScoreV2 {
+    max_judgments: usize,
+    combo: usize,
+    combo_total: f64,
+    hit_value_total: f64,
+    hit_count: usize,
+}
+impl ManiaScoreV2 {
+    /// combo is capped at 400 so long maps dont end up all combo
+    fn combo_value(combo: usize) -> f64 {
+        (combo.min(400) as f64).sqrt()
+    }
+    fn hit_value(j: ManiaHitJudgments) -> f64 {
+        match j {
+            ManiaHitJudgments::Marvelous => 305.0,
+            other => osu_hit_value(other),
+        }
+    }
+}
+impl ScoringSystem for ManiaScoreV2 {
+    fn reset(&mut self, max_judgments: usize) {
+        *self = Self { max_judgments, ..Default::default() }
+    }
+
+    fn add_hit(&mut self, hit: ScoringHit) {
+        let j = judgment_from_str(hit.judgment);
+        if let ManiaHitJudgments::Miss = j {
+            self.combo = 0;
+        } else {
+            self.combo += 1;
+        }
+
+        self.combo_total += Self::combo_value(self.combo);
+        self.hit_value_total += Self::hit_value(j);
+        self.hit_count += 1;
+    }
+
+    fn score(&self) -> u64 {
+        let max_judgments = self.max_judgments.max(self.hit_count).max(1);
+        let max_combo_total = (1..=max_judgments).map(Self::combo_value).sum::<f64>();
+
+        let acc_portion = self.hit_value_total / (305.0 * max_judgments as f64);
+        let combo_portion = self.combo_total / max_combo_total;
+        (MAX_SCORE * (0.99 * acc_portion + 0.01 * combo_portion)).round() as u64
+    }
+    fn accuracy(&self) -> f64 {
+        if self.hit_count == 0 { return 1.0 }
+        self.hit_value_total / (305.0 * self.hit_count as f64)
+    }
+}
+
+
+/// quaver's accuracy, from https://wiki.quavergame.com/docs/gameplay#accuracy
+/// score is quaver's judgment weighting with a multiplier that builds up with combo
+#[derive(Default)]
+pub struct QuaverScoring {
+    max_judgments: usize,
+    multiplier_count: usize,
+    score: f64,
+    acc_total: f64,
+    hit_count: usize,
+}
+impl QuaverScoring {
+    /// the multiplier goes up every 10 hits, up to 15 times
+    const MAX_MULTIPLIER_COUNT: usize = 150;
+
+    fn score_weight(j: ManiaHitJudgments) -> f64 {
+        match j {
+            ManiaHitJudgments::Marvelous => 100.0,
+            ManiaHitJudgments::Perfect => 50.0,
+            ManiaHitJudgments::Great => 25.0,
+            ManiaHitJudgments::Good => 10.0,
+            ManiaHitJudgments::Okay => 5.0,
+            ManiaHitJudgments::Miss => 0.0,
+        }
+    }
+    fn acc_weight(j: ManiaHitJudgments) -> f64 {
+        match j {
+            ManiaHitJudgments::Marvelous => 1.0,
+            ManiaHitJudgments::Perfect => 0.9825,
+            ManiaHitJudgments::Great => 0.65,
+            ManiaHitJudgments::Good => 0.25,
+            ManiaHitJudgments::Okay => -1.0,
+            ManiaHitJudgments::Miss => -0.5,
+        }
+    }
+    fn multiplier(count: usize) -> f64 {
+        1.0 + (count / 10) as f64 * 0.1
+    }
+}
+impl ScoringSystem for QuaverScoring {
+    fn reset(&mut self, max_judgments: usize) {
+        *self = Self { max_judgments, ..Default::default() }
+    }
+
+    fn add_hit(&mut self, hit: ScoringHit) {
+        let j = judgment_from_str(hit.judgment);
+        self.multiplier_count = match j {
+            ManiaHitJudgments::Marvelous | ManiaHitJudgments::Perfect | ManiaHitJudgments::Great => (self.multiplier_count + 1).min(Self::MAX_MULTIPLIER_COUNT),
+            ManiaHitJudgments::Good | ManiaHitJudgments::Okay => self.multiplier_count.saturating_sub(10),
+            ManiaHitJudgments::Miss => self.multiplier_count.saturating_sub(20),
+        };
+
+        self.score += Self::score_weight(j) * Self::multiplier(self.multiplier_count);
+        self.acc_total += Self::acc_weight(j);
+        self.hit_count += 1;
+    }
+
+    fn score(&self) -> u64 {
+        let max_judgments = self.max_judgments.max(self.hit_count).max(1);
+        let max_score = (1..=max_judgments)
+            .map(|i| 100.0 * Self::multiplier(i.min(Self::MAX_MULTIPLIER_COUNT)))
+            .sum::<f64>();
+
+        (MAX_SCORE * self.score / max_score).round() as u64
+    }
+    fn accuracy(&self) -> f64 {
+        if self.hit_count == 0 { return 1.0 }
+        self.acc_total.max(0.0) / self.hit_count as f64
+    }
+}
+
+
+/// etterna's wife3 accuracy, from the etterna source (at J4).
+/// etterna doesnt really have a score, so the score is just the points scaled to 1,000,000
+#[derive(Default)]
+pub struct Wife3Scoring {
+    max_judgments: usize,
+    points: f64,
+    hit_count: usize,
+}
+impl Wife3Scoring {
+    const MAX_POINTS: f64 = 2.0;
+    const MISS_WEIGHT: f64 = -5.5;
+    const RIDIC: f64 = 5.0;
+    const MAX_BOO_WEIGHT: f64 = 180.0;
+    const ZERO: f64 = 65.0;
+    const DEV: f64 = 22.7;
+
+    fn points(offset: f64) -> f64 {
+        let offset = offset.abs();
+        if offset <= Self::RIDIC { return Self::MAX_POINTS }
+        if offset <= Self::ZERO { return Self::MAX_POINTS * erf((Self::ZERO - offset) / Self::DEV) }
+        if offset <= Self::MAX_BOO_WEIGHT { return (offset - Self::ZERO) * Self::MISS_WEIGHT / (Self::MAX_BOO_WEIGHT - Self::ZERO) }
+        Self::MISS_WEIGHT
+    }
+
+    /// offset to use when a hit doesnt have one, roughly the middle of the judgment's window
+    fn fallback_offset(j: ManiaHitJudgments) -> Option<f64> {
+        match j {
+            ManiaHitJudgments::Marvelous => Some(0.0),
+            ManiaHitJudgments::Perfect => Some(30.0),
+            ManiaHitJudgments::Great => Some(60.0),
+            ManiaHitJudgments::Good => Some(90.0),
+            ManiaHitJudgments::Okay => Some(117.0),
+            ManiaHitJudgments::Miss => None,
+        }
+    }
+}
+impl ScoringSystem for Wife3Scoring {
+    fn reset(&mut self, max_judgments: usize) {
+        *self = Self { max_judgments, ..Default::default() }
+    }
+
+    fn add_hit(&mut self, hit: ScoringHit) {
+        let j = judgment_from_str(hit.judgment);
+        let offset = match j {
+            ManiaHitJudgments::Miss => None,
+            _ => hit.offset.map(|o| o as f64).or_else(|| Self::fallback_offset(j)),
+        };
+
+        self.points += offset.map(Self::points).unwrap_or(Self::MISS_WEIGHT);
+        self.hit_count += 1;
+    }
+
+    fn score(&self) -> u64 {
+        let max_judgments = self.max_judgments.max(self.hit_count).max(1);
+        (MAX_SCORE * self.points.max(0.0) / (Self::MAX_POINTS * max_judgments as f64)).round() as u64
+    }
+    fn accuracy(&self) -> f64 {
+        if self.hit_count == 0 { return 1.0 }
+        self.points / (Self::MAX_POINTS * self.hit_count as f64)
+    }
+}
+
+/// abramowitz and stegun's approximation, std doesnt have erf
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let y = 1.0 - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t + 0.254829592) * t * (-x * x).exp();
+    sign * y
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn systems() -> Vec<(&'static str, Box<dyn ScoringSystem>)> {
+        vec![
+            ("v1", Box::new(ManiaScoreV1::default())),
+            ("v2", Box::new(ManiaScoreV2::default())),
+            ("quaver", Box::new(QuaverScoring::default())),
+            ("wife3", Box::new(Wife3Scoring::default())),
+        ]
+    }
+
+    fn play(system: &mut dyn ScoringSystem, hits: &[(ManiaHitJudgments, Option<f32>)]) {
+        system.reset(hits.len());
+        for (j, offset) in hits {
+            system.add_hit(ScoringHit { judgment: j.as_str_internal(), offset: *offset });
+        }
+    }
+
+    #[test]
+    fn perfect_play_is_max_score() {
+        let hits = vec![(ManiaHitJudgments::Marvelous, Some(0.0)); 500];
+        for (name, mut system) in systems() {
+            play(system.as_mut(), &hits);
+            assert_eq!(system.score(), MAX_SCORE as u64, "{name}");
+            assert!((system.accuracy() - 1.0).abs() < 1e-9, "{name}: {}", system.accuracy());
+        }
+    }
+
+    #[test]
+    fn all_misses_is_no_score() {
+        let hits = vec![(ManiaHitJudgments::Miss, None); 100];
+        for (name, mut system) in systems() {
+            play(system.as_mut(), &hits);
+            assert_eq!(system.score(), 0, "{name}");
+            assert!(system.accuracy() <= 0.0, "{name}: {}", system.accuracy());
+        }
+    }
+
+    #[test]
+    fn no_hits_is_full_acc() {
+        for (name, mut system) in systems() {
+            system.reset(100);
+            assert_eq!(system.score(), 0, "{name}");
+            assert_eq!(system.accuracy(), 1.0, "{name}");
+        }
+    }
+
+    #[test]
+    fn reset_clears_the_play() {
+        for (name, mut system) in systems() {
+            play(system.as_mut(), &[(ManiaHitJudgments::Miss, None); 10]);
+            play(system.as_mut(), &[(ManiaHitJudgments::Marvelous, Some(0.0)); 10]);
+            assert_eq!(system.score(), MAX_SCORE as u64, "{name}");
+        }
+    }
+
+    #[test]
+    fn worse_judgments_are_worth_less() {
+        use ManiaHitJudgments::*;
+        for (name, mut system) in systems() {
+            let mut last = f64::MAX;
+            for j in [Marvelous, Perfect, Great, Good, Okay, Miss] {
+                play(system.as_mut(), &[(j, None); 10]);
+                assert!(system.accuracy() <= last, "{name}: {j:?} acc {} > {last}", system.accuracy());
+                last = system.accuracy();
+            }
+        }
+    }
+
+    #[test]
+    fn v1_acc_treats_marvelous_as_perfect() {
+        let mut v1 = ManiaScoreV1::default();
+        play(&mut v1, &[(ManiaHitJudgments::Marvelous, None), (ManiaHitJudgments::Perfect, None)]);
+        assert_eq!(v1.accuracy(), 1.0);
+
+        let mut v2 = ManiaScoreV2::default();
+        play(&mut v2, &[(ManiaHitJudgments::Marvelous, None), (ManiaHitJudgments::Perfect, None)]);
+        assert_eq!(v2.accuracy(), 605.0 / 610.0);
+    }
+
+    #[test]
+    fn v1_bonus_drops_with_bad_hits() {
+        let mut clean = ManiaScoreV1::default();
+        play(&mut clean, &[(ManiaHitJudgments::Perfect, None); 4]);
+
+        // the good takes bonus away, so the perfects after it are worth less
+        let mut dirty = ManiaScoreV1::default();
+        play(&mut dirty, &[(ManiaHitJudgments::Good, None), (ManiaHitJudgments::Perfect, None), (ManiaHitJudgments::Perfect, None), (ManiaHitJudgments::Perfect, None)]);
+
+        assert!(dirty.score() < clean.score());
+    }
+
+    #[test]
+    fn quaver_acc_weights() {
+        let mut system = QuaverScoring::default();
+        play(&mut system, &[(ManiaHitJudgments::Great, None)]);
+        assert_eq!(system.accuracy(), 0.65);
+
+        play(&mut system, &[(ManiaHitJudgments::Marvelous, None), (ManiaHitJudgments::Perfect, None)]);
+        assert_eq!(system.accuracy(), (1.0 + 0.9825) / 2.0);
+    }
+
+    #[test]
+    fn quaver_multiplier_builds_with_combo() {
+        assert_eq!(QuaverScoring::multiplier(0), 1.0);
+        assert_eq!(QuaverScoring::multiplier(9), 1.0);
+        assert!((QuaverScoring::multiplier(10) - 1.1).abs() < 1e-9);
+        assert!((QuaverScoring::multiplier(QuaverScoring::MAX_MULTIPLIER_COUNT) - 2.5).abs() < 1e-9);
+
+        // a miss early on costs more score than just the note itself
+        let mut system = QuaverScoring::default();
+        let mut hits = vec![(ManiaHitJudgments::Marvelous, None); 200];
+        hits[20] = (ManiaHitJudgments::Miss, None);
+        play(&mut system, &hits);
+        assert!(system.score() < (MAX_SCORE * 199.0 / 200.0) as u64);
+    }
+
+    #[test]
+    fn wife3_points() {
+        assert_eq!(Wife3Scoring::points(0.0), Wife3Scoring::MAX_POINTS);
+        assert_eq!(Wife3Scoring::points(5.0), Wife3Scoring::MAX_POINTS);
+        assert_eq!(Wife3Scoring::points(30.0), Wife3Scoring::points(-30.0));
+        assert!(Wife3Scoring::points(65.0).abs() < 1e-6);
+        assert!(Wife3Scoring::points(100.0) < 0.0);
+        assert_eq!(Wife3Scoring::points(200.0), Wife3Scoring::MISS_WEIGHT);
+
+        // later hits are never worth more
+        let mut last = f64::MAX;
+        for offset in 0..200 {
+            let points = Wife3Scoring::points(offset as f64);
+            assert!(points <= last + 1e-9, "{offset}ms");
+            last = points;
+        }
+    }
+
+    #[test]
+    fn wife3_uses_offsets_when_it_has_them() {
+        let mut with_offset = Wife3Scoring::default();
+        play(&mut with_offset, &[(ManiaHitJudgments::Perfect, Some(10.0))]);
+
+        // without an offset it falls back to the middle of the window
+        let mut without_offset = Wife3Scoring::default();
+        play(&mut without_offset, &[(ManiaHitJudgments::Perfect, None)]);
+
+        assert_eq!(with_offset.accuracy(), Wife3Scoring::points(10.0) / Wife3Scoring::MAX_POINTS);
+        assert_eq!(without_offset.accuracy(), Wife3Scoring::points(30.0) / Wife3Scoring::MAX_POINTS);
+
+        // misses are always the miss weight, even with an offset
+        let mut miss = Wife3Scoring::default();
+        play(&mut miss, &[(ManiaHitJudgments::Miss, Some(0.0))]);
+        assert_eq!(miss.accuracy(), Wife3Scoring::MISS_WEIGHT / Wife3Scoring::MAX_POINTS);
+    }
+
+    #[test]
+    fn erf_approximation() {
+        assert!(erf(0.0).abs() < 1e-6);
+        assert!((erf(1.0) - 0.8427).abs() < 1e-3);
+        assert!((erf(-1.0) + 0.8427).abs() < 1e-3);
+        assert!((erf(5.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rescore_uses_timings_then_remaining_judgments() {
+        let mut score = IngameScore::new(Score::new(String::new(), String::new(), "mania".to_owned()), false, false);
+        score.judgments.insert("xgeki".to_owned(), 2);
+        score.judgments.insert("xmiss".to_owned(), 1);
+        // one of the marvelouses has a timing, the other and the miss dont
+        score.timings.add(1000.0, 3.0, "xgeki");
+
+        let (_, acc) = ScoringSystemKind::Wife3.rescore(&score).unwrap();
+        let expected = (Wife3Scoring::points(3.0) + Wife3Scoring::points(0.0) + Wife3Scoring::MISS_WEIGHT) / (3.0 * Wife3Scoring::MAX_POINTS);
+        assert!((acc - expected).abs() < 1e-9);
+
+        assert!(ScoringSystemKind::Default.rescore(&score).is_none());
+    }
+}
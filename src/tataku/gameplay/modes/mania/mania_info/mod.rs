@@ -1,5 +1,7 @@
 mod mania_info;
 mod mania_hit_judgments;
+mod mania_scoring;
 
 pub use mania_info::*;
 pub use mania_hit_judgments::*;
+pub use mania_scoring::*;
@@ -6,10 +6,19 @@ const BUCKET_LENGTH:f32 = 500.0;
 
 pub struct OsuDifficultyCalculator {
     notes: Vec<OsuDifficultyHitObject>,
+    metadata: BeatmapMeta,
 }
 impl OsuDifficultyCalculator {
+    /// how much harder aim is with the mods' circle size, compared to the map's circle size
+    fn aim_multiplier(&self, mods: &ModManager) -> f64 {
+        // same as the cs scale in the ScalingHelper
+        let cs_scale = |cs: f32| 1.0 - 0.7 * (cs as f64 - 5.0) / 5.0;
+        cs_scale(OsuGame::get_cs(&self.metadata, &ModManager::default())) / cs_scale(OsuGame::get_cs(&self.metadata, mods))
+    }
+
     fn calc_aim(&mut self, mods: &ModManager) -> TatakuResult<Vec<f64>> {
         let mut start_bucket_time = self.notes.first().unwrap().time;
+        let aim_multiplier = self.aim_multiplier(mods);
 
         let bucket_length = BUCKET_LENGTH * mods.get_speed();
         let mut aim_density = Vec::new();
@@ -29,7 +38,7 @@ impl OsuDifficultyCalculator {
 
             match note1.note_type {
                 NoteType::Note | NoteType::Slider => {
-                    aims += note1.end_pos.distance(note2.pos);
+                    aims += note1.end_pos.distance(note2.pos) * aim_multiplier;
                 },
 
                 NoteType::Spinner => {},
@@ -130,14 +139,17 @@ impl DiffCalc for OsuDifficultyCalculator {
         notes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
 
         Ok(Self {
-            notes
+            notes,
+            metadata: meta.clone(),
         })
     }
 
-    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<f32> {
+    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<DifficultyAttributes> {
         let aim = self.calc_aim(mods)?;
         let note_density = self.calc_density(mods)?;
 
+        let mut aim_strain = Vec::new();
+        let mut speed_strain = Vec::new();
         let mut diff = Vec::new();
 
         for (strain, density) in aim.into_iter().zip(note_density.into_iter()) {
@@ -146,26 +158,15 @@ impl DiffCalc for OsuDifficultyCalculator {
 
             let combined = strain_value + density_value;
 
+            aim_strain.push(strain_value);
+            speed_strain.push(density_value);
             diff.push(combined);
         }
 
-        let mut difficulty = 0.0;
-        let mut weight = 1.0;
-
-        const PERCENT: f32 = 0.99;
-
-        // Sort by descending
-        diff.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
-
-        for x in diff {
-            difficulty += x * weight;
-            weight *= PERCENT;
-        }
-
-        difficulty /= (1.0 - weight) / (1.0 - PERCENT);
-
-
-        Ok(difficulty)
+        Ok(DifficultyAttributes::new(weighted_strain(diff))
+            .with_skill("Aim", weighted_strain(aim_strain))
+            .with_skill("Speed", weighted_strain(speed_strain))
+        )
     }
 }
 
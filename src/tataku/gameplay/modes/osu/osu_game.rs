@@ -41,6 +41,7 @@ pub struct OsuGame {
 
     /// cached settings, saves on locking
     game_settings: Arc<StandardSettings>,
+    keybinds: ContextBindings,
 
     /// autoplay helper
     auto_helper: StandardAutoHelper,
@@ -49,6 +50,14 @@ pub struct OsuGame {
     new_combos: Vec<usize>,
 
     use_controller_cursor: bool,
+    /// which controller axes are currently pressed
+    controller_axis_held: HashSet<KeyPress>,
+
+    use_touch_cursor: bool,
+    /// touch id to the key its holding
+    touch_keys: HashMap<i64, KeyPress>,
+    /// the touch the cursor follows, which is the latest one
+    cursor_touch: Option<i64>,
     window_size: Arc<WindowSize>,
     end_time: f32,
 
@@ -60,6 +69,69 @@ pub struct OsuGame {
     mods: Arc<ModManager>
 }
 impl OsuGame {
+    /// get the keys an input is bound to
+    fn bound_keys(&self, target: &InputTarget) -> Vec<KeyPress> {
+        self.keybinds
+            .actions_for(target)
+            .filter_map(|action| match action.as_str() {
+                "left" => Some(KeyPress::Left),
+                "right" => Some(KeyPress::Right),
+                "left_mouse" => Some(KeyPress::LeftMouse),
+                "right_mouse" => Some(KeyPress::RightMouse),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// what a touch does. the cursor follows the latest touch, and each touch holds whichever key is free.
+    /// `pos` is where the touch is in playfield coords
+    fn touch_frames(touch_keys: &mut HashMap<i64, KeyPress>, cursor_touch: &mut Option<i64>, touch: TouchInput, pos: Vector2) -> Vec<ReplayFrame> {
+        let mut frames = Vec::new();
+
+        if touch.phase == TouchPhase::Down {
+            *cursor_touch = Some(touch.id);
+        }
+        if *cursor_touch == Some(touch.id) {
+            frames.push(ReplayFrame::MousePos(pos.x as f32, pos.y as f32));
+        }
+
+        match touch.phase {
+            TouchPhase::Down => {
+                // if both keys are held already, the touch only moves the cursor
+                let free = [KeyPress::Left, KeyPress::Right].into_iter().find(|k| !touch_keys.values().any(|held| held == k));
+                if let Some(key) = free {
+                    touch_keys.insert(touch.id, key);
+                    frames.push(ReplayFrame::Press(key));
+                }
+            }
+            TouchPhase::Move => {}
+            TouchPhase::Up => {
+                if *cursor_touch == Some(touch.id) {
+                    *cursor_touch = None;
+                }
+                if let Some(key) = touch_keys.remove(&touch.id) {
+                    frames.push(ReplayFrame::Release(key));
+                }
+            }
+        }
+
+        frames
+    }
+
+    /// get the keys a controller button is bound to.
+    /// controllers that havent been set up or bound use the bumpers
+    fn controller_keys(&self, c: &Box<dyn Controller>, btn: u8) -> Vec<KeyPress> {
+        if self.keybinds.has_controller(&c.get_name()) {
+            return self.bound_keys(&InputTarget::controller(c, btn))
+        }
+
+        match c.map_button(btn) {
+            Some(ControllerButton::Left_Bumper) => vec![KeyPress::Left],
+            Some(ControllerButton::Right_Bumper) => vec![KeyPress::Right],
+            _ => Vec::new(),
+        }
+    }
+
     async fn playfield_changed(&mut self) {
         let new_scale = Arc::new(ScalingHelper::new(self.cs, self.window_size.0, self.mods.has_mod(HardRock.name())).await);
         self.apply_playfield(new_scale).await
@@ -143,10 +215,23 @@ impl OsuGame {
     fn setup_hitwindows(&mut self) {
         // windows
         let od = Self::get_od(&self.metadata, &self.mods);
-        let w_miss = map_difficulty(od, 225.0, 175.0, 125.0); // idk
-        let w_50   = map_difficulty(od, 200.0, 150.0, 100.0);
-        let w_100  = map_difficulty(od, 140.0, 100.0, 60.0);
-        let w_300  = map_difficulty(od, 80.0, 50.0, 20.0);
+        let od_windows = vec![
+            map_difficulty(od, 80.0, 50.0, 20.0),
+            map_difficulty(od, 140.0, 100.0, 60.0),
+            map_difficulty(od, 200.0, 150.0, 100.0),
+            map_difficulty(od, 225.0, 175.0, 125.0), // idk
+        ];
+
+        // osu judges with mania's perfect, good, okay and miss windows
+        let (preset, custom_windows) = JudgmentPreset::from_id(self.mods.judgment_preset.as_ref(), &self.playmode());
+        let windows = match preset {
+            JudgmentPreset::OsuOd => od_windows,
+            preset => preset.windows(od, &custom_windows, &[1, 3, 4, 5]).unwrap_or_else(|| {
+                warn!("invalid custom judgment windows {custom_windows:?}, using od");
+                od_windows
+            }),
+        };
+        let (w_300, w_100, w_50, w_miss) = (windows[0], windows[1], windows[2], windows[3]);
         self.miss_window = w_miss;
 
         self.hit_windows = vec![
@@ -265,8 +350,14 @@ impl GameMode for OsuGame {
                     cs,
 
                     use_controller_cursor: false,
+                    controller_axis_held: HashSet::new(),
+
+                    use_touch_cursor: false,
+                    touch_keys: HashMap::new(),
+                    cursor_touch: None,
         
                     game_settings: std_settings.clone(),
+                    keybinds: get_settings!().keybinds.get_bindings("osu"),
                     auto_helper: StandardAutoHelper::new(),
                     new_combos: Vec::new(),
                     stack_leniency,
@@ -438,7 +529,7 @@ impl GameMode for OsuGame {
 
     async fn handle_replay_frame(&mut self, frame:ReplayFrame, time:f32, manager:&mut IngameManager) {
         if !manager.replaying {
-            manager.replay.frames.push((time, frame.clone()));
+            manager.record_replay_frame(time, frame.clone());
             manager.outgoing_spectator_frame((time, SpectatorFrameData::ReplayFrame{frame}));
         }
 
@@ -549,7 +640,7 @@ impl GameMode for OsuGame {
         if manager.current_mods.has_autoplay() {
             let mut pending_frames = Vec::new();
 
-            self.auto_helper.update(time, &mut self.notes, &self.scaling_helper, &mut pending_frames);
+            self.auto_helper.update(time, &mut self.notes, &self.scaling_helper, &manager.autoplay_profile(), &mut pending_frames);
 
             // handle presses and mouse movements now, and releases later
             for frame in pending_frames.iter() {
@@ -706,7 +797,7 @@ impl GameMode for OsuGame {
 
 
         // if this is a replay, we need to draw the replay curser
-        if manager.replaying || manager.current_mods.has_autoplay() || self.use_controller_cursor {
+        if manager.replaying || manager.current_mods.has_autoplay() || self.use_controller_cursor || self.use_touch_cursor {
             CursorManager::set_pos(self.mouse_pos, true)
         }
 
@@ -715,6 +806,19 @@ impl GameMode for OsuGame {
             note.draw(args, list).await;
         }
 
+        // draw the ghost's cursor
+        if self.game_settings.ghost_cursor {
+            if let Some(pos) = manager.ghost.as_ref().and_then(|g| g.cursor_pos) {
+                list.push(Circle::new(
+                    Color::WHITE.alpha(0.4),
+                    -100.0,
+                    self.scaling_helper.scale_coords(pos),
+                    10.0 * self.scaling_helper.scale,
+                    Some(Border::new(Color::BLACK.alpha(0.4), 1.0))
+                ));
+            }
+        }
+
         // draw follow points
         let time = manager.time();
         if self.game_settings.draw_follow_points {
@@ -790,6 +894,9 @@ impl GameMode for OsuGame {
     async fn reset(&mut self, _beatmap:&Beatmap) {
         // let ar = scale_by_mods(self.metadata.ar, 0.5, 1.4, &self.mods).clamp(1.0, 11.0);
 
+        // new helper so humanized autoplay plays the same way again
+        self.auto_helper = StandardAutoHelper::new();
+
         // reset notes
         let hwm = self.miss_window;
         for note in self.notes.iter_mut() {
@@ -829,6 +936,7 @@ impl GameMode for OsuGame {
     }
     
     async fn force_update_settings(&mut self, settings: &Settings) {
+        self.keybinds = settings.keybinds.get_bindings("osu");
         let settings = settings.standard_settings.clone();
         let settings = Arc::new(settings);
 
@@ -863,19 +971,21 @@ impl GameMode for OsuGame {
             let scale = self.scaling_helper.settings_scale;
 
             self.apply_playfield(Arc::new(ScalingHelper::new_offset_scale(cs, size, pos, scale, has_hr))).await;
-            self.setup_hitwindows();
             
             for note in self.notes.iter_mut() {
                 note.set_ar(ar);
             }
         }
+
+        // od or the judgment preset might have changed
+        self.setup_hitwindows();
     }
 }
 
 #[async_trait]
 impl GameModeInput for OsuGame {
 
-    async fn key_down(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_down(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         if key == piston::Key::LCtrl {
             let old = get_settings!().standard_settings.get_playfield();
             self.move_playfield = Some((old.1, self.window_mouse_pos));
@@ -887,16 +997,12 @@ impl GameModeInput for OsuGame {
             return;
         }
 
-        let time = manager.time();
-        if key == self.game_settings.left_key {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::Left), time, manager).await;
-        }
-        if key == self.game_settings.right_key {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::Right), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
     }
     
-    async fn key_up(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_up(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         if key == piston::Key::LCtrl {
             self.move_playfield = None;
             return;
@@ -908,17 +1014,13 @@ impl GameModeInput for OsuGame {
             return;
         }
 
-        let time = manager.time();
-        if key == self.game_settings.left_key {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::Left), time, manager).await;
-        }
-        if key == self.game_settings.right_key {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::Right), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
         }
     }
     
 
-    async fn mouse_move(&mut self, pos:Vector2, manager:&mut IngameManager) {
+    async fn mouse_move(&mut self, pos:Vector2, time:f32, manager:&mut IngameManager) {
         self.window_mouse_pos = pos;
         
         if let Some((original, mouse_start)) = self.move_playfield {
@@ -955,12 +1057,11 @@ impl GameModeInput for OsuGame {
         }
 
         // convert window pos to playfield pos
-        let time = manager.time();
         let pos = self.scaling_helper.descale_coords(pos);
         self.handle_replay_frame(ReplayFrame::MousePos(pos.x as f32, pos.y as f32), time, manager).await;
     }
     
-    async fn mouse_down(&mut self, btn:piston::MouseButton, manager:&mut IngameManager) {
+    async fn mouse_down(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
         if self.game_settings.ignore_mouse_buttons {return}
         
         // dont accept mouse input when autoplay is enabled, or a replay is being watched
@@ -968,16 +1069,12 @@ impl GameModeInput for OsuGame {
             return;
         }
 
-        let time = manager.time();
-        if btn == MouseButton::Left {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftMouse), time, manager).await;
-        }
-        if btn == MouseButton::Right {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::RightMouse), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
     }
     
-    async fn mouse_up(&mut self, btn:piston::MouseButton, manager:&mut IngameManager) {
+    async fn mouse_up(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
         if self.game_settings.ignore_mouse_buttons {return}
 
         // dont accept mouse input when autoplay is enabled, or a replay is being watched
@@ -985,12 +1082,8 @@ impl GameModeInput for OsuGame {
             return;
         }
 
-        let time = manager.time();
-        if btn == MouseButton::Left {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftMouse), time, manager).await;
-        }
-        if btn == MouseButton::Right {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::RightMouse), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
         }
     }
 
@@ -1006,46 +1099,41 @@ impl GameModeInput for OsuGame {
     }
 
 
-    async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, manager:&mut IngameManager) {
+    async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
         // dont accept controller input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        if Some(ControllerButton::Left_Bumper) == c.map_button(btn) {
-            let time = manager.time();
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::Left), time, manager).await;
-        }
-
-        if Some(ControllerButton::Right_Bumper) == c.map_button(btn) {
-            let time = manager.time();
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::Right), time, manager).await;
+        for key in self.controller_keys(c, btn) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
     }
     
-    async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, manager:&mut IngameManager) {
+    async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
         // dont accept controller input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        if Some(ControllerButton::Left_Bumper) == c.map_button(btn) {
-            let time = manager.time();
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::Left), time, manager).await;
-        }
-
-        if Some(ControllerButton::Right_Bumper) == c.map_button(btn) {
-            let time = manager.time();
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::Right), time, manager).await;
+        for key in self.controller_keys(c, btn) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
         }
     }
     
-    async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>, manager:&mut IngameManager) {
+    async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>, time:f32, manager:&mut IngameManager) {
         // dont accept controller input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
+        // any axes bound as keys
+        let inputs = self.keybinds.axis_inputs(c, |target| self.bound_keys(target));
+        for (key, pressed) in get_axis_presses(&inputs, &axis_data, &mut self.controller_axis_held) {
+            let frame = if pressed { ReplayFrame::Press(key) } else { ReplayFrame::Release(key) };
+            self.handle_replay_frame(frame, time, manager).await;
+        }
+
         self.use_controller_cursor = true;
 
         let mut new_pos = self.mouse_pos;
@@ -1068,11 +1156,24 @@ impl GameModeInput for OsuGame {
             }
         }
 
-        let time = manager.time();
         let new_pos = scaling_helper.descale_coords(new_pos);
         self.handle_replay_frame(ReplayFrame::MousePos(new_pos.x as f32, new_pos.y as f32), time, manager).await;
     }
 
+    /// the cursor jumps to each new touch and follows it, and each touch holds whichever key is free
+    async fn touch(&mut self, touch:TouchInput, time:f32, manager:&mut IngameManager) {
+        // dont accept touch input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
+        self.use_touch_cursor = true;
+
+        let pos = self.scaling_helper.descale_coords(touch.pos);
+        for frame in Self::touch_frames(&mut self.touch_keys, &mut self.cursor_touch, touch, pos) {
+            self.handle_replay_frame(frame, time, manager).await;
+        }
+    }
+
 }
 
 #[async_trait]
@@ -1147,6 +1248,10 @@ struct StandardAutoHelper {
     release_queue: Vec<ReplayFrame>,
 
     press_counter: usize,
+
+    /// hit offset for each note, None if the note should be missed
+    offsets: Vec<Option<f32>>,
+    cursor_style: AutoplayCursorStyle,
 }
 impl StandardAutoHelper {
     fn new() -> Self {
@@ -1160,7 +1265,10 @@ impl StandardAutoHelper {
             holding: HashMap::new(),
 
             release_queue: Vec::new(),
-            press_counter: 0
+            press_counter: 0,
+
+            offsets: Vec::new(),
+            cursor_style: AutoplayCursorStyle::Linear,
         }
     }
     fn get_release_queue(&mut self) -> Vec<ReplayFrame> {
@@ -1175,9 +1283,14 @@ impl StandardAutoHelper {
         }
     }
 
-    fn update(&mut self, time:f32, notes: &mut Vec<Box<dyn OsuHitObject>>, scaling_helper: &Arc<ScalingHelper>, frames: &mut Vec<ReplayFrame>) {
+    fn update(&mut self, time:f32, notes: &mut Vec<Box<dyn OsuHitObject>>, scaling_helper: &Arc<ScalingHelper>, profile: &AutoplayProfile, frames: &mut Vec<ReplayFrame>) {
         let mut any_checked = false;
 
+        if self.offsets.len() != notes.len() {
+            self.offsets = HumanizedAutoplay::new(*profile).plan(notes.len());
+            self.cursor_style = profile.cursor_style;
+        }
+
         let map_over = time > notes.last().map(|n| n.end_time(100.0)).unwrap_or(0.0);
         if map_over { return; }
 
@@ -1218,7 +1331,10 @@ impl StandardAutoHelper {
                 continue;
             }
             
-            if time >= note.time() {
+            // planned miss
+            let Some(offset) = self.offsets.get(i).copied().flatten() else { continue };
+
+            if time >= note.time() + offset {
                 let pos = scaling_helper.descale_coords(note.pos_at(time));
                 // move the mouse to the pos
                 frames.push(ReplayFrame::MousePos(
@@ -1266,7 +1382,7 @@ impl StandardAutoHelper {
         let current = time - self.point_trail_start_time;
         let len = current / duration;
         
-        let new_pos = Vector2::lerp(self.point_trail_start_pos, self.point_trail_end_pos, len as f64);
+        let new_pos = self.cursor_style.pos_at(self.point_trail_start_pos, self.point_trail_end_pos, len as f64, self.press_counter);
         frames.push(ReplayFrame::MousePos(
             new_pos.x as f32,
             new_pos.y as f32
@@ -1366,3 +1482,76 @@ impl ScalingHelper {
         v
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: i64, phase: TouchPhase) -> TouchInput {
+        TouchInput::new(id, Vector2::new(id as f64 * 10.0, 0.0), phase)
+    }
+
+    /// run touches through, keeping track of the state between them
+    struct Touches {
+        keys: HashMap<i64, KeyPress>,
+        cursor: Option<i64>,
+    }
+    impl Touches {
+        fn new() -> Self {
+            Self { keys: HashMap::new(), cursor: None }
+        }
+        fn send(&mut self, id: i64, phase: TouchPhase) -> Vec<ReplayFrame> {
+            let touch = touch(id, phase);
+            OsuGame::touch_frames(&mut self.keys, &mut self.cursor, touch, touch.pos)
+        }
+    }
+
+    #[test]
+    fn touch_moves_cursor_and_presses() {
+        let mut touches = Touches::new();
+        let frames = touches.send(1, TouchPhase::Down);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(x, _), ReplayFrame::Press(KeyPress::Left)] if x == 10.0));
+
+        let frames = touches.send(1, TouchPhase::Move);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(..)]));
+
+        let frames = touches.send(1, TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(..), ReplayFrame::Release(KeyPress::Left)]));
+        assert!(touches.keys.is_empty());
+        assert_eq!(touches.cursor, None);
+    }
+
+    #[test]
+    fn second_touch_takes_the_cursor_and_the_other_key() {
+        let mut touches = Touches::new();
+        touches.send(1, TouchPhase::Down);
+
+        let frames = touches.send(2, TouchPhase::Down);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(x, _), ReplayFrame::Press(KeyPress::Right)] if x == 20.0));
+
+        // the first touch no longer moves the cursor, but still holds its key
+        let frames = touches.send(1, TouchPhase::Move);
+        assert!(frames.is_empty());
+        let frames = touches.send(1, TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::Release(KeyPress::Left)]));
+        assert_eq!(touches.cursor, Some(2));
+
+        // the freed key goes to the next touch
+        let frames = touches.send(3, TouchPhase::Down);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(..), ReplayFrame::Press(KeyPress::Left)]));
+    }
+
+    #[test]
+    fn third_touch_only_moves_the_cursor() {
+        let mut touches = Touches::new();
+        touches.send(1, TouchPhase::Down);
+        touches.send(2, TouchPhase::Down);
+
+        let frames = touches.send(3, TouchPhase::Down);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(..)]));
+
+        let frames = touches.send(3, TouchPhase::Up);
+        assert!(matches!(frames[..], [ReplayFrame::MousePos(..)]));
+        assert_eq!(touches.keys.len(), 2);
+    }
+}
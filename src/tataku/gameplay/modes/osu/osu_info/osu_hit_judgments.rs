@@ -44,6 +44,20 @@ impl HitJudgments for OsuHitJudgments {
             Box::new(Miss),
         ]
     }
+    fn all_variants(&self) -> Vec<Box<dyn HitJudgments>> {
+        vec![
+            Box::new(X300),
+            Box::new(X100),
+            Box::new(X50),
+            Box::new(Miss),
+            Box::new(SliderDot),
+            Box::new(SliderDotMiss),
+            Box::new(SliderEnd),
+            Box::new(SliderEndMiss),
+            Box::new(SpinnerMiss),
+            Box::new(SpinnerPoint),
+        ]
+    }
 
     fn get_health(&self) -> f32 {
         match self {
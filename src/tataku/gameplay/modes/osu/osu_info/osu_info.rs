@@ -19,6 +19,20 @@ impl GameModeInfo for OsuGameInfo {
         / (300.0 * (miss + x50 + x100 + x300 + katu + geki))
     }
 
+    fn calc_perf(&self, info: PerformanceCalcInfo) -> PerformanceInfo {
+        let acc_mult = info.acc_multiplier(0.99);
+        let miss_mult = info.miss_multiplier(0.97);
+
+        // higher od means tighter timing, so acc is worth more
+        let mods = ModManager::default().with_mods(info.score.mods());
+        let od_mult = 1.0 + (OsuGame::get_od(info.map, &mods) - 5.0) / 50.0;
+
+        PerformanceInfo::new()
+            .with_part("Aim", info.attributes.skill("Aim") * 0.8 * acc_mult.sqrt() * miss_mult * info.combo_ratio().sqrt())
+            .with_part("Speed", info.attributes.skill("Speed") * 0.8 * acc_mult * miss_mult)
+            .with_part("Acc", info.diff * 0.2 * acc_mult.powi(2) * od_mult)
+    }
+
     fn get_mods(&self) -> Vec<GameplayModGroup> { 
         vec![
             GameplayModGroup::new("Difficulty")
@@ -29,6 +43,17 @@ impl GameModeInfo for OsuGameInfo {
     }
 
 
+    fn keybinds(&self) -> Vec<KeybindContext> {
+        vec![
+            KeybindContext::new("osu", "Osu", vec![
+                KeybindAction::new("left", "Key 1", vec![InputTarget::Key(Key::S)]),
+                KeybindAction::new("right", "Key 2", vec![InputTarget::Key(Key::D)]),
+                KeybindAction::new("left_mouse", "Mouse 1", vec![InputTarget::Mouse(MouseButton::Left)]),
+                KeybindAction::new("right_mouse", "Mouse 2", vec![InputTarget::Mouse(MouseButton::Right)]),
+            ])
+        ]
+    }
+
     fn get_diff_string(&self, info: &BeatmapMetaWithDiff, mods: &ModManager) -> String {
         let speed = mods.get_speed();
         let symb = if speed > 1.0 {"+"} else if speed < 1.0 {"-"} else {""};
@@ -70,8 +95,8 @@ impl GameModeInfo for OsuGameInfo {
     fn get_judgments(&self) -> Box<dyn crate::prelude::HitJudgments> {
         Box::new(OsuHitJudgments::Miss)
     }
-    async fn create_game(&self, beatmap: &Beatmap) -> TatakuResult<Box<dyn GameMode>> {
-        let game = OsuGame::new(beatmap, false).await?;
+    async fn create_game(&self, beatmap: &Beatmap, headless: bool) -> TatakuResult<Box<dyn GameMode>> {
+        let game = OsuGame::new(beatmap, headless).await?;
         Ok(Box::new(game))
     }
     async fn create_diffcalc(&self, map: &BeatmapMeta) -> TatakuResult<Box<dyn DiffCalc>> {
@@ -12,6 +12,7 @@ impl GameplayMod for Easy {
     
     fn score_multiplier(&self) -> f32 { 0.6 }
     fn removes(&self) -> &'static [&'static str] { &["hardrock"] }
+    fn affects_difficulty(&self) -> bool { true }
 }
 
 
@@ -24,4 +25,5 @@ impl GameplayMod for HardRock {
 
     fn score_multiplier(&self) -> f32 { 1.4 }
     fn removes(&self) -> &'static [&'static str] { &["easy"] }
+    fn affects_difficulty(&self) -> bool { true }
 }
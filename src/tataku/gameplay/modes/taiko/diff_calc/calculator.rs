@@ -172,10 +172,12 @@ impl DiffCalc for TaikoDifficultyCalculator {
         })
     }
 
-    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<f32> {
+    async fn calc(&mut self, mods: &ModManager) -> TatakuResult<DifficultyAttributes> {
         let strain = self.strain(mods)?;
         let note_density = self.note_density(mods)?;
 
+        let mut colour_strain = Vec::new();
+        let mut speed_strain = Vec::new();
         let mut diff = Vec::new();
 
         let mut lines = vec!["strainvalue,densityvalue,combined,diff".to_owned()];
@@ -185,28 +187,15 @@ impl DiffCalc for TaikoDifficultyCalculator {
 
             let combined = strain_value + density_value;
 
+            colour_strain.push(strain_value);
+            speed_strain.push(density_value);
             diff.push(combined);
             if WRITE_DEBUG_FILES {
                 lines.push(format!("{},{},{}", strain_value, density_value, combined));
             }
         }
         
-        // let count = diff.len() as f32;
-
-        let mut difficulty = 0.0;
-        let mut weight = 1.0;
-
-        const PERCENT: f32 = 0.99;
-
-        // Sort by descending
-        diff.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
-
-        for x in diff {
-            difficulty += x * weight;
-            weight *= PERCENT;
-        }
-
-        difficulty /= (1.0 - weight) / (1.0 - PERCENT);
+        let difficulty = weighted_strain(diff);
 
         // TEMP: for writing to csv, nicer graphs
         if WRITE_DEBUG_FILES {
@@ -307,7 +296,10 @@ impl DiffCalc for TaikoDifficultyCalculator {
             }
         }
 
-        Ok(difficulty)
+        Ok(DifficultyAttributes::new(difficulty)
+            .with_skill("Colour", weighted_strain(colour_strain))
+            .with_skill("Speed", weighted_strain(speed_strain))
+        )
     }
 
 }
@@ -57,6 +57,13 @@ pub struct TaikoGame {
     auto_helper: TaikoAutoHelper,
 
     taiko_settings: Arc<TaikoSettings>,
+    keybinds: ContextBindings,
+    /// which controller axes are currently pressed
+    controller_axis_held: HashSet<KeyPress>,
+    /// touch id to the drum zone its holding
+    touch_keys: HashMap<i64, KeyPress>,
+    /// the drum zones are only drawn once touch has been used
+    show_touch_zones: bool,
     metadata: Arc<BeatmapMeta>,
     playfield: Arc<TaikoPlayfield>,
 
@@ -76,6 +83,92 @@ pub struct TaikoGame {
     current_mods: Arc<ModManager>
 }
 impl TaikoGame {
+    /// get the keys an input is bound to
+    fn bound_keys(&self, target: &InputTarget) -> Vec<KeyPress> {
+        self.keybinds
+            .actions_for(target)
+            .filter_map(|action| match action.as_str() {
+                "left_kat" => Some(KeyPress::LeftKat),
+                "left_don" => Some(KeyPress::LeftDon),
+                "right_don" => Some(KeyPress::RightDon),
+                "right_kat" => Some(KeyPress::RightKat),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// buttons for left kat, left don, right don and right kat on controllers we know the layout of
+    fn known_controller_buttons(controller: &str) -> Option<[u8; 4]> {
+        match controller {
+            "Taiko Controller"|"HORI CO.,LTD. Taiko Controller" => Some([6, 10, 11, 7]),
+            "Xbox Controller"|"Microsoft X-Box One S pad"|"Microsoft X-Box One pad"|"Microsoft XBox One X pad" => Some([13, 12, 0, 1]),
+            "Wireless Controller"|"Sony Interactive Entertainment Wireless Controller" => Some([17, 15, 0, 2]),
+            _ => None,
+        }
+    }
+
+    /// bind a controller we know the layout of the first time its used, unless its been bound already
+    async fn setup_known_controller(&mut self, c: &Box<dyn Controller>) {
+        let name = c.get_name();
+        if self.keybinds.has_controller(&name) { return }
+        let Some(buttons) = Self::known_controller_buttons(&name) else { return };
+
+        trace!("Setting up new controller");
+        let inputs = buttons.map(|b| ControllerInputConfig::new(Some(b), None));
+
+        let mut settings = get_settings_mut!();
+        settings.keybinds.set_controller_bindings("taiko", &name, &["left_kat", "left_don", "right_don", "right_kat"], &inputs);
+        self.keybinds = settings.keybinds.get_bindings("taiko");
+        settings.save().await;
+    }
+
+    /// the playfield is split into four zones across: kat, don, don, kat.
+    /// touches past either side of the playfield count as the outer kat
+    fn touch_zone(playfield: &TaikoPlayfield, pos: Vector2) -> KeyPress {
+        let x = (pos.x - playfield.pos.x) / playfield.size.x;
+        if x < 0.25 { KeyPress::LeftKat }
+        else if x < 0.5 { KeyPress::LeftDon }
+        else if x < 0.75 { KeyPress::RightDon }
+        else { KeyPress::RightKat }
+    }
+
+    /// what a touch does to the drum, given the zone its over now.
+    /// sliding a finger into another zone doesnt hit it, only new touches do
+    fn touch_frame(touch_keys: &mut HashMap<i64, KeyPress>, touch: TouchInput, zone: KeyPress) -> Option<ReplayFrame> {
+        match touch.phase {
+            TouchPhase::Down => {
+                touch_keys.insert(touch.id, zone);
+                Some(ReplayFrame::Press(zone))
+            }
+            TouchPhase::Move => None,
+            TouchPhase::Up => touch_keys.remove(&touch.id).map(ReplayFrame::Release),
+        }
+    }
+
+    /// draw the drum zones on the bottom half of the screen, once touch has been used
+    fn draw_touch_zones(&self, list: &mut RenderableCollection) {
+        if !self.show_touch_zones { return }
+
+        let size = Vector2::new(self.playfield.size.x / 4.0, self.playfield.size.y / 2.0);
+        let zones = [
+            (KeyPress::LeftKat, self.taiko_settings.kat_color),
+            (KeyPress::LeftDon, self.taiko_settings.don_color),
+            (KeyPress::RightDon, self.taiko_settings.don_color),
+            (KeyPress::RightKat, self.taiko_settings.kat_color),
+        ];
+
+        for (i, (key, color)) in zones.into_iter().enumerate() {
+            let held = self.touch_keys.values().any(|k| *k == key);
+            list.push(Rectangle::new(
+                color.alpha(if held { 0.4 } else { 0.1 }),
+                NOTE_DEPTH_RANGE.end + 10.0,
+                self.playfield.pos + Vector2::new(size.x * i as f64, size.y),
+                size,
+                Some(Border::new(color.alpha(0.5), 2.0))
+            ));
+        }
+    }
+
     async fn play_sound(&self, manager: &mut IngameManager, note_time:f32,  hit_type: HitType, finisher: bool) {
         let hitsound;
         match (hit_type, finisher) {
@@ -102,9 +195,22 @@ impl TaikoGame {
         let od = Self::get_od(&self.metadata, &self.current_mods);
 
         // windows
-        let w_miss = map_difficulty(od, 135.0, 95.0, 70.0);
-        let w_100 = map_difficulty(od, 120.0, 80.0, 50.0);
-        let w_300 = map_difficulty(od, 50.0, 35.0, 20.0);
+        let od_windows = vec![
+            map_difficulty(od, 50.0, 35.0, 20.0),
+            map_difficulty(od, 120.0, 80.0, 50.0),
+            map_difficulty(od, 135.0, 95.0, 70.0),
+        ];
+
+        // taiko judges with mania's perfect, good and miss windows
+        let (preset, custom_windows) = JudgmentPreset::from_id(self.current_mods.judgment_preset.as_ref(), &self.playmode());
+        let windows = match preset {
+            JudgmentPreset::OsuOd => od_windows,
+            preset => preset.windows(od, &custom_windows, &[1, 3, 5]).unwrap_or_else(|| {
+                warn!("invalid custom judgment windows {custom_windows:?}, using od");
+                od_windows
+            }),
+        };
+        let (w_300, w_100, w_miss) = (windows[0], windows[1], windows[2]);
 
         use TaikoHitJudgments::*;
         self.hit_windows = vec![
@@ -189,6 +295,7 @@ impl TaikoGame {
 impl GameMode for TaikoGame {
     async fn new(beatmap:&Beatmap, diff_calc_only:bool) -> TatakuResult<Self> {
         let mut settings = get_settings!().taiko_settings.clone();
+        let keybinds = get_settings!().keybinds.get_bindings("taiko");
         let metadata = beatmap.get_beatmap_meta();
         // calculate the hit area
         settings.init_settings().await;
@@ -225,6 +332,10 @@ impl GameMode for TaikoGame {
 
                     auto_helper: TaikoAutoHelper::new(),
                     taiko_settings: settings.clone(),
+                    keybinds: keybinds.clone(),
+                    controller_axis_held: HashSet::new(),
+                    touch_keys: HashMap::new(),
+                    show_touch_zones: false,
                     playfield: playfield.clone(),
                     metadata,
                     
@@ -345,6 +456,10 @@ impl GameMode for TaikoGame {
 
                     auto_helper: TaikoAutoHelper::new(),
                     taiko_settings: settings.clone(),
+                    keybinds: keybinds.clone(),
+                    controller_axis_held: HashSet::new(),
+                    touch_keys: HashMap::new(),
+                    show_touch_zones: false,
                     playfield: playfield.clone(),
                     metadata,
 
@@ -408,7 +523,7 @@ impl GameMode for TaikoGame {
 
     async fn handle_replay_frame(&mut self, frame:ReplayFrame, time:f32, manager:&mut IngameManager) {
         if !manager.replaying {
-            manager.replay.frames.push((time, frame.clone()));
+            manager.record_replay_frame(time, frame.clone());
             manager.outgoing_spectator_frame((time, SpectatorFrameData::ReplayFrame{frame}));
         }
         let key = match frame {
@@ -550,11 +665,17 @@ impl GameMode for TaikoGame {
             ];
 
             // get auto inputs
-            self.auto_helper.update(time, &mut queues, &mut pending_frames);
+            let skipped = self.auto_helper.update(time, &mut queues, &manager.autoplay_profile(), &mut pending_frames);
 
             self.notes = queues.remove(0);
             self.other_notes = queues.remove(0);
 
+            for _ in 0..skipped {
+                let j = &TaikoHitJudgments::Miss;
+                manager.add_judgment(j).await;
+                Self::add_hit_indicator(time, j, false, &self.taiko_settings, &self.judgement_helper, manager);
+            }
+
             for frame in pending_frames.iter() {
                 self.handle_replay_frame(*frame, time, manager).await;
             }
@@ -662,6 +783,8 @@ impl GameMode for TaikoGame {
             }
         }
 
+        self.draw_touch_zones(list);
+
         // draw the playfield
         list.push(self.taiko_settings.get_playfield(args.window_size[0], manager.current_timing_point().kiai));
 
@@ -684,6 +807,9 @@ impl GameMode for TaikoGame {
     }
 
     async fn reset(&mut self, beatmap:&Beatmap) {
+        // new helper so humanized autoplay plays the same way again
+        self.auto_helper = TaikoAutoHelper::new();
+
         for queue in [&mut self.notes, &mut self.other_notes] {
             queue.index = 0;
                 
@@ -803,6 +929,7 @@ impl GameMode for TaikoGame {
 
 
     async fn force_update_settings(&mut self, settings: &Settings) {
+        self.keybinds = settings.keybinds.get_bindings("taiko");
         let old_sv_mult = self.taiko_settings.sv_multiplier;
         let sv_static = self.current_mods.has_mod(NoSV.name());
         
@@ -943,6 +1070,8 @@ impl GameMode for TaikoGame {
             }
         }
 
+        // the judgment preset might have changed
+        self.setup_hitwindows().await;
     }
 
     
@@ -971,186 +1100,113 @@ impl GameMode for TaikoGame {
 
 #[async_trait]
 impl GameModeInput for TaikoGame {
-    async fn key_down(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_down(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         // dont accept key input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        let time = manager.time();
-
-        if key == self.taiko_settings.left_kat {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftKat), time, manager).await;
-        }
-        if key == self.taiko_settings.left_don {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftDon), time, manager).await;
-        }
-        if key == self.taiko_settings.right_don {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::RightDon), time, manager).await;
-        }
-        if key == self.taiko_settings.right_kat {
-            self.handle_replay_frame(ReplayFrame::Press(KeyPress::RightKat), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
     }
     
-    async fn key_up(&mut self, key:piston::Key, manager:&mut IngameManager) {
+    async fn key_up(&mut self, key:piston::Key, time:f32, manager:&mut IngameManager) {
         
         // dont accept key input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        let time = manager.time();
-
-        if key == self.taiko_settings.left_kat {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftKat), time, manager).await;
-        }
-        if key == self.taiko_settings.left_don {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftDon), time, manager).await;
-        }
-        if key == self.taiko_settings.right_don {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::RightDon), time, manager).await;
-        }
-        if key == self.taiko_settings.right_kat {
-            self.handle_replay_frame(ReplayFrame::Release(KeyPress::RightKat), time, manager).await;
+        for key in self.bound_keys(&InputTarget::Key(key)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
         }
     }
 
 
-    async fn mouse_down(&mut self, btn:piston::MouseButton, manager:&mut IngameManager) {
+    async fn mouse_down(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
         
         // dont accept mouse input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying || self.taiko_settings.ignore_mouse_buttons {
             return;
         }
         
-        let time = manager.time();
-        match btn {
-            piston::MouseButton::Left => self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftDon), time, manager).await,
-            piston::MouseButton::Right => self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftKat), time, manager).await,
-            _ => {}
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
         }
     }
 
-    async fn mouse_up(&mut self, btn:piston::MouseButton, manager:&mut IngameManager) {
+    async fn mouse_up(&mut self, btn:piston::MouseButton, time:f32, manager:&mut IngameManager) {
         
         // dont accept mouse input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying || self.taiko_settings.ignore_mouse_buttons {
             return;
         }
         
-        let time = manager.time();
-        match btn {
-            piston::MouseButton::Left => self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftDon), time, manager).await,
-            piston::MouseButton::Right => self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftKat), time, manager).await,
-            _ => {}
+        for key in self.bound_keys(&InputTarget::Mouse(btn)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
         }
     }
 
 
-    async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, manager:&mut IngameManager) {
+    async fn controller_press(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
         // dont accept controller input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        if let Some(c_config) = self.taiko_settings.clone().controller_config.get(&*c.get_name()) {
-            let time = manager.time();
-
-            if c_config.left_kat.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftKat), time, manager).await;
-            }
-
-            if c_config.left_don.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Press(KeyPress::LeftDon), time, manager).await;
-            }
-
-            if c_config.right_don.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Press(KeyPress::RightDon), time, manager).await;
-            }
-
-            if c_config.right_kat.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Press(KeyPress::RightKat), time, manager).await;
-            }
-
-            // skip
-            if Some(ControllerButton::Y) == c.map_button(btn) {
-                self.skip_intro(manager);
-            }
+        self.setup_known_controller(c).await;
+        for key in self.bound_keys(&InputTarget::controller(c, btn)) {
+            self.handle_replay_frame(ReplayFrame::Press(key), time, manager).await;
+        }
 
-        } else {
-            trace!("Controller with no setup");
-
-            // TODO: if this is slow, we should store controller configs separately
-            // but i dont think this will be an issue, as its unlikely to happen in the first place,
-            // and if there is lag, the user is likely to retry the man anyways
-            trace!("Setting up new controller");
-            let mut new_settings = self.taiko_settings.as_ref().clone();
-            new_settings.controller_config.insert((*c.get_name()).clone(), TaikoControllerConfig::defaults(c.get_name()));
-
-            // update the global settings
-            {
-                let mut settings = get_settings_mut!();
-                settings.taiko_settings = new_settings.clone();
-                settings.save().await;
-            }
-            
-            self.taiko_settings = Arc::new(new_settings);
-            // rerun the handler now that the thing is setup
-            self.controller_press(c, btn, manager).await;
+        // skip
+        if Some(ControllerButton::Y) == c.map_button(btn) {
+            self.skip_intro(manager);
         }
     }
 
-    async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, manager:&mut IngameManager) {
+    async fn controller_release(&mut self, c: &Box<dyn Controller>, btn: u8, time:f32, manager:&mut IngameManager) {
         // dont accept controller input when autoplay is enabled, or a replay is being watched
         if manager.current_mods.has_autoplay() || manager.replaying {
             return;
         }
 
-        if let Some(c_config) = self.taiko_settings.clone().controller_config.get(&*c.get_name()) {
-            let time = manager.time();
+        for key in self.bound_keys(&InputTarget::controller(c, btn)) {
+            self.handle_replay_frame(ReplayFrame::Release(key), time, manager).await;
+        }
 
-            if c_config.left_kat.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftKat), time, manager).await;
-            }
+        // skip
+        if Some(ControllerButton::Y) == c.map_button(btn) {
+            self.skip_intro(manager);
+        }
+    }
 
-            if c_config.left_don.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Release(KeyPress::LeftDon), time, manager).await;
-            }
+    async fn controller_axis(&mut self, c: &Box<dyn Controller>, axis_data:HashMap<u8, (bool, f64)>, time:f32, manager:&mut IngameManager) {
+        // dont accept controller input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
 
-            if c_config.right_don.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Release(KeyPress::RightDon), time, manager).await;
-            }
+        // triggers and sticks can be bound as drum inputs
+        let inputs = self.keybinds.axis_inputs(c, |target| self.bound_keys(target));
+        for (key, pressed) in get_axis_presses(&inputs, &axis_data, &mut self.controller_axis_held) {
+            let frame = if pressed { ReplayFrame::Press(key) } else { ReplayFrame::Release(key) };
+            self.handle_replay_frame(frame, time, manager).await;
+        }
+    }
 
-            if c_config.right_kat.check_button(btn) {
-                self.handle_replay_frame(ReplayFrame::Release(KeyPress::RightKat), time, manager).await;
-            }
+    async fn touch(&mut self, touch:TouchInput, time:f32, manager:&mut IngameManager) {
+        // dont accept touch input when autoplay is enabled, or a replay is being watched
+        if manager.current_mods.has_autoplay() || manager.replaying {
+            return;
+        }
 
-            // skip
-            if Some(ControllerButton::Y) == c.map_button(btn) {
-                self.skip_intro(manager);
-            }
+        self.show_touch_zones = true;
 
-        } else {
-            trace!("Controller with no setup");
-
-            // TODO: if this is slow, we should store controller configs separately
-            // but i dont think this will be an issue, as its unlikely to happen in the first place,
-            // and if there is lag, the user is likely to retry the man anyways
-            trace!("Setting up new controller");
-            let mut new_settings = self.taiko_settings.as_ref().clone();
-            new_settings.controller_config.insert((*c.get_name()).clone(), TaikoControllerConfig::defaults(c.get_name()));
-
-            // update the global settings
-            {
-                let mut settings = get_settings_mut!();
-                settings.taiko_settings = new_settings.clone();
-                settings.save().await;
-            }
-            
-            self.taiko_settings = Arc::new(new_settings);
-            // rerun the handler now that the thing is setup
-            self.controller_release(c, btn, manager).await;
+        let zone = Self::touch_zone(&self.playfield, touch.pos);
+        if let Some(frame) = Self::touch_frame(&mut self.touch_keys, touch, zone) {
+            self.handle_replay_frame(frame, time, manager).await;
         }
     }
 
@@ -1282,6 +1338,9 @@ struct TaikoAutoHelper {
 
     last_hit: f32,
     last_update: f32,
+
+    /// hit offset for each note in each queue, None if the note should be missed
+    offsets: Vec<Vec<Option<f32>>>,
 }
 impl TaikoAutoHelper {
     fn new() -> Self {
@@ -1289,21 +1348,48 @@ impl TaikoAutoHelper {
             don_presses: 0, 
             kat_presses: 0, 
             last_hit: 0.0, 
-            last_update: 0.0
+            last_update: 0.0,
+            offsets: Vec::new(),
         }
     }
 
-    fn update(&mut self, time: f32, queue: &mut Vec<TaikoNoteQueue>, frames: &mut Vec<ReplayFrame>) {
+    /// returns how many planned misses were skipped, which still need their miss judgment
+    fn update(&mut self, time: f32, queue: &mut Vec<TaikoNoteQueue>, profile: &AutoplayProfile, frames: &mut Vec<ReplayFrame>) -> usize {
         let catching_up = time - self.last_update > 20.0;
         self.last_update = time;
 
         // if catching_up { trace!("catching up") }
 
-        for queue in queue.iter_mut() {
+        if self.offsets.len() != queue.len() {
+            let mut humanizer = HumanizedAutoplay::new(*profile);
+            self.offsets = queue.iter().map(|q| humanizer.plan(q.notes.len())).collect();
+        }
+
+        let mut skipped = 0;
+        for (queue_num, queue) in queue.iter_mut().enumerate() {
+            // drumrolls and spinners dont get an offset
+            let offsets = &self.offsets[queue_num];
+            let should_hit = |i: usize, note: &Box<dyn TaikoHitObject>| {
+                let offset = if note.note_type() == NoteType::Note { offsets.get(i).copied().flatten() } else { Some(0.0) };
+                offset.map(|offset| time > note.time() + offset).unwrap_or_default()
+            };
+
+            // a planned miss stays the current note until its window is over, so any press for the next note would be judged against it.
+            // skip it once the next note is due instead
+            while let Some(note) = queue.notes.get(queue.index) {
+                let planned_miss = note.note_type() == NoteType::Note && !note.was_hit() && offsets.get(queue.index).copied().flatten().is_none();
+                let next_due = queue.notes.get(queue.index + 1).map(|next| should_hit(queue.index + 1, next)).unwrap_or_default();
+                if !(planned_miss && next_due) { break }
+
+                queue.notes[queue.index].miss(time);
+                queue.next();
+                skipped += 1;
+            }
+
             let mut queue_index = queue.index;
             let mut note_hit = false;
 
-            for (i, note) in queue.iter_mut().enumerate().skip(queue_index).filter(|(_, note)|time > note.time() && !note.was_hit()) {
+            for (i, note) in queue.iter_mut().enumerate().skip(queue_index).filter(|(i, note)|should_hit(*i, note) && !note.was_hit()) {
                 // note is the note we need to hit
 
                 // if note is a drumroll/spinner, we need to time when to hit it
@@ -1390,9 +1476,10 @@ impl TaikoAutoHelper {
             }
 
             queue.index = queue_index;
-            if note_hit { return }
+            if note_hit { return skipped }
         }
 
+        skipped
     }
 }
 
@@ -1512,4 +1599,76 @@ impl DerefMut for TaikoNoteQueue {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.notes
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playfield() -> TaikoPlayfield {
+        TaikoPlayfield {
+            pos: Vector2::new(100.0, 0.0),
+            size: Vector2::new(400.0, 200.0),
+        }
+    }
+
+    fn touch(id: i64, x: f64, phase: TouchPhase) -> (TouchInput, KeyPress) {
+        let touch = TouchInput::new(id, Vector2::new(x, 150.0), phase);
+        (touch, TaikoGame::touch_zone(&playfield(), touch.pos))
+    }
+
+    #[test]
+    fn touch_zones_follow_the_playfield() {
+        let playfield = playfield();
+        let zone = |x| TaikoGame::touch_zone(&playfield, Vector2::new(x, 150.0));
+
+        assert_eq!(zone(110.0), KeyPress::LeftKat);
+        assert_eq!(zone(250.0), KeyPress::LeftDon);
+        assert_eq!(zone(350.0), KeyPress::RightDon);
+        assert_eq!(zone(490.0), KeyPress::RightKat);
+
+        // past the sides of the playfield
+        assert_eq!(zone(0.0), KeyPress::LeftKat);
+        assert_eq!(zone(1000.0), KeyPress::RightKat);
+    }
+
+    #[test]
+    fn sliding_into_another_zone_doesnt_hit() {
+        let mut keys = HashMap::new();
+
+        let (t, zone) = touch(1, 250.0, TouchPhase::Down);
+        assert!(matches!(TaikoGame::touch_frame(&mut keys, t, zone), Some(ReplayFrame::Press(KeyPress::LeftDon))));
+
+        let (t, zone) = touch(1, 450.0, TouchPhase::Move);
+        assert!(TaikoGame::touch_frame(&mut keys, t, zone).is_none());
+
+        // the release is for the zone it went down in
+        let (t, zone) = touch(1, 450.0, TouchPhase::Up);
+        assert!(matches!(TaikoGame::touch_frame(&mut keys, t, zone), Some(ReplayFrame::Release(KeyPress::LeftDon))));
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn touches_release_separately() {
+        let mut keys = HashMap::new();
+        for (id, x) in [(1, 110.0), (2, 250.0), (3, 350.0)] {
+            let (t, zone) = touch(id, x, TouchPhase::Down);
+            TaikoGame::touch_frame(&mut keys, t, zone);
+        }
+
+        let (t, zone) = touch(2, 250.0, TouchPhase::Up);
+        assert!(matches!(TaikoGame::touch_frame(&mut keys, t, zone), Some(ReplayFrame::Release(KeyPress::LeftDon))));
+        let (t, zone) = touch(1, 110.0, TouchPhase::Up);
+        assert!(matches!(TaikoGame::touch_frame(&mut keys, t, zone), Some(ReplayFrame::Release(KeyPress::LeftKat))));
+
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys.get(&3), Some(&KeyPress::RightDon));
+    }
+
+    #[test]
+    fn unknown_touch_release_does_nothing() {
+        // ie a touch that went down before gameplay started
+        let mut keys = HashMap::new();
+        let (t, zone) = touch(5, 250.0, TouchPhase::Up);
+        assert!(TaikoGame::touch_frame(&mut keys, t, zone).is_none());
+    }
+}
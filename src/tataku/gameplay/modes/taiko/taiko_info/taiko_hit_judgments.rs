@@ -24,6 +24,17 @@ impl HitJudgments for TaikoHitJudgments {
             Box::new(Miss),
         ]
     }
+    fn all_variants(&self) -> Vec<Box<dyn HitJudgments>> {
+        vec![
+            Box::new(Geki),
+            Box::new(X300),
+            Box::new(Katu),
+            Box::new(X100),
+            Box::new(Miss),
+            Box::new(SliderPoint),
+            Box::new(SpinnerPoint),
+        ]
+    }
 
     fn get_health(&self) -> f32 {
         match self {
@@ -16,6 +16,16 @@ impl GameModeInfo for TaikoGameInfo {
         / (miss + x100 + x300)
     }
 
+    fn calc_perf(&self, info: PerformanceCalcInfo) -> PerformanceInfo {
+        let acc_mult = info.acc_multiplier(0.99);
+        let miss_mult = info.miss_multiplier(0.98);
+
+        PerformanceInfo::new()
+            .with_part("Colour", info.attributes.skill("Colour") * 0.8 * acc_mult * miss_mult)
+            .with_part("Speed", info.attributes.skill("Speed") * 0.8 * acc_mult * miss_mult)
+            .with_part("Acc", info.diff * 0.2 * acc_mult.powi(2))
+    }
+
     fn get_mods(&self) -> Vec<GameplayModGroup> { 
         vec![
             GameplayModGroup::new("Skill")
@@ -37,6 +47,39 @@ impl GameModeInfo for TaikoGameInfo {
         ]
     }
 
+    fn keybinds(&self) -> Vec<KeybindContext> {
+        const ACTIONS:[(&str, &str, Option<MouseButton>); 4] = [
+            ("left_kat", "Left Kat", Some(MouseButton::Right)),
+            ("left_don", "Left Don", Some(MouseButton::Left)),
+            ("right_don", "Right Don", None),
+            ("right_kat", "Right Kat", None),
+        ];
+
+        // keys are in the same order as the actions
+        let targets = |key: Key, mouse: Option<MouseButton>| {
+            let mut targets = vec![InputTarget::Key(key)];
+            targets.extend(mouse.map(InputTarget::Mouse));
+            targets
+        };
+        let layout = |keys: [Key; 4]| ACTIONS
+            .iter()
+            .zip(keys)
+            .map(|((action, _, mouse), key)| (action.to_string(), targets(key, *mouse)))
+            .collect::<KeybindProfile>();
+
+        let actions = ACTIONS
+            .iter()
+            .zip([Key::D, Key::F, Key::J, Key::K])
+            .map(|((action, display_name, mouse), key)| KeybindAction::new(action, display_name, targets(key, *mouse)))
+            .collect();
+
+        vec![
+            KeybindContext::new("taiko", "Taiko", actions)
+                .with_preset("kddk", layout([Key::D, Key::F, Key::J, Key::K]))
+                .with_preset("ddkk", layout([Key::J, Key::D, Key::F, Key::K]))
+        ]
+    }
+
     fn get_diff_string(&self, info: &BeatmapMetaWithDiff, mods: &ModManager) -> String {
         let speed = mods.get_speed();
         let symb = if speed > 1.0 {"+"} else if speed < 1.0 {"-"} else {""};
@@ -76,8 +119,12 @@ impl GameModeInfo for TaikoGameInfo {
     fn get_judgments(&self) -> Box<dyn crate::prelude::HitJudgments> {
         Box::new(super::TaikoHitJudgments::Miss)
     }
-    async fn create_game(&self, beatmap: &Beatmap) -> TatakuResult<Box<dyn GameMode>> {
-        let game = TaikoGame::new(beatmap, false).await?;
+    fn get_health_helper(&self, map: &BeatmapMeta, mods: &ModManager) -> HealthHelper {
+        // taiko doesnt drain health between notes
+        HealthHelper::new(HealthPreset::from_beatmap_type(map.beatmap_type), map.get_hp(mods)).without_drain()
+    }
+    async fn create_game(&self, beatmap: &Beatmap, headless: bool) -> TatakuResult<Box<dyn GameMode>> {
+        let game = TaikoGame::new(beatmap, headless).await?;
         Ok(Box::new(game))
     }
     async fn create_diffcalc(&self, map: &BeatmapMeta) -> TatakuResult<Box<dyn DiffCalc>> {
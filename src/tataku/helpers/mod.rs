@@ -2,10 +2,12 @@ use crate::prelude::*;
 
 mod crypto;
 mod score_helper;
+mod rating_helper;
 mod score_submit_helper;
 
 pub use crypto::*;
 pub use score_helper::*;
+pub use rating_helper::*;
 pub use score_submit_helper::*;
 
 
@@ -0,0 +1,145 @@
+/*
+ * this is a helper to keep track of the best performance on each map, and the local rating calculated from them
+ */
+
+use crate::prelude::*;
+
+/// how many top plays count towards the rating
+const MAX_RATED_PLAYS:usize = 100;
+
+lazy_static::lazy_static! {
+    /// playmode -> local rating, updated whenever the best performances change
+    static ref LOCAL_RATINGS: ShardedLock<HashMap<PlayMode, f32>> = ShardedLock::new(HashMap::new());
+}
+
+pub struct RatingHelper;
+impl RatingHelper {
+    /// get the cached local rating for a playmode
+    pub fn get_rating(playmode: &PlayMode) -> Option<f32> {
+        LOCAL_RATINGS.read().unwrap().get(playmode).cloned()
+    }
+
+    /// calculate a rating from a list of performances, using the playmode's weighting
+    pub fn calc_rating(playmode: &PlayMode, mut perfs: Vec<f32>) -> f32 {
+        let Some(info) = get_gamemode_info(playmode) else { return 0.0 };
+        perfs.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+        perfs
+            .into_iter()
+            .take(MAX_RATED_PLAYS)
+            .enumerate()
+            .map(|(i, perf)| perf * info.rating_weight(i))
+            .sum()
+    }
+
+    fn set_rating(playmode: &PlayMode, perfs: Vec<f32>) {
+        let rating = Self::calc_rating(playmode, perfs);
+        LOCAL_RATINGS.write().unwrap().insert(playmode.clone(), rating);
+    }
+
+    /// check if a new score is a best performance, and update the rating if it is
+    pub async fn score_set(score: &Score, map: &BeatmapMeta) {
+        let perf = match calc_perf(score, map) {
+            Some(perf) => perf,
+            // the diff for these mods hasnt been calculated yet, so calculate it now
+            None => {
+                let mods = ModManager::default()
+                    .with_speed_f32(score.speed)
+                    .with_mods(score.mods());
+                let Some(diff) = calc_map_diff(map, &score.playmode, &mods).await else { return };
+                calc_perf_with_diff(score, map, &diff)
+            }
+        };
+        let version = diff_calc_version(&score.playmode);
+
+        let mut bests = Database::get_best_performances(&score.playmode).await;
+        match bests.iter_mut().find(|b| b.map_hash == score.beatmap_hash) {
+            Some(best) if best.performance >= perf.total && best.diff_calc_version == version => return,
+            Some(best) => {
+                best.score_hash = score.hash();
+                best.performance = perf.total;
+                best.diff_calc_version = version;
+                Database::save_best_performance(best);
+            }
+            None => {
+                let best = BestPerformance {
+                    map_hash: score.beatmap_hash.clone(),
+                    playmode: score.playmode.clone(),
+                    score_hash: score.hash(),
+                    performance: perf.total,
+                    diff_calc_version: version,
+                };
+                Database::save_best_performance(&best);
+                bests.push(best);
+            }
+        }
+
+        Self::set_rating(&score.playmode, bests.into_iter().map(|b| b.performance).collect());
+    }
+
+    /// make sure the best performances for every playmode are up to date, and load the ratings
+    pub async fn check_all_playmodes() {
+        for playmode in AVAILABLE_PLAYMODES {
+            let playmode = playmode.to_string();
+            let version = diff_calc_version(&playmode);
+
+            let bests = Database::get_best_performances(&playmode).await;
+            let outdated = bests.iter().any(|b| b.diff_calc_version != version);
+
+            // if the bests were never fully calculated (ie scores set before they were tracked), or were calculated with an old diff calc
+            let needs_recalc = Database::get_best_performance_version(&playmode).await != Some(version);
+
+            if outdated || needs_recalc {
+                Self::recalc(&playmode).await;
+            } else {
+                Self::set_rating(&playmode, bests.into_iter().map(|b| b.performance).collect());
+            }
+        }
+    }
+
+    /// recalculate the best performance for every map from all the local scores
+    pub async fn recalc(playmode: &PlayMode) {
+        info!("recalculating best performances for {playmode}");
+        let scores = Database::get_scores_for_playmode(playmode).await;
+
+        // make sure diffs exist for all the mods the scores were set with
+        let mut mod_sets = HashSet::new();
+        for score in scores.iter() {
+            let mods = ModManager::default().with_mods(score.mods()).difficulty_mods(playmode);
+            let mut list = mods.mods.into_iter().collect::<Vec<_>>();
+            list.sort();
+            mod_sets.insert(list);
+        }
+        for mods in mod_sets {
+            let mods = ModManager::default().with_mods(mods.into_iter().collect());
+            do_diffcalc(playmode.clone(), &mods).await;
+        }
+
+        // map_hash -> best
+        let version = diff_calc_version(playmode);
+        let mut bests:HashMap<String, BestPerformance> = HashMap::new();
+        for score in scores.iter() {
+            let Some(map) = BEATMAP_MANAGER.read().await.get_by_hash(&score.beatmap_hash) else { continue };
+            let Some(perf) = calc_perf(score, &map) else { continue };
+
+            if bests.get(&score.beatmap_hash).map(|b| b.performance >= perf.total).unwrap_or_default() { continue }
+            bests.insert(score.beatmap_hash.clone(), BestPerformance {
+                map_hash: score.beatmap_hash.clone(),
+                playmode: playmode.clone(),
+                score_hash: score.hash(),
+                performance: perf.total,
+                diff_calc_version: version,
+            });
+        }
+
+        Database::clear_best_performances(playmode).await;
+        for best in bests.values() {
+            Database::save_best_performance(best);
+        }
+
+        Database::set_best_performance_version(playmode, version).await;
+
+        Self::set_rating(playmode, bests.into_values().map(|b| b.performance).collect());
+        info!("best performances for {playmode} done");
+    }
+}
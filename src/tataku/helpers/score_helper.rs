@@ -39,7 +39,7 @@ impl ScoreHelper {
                     }
                     
                     let mut thing = scores_clone.write().await;
-                    thing.scores = local_scores.into_iter().map(|s|IngameScore::new(s, false, false)).collect();
+                    thing.scores = local_scores;
                     thing.done = true;
                 });
                 
@@ -371,34 +371,30 @@ mod quaver {
             let mut judgments = HashMap::new();
             judgments.insert("x50".to_owned(),   s.count_okay as u16);
             judgments.insert("x100".to_owned(),  s.count_good as u16);
-            judgments.insert("x300".to_owned(),  s.count_marv as u16);
-            judgments.insert("xgeki".to_owned(), s.count_perf as u16);
+            judgments.insert("x300".to_owned(),  s.count_perf as u16);
+            judgments.insert("xgeki".to_owned(), s.count_marv as u16);
             judgments.insert("xkatu".to_owned(), s.count_great as u16);
             judgments.insert("xmiss".to_owned(), s.count_miss as u16);
 
 
             let mut score = Score::default();
             score.username = s.user.username.clone();
+            score.beatmap_hash = map_hash.clone();
+            // quaver is only mania
+            score.playmode = "mania".to_owned();
             score.score = s.total_score;
             score.combo = s.max_combo as u16;
             score.max_combo = s.max_combo as u16;
             score.judgments = judgments;
-            score.speed = 1.0;
             score.accuracy = s.accuracy as f64 / 100.0;
-            // check mods
-            for m in s.mods_string.split(", ") {
-                if m.ends_with("x") {
-                    if let Ok(speed) = m.trim_end_matches("x").parse() {
-                        score.speed = speed;
-                        continue;
-                    }
-                }
 
-                score.mods_mut().insert(m.to_lowercase());
-            }
+            // check mods
+            let mods = QuaverMods::to_mod_manager(s.mods);
+            score.speed = mods.get_speed();
+            *score.mods_mut() = mods.mods;
             
 
-            let mut score = IngameScore::new(score, false, false);
+            let mut score = IngameScore::new(score, false, false).with_judgment_preset(mods.judgment_preset);
             score.replay_location = ReplayLocation::Online(Arc::new(QuaverReplayDownloader::new(score.score.clone(), s.id)));
 
             score
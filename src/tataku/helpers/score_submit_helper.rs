@@ -1,83 +1,233 @@
 use crate::prelude::*;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use tokio::sync::{ Notify, broadcast };
+
+lazy_static::lazy_static! {
+    /// wakes the queue up when theres something new to submit
+    static ref QUEUE_NOTIFY: Notify = Notify::new();
+    /// the queue is waiting for a username and password to be set
+    static ref QUEUE_PAUSED: AtomicBool = AtomicBool::new(false);
+    /// every submit attempt, as (score_hash, attempt)
+    static ref SUBMIT_RESULTS: broadcast::Sender<(String, SubmitAttempt)> = broadcast::channel(100).0;
+}
+
+#[derive(Clone, Debug)]
+pub enum SubmitAttempt {
+    /// the score was either accepted or rejected
+    Response(SubmitResponse),
+    /// couldnt reach the score server, it'll be tried again later
+    Failed(String),
+    /// the score couldnt be sent from here (ie the map isnt installed), so the server never saw it
+    LocalError(String),
+}
+impl SubmitAttempt {
+    /// what to store for the score after this attempt. None if it should stay in the queue
+    pub fn status(&self) -> Option<SubmissionStatus> {
+        match self {
+            Self::Response(response) => Some(response.into()),
+            Self::LocalError(reason) => Some(SubmissionStatus::Failed(reason.clone())),
+            Self::Failed(_) => None,
+        }
+    }
+}
+
+/// what gets posted to the score server.
+/// tataku-common's [`ScoreSubmit`] has no room for the replay's extras, so they're sent next to it
+#[derive(Serialize, Deserialize)]
+pub struct ScoreSubmitPayload {
+    #[serde(flatten)]
+    pub submit: ScoreSubmit,
+    #[serde(flatten)]
+    pub extras: ReplayExtras,
+}
 
 pub struct ScoreSubmitHelper {
     pub replay: Replay,
-    settings: Settings,
+    pub extras: ReplayExtras,
     pub response: RwLock<Option<SubmitResponse>>,
+    /// the score couldnt be submitted right away, and is waiting in the queue
+    pub queued: AtomicBool,
 }
 
 impl ScoreSubmitHelper {
-    pub fn new(replay: Replay, settings: &Settings) -> Arc<Self> {
-        Arc::new(Self { replay, settings: settings.clone(), response: RwLock::new(None) })
-    } 
+    pub fn new(replay: Replay, extras: ReplayExtras) -> Arc<Self> {
+        Arc::new(Self { replay, extras, response: RwLock::new(None), queued: AtomicBool::new(false) })
+    }
 
+    /// add the score to the submit queue, and wait for it to be submitted
     pub fn submit(self: Arc<Self>) {
         tokio::spawn(async move {
-            trace!("submitting score");
-            let replay = &self.replay;
-            let score = replay.score_data.as_ref().unwrap();
-
-            let username = self.settings.username.clone();
-            let password = self.settings.password.clone();
-            if username.is_empty() || password.is_empty() { 
-                warn!("no user or pass, not submitting score");
-                *self.response.write().await = Some(SubmitResponse::NotSubmitted(NotSubmittedReason::NoUser, "No user/password in settings".to_owned()));
-                return
+            let Some(score_hash) = self.replay.score_data.as_ref().map(|s| s.hash()) else { return };
+
+            // subscribe before queueing so the result cant be missed
+            let mut results = SUBMIT_RESULTS.subscribe();
+            let verified = Self::verify(&self.replay, &self.extras).await;
+            Database::queue_score_submission(&self.replay, &self.extras, verified).await;
+            QUEUE_NOTIFY.notify_one();
+
+            if !ONLINE_MANAGER.read().await.connection.is_connected() || Self::paused() {
+                self.queued.store(true, Ordering::Release);
+            }
+
+            loop {
+                match results.recv().await {
+                    Ok((hash, attempt)) if hash == score_hash => match attempt {
+                        SubmitAttempt::Response(response) => {
+                            *self.response.write().await = Some(response);
+                            return;
+                        }
+                        // only shown in the score menu, its stored as a local failure
+                        SubmitAttempt::LocalError(reason) => {
+                            *self.response.write().await = Some(SubmitResponse::NotSubmitted(NotSubmittedReason::InternalError, reason));
+                            return;
+                        }
+                        SubmitAttempt::Failed(_) => self.queued.store(true, Ordering::Release),
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+
+                // nothing is waiting on this anymore
+                if Arc::strong_count(&self) == 1 { return }
             }
+        });
+    }
 
+    /// put every score which didnt get submitted back in the queue
+    pub async fn resubmit_all() {
+        Database::requeue_unsubmitted_submissions().await;
+        QUEUE_NOTIFY.notify_one();
+    }
 
-            let map = match BEATMAP_MANAGER.read().await.beatmaps_by_hash.get(&score.beatmap_hash) {
-                None => { // what
-                    *self.response.write().await = Some(SubmitResponse::NotSubmitted(NotSubmittedReason::MapNotFound, "Map not found locally".to_owned()));
-                    return 
+    /// check the replay actually produces the score we're about to submit, or None if it couldnt be checked.
+    /// this is done once when the score is queued, since simulating the whole map is too slow to redo on every retry.
+    /// the server has the final say, so a mismatch is only logged
+    async fn verify(replay: &Replay, extras: &ReplayExtras) -> Option<bool> {
+        let score = replay.score_data.as_ref()?;
+        let map = BEATMAP_MANAGER.read().await.beatmaps_by_hash.get(&score.beatmap_hash).cloned()?;
+
+        match ReplaySimulator::verify(replay, extras, &map).await {
+            Ok(verification) => {
+                if !verification.matches {
+                    warn!("replay did not reproduce the score, submitting anyways: {:?}", verification.mismatched_judgments);
                 }
-                Some(map) => map.clone()
-            };
-            let game = match &map.beatmap_type {
-                BeatmapType::Osu => MapGame::Osu,
-                BeatmapType::Quaver => MapGame::Quaver,
-                other => MapGame::Other(format!("{other:?}").to_lowercase())
-            };
-            let map_info = ScoreMapInfo {
-                game,
-                map_hash: score.beatmap_hash.clone(),
-                playmode: score.playmode.clone(),
+                Some(verification.matches)
+            }
+            Err(e) => {
+                warn!("error verifying replay, submitting anyways: {e}");
+                None
+            }
+        }
+    }
+
+    /// is the queue waiting for a username and password to be set
+    pub fn paused() -> bool {
+        QUEUE_PAUSED.load(Ordering::Acquire)
+    }
+
+    /// the username and password to submit with, if they've been set
+    fn credentials() -> Option<(String, String)> {
+        let settings = get_settings!();
+        if settings.username.is_empty() || settings.password.is_empty() { return None }
+        Some((settings.username.clone(), settings.password.clone()))
+    }
+
+    /// submits everything in the queue, retrying with backoff when the score server cant be reached.
+    /// this runs forever
+    pub async fn run_queue() {
+        let mut attempt = 0;
+
+        loop {
+            // cant submit anything without an account, so wait until theres one
+            let credentials = Self::credentials();
+            QUEUE_PAUSED.store(credentials.is_none(), Ordering::Release);
+
+            // no point trying if we're offline either
+            let connected = ONLINE_MANAGER.read().await.connection.is_connected();
+            let Some(credentials) = credentials.filter(|_| connected) else {
+                let _ = tokio::time::timeout(Duration::from_secs(1), QUEUE_NOTIFY.notified()).await;
+                continue;
             };
-            let score_submit = ScoreSubmit {
+
+            let pending = Database::get_pending_submissions().await;
+            if pending.is_empty() {
+                QUEUE_NOTIFY.notified().await;
+                continue;
+            }
+
+            for submission in pending {
+                let result = Self::try_submit(&submission.replay, &submission.extras, credentials.clone()).await;
+                let _ = SUBMIT_RESULTS.send((submission.score_hash.clone(), result.clone()));
+
+                if let Some(status) = result.status() {
+                    attempt = 0;
+                    Database::set_submission_status(&submission.score_hash, &status).await;
+                } else if let SubmitAttempt::Failed(e) = result {
+                    attempt += 1;
+                    let delay = backoff_delay(attempt);
+                    warn!("error submitting score, trying again in {:.1}s: {e}", delay.as_secs_f32());
+                    tokio::time::sleep(delay).await;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// try to submit a score to the score server in the settings
+    pub async fn try_submit(replay: &Replay, extras: &ReplayExtras, (username, password): (String, String)) -> SubmitAttempt {
+        trace!("submitting score");
+        let Some(score) = replay.score_data.as_ref() else {
+            return SubmitAttempt::LocalError("Replay has no score".to_owned())
+        };
+
+        let map = match BEATMAP_MANAGER.read().await.beatmaps_by_hash.get(&score.beatmap_hash) {
+            None => { // what
+                return SubmitAttempt::LocalError("Map not found locally".to_owned())
+            }
+            Some(map) => map.clone()
+        };
+
+        let game = match &map.beatmap_type {
+            BeatmapType::Osu => MapGame::Osu,
+            BeatmapType::Quaver => MapGame::Quaver,
+            other => MapGame::Other(format!("{other:?}").to_lowercase())
+        };
+        let map_info = ScoreMapInfo {
+            game,
+            map_hash: score.beatmap_hash.clone(),
+            playmode: score.playmode.clone(),
+        };
+        let score_submit = ScoreSubmitPayload {
+            submit: ScoreSubmit {
                 username,
                 password,
                 game: "tataku".to_owned(),
                 replay: replay.clone(),
                 map_info
-            };
+            },
+            extras: extras.clone(),
+        };
 
-            if let Ok(replay_data) = serde_json::to_string(&score_submit) {
-                let url = format!("{}/score_submit", self.settings.score_url);
-                
-                let c = reqwest::Client::new();
-                let res = c
-                    .post(url)
-                    .header("Content-Type", "application/json")
-                    .body(replay_data)
-                    .send()
-                    .await;
-
-                match res {
-                    Ok(resp) => {
-                        if let Some(resp) = resp.text().await.log_error().ok().and_then(|t|serde_json::from_str::<SubmitResponse>(&t).log_error().ok()) {
-                            // trace!("score submitted successfully");
-                            *self.response.write().await = Some(resp);
-                        } else {
-                            *self.response.write().await = Some(SubmitResponse::NotSubmitted(NotSubmittedReason::InternalError, "Error reading server response".to_owned()));
-                        }
-                    },
-                    Err(e) => NotificationManager::add_error_notification("error submitting score", format!("{e}")).await,
-                };
-            } else {
-                *self.response.write().await = Some(SubmitResponse::NotSubmitted(NotSubmittedReason::InternalError, "Error serializing replay".to_owned()));
-            }
-            
-        });
-    }
+        let Ok(replay_data) = serde_json::to_string(&score_submit) else {
+            return SubmitAttempt::LocalError("Error serializing replay".to_owned())
+        };
+
+        let url = format!("{}/score_submit", get_settings!().score_url);
+        let c = reqwest::Client::new();
+        let res = c
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(replay_data)
+            .send()
+            .await;
 
+        match res {
+            Ok(resp) => match resp.text().await.log_error().ok().and_then(|t|serde_json::from_str::<SubmitResponse>(&t).log_error().ok()) {
+                Some(resp) => SubmitAttempt::Response(resp),
+                // probably a server error page, so try again later
+                None => SubmitAttempt::Failed("Error reading server response".to_owned()),
+            },
+            Err(e) => SubmitAttempt::Failed(e.to_string()),
+        }
+    }
 }
@@ -1,9 +1,17 @@
 use crate::prelude::*;
 
 const DIFF_FILE:&str = "diffs.db";
+/// written at the start of the diff file, so versioned files can be told apart from the old unversioned format
+const DIFF_FILE_MAGIC:&[u8] = b"TKDIFF";
+/// bump this whenever the format of the diff file changes
+///
+/// 1: entries include difficulty mods
+/// 2: diff calc version for each playmode is stored before the entries
+/// 3: entries store the diff of each skill along with the overall diff
+const DIFF_FILE_VERSION:u16 = 3;
 
 lazy_static::lazy_static! {
-    pub static ref BEATMAP_DIFFICULTIES: Arc<ShardedLock<HashMap<DifficultyEntry, f32>>> = Arc::new(ShardedLock::new(HashMap::new()));
+    pub static ref BEATMAP_DIFFICULTIES: Arc<ShardedLock<HashMap<DifficultyEntry, DifficultyAttributes>>> = Arc::new(ShardedLock::new(HashMap::new()));
 }
 
 
@@ -19,7 +27,7 @@ pub async fn init_diffs() {
 
     #[cfg(feature="debug_perf_rating")]
     for (k, v) in &all_diffs {
-        info!("{k:?} -> {v}")
+        info!("{k:?} -> {v:?}")
     }
 
     *BEATMAP_DIFFICULTIES.write().unwrap() = all_diffs;
@@ -28,15 +36,20 @@ pub async fn init_diffs() {
 
 
 pub fn get_diff(map: &BeatmapMeta, playmode: &String, mods: &ModManager) -> Option<f32> {
-    let mut mods = mods.clone();
-    mods.mods.clear();
+    get_diff_attributes(map, playmode, mods).map(|d| d.diff)
+}
+
+/// get the overall diff along with the diff of each skill
+pub fn get_diff_attributes(map: &BeatmapMeta, playmode: &String, mods: &ModManager) -> Option<DifficultyAttributes> {
+    let mods = mods.difficulty_mods(playmode);
 
     let diff_key = DifficultyEntry::new(map.beatmap_hash.clone(), playmode.clone(), mods);
     BEATMAP_DIFFICULTIES.read().unwrap().get(&diff_key).cloned()
 }
 
 
-pub async fn do_diffcalc(playmode: PlayMode) {
+/// calculate any missing diffs for nomod, and for the difficulty-affecting mods in `mods`
+pub async fn do_diffcalc(playmode: PlayMode, mods: &ModManager) {
     debug!("diffcalc initiated for mode {playmode}");
 
     let maps = BEATMAP_MANAGER
@@ -49,7 +62,12 @@ pub async fn do_diffcalc(playmode: PlayMode) {
         .filter(|m| m.check_mode_override(playmode.clone()) == playmode);
 
 
-    let mod_mutations = vec![ModManager::default()];
+    let mut mod_mutations = vec![ModManager::default()];
+    let requested_mods = mods.difficulty_mods(&playmode);
+    if !requested_mods.mods.is_empty() {
+        mod_mutations.push(requested_mods);
+    }
+
     let existing = BEATMAP_DIFFICULTIES.read().unwrap().clone();
     let mut data = HashMap::new();
 
@@ -68,15 +86,15 @@ pub async fn do_diffcalc(playmode: PlayMode) {
                 if calc.as_ref().is_none() {
                     calc = calc_diff(map, playmode.clone()).await.ok();
                     if calc.is_none() { 
-                        data.insert(diff_key, -1.0);
+                        data.insert(diff_key, DifficultyAttributes::new(-1.0));
                         continue 'maps 
                     }
                 }
                 
-                let diff = calc.as_mut().unwrap().calc(&mods).await.unwrap_or(0.0).normal_or(0.0);
+                let diff = calc.as_mut().unwrap().calc(&mods).await.unwrap_or_default();
                 
                 #[cfg(feature="debug_perf_rating")]
-                info!("[calc] {diff_key:?} -> {diff}");
+                info!("[calc] {diff_key:?} -> {diff:?}");
                 data.insert(diff_key, diff);
             }
         }
@@ -98,21 +116,76 @@ pub async fn do_diffcalc(playmode: PlayMode) {
     }
 }
 
+/// get the diff of a single map with `mods`, calculating it if it hasnt been already
+pub async fn calc_map_diff(map: &BeatmapMeta, playmode: &PlayMode, mods: &ModManager) -> Option<DifficultyAttributes> {
+    if let Some(diff) = get_diff_attributes(map, playmode, mods) { return Some(diff) }
+
+    let mods = mods.difficulty_mods(playmode);
+    let mut calc = calc_diff(map, playmode.clone()).await.ok()?;
+    let diff = calc.calc(&mods).await.unwrap_or_default();
 
+    let diff_key = DifficultyEntry::new(map.beatmap_hash.clone(), playmode.clone(), mods);
+    BEATMAP_DIFFICULTIES.write().unwrap().insert(diff_key, diff.clone());
 
-fn load_all_diffs() -> TatakuResult<HashMap<DifficultyEntry, f32>> {
-    if exists(DIFF_FILE) {
-        let data = std::fs::read(DIFF_FILE)?;
+    if let Err(e) = save_all_diffs() {
+        error!("error saving diffs: {e}");
+    }
+    Some(diff)
+}
+
+
+fn load_all_diffs() -> TatakuResult<HashMap<DifficultyEntry, DifficultyAttributes>> {
+    if !exists(DIFF_FILE) { return Ok(Default::default()) }
+    let data = std::fs::read(DIFF_FILE)?;
+
+    // playmode -> diff calc version the entries were calculated with
+    // files from before this was stored were all calculated with version 1
+    let mut calc_versions:HashMap<String, u32> = HashMap::new();
+
+    let entries:HashMap<DifficultyEntry, DifficultyAttributes> = if !data.starts_with(DIFF_FILE_MAGIC) {
+        // old unversioned file, which only has nomod entries
         let mut reader = SerializationReader::new(data);
-        Ok(reader.read()?)
+        let entries:HashMap<LegacyDifficultyEntry, f32> = reader.read()?;
+        entries.into_iter().map(|(k, v)| (k.0, DifficultyAttributes::new(v))).collect()
     } else {
-        Ok(Default::default())
-    }
+        let mut reader = SerializationReader::new(data[DIFF_FILE_MAGIC.len()..].to_vec());
+        let version:u16 = reader.read()?;
+        // entries from before version 3 have no skills, but they were calculated with diff calc version 1 so theyre removed below anyway
+        let only_diffs = |entries:HashMap<DifficultyEntry, f32>| entries.into_iter().map(|(k, v)| (k, DifficultyAttributes::new(v))).collect();
+        match version {
+            1 => only_diffs(reader.read()?),
+            2 => {
+                calc_versions = reader.read()?;
+                only_diffs(reader.read()?)
+            }
+            DIFF_FILE_VERSION => {
+                calc_versions = reader.read()?;
+                reader.read()?
+            }
+            _ => {
+                warn!("unknown diff file version {version}, diffs will be recalculated");
+                return Ok(Default::default())
+            }
+        }
+    };
+
+    // remove any entries calculated with an old diff calc
+    Ok(entries
+        .into_iter()
+        .filter(|(k, _)| calc_versions.get(&k.playmode).cloned().unwrap_or(1) == diff_calc_version(&k.playmode))
+        .collect()
+    )
 }
 
 fn save_all_diffs() -> TatakuResult<()> {
     let entries = &*BEATMAP_DIFFICULTIES.read().unwrap();
-    let bytes = SimpleWriter::new().write(entries.clone()).done();
+    let mut bytes = DIFF_FILE_MAGIC.to_vec();
+    let calc_versions:HashMap<String, u32> = AVAILABLE_PLAYMODES
+        .iter()
+        .map(|m| (m.to_string(), diff_calc_version(&m.to_string())))
+        .collect();
+
+    bytes.extend(SimpleWriter::new().write(DIFF_FILE_VERSION).write(calc_versions).write(entries.clone()).done());
     Ok(std::fs::write(DIFF_FILE, bytes)?)
 }
 
@@ -139,9 +212,11 @@ impl Serializable for DifficultyEntry {
         let map_hash = sr.read()?;
         let playmode = sr.read()?;
         let speed = sr.read()?;
+        let mod_list:Vec<String> = sr.read()?;
 
         let mut mods = ModManager::default();
         mods.speed = speed;
+        mods.mods = mod_list.into_iter().collect();
 
         Ok(Self {
             map_hash,
@@ -154,5 +229,32 @@ impl Serializable for DifficultyEntry {
         sw.write(self.map_hash.clone());
         sw.write(self.playmode.clone());
         sw.write(self.mods.speed);
+
+        // sorted so the same mods always write the same bytes
+        let mut mod_list = self.mods.mods.iter().cloned().collect::<Vec<_>>();
+        mod_list.sort();
+        sw.write(mod_list);
+    }
+}
+
+/// entry from before the diff file was versioned, which didnt store any mods
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct LegacyDifficultyEntry(DifficultyEntry);
+impl Serializable for LegacyDifficultyEntry {
+    fn read(sr:&mut SerializationReader) -> SerializationResult<Self> where Self: Sized {
+        let map_hash = sr.read()?;
+        let playmode = sr.read()?;
+        let speed = sr.read()?;
+
+        let mut mods = ModManager::default();
+        mods.speed = speed;
+
+        Ok(Self(DifficultyEntry::new(map_hash, playmode, mods)))
+    }
+
+    fn write(&self, sw:&mut SerializationWriter) {
+        sw.write(self.0.map_hash.clone());
+        sw.write(self.0.playmode.clone());
+        sw.write(self.0.mods.speed);
     }
 }
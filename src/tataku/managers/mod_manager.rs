@@ -9,7 +9,13 @@ pub struct ModManager {
     /// use get/set_speed instead of direct access to this
     pub speed: u16,
     
-    pub mods: HashSet<String>
+    pub mods: HashSet<String>,
+
+    /// judgment preset id, see [`JudgmentPreset::to_id`]. None is the playmode's default
+    pub judgment_preset: Option<String>,
+
+    /// scoring system to use. like the preset, this isnt one of the mods so it doesnt change the score's hash
+    pub scoring_system: ScoringSystemKind,
 }
 
 // static 
@@ -134,6 +140,10 @@ impl ModManager {
         self.add_mod(m);
         self
     }
+    pub fn with_mods(mut self, mods: HashSet<String>) -> Self {
+        self.mods = mods;
+        self
+    }
     pub fn with_speed(mut self, speed: u16) -> Self {
         self.speed = speed;
         self
@@ -165,6 +175,21 @@ impl ModManager {
         self.mods.contains(m.as_ref())
     }
 
+    /// get a copy of these mods with only the speed and the mods which affect difficulty for this playmode
+    pub fn difficulty_mods(&self, playmode: &String) -> Self {
+        let mut mods = Self::default().with_speed(self.speed);
+        if self.mods.is_empty() { return mods }
+
+        let available = Self::mods_for_playmode_as_hashmap(playmode);
+        mods.mods = self.mods
+            .iter()
+            .filter(|m| available.get(*m).map(|m| m.affects_difficulty()).unwrap_or_default())
+            .cloned()
+            .collect();
+
+        mods
+    }
+
 
     // common mods
     pub fn has_nofail(&self) -> bool {
@@ -337,7 +337,7 @@ impl SpectatorManager {
     }
     pub async fn mouse_move(&mut self, pos:Vector2, game:&mut Game) {
         if let Some(manager) = self.game_manager.as_mut() {
-            manager.mouse_move(pos).await
+            manager.mouse_move(pos, Instant::now()).await
         }
         
         // update score menu
@@ -347,7 +347,7 @@ impl SpectatorManager {
     }
     pub async fn mouse_down(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
         if let Some(manager) = self.game_manager.as_mut() {
-            manager.mouse_down(button).await;
+            manager.mouse_down(button, Instant::now()).await;
         }
 
         // update score menu
@@ -357,7 +357,7 @@ impl SpectatorManager {
     }
     pub async fn mouse_up(&mut self, _pos:Vector2, button:MouseButton, _mods:KeyModifiers, _game:&mut Game) {
         if let Some(manager) = self.game_manager.as_mut() {
-            manager.mouse_up(button).await
+            manager.mouse_up(button, Instant::now()).await
         }
     }
 
@@ -384,7 +384,7 @@ impl SpectatorManager {
 
         // update score menu
         if let Some(manager) = self.game_manager.as_mut() {
-            manager.key_down(key, mods).await
+            manager.key_down(key, mods, Instant::now()).await
         }
 
         // update score menu
@@ -394,7 +394,7 @@ impl SpectatorManager {
     }
     pub async fn key_up(&mut self, key:piston::Key, _mods:KeyModifiers, game:&mut Game) {
         if let Some(manager) = self.game_manager.as_mut() {
-            manager.key_up(key).await
+            manager.key_up(key, Instant::now()).await
         }
 
         // update score menu
@@ -11,6 +11,8 @@ const DEFAULT_HEIGHT: f64 = 768.0;
 
 pub struct BeatmapSelectMenu {
     current_scores: HashMap<String, IngameScore>,
+    /// what to race against when the map is played
+    ghost: Option<GhostSource>,
     beatmap_scroll: ScrollableArea,
     leaderboard_scroll: ScrollableArea,
     back_button: MenuButton<Font2, Text>,
@@ -52,6 +54,25 @@ pub struct BeatmapSelectMenu {
     new_beatmap_helper: LatestBeatmapHelper
 }
 impl BeatmapSelectMenu {
+    /// shortcuts checked while picking a map
+    pub fn keybind_context() -> KeybindContext {
+        KeybindContext::new("song_select", "Song Select", vec![
+            KeybindAction::new("mods", "Open Mods", vec![InputTarget::ctrl(Key::M)]),
+            KeybindAction::new("practice", "Practice Mode", vec![InputTarget::ctrl(Key::P)]),
+            KeybindAction::new("ghost", "Race Your Best", vec![InputTarget::ctrl(Key::G)]),
+            KeybindAction::new("autoplay", "Toggle Autoplay", vec![InputTarget::ctrl(Key::A)]),
+            KeybindAction::new("no_fail", "Toggle Nofail", vec![InputTarget::ctrl(Key::N)]),
+            KeybindAction::new("speed_up", "Speed Up", vec![InputTarget::ctrl(Key::Equals)]),
+            KeybindAction::new("speed_down", "Speed Down", vec![InputTarget::ctrl(Key::Minus)]),
+            KeybindAction::new("mode_osu", "Switch to Osu", vec![InputTarget::alt(Key::D1)]),
+            KeybindAction::new("mode_taiko", "Switch to Taiko", vec![InputTarget::alt(Key::D2)]),
+            KeybindAction::new("mode_mania", "Switch to Mania", vec![InputTarget::alt(Key::D4)]),
+            KeybindAction::new("refresh", "Refresh Maps", vec![InputTarget::Key(Key::F5)]),
+            KeybindAction::new("full_refresh", "Full Refresh", vec![InputTarget::ctrl(Key::F5)]),
+            KeybindAction::new("diffcalc", "Calculate Difficulties", vec![InputTarget::ctrl(Key::F7)]),
+        ])
+    }
+
     pub async fn new() -> BeatmapSelectMenu {
         let font = get_font();
         let window_size = WindowSize::get();
@@ -105,6 +126,7 @@ impl BeatmapSelectMenu {
             // pending_refresh: false,
             map_changing: (false, false, 0),
             current_scores: HashMap::new(),
+            ghost: None,
             back_button: MenuButton::back_button(window_size.0, font.clone()),
 
             beatmap_scroll,
@@ -240,7 +262,7 @@ impl BeatmapSelectMenu {
             
             tokio::spawn(async move {
                 for mode in modes_needing_diffcalc {
-                    do_diffcalc(mode).await;
+                    do_diffcalc(mode, &mods).await;
                 }
                 fuze.ignite(());
             });
@@ -257,13 +279,51 @@ impl BeatmapSelectMenu {
             // clear lists
             self.leaderboard_scroll.clear();
             self.current_scores.clear();
+
+            // leaderboard scores are only for the map they were picked on
+            if let Some(GhostSource::Score(_)) = &self.ghost {
+                self.ghost = None;
+            }
         }
     }
 
     async fn play_map(&self, game: &mut Game, map: &BeatmapMeta) {
+        // in a lobby, picking a map sets the lobby's map instead
+        let (in_lobby, is_host) = {
+            let online = ONLINE_MANAGER.read().await;
+            (online.lobby.is_some(), online.is_lobby_host())
+        };
+        if in_lobby {
+            if !is_host {
+                NotificationManager::add_text_notification("Only the host can pick the map", 2000.0, Color::RED).await;
+                return;
+            }
+
+            OnlineManager::lobby_set_map(LobbyBeatmap::new(map, self.mode.clone()));
+            OnlineManager::lobby_set_mods(&self.mods);
+            LobbyMenu::return_to_lobby(game).await;
+            return;
+        }
+
         // Audio::stop_song();
         match manager_from_playmode(self.mode.clone(), map).await {
-            Ok(manager) => game.queue_state_change(GameState::Ingame(manager)),
+            Ok(mut manager) => {
+                let replay = match &self.ghost {
+                    Some(GhostSource::Score(score)) => score.get_replay().await,
+                    Some(GhostSource::PersonalBest) => {
+                        let replay = GhostSource::personal_best(map, &map.check_mode_override(self.mode.clone())).await;
+                        if replay.is_none() {
+                            NotificationManager::add_text_notification("No best score to race against", 3000.0, Color::YELLOW).await;
+                        }
+                        replay
+                    }
+                    None => None,
+                };
+                if let Some((replay, extras)) = replay {
+                    manager.set_ghost(replay, extras).await;
+                }
+                game.queue_state_change(GameState::Ingame(manager))
+            }
             Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await
         }
     }
@@ -321,6 +381,8 @@ impl BeatmapSelectMenu {
 
     async fn actual_on_click(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
         if self.back_button.on_click(pos, button, mods) {
+            if LobbyMenu::return_to_lobby(game).await { return }
+
             let menu = game.menus.get("main").unwrap().clone();
             game.queue_state_change(GameState::InMenu(menu));
             return;
@@ -382,6 +444,23 @@ impl BeatmapSelectMenu {
         
         // check if leaderboard item was clicked
         if let Some(score_tag) = self.leaderboard_scroll.on_click_tagged(pos, button, mods) {
+            // right click picks the score to race against
+            if button == MouseButton::Right {
+                if let Some(score) = self.current_scores.get(&score_tag) {
+                    if let Some(GhostSource::Score(ghost)) = &self.ghost {
+                        if ghost.hash() == score.hash() {
+                            self.ghost = None;
+                            NotificationManager::add_text_notification("Ghost cleared", 3000.0, Color::GREEN).await;
+                            return;
+                        }
+                    }
+
+                    NotificationManager::add_text_notification(&format!("Racing against {}", score.username), 3000.0, Color::GREEN).await;
+                    self.ghost = Some(GhostSource::Score(score.clone()));
+                }
+                return;
+            }
+
             // score display
             if let Some(score) = self.current_scores.get(&score_tag) {
                 let score = score.clone();
@@ -432,9 +511,12 @@ impl BeatmapSelectMenu {
 
     async fn reload_leaderboard(&mut self) {
         self.leaderboard_scroll.clear();
+        let map = BEATMAP_MANAGER.read().await.current_beatmap.clone().unwrap_or_default();
+        let mut submissions = Database::get_submission_statuses(&map.beatmap_hash).await;
         
-        for (_, s) in self.current_scores.iter() {
-            self.leaderboard_scroll.add_item(Box::new(LeaderboardItem::new(s.clone()).load_image().await));
+        for (hash, s) in self.current_scores.iter() {
+            let item = LeaderboardItem::new(s.clone(), &map).with_submission(submissions.remove(hash));
+            self.leaderboard_scroll.add_item(Box::new(item.load_image().await));
         }
     }
 }
@@ -480,7 +562,8 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
         self.beatmap_scroll.update();
         self.leaderboard_scroll.update();
         self.settings.update();
-        self.mods.update();
+        let old_mods = self.mods.mods.clone();
+        let mods_changed = self.mods.update() && self.mods.mods != old_mods;
 
         for i in self.interactables() {
             i.update();
@@ -489,7 +572,8 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
         // update bg game
         self.menu_game.update().await;
 
-        let mut filter_pending = false;
+        // diffs might not be calculated for the new mods yet
+        let mut filter_pending = mods_changed;
         if let Some(_) = self.diffcalc_complete.as_ref().and_then(|b| b.exploded()) {
             debug!("diffcalc done, reload maps");
             filter_pending = true;
@@ -539,9 +623,12 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
                 scores.sort_by(|a, b| b.score.score.cmp(&a.score.score));
 
                 // add scores to list
+                let map = BEATMAP_MANAGER.read().await.current_beatmap.clone().unwrap_or_default();
+                let mut submissions = Database::get_submission_statuses(&map.beatmap_hash).await;
                 for s in scores.iter() {
                     self.current_scores.insert(s.hash(), s.clone());
-                    self.leaderboard_scroll.add_item(Box::new(LeaderboardItem::new(s.to_owned()).load_image().await));
+                    let item = LeaderboardItem::new(s.to_owned(), &map).with_submission(submissions.remove(&s.hash()));
+                    self.leaderboard_scroll.add_item(Box::new(item.load_image().await));
                 }
             }
         }
@@ -728,8 +815,9 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
 
     async fn on_key_press(&mut self, key:piston::Key, game:&mut Game, mods:KeyModifiers) {
         use piston::Key::*;
+        let keybinds = get_settings!().keybinds.get_bindings("song_select");
 
-        if key == Key::M && mods.ctrl { 
+        if keybinds.key_matches("mods", key, &mods) {
             let mut found = false;
             for d in game.dialogs.iter_mut() {
                 if d.name() == "mod_menu" {
@@ -756,102 +844,116 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
             }
         }
 
-        if key == Left && !mods.alt {
+        // tab too, so maps can be picked with a drum
+        let previous = key == Left || (key == Tab && mods.shift);
+        let next = key == Right || (key == Tab && !mods.shift);
+
+        if previous && !mods.alt {
             if let Some(hash) = self.beatmap_scroll.select_previous_item() {
                 self.select_map(game, hash, false).await;
                 self.beatmap_scroll.scroll_to_selection();
             }
         }
-        if key == Right && !mods.alt  {
+        if next && !mods.alt  {
             if let Some(hash) = self.beatmap_scroll.select_next_item() {
                 self.select_map(game, hash, false).await;
                 self.beatmap_scroll.scroll_to_selection();
             }
         }
 
-        if key == F7 && mods.ctrl {
+        // practice mode
+        if keybinds.key_matches("practice", key, &mods) {
+            if let Some(map) = BEATMAP_MANAGER.read().await.current_beatmap.clone() {
+                match PracticeDialog::new(&map, self.mode.clone()).await {
+                    Ok(dialog) => game.add_dialog(Box::new(dialog)),
+                    Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await,
+                }
+            }
+        }
+
+        // race against your best score
+        if keybinds.key_matches("ghost", key, &mods) {
+            if let Some(GhostSource::PersonalBest) = &self.ghost {
+                self.ghost = None;
+                NotificationManager::add_text_notification("Ghost cleared", 3000.0, Color::GREEN).await;
+            } else {
+                self.ghost = Some(GhostSource::PersonalBest);
+                NotificationManager::add_text_notification("Racing against your best score", 3000.0, Color::GREEN).await;
+            }
+        }
+
+        if keybinds.key_matches("diffcalc", key, &mods) {
             let playmode = self.mode.clone();
+            let mods = self.mods.clone();
             tokio::spawn(async move {
-                do_diffcalc(playmode).await;
+                do_diffcalc(playmode, &mods).await;
             });
         }
 
         if key == Escape {
+            if LobbyMenu::return_to_lobby(game).await { return }
+
             let menu = game.menus.get("main").unwrap().clone();
             game.queue_state_change(GameState::InMenu(menu));
             return;
         }
-        if key == F5 {
-            if mods.ctrl {
-                NotificationManager::add_text_notification("Doing a full refresh", 5000.0, Color::RED).await;
-                BEATMAP_MANAGER.write().await.full_refresh().await;
-            } else {
-                self.refresh_maps().await;
-            }
+        if keybinds.key_matches("full_refresh", key, &mods) {
+            NotificationManager::add_text_notification("Doing a full refresh", 5000.0, Color::RED).await;
+            BEATMAP_MANAGER.write().await.full_refresh().await;
+            return;
+        }
+        if keybinds.key_matches("refresh", key, &mods) {
+            self.refresh_maps().await;
             return;
         }
 
         // mode change
-        if mods.alt {
-            let new_mode = match key {
-                D1 => Some("osu".to_owned()),
-                D2 => Some("taiko".to_owned()),
-                // D3 => Some("catch".to_owned()),
-                D4 => Some("mania".to_owned()),
-                _ => None
-            };
+        let new_mode = [("mode_osu", "osu"), ("mode_taiko", "taiko"), ("mode_mania", "mania")]
+            .into_iter()
+            .find(|(action, _)| keybinds.key_matches(action, key, &mods))
+            .map(|(_, mode)| mode.to_owned());
 
-            if let Some(new_mode) = new_mode {
-                self.set_selected_mode(new_mode.clone()).await;
-                let display = gamemode_display_name(&new_mode);
-                NotificationManager::add_text_notification(&format!("Mode changed to {}", display), 1000.0, Color::BLUE).await;
-                self.mode = new_mode;
-                self.load_scores().await;
-            }
+        if let Some(new_mode) = new_mode {
+            self.set_selected_mode(new_mode.clone()).await;
+            let display = gamemode_display_name(&new_mode);
+            NotificationManager::add_text_notification(&format!("Mode changed to {}", display), 1000.0, Color::BLUE).await;
+            self.mode = new_mode;
+            self.load_scores().await;
         }
 
         // mods and speed
-        if mods.ctrl {
-            let mut speed = self.mods.get_speed();
-            let prev_speed = speed;
-            const SPEED_DIFF:f32 = 0.05;
-
-            match key {
-                Equals => speed += SPEED_DIFF, // map speed up
-                Minus => speed -= SPEED_DIFF, // map speed down
-                 
-                // autoplay enable/disable
-                A => {
-                    let mut manager = ModManager::get_mut();
-                    let state = if manager.toggle_mod("autoplay") {"on"} else {"off"};
-                    NotificationManager::add_text_notification(&format!("Autoplay {}", state), 2000.0, Color::BLUE).await;
-                }
+        let mut speed = self.mods.get_speed();
+        let prev_speed = speed;
+        const SPEED_DIFF:f32 = 0.05;
 
-                // nofail enable/disable
-                N => {
-                    let mut manager = ModManager::get_mut();
+        if keybinds.key_matches("speed_up", key, &mods) { speed += SPEED_DIFF }
+        if keybinds.key_matches("speed_down", key, &mods) { speed -= SPEED_DIFF }
 
-                    let state = if manager.toggle_mod("no_fail") {"on"} else {"off"};
-                    NotificationManager::add_text_notification(&format!("Nofail {}", state), 2000.0, Color::BLUE).await;
-                }
+        // autoplay enable/disable
+        if keybinds.key_matches("autoplay", key, &mods) {
+            let state = if ModManager::get_mut().toggle_mod("autoplay") {"on"} else {"off"};
+            NotificationManager::add_text_notification(&format!("Autoplay {}", state), 2000.0, Color::BLUE).await;
+        }
 
-                _ => {}
-            }
+        // nofail enable/disable
+        if keybinds.key_matches("no_fail", key, &mods) {
+            let state = if ModManager::get_mut().toggle_mod("no_fail") {"on"} else {"off"};
+            NotificationManager::add_text_notification(&format!("Nofail {}", state), 2000.0, Color::BLUE).await;
+        }
 
-            speed = speed.clamp(SPEED_DIFF, 10.0);
-            if speed != prev_speed {
-                ModManager::get_mut().set_speed(speed);
+        speed = speed.clamp(SPEED_DIFF, 10.0);
+        if speed != prev_speed {
+            ModManager::get_mut().set_speed(speed);
 
-                // update audio speed
-                if let Some(song) = AudioManager::get_song().await {
-                    song.set_rate(speed);
-                }
+            // update audio speed
+            if let Some(song) = AudioManager::get_song().await {
+                song.set_rate(speed);
+            }
 
-                // force diff recalc
-                self.set_selected_mode(self.mode.clone()).await;
+            // force diff recalc
+            self.set_selected_mode(self.mode.clone()).await;
 
-                NotificationManager::add_text_notification(&format!("Map speed: {:.2}x", speed), 2000.0, Color::BLUE).await;
-            }
+            NotificationManager::add_text_notification(&format!("Map speed: {:.2}x", speed), 2000.0, Color::BLUE).await;
         }
 
         // if enter was hit, or a beatmap item was updated
@@ -895,32 +997,6 @@ impl AsyncMenu<Game> for BeatmapSelectMenu {
 }
 #[async_trait]
 impl ControllerInputMenu<Game> for BeatmapSelectMenu {
-    async fn controller_down(&mut self, game:&mut Game, controller: &Box<dyn Controller>, button: u8) -> bool {
-        if let Some(ControllerButton::DPad_Up) = controller.map_button(button) {
-            self.on_key_press(Key::Up, game, KeyModifiers::default()).await
-        }
-        if let Some(ControllerButton::DPad_Down) = controller.map_button(button) {
-            self.on_key_press(Key::Down, game, KeyModifiers::default()).await
-        }
-        if let Some(ControllerButton::DPad_Left) = controller.map_button(button) {
-            self.on_key_press(Key::Left, game, KeyModifiers::default()).await
-        }
-        if let Some(ControllerButton::DPad_Right) = controller.map_button(button) {
-            self.on_key_press(Key::Right, game, KeyModifiers::default()).await
-        }
-
-        if let Some(ControllerButton::A) = controller.map_button(button) {
-            self.on_key_press(Key::Return, game, KeyModifiers::default()).await
-        }
-
-        if let Some(ControllerButton::B) = controller.map_button(button) {
-            let menu = game.menus.get("main").unwrap().clone();
-            game.queue_state_change(GameState::InMenu(menu));
-        }
-        
-        false
-    }
-
     async fn controller_axis(&mut self, _game:&mut Game, controller: &Box<dyn Controller>, axis_data: HashMap<u8, (bool, f64)>) -> bool {
         for (axis, (_, val)) in axis_data {
             if Some(ControllerAxis::Right_Y) == controller.map_axis(axis) && val.abs() > 0.1 {
@@ -933,3 +1009,22 @@ impl ControllerInputMenu<Game> for BeatmapSelectMenu {
     }
 }
 
+
+/// what to race against
+enum GhostSource {
+    /// a score picked from the leaderboard
+    Score(IngameScore),
+    /// the best local score by the player on whatever map is played
+    PersonalBest,
+}
+impl GhostSource {
+    async fn personal_best(map: &BeatmapMeta, playmode: &PlayMode) -> Option<(Replay, ReplayExtras)> {
+        let username = get_settings!().username.clone();
+        let best = Database::get_scores(&map.beatmap_hash, playmode.clone()).await
+            .into_iter()
+            .filter(|s| s.username == username)
+            .max_by_key(|s| s.score.score)?;
+
+        best.get_replay().await
+    }
+}
@@ -18,6 +18,12 @@ pub struct LeaderboardItem {
     font: Font2,
 
     score_mods: String,
+    /// local perf calc for this score, its breakdown is shown in the tooltip
+    performance: Option<PerformanceInfo>,
+    /// shown in a tooltip when hovered
+    hit_stats: Option<HitTimingStats>,
+    /// submit status, if this is one of our local scores
+    submission: Option<SubmissionStatus>,
 
     ui_scale: Vector2,
 
@@ -27,13 +33,24 @@ pub struct LeaderboardItem {
     pub theme: ThemeHelper,
 }
 impl LeaderboardItem {
-    pub fn new(score:IngameScore) -> LeaderboardItem {
+    pub fn new(score:IngameScore, map: &BeatmapMeta) -> LeaderboardItem {
         let pos = Vector2::ZERO;
         let size = LEADERBOARD_ITEM_SIZE;
 
         let tag = score.hash(); //username.clone();
         let font = get_font();
-        let score_mods = ModManager::short_mods_string(score.mods(), false, &score.playmode);
+        let mut score_mods = ModManager::short_mods_string(score.mods(), false, &score.playmode);
+        // scores with different judgment presets should still be easy to tell apart
+        if score.judgment_preset.is_some() {
+            let preset = score.get_judgment_preset().0.short_name();
+            score_mods = if score_mods.is_empty() { preset.to_owned() } else { format!("{score_mods} {preset}") };
+        }
+        if score.scoring_system != ScoringSystemKind::Default {
+            let scoring = score.scoring_system.short_name();
+            score_mods = if score_mods.is_empty() { scoring.to_owned() } else { format!("{score_mods} {scoring}") };
+        }
+        let performance = calc_perf(&score, map);
+        let hit_stats = score.hit_stats();
 
         LeaderboardItem {
             pos,
@@ -44,6 +61,9 @@ impl LeaderboardItem {
             selected: false,
             font,
             score_mods,
+            performance,
+            hit_stats,
+            submission: None,
             ui_scale: Vector2::ONE,
 
             color_override: None,
@@ -52,6 +72,10 @@ impl LeaderboardItem {
             theme: ThemeHelper::new(),
         }
     }
+    pub fn with_submission(mut self, submission: Option<SubmissionStatus>) -> Self {
+        self.submission = submission;
+        self
+    }
     pub async fn load_image(mut self) -> Self {
         self.image = SkinManager::get_texture("menu-button-background", true).await;
         self
@@ -71,11 +95,18 @@ impl ScrollableItem for LeaderboardItem {
 
         let now = chrono::Utc::now().timestamp() as u64;
         let time_diff = now as i64 - self.score.time as i64;
+        let perf = self.performance.as_ref().map(|p| format!(" | {:.2}pr", p.total)).unwrap_or_default();
         let time_diff_str = if time_diff < 60 * 5 {
             format!(" | {time_diff}s")
         } else {
             String::new()
         };
+        let submission_str = match &self.submission {
+            Some(SubmissionStatus::Pending) => " (pending)",
+            Some(SubmissionStatus::Rejected(_)) => " (rejected)",
+            Some(SubmissionStatus::Failed(_)) => " (not submitted)",
+            _ => "",
+        };
         
         let color = if let Some(color) = self.color_override {
             color
@@ -123,7 +154,7 @@ impl ScrollableItem for LeaderboardItem {
             parent_depth + 4.0,
             self.pos + pos_offset + PADDING * self.ui_scale,
             (15.0 * self.ui_scale.y) as u32,
-            format!("{}: {}", self.score.username, crate::format_number(self.score.score.score)),
+            format!("{}: {}{submission_str}", self.score.username, crate::format_number(self.score.score.score)),
             self.font.clone()
         ));
 
@@ -133,8 +164,54 @@ impl ScrollableItem for LeaderboardItem {
             parent_depth + 4.0,
             self.pos + pos_offset + (PADDING + Vector2::new(0.0, PADDING.y + 15.0)) * self.ui_scale,
             (12.0 * self.ui_scale.y) as u32,
-            format!("{}x, {:.2}%, {}{time_diff_str}", crate::format_number(self.score.max_combo), calc_acc(&self.score) * 100.0, self.score_mods),
+            format!("{}x, {:.2}%, {}{perf}{time_diff_str}", crate::format_number(self.score.max_combo), self.score.calc_acc() * 100.0, self.score_mods),
             self.font.clone()
         ));
+
+        // performance, hit timing and submission tooltip
+        let mut lines = Vec::new();
+        let mut width = 160.0;
+        if let Some(perf) = &self.performance {
+            lines.extend(perf.parts.iter().map(|(name, value)| format!("{name}: {value:.2}pr")));
+        }
+        if let Some(stats) = self.hit_stats {
+            lines.push(format!("UR: {:.2}", stats.unstable_rate));
+            lines.push(format!("Mean: {:.2}ms", stats.mean));
+            lines.push(format!("Early: {}, Late: {}", stats.early_count, stats.late_count));
+        }
+        match &self.submission {
+            Some(SubmissionStatus::Pending) => lines.push("Waiting to be submitted".to_owned()),
+            Some(SubmissionStatus::Rejected(reason)) => {
+                lines.push(format!("Rejected: {reason}"));
+                width = 260.0;
+            }
+            Some(SubmissionStatus::Failed(reason)) => {
+                lines.push(format!("Not submitted: {reason}"));
+                width = 260.0;
+            }
+            _ => {}
+        }
+
+        if self.hover && !lines.is_empty() {
+            let tooltip_pos = self.pos + pos_offset + Vector2::with_x(self.size.x + 5.0);
+
+            list.push(Rectangle::new(
+                Color::BLACK.alpha(0.8),
+                parent_depth - 1.0,
+                tooltip_pos,
+                Vector2::new(width, 5.0 + lines.len() as f64 * 17.0) * self.ui_scale,
+                Some(Border::new(color, 1.5 * self.ui_scale.y))
+            ));
+            for (i, line) in lines.into_iter().enumerate() {
+                list.push(Text::new(
+                    Color::WHITE,
+                    parent_depth - 2.0,
+                    tooltip_pos + (PADDING + Vector2::with_y(i as f64 * 17.0)) * self.ui_scale,
+                    (12.0 * self.ui_scale.y) as u32,
+                    line,
+                    self.font.clone()
+                ));
+            }
+        }
     }
 }
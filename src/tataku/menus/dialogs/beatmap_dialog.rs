@@ -6,6 +6,7 @@ pub struct BeatmapDialog {
     bounds: Rectangle,
     target_map: String,
     delete_map: MenuButton<Font2, Text>,
+    practice_map: MenuButton<Font2, Text>,
     should_close: bool,
 }
 impl BeatmapDialog {
@@ -16,7 +17,7 @@ impl BeatmapDialog {
         const BUTTON_SIZE:Vector2 = Vector2::new(100.0, 30.0);
 
         let offset = 100.0;
-        let count = 0;
+        let mut count = 0;
 
         let delete_map = MenuButton::new(
             Vector2::new((window.x - BUTTON_SIZE.x) / 2.0, offset + (count as f64 * (BUTTON_SIZE.y + Y_PADDING))),
@@ -24,6 +25,14 @@ impl BeatmapDialog {
             "Delete Map",
             get_font(),
         );
+        count += 1;
+
+        let practice_map = MenuButton::new(
+            Vector2::new((window.x - BUTTON_SIZE.x) / 2.0, offset + (count as f64 * (BUTTON_SIZE.y + Y_PADDING))),
+            BUTTON_SIZE,
+            "Practice",
+            get_font(),
+        );
 
 
         let bounds = Rectangle::new(
@@ -40,6 +49,7 @@ impl BeatmapDialog {
         Self {
             bounds,
             delete_map,
+            practice_map,
             target_map: map_hash,
 
             should_close: false
@@ -71,7 +81,8 @@ impl Dialog<Game> for BeatmapDialog {
     }
 
     async fn on_mouse_move(&mut self, pos:&Vector2, _g:&mut Game) {
-        self.delete_map.on_mouse_move(*pos)
+        self.delete_map.on_mouse_move(*pos);
+        self.practice_map.on_mouse_move(*pos);
     }
     async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, game:&mut Game) -> bool {
         if self.delete_map.on_click(*pos, *button, *mods) {
@@ -80,6 +91,19 @@ impl Dialog<Game> for BeatmapDialog {
             BEATMAP_MANAGER.write().await.delete_beatmap(self.target_map.clone(), game).await;
             self.should_close = true;
         }
+
+        if self.practice_map.on_click(*pos, *button, *mods) {
+            let map = BEATMAP_MANAGER.read().await.get_by_hash(&self.target_map);
+            let playmode = GlobalValueManager::get::<CurrentPlaymode>().map(|p| p.0.clone()).unwrap_or("osu".to_owned());
+
+            if let Some(map) = map {
+                match PracticeDialog::new(&map, playmode).await {
+                    Ok(dialog) => game.add_dialog(Box::new(dialog)),
+                    Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await,
+                }
+            }
+            self.should_close = true;
+        }
         true
     }
 
@@ -92,6 +116,7 @@ impl Dialog<Game> for BeatmapDialog {
         // draw buttons
         let depth = depth - 0.0001;
         self.delete_map.draw(*args, Vector2::ZERO, depth, list);
+        self.practice_map.draw(*args, Vector2::ZERO, depth, list);
 
         list.push(bg_rect);
     }
@@ -0,0 +1,213 @@
+use crate::prelude::*;
+
+const BASE_SIZE:Vector2 = Vector2::new(500.0, 480.0);
+const LINE_HEIGHT:f64 = 30.0;
+
+/// how far an axis has to move from where it rests before it counts as an input
+const AXIS_DETECT_DISTANCE:f64 = 0.6;
+/// how far from where it rests a bound axis counts as pressed
+const AXIS_PRESS_DISTANCE:f64 = 0.5;
+/// how close to where it rests an axis has to be before it counts as let go
+const AXIS_REST_DISTANCE:f64 = 0.2;
+
+/// called with the controller name and an input for each input name once calibration is done
+pub type CalibrationCallback = Box<dyn FnOnce(&String, Vec<ControllerInputConfig>) + Send + Sync>;
+
+/// walks through a list of inputs, asking for each to be pressed on the controller.
+/// works with any controller, since it only cares about the raw button and axis ids
+pub struct ControllerCalibrationDialog {
+    pos: Vector2,
+    should_close: bool,
+
+    title: String,
+    input_names: Vec<String>,
+    /// how many inputs need to be set before calibration can be finished early
+    min_inputs: usize,
+
+    /// controller being calibrated, picked by whichever controller is used first
+    controller: Option<Arc<String>>,
+    inputs: Vec<ControllerInputConfig>,
+
+    /// controller name, then axis id to where that axis sits when its not being touched
+    axis_rest: HashMap<Arc<String>, HashMap<u8, f64>>,
+    /// an axis that was just bound, which has to be let go before anything else is accepted
+    waiting_for_axis: Option<u8>,
+
+    on_done: Option<CalibrationCallback>,
+}
+impl ControllerCalibrationDialog {
+    pub fn new(title: impl ToString, input_names: Vec<String>, min_inputs: usize, on_done: CalibrationCallback) -> Self {
+        let window_size = WindowSize::get();
+
+        Self {
+            pos: (window_size.0 - BASE_SIZE) / 2.0,
+            should_close: false,
+
+            title: title.to_string(),
+            input_names,
+            min_inputs,
+
+            controller: None,
+            inputs: Vec::new(),
+
+            axis_rest: HashMap::new(),
+            waiting_for_axis: None,
+
+            on_done: Some(on_done),
+        }
+    }
+
+    /// set where each axis rests, which should be read when calibration starts.
+    /// otherwise the first value seen is used, which is wrong if the axis was already being held
+    pub fn with_axis_rest(mut self, axis_rest: HashMap<Arc<String>, HashMap<u8, f64>>) -> Self {
+        self.axis_rest = axis_rest;
+        self
+    }
+
+    /// is this controller the one being calibrated? the first controller used gets picked
+    fn check_controller(&mut self, controller: &Box<dyn Controller>) -> bool {
+        let name = controller.get_name();
+        match &self.controller {
+            Some(current) => current == &name,
+            None => {
+                self.controller = Some(name);
+                true
+            }
+        }
+    }
+
+    fn add_input(&mut self, input: ControllerInputConfig) {
+        self.inputs.push(input);
+        if self.inputs.len() >= self.input_names.len() {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.should_close = true;
+
+        let Some(controller) = &self.controller else { return };
+        let Some(on_done) = self.on_done.take() else { return };
+        on_done(controller, std::mem::take(&mut self.inputs));
+
+        let name = controller.clone();
+        tokio::spawn(async move {
+            NotificationManager::add_text_notification(&format!("Controller set up: {name}"), 3000.0, Color::GREEN).await;
+        });
+    }
+
+    fn describe_input(input: &ControllerInputConfig) -> String {
+        match (input.button, input.axis) {
+            (Some(button), _) => format!("Button {button}"),
+            (None, Some(axis)) => format!("Axis {} {}", axis.axis_id, if axis.inverted { "-" } else { "+" }),
+            (None, None) => "Skipped".to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl Dialog<Game> for ControllerCalibrationDialog {
+    fn name(&self) -> &'static str { "controller_calibration" }
+    fn should_close(&self) -> bool { self.should_close }
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(self.pos, BASE_SIZE)
+    }
+
+    async fn draw(&mut self, _args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
+        let font = get_font();
+        self.draw_background(*depth + 10.0, Color::BLACK, list);
+
+        let mut pos = self.pos + Vector2::new(20.0, 10.0);
+        list.push(Text::new(Color::WHITE, *depth, pos, 25, self.title.clone(), font.clone()));
+        pos.y += LINE_HEIGHT + 5.0;
+
+        let controller = match &self.controller {
+            Some(name) => format!("Controller: {name}"),
+            None => "Press the first input on the controller to set up".to_owned(),
+        };
+        list.push(Text::new(Color::WHITE, *depth, pos, 18, controller, font.clone()));
+        pos.y += LINE_HEIGHT;
+
+        for (i, name) in self.input_names.iter().enumerate() {
+            let (text, color) = match self.inputs.get(i) {
+                Some(input) => (format!("{name}: {}", Self::describe_input(input)), Color::WHITE),
+                None if i == self.inputs.len() && self.waiting_for_axis.is_some() => (format!("{name}: let go of the axis"), Color::YELLOW),
+                None if i == self.inputs.len() => (format!("{name}: press now"), Color::YELLOW),
+                None => (format!("{name}:"), Color::WHITE.alpha(0.5)),
+            };
+            list.push(Text::new(color, *depth, pos, 18, text, font.clone()));
+            pos.y += LINE_HEIGHT;
+        }
+
+        let hint = if self.inputs.len() >= self.min_inputs {
+            "Space: skip, Enter: finish, Esc: cancel"
+        } else {
+            "Space: skip, Esc: cancel"
+        };
+        list.push(Text::new(Color::WHITE, *depth, self.pos + Vector2::new(20.0, BASE_SIZE.y - LINE_HEIGHT - 10.0), 18, hint.to_owned(), font.clone()));
+    }
+
+    async fn on_key_press(&mut self, key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
+        match key {
+            Key::Escape => self.should_close = true,
+            Key::Space => self.add_input(ControllerInputConfig::default()),
+            Key::Return if self.inputs.len() >= self.min_inputs => self.finish(),
+            _ => {}
+        }
+
+        true
+    }
+
+    async fn on_controller_press(&mut self, controller: &Box<dyn Controller>, button: u8) -> bool {
+        if !self.check_controller(controller) { return true }
+        if self.waiting_for_axis.is_some() { return true }
+
+        // dont let one button be bound to two inputs
+        if self.inputs.iter().any(|i| i.check_button(button)) { return true }
+
+        self.add_input(ControllerInputConfig::new(Some(button), None));
+        true
+    }
+    async fn on_controller_release(&mut self, _controller: &Box<dyn Controller>, _button: u8) -> bool {
+        true
+    }
+
+    async fn on_controller_axis(&mut self, controller: &Box<dyn Controller>, axis_data: &HashMap<u8, (bool, f64)>) {
+        let name = controller.get_name();
+        if self.controller.as_ref().filter(|c| *c != &name).is_some() { return }
+        let rest_values = self.axis_rest.entry(name).or_default();
+
+        let mut detected = None;
+        for (axis, (_, value)) in axis_data.iter() {
+            // if the axis wasnt known when calibration started (ie, the controller was just plugged in), the first value we see is where it rests
+            let rest = *rest_values.entry(*axis).or_insert(*value);
+            let moved = value - rest;
+
+            if self.waiting_for_axis == Some(*axis) {
+                if moved.abs() < AXIS_REST_DISTANCE {
+                    self.waiting_for_axis = None;
+                }
+                continue;
+            }
+
+            if detected.is_none() && moved.abs() >= AXIS_DETECT_DISTANCE {
+                // triggers usually rest at -1, so the threshhold is relative to the rest value
+                let inverted = moved < 0.0;
+                let rest = if inverted { -rest } else { rest };
+                detected = Some(AxisConfig::new(*axis, rest + AXIS_PRESS_DISTANCE, inverted));
+            }
+        }
+
+        let Some(axis) = detected else { return };
+        if self.waiting_for_axis.is_some() { return }
+        if self.inputs.iter().any(|i| i.axis.filter(|a| a.axis_id == axis.axis_id && a.inverted == axis.inverted).is_some()) { return }
+
+        self.check_controller(controller);
+        self.waiting_for_axis = Some(axis.axis_id);
+        self.add_input(ControllerInputConfig::new(None, Some(axis)));
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.pos = (window_size.0 - BASE_SIZE) / 2.0;
+    }
+}
@@ -13,6 +13,9 @@ pub struct GameImportDialog {
 
     add_button: MenuButton<Font2, Text>,
     confirm_button: MenuButton<Font2, Text>,
+
+    /// the inputs, then the add and done buttons
+    focus: FocusRing,
 }
 impl GameImportDialog {
     pub async fn new() -> Self {
@@ -62,7 +65,47 @@ impl GameImportDialog {
             input_scrollable: scrollable,
 
             add_button,
-            confirm_button
+            confirm_button,
+
+            focus: FocusRing::new(),
+        }
+    }
+
+    /// bounds of everything that can be focused, relative to the dialog
+    fn focus_items(&self) -> Vec<Rectangle> {
+        let mut list = FocusRing::item_bounds(&self.input_scrollable.items);
+        list.push(Rectangle::bounds_only(self.add_button.get_pos(), self.add_button.size()));
+        list.push(Rectangle::bounds_only(self.confirm_button.get_pos(), self.confirm_button.size()));
+        list
+    }
+
+    /// move focus, or click the focused item. returns true if the input was used
+    async fn navigate(&mut self, input: NavigationInput, game: &mut Game) -> bool {
+        let items = self.focus_items();
+
+        match input {
+            NavigationInput::Confirm => {
+                let Some(pos) = self.focus.focused_center(&items) else { return false };
+                // items check hover when clicked
+                self.input_scrollable.on_mouse_move(pos);
+                self.add_button.on_mouse_move(pos);
+                self.confirm_button.on_mouse_move(pos);
+
+                let pos = pos + self.pos;
+                let mods = KeyModifiers::default();
+                self.on_mouse_down(&pos, &MouseButton::Left, &mods, game).await;
+                self.on_mouse_up(&pos, &MouseButton::Left, &mods, game).await;
+                true
+            }
+            // left and right move the text cursor
+            NavigationInput::Up | NavigationInput::Down | NavigationInput::Next | NavigationInput::Previous => {
+                let used = self.focus.navigate(input, &items);
+                if self.focus.focused().filter(|i| *i < self.input_scrollable.items.len()).is_some() {
+                    self.focus.scroll_into_view(&mut self.input_scrollable);
+                }
+                used
+            }
+            _ => false,
         }
     }
 }
@@ -83,6 +126,7 @@ impl Dialog<Game> for GameImportDialog {
         self.input_scrollable.draw(*args, pos, *depth, list);
         self.add_button.draw(*args, pos, *depth, list);
         self.confirm_button.draw(*args, pos, *depth, list);
+        self.focus.draw(&self.focus_items(), pos, *depth, list);
     }
 
     async fn update(&mut self, _g:&mut Game) {
@@ -92,6 +136,7 @@ impl Dialog<Game> for GameImportDialog {
     }
 
     async fn on_mouse_move(&mut self, p:&Vector2, _g:&mut Game) {
+        self.focus.hide();
         let p = *p - self.pos;
         self.input_scrollable.on_mouse_move(p);
         self.add_button.on_mouse_move(p);
@@ -154,7 +199,11 @@ impl Dialog<Game> for GameImportDialog {
         true
     }
 
-    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, _g:&mut Game) -> bool {
+    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, game:&mut Game) -> bool {
+        if let Some(input) = NavigationInput::from_key(*key, mods) {
+            if self.navigate(input, game).await { return true }
+        }
+
         if key == &Key::Escape { self.should_close = true }
         
         self.input_scrollable.on_key_press(*key, *mods);
@@ -0,0 +1,234 @@
+use crate::prelude::*;
+
+const BASE_SIZE:Vector2 = Vector2::new(700.0, 640.0);
+const LINE_HEIGHT:f64 = 30.0;
+const PADDING:f64 = 20.0;
+const INPUT_SIZE:Vector2 = Vector2::new(BASE_SIZE.x - PADDING * 2.0, 40.0);
+
+/// lets the user rebind the actions in each keybind context, and switch between profiles.
+/// targets bound to more than one action in a context are shown in red
+pub struct KeybindsDialog {
+    pos: Vector2,
+    should_close: bool,
+    mouse_pos: Vector2,
+
+    context_index: usize,
+    /// action being rebound, and if the new input should be added instead of replacing the old ones
+    listening: Option<(usize, bool)>,
+
+    /// name for a new profile
+    profile_input: TextInput<Font2, Text>,
+}
+impl KeybindsDialog {
+    pub fn new() -> Self {
+        let window_size = WindowSize::get();
+
+        Self {
+            pos: (window_size.0 - BASE_SIZE) / 2.0,
+            should_close: false,
+            mouse_pos: Vector2::ZERO,
+
+            context_index: 0,
+            listening: None,
+
+            profile_input: TextInput::new(
+                Vector2::new(PADDING, BASE_SIZE.y - INPUT_SIZE.y - LINE_HEIGHT - PADDING),
+                INPUT_SIZE,
+                "New Profile Name",
+                "",
+                get_font()
+            ),
+        }
+    }
+
+    fn context(&self) -> &'static KeybindContext {
+        &KeybindContext::all()[self.context_index]
+    }
+
+    fn change_context(&mut self, delta: isize) {
+        let count = KeybindContext::all().len() as isize;
+        self.context_index = (self.context_index as isize + delta).rem_euclid(count) as usize;
+        self.listening = None;
+    }
+
+    /// bounds of a line, relative to the dialog
+    fn line_bounds(line: usize) -> Rectangle {
+        Rectangle::bounds_only(
+            Vector2::new(PADDING, PADDING + LINE_HEIGHT * line as f64),
+            Vector2::new(BASE_SIZE.x - PADDING * 2.0, LINE_HEIGHT)
+        )
+    }
+    /// which line is under `pos` (relative to the dialog)
+    fn line_at(&self, pos: Vector2) -> Option<usize> {
+        let lines = 3 + self.context().actions.len();
+        (0..lines).find(|i| Self::line_bounds(*i).contains(pos))
+    }
+
+    fn cycle_profile(&mut self) {
+        let context = &self.context().name;
+        let mut settings = get_settings_mut!();
+
+        let profiles = settings.keybinds.profile_names(context);
+        let current = profiles.iter().position(|p| p == settings.keybinds.active_profile(context)).unwrap_or_default();
+        let next = profiles[(current + 1) % profiles.len()].clone();
+        settings.keybinds.set_profile(context, next);
+    }
+
+    fn create_profile(&mut self) {
+        let name = self.profile_input.get_text().trim().to_owned();
+        if name.is_empty() { return }
+        self.profile_input.set_text(String::new());
+
+        get_settings_mut!().keybinds.new_profile(&self.context().name, name);
+    }
+
+    /// bind the action being listened for to `target`
+    fn bind(&mut self, target: InputTarget) {
+        let Some((index, add)) = self.listening.take() else { return };
+        let context = self.context();
+        let action = &context.actions[index].name;
+
+        let mut settings = get_settings_mut!();
+        let mut targets = if add { settings.keybinds.get_bindings(&context.name).get(action).to_vec() } else { Vec::new() };
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+        settings.keybinds.set_binding(&context.name, action, targets);
+    }
+}
+
+#[async_trait]
+impl Dialog<Game> for KeybindsDialog {
+    fn name(&self) -> &'static str { "keybinds" }
+    fn should_close(&self) -> bool { self.should_close }
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(self.pos, BASE_SIZE)
+    }
+
+    async fn draw(&mut self, args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
+        let font = get_font();
+        self.draw_background(*depth + 10.0, Color::BLACK, list);
+
+        let context = self.context();
+        let settings = get_settings!();
+        let bindings = settings.keybinds.get_bindings(&context.name);
+        let conflicts = bindings.conflicts();
+
+        // hover highlight
+        if let Some(line) = self.line_at(self.mouse_pos - self.pos) {
+            let bounds = Self::line_bounds(line);
+            list.push(Rectangle::new(Color::WHITE.alpha(0.1), *depth + 5.0, self.pos + bounds.pos, bounds.size, None));
+        }
+
+        let mut push_line = |line: usize, color: Color, text: String| {
+            let pos = self.pos + Self::line_bounds(line).pos;
+            list.push(Text::new(color, *depth, pos, 20, text, font.clone()));
+        };
+
+        push_line(0, Color::WHITE, format!("< {} >", context.display_name));
+        push_line(1, Color::WHITE, format!("Profile: {} (click to switch)", settings.keybinds.active_profile(&context.name)));
+
+        for (i, action) in context.actions.iter().enumerate() {
+            let targets = bindings.get(&action.name);
+            let conflicting = targets.iter().any(|t| conflicts.contains(t));
+
+            let (text, color) = match self.listening {
+                Some((index, _)) if index == i => (format!("{}: press an input (Esc to cancel, Delete to clear)", action.display_name), Color::YELLOW),
+                _ if targets.is_empty() => (format!("{}: Unbound", action.display_name), Color::WHITE.alpha(0.5)),
+                _ => {
+                    let targets = targets.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ");
+                    let suffix = if conflicting { " (conflict)" } else { "" };
+                    (format!("{}: {targets}{suffix}", action.display_name), if conflicting { Color::RED } else { Color::WHITE })
+                }
+            };
+            push_line(3 + i, color, text);
+        }
+
+        self.profile_input.draw(*args, self.pos, *depth, list);
+
+        let hint = "Left click: rebind, Right click: add binding, Enter: create profile, Esc: close";
+        list.push(Text::new(Color::WHITE, *depth, self.pos + Vector2::new(PADDING, BASE_SIZE.y - LINE_HEIGHT - 10.0), 16, hint.to_owned(), font.clone()));
+    }
+
+    async fn on_mouse_move(&mut self, pos:&Vector2, _g:&mut Game) {
+        self.mouse_pos = *pos;
+        self.profile_input.on_mouse_move(*pos - self.pos);
+    }
+
+    async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, _g:&mut Game) -> bool {
+        if self.listening.is_some() {
+            self.bind(InputTarget::Mouse(*button));
+            return true;
+        }
+
+        let pos = *pos - self.pos;
+        self.profile_input.on_click(pos, *button, *mods);
+
+        match self.line_at(pos) {
+            Some(0) if pos.x < BASE_SIZE.x / 2.0 => self.change_context(-1),
+            Some(0) => self.change_context(1),
+            Some(1) => self.cycle_profile(),
+            Some(2) | None => {}
+            Some(line) => self.listening = Some((line - 3, *button == MouseButton::Right)),
+        }
+
+        true
+    }
+
+    async fn on_text(&mut self, text:&String) -> bool {
+        if self.listening.is_none() {
+            self.profile_input.on_text(text.clone());
+        }
+        true
+    }
+
+    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, _g:&mut Game) -> bool {
+        if let Some((index, _)) = self.listening {
+            match key {
+                Key::Escape => self.listening = None,
+                Key::Delete => {
+                    self.listening = None;
+                    let context = self.context();
+                    get_settings_mut!().keybinds.set_binding(&context.name, &context.actions[index].name, Vec::new());
+                }
+                // wait to see if this is part of a combo, it gets bound by itself when its released
+                _ if is_modifier(key) => {}
+                _ => self.bind(InputTarget::key_with(*key, mods)),
+            }
+            return true;
+        }
+
+        match key {
+            Key::Escape => self.should_close = true,
+            Key::Return => self.create_profile(),
+            _ => self.profile_input.on_key_press(*key, *mods),
+        }
+
+        true
+    }
+    async fn on_key_release(&mut self, key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
+        // a modifier was pressed and released without anything else
+        if self.listening.is_some() && is_modifier(key) {
+            self.bind(InputTarget::Key(*key));
+        }
+
+        self.profile_input.on_key_release(*key);
+        true
+    }
+
+    async fn on_controller_press(&mut self, controller: &Box<dyn Controller>, button: u8) -> bool {
+        self.bind(InputTarget::controller(controller, button));
+        true
+    }
+    async fn on_controller_release(&mut self, _controller: &Box<dyn Controller>, _button: u8) -> bool {
+        true
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.pos = (window_size.0 - BASE_SIZE) / 2.0;
+    }
+}
+
+fn is_modifier(key: &Key) -> bool {
+    matches!(key, Key::LCtrl | Key::RCtrl | Key::LAlt | Key::RAlt | Key::LShift | Key::RShift)
+}
@@ -4,8 +4,12 @@ mod mods_dialog;
 mod skin_select;
 mod game_import;
 mod normal_dialog;
+mod practice_dialog;
+mod profile_dialog;
 mod beatmap_dialog;
 mod game_ui_editor;
+mod controller_calibration;
+mod keybinds_dialog;
 // mod changelog_dialog;
 
 pub use dialog::*;
@@ -14,6 +18,10 @@ pub use mods_dialog::*;
 pub use skin_select::*;
 pub use game_import::*;
 pub use normal_dialog::*;
+pub use practice_dialog::*;
+pub use profile_dialog::*;
 pub use beatmap_dialog::*;
 pub use game_ui_editor::*;
+pub use controller_calibration::*;
+pub use keybinds_dialog::*;
 // pub use changelog_dialog::*;
@@ -6,7 +6,7 @@ pub struct ModDialog {
 
     window_size: Arc<WindowSize>,
 
-    selected_index: usize
+    focus: FocusRing,
 }
 impl ModDialog {
     pub async fn new(groups: Vec<GameplayModGroup>) -> Self {
@@ -43,30 +43,12 @@ impl ModDialog {
             should_close: false,
             scroll,
             window_size,
-            selected_index: 0
+            focus: FocusRing::new(),
         }
     }
 
-    fn increment_index(&mut self) {
-        if self.scroll.items.len() == 0 { return } // should never happen but just to be safe
-
-        let old = self.selected_index;
-        self.selected_index = (self.selected_index + 1) % self.scroll.items.len();
-
-        self.scroll.items.get_mut(old).unwrap().set_selected(false);
-        self.scroll.items.get_mut(self.selected_index).unwrap().set_selected(true);
-    }
-    fn deincrement_index(&mut self) {
-        if self.scroll.items.len() == 0 { return } // should never happen but just to be safe
-
-        let old = self.selected_index;
-        self.selected_index = if self.selected_index == 0 { self.scroll.items.len() - 1 } else { self.selected_index - 1 };
-
-        self.scroll.items.get_mut(old).unwrap().set_selected(false);
-        self.scroll.items.get_mut(self.selected_index).unwrap().set_selected(true);
-    }
     fn toggle_current(&mut self) {
-        if let Some(i) = self.scroll.items.get_mut(self.selected_index) {
+        if let Some(i) = self.focus.focused().and_then(|i| self.scroll.items.get_mut(i)) {
             i.on_key_press(Key::Space, Default::default());
         }
     }
@@ -87,32 +69,31 @@ impl Dialog<Game> for ModDialog {
     async fn draw(&mut self, args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
         self.draw_background(depth + 1.00000001, Color::BLACK, list);
         self.scroll.draw(*args, Vector2::ZERO, *depth, list);
+        self.focus.draw(&FocusRing::item_bounds(&self.scroll.items), Vector2::ZERO, *depth, list);
     }
 
-    async fn on_key_press(&mut self, key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
-        match key {
-            Key::Escape => {
-                self.should_close = true;
-                true
-            }
-            Key::Up => {
-                self.deincrement_index();
-                true
-            }
-            Key::Down => {
-                self.increment_index();
-                true
-            }
-            Key::Space | Key::Return => {
-                self.toggle_current();
-                true
-            }
+    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, _g:&mut Game) -> bool {
+        if *key == Key::Space {
+            self.toggle_current();
+            return true;
+        }
 
-            _ => false
+        match NavigationInput::from_key(*key, mods) {
+            Some(NavigationInput::Back) => self.should_close = true,
+            Some(NavigationInput::Confirm) => self.toggle_current(),
+            Some(input) => {
+                let items = FocusRing::item_bounds(&self.scroll.items);
+                self.focus.navigate(input, &items);
+                self.focus.scroll_into_view(&mut self.scroll);
+            }
+            None => return false,
         }
+
+        true
     }
 
     async fn on_mouse_move(&mut self, pos:&Vector2, _g:&mut Game) {
+        self.focus.hide();
         self.scroll.on_mouse_move(*pos);
     }
 
@@ -135,21 +116,13 @@ impl Dialog<Game> for ModDialog {
         self.window_size = window_size;
     }
 
-
-    
     async fn on_controller_press(&mut self, controller: &Box<dyn Controller>, button: u8) -> bool {
-        let Some(button) = controller.map_button(button) else { return false };
-        
-        match button {
-            ControllerButton::A => self.toggle_current(),
-            ControllerButton::DPad_Up => self.deincrement_index(),
-            ControllerButton::DPad_Down => self.increment_index(),
-            
-            ControllerButton::B | ControllerButton::Start => self.should_close = true,
-
-            _ => {}
+        // everything else is handled as navigation
+        if let Some(ControllerButton::Start) = controller.map_button(button) {
+            self.should_close = true;
+            return true;
         }
-        true
+        false
     }
     async fn on_controller_release(&mut self, _controller: &Box<dyn Controller>, _button: u8) -> bool {
         true
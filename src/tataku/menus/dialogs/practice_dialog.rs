@@ -0,0 +1,244 @@
+use crate::prelude::*;
+
+const BASE_SIZE:Vector2 = Vector2::new(700.0, 420.0);
+const TIMELINE_POS:Vector2 = Vector2::new(20.0, 80.0);
+const TIMELINE_SIZE:Vector2 = Vector2::new(BASE_SIZE.x - 40.0, 40.0);
+const ITEM_SIZE:Vector2 = Vector2::new(BASE_SIZE.x - 40.0, 50.0);
+const BUTTON_SIZE:Vector2 = Vector2::new(100.0, 50.0);
+
+/// lets the user pick an A-B section, loop count and rate step before starting a practice session
+pub struct PracticeDialog {
+    pos: Vector2,
+    should_close: bool,
+
+    /// manager to start once the user is done
+    manager: Option<IngameManager>,
+    map_name: String,
+
+    /// timeline range (ms)
+    first_note: f32,
+    last_note: f32,
+
+    /// A-B points (ms)
+    start: f32,
+    end: f32,
+    mouse_pos: Vector2,
+
+    scroll: ScrollableArea,
+    start_button: MenuButton<Font2, Text>,
+    cancel_button: MenuButton<Font2, Text>,
+}
+impl PracticeDialog {
+    pub async fn new(map: &BeatmapMeta, playmode: PlayMode) -> TatakuResult<Self> {
+        let manager = manager_from_playmode(playmode, map).await?;
+        let window_size = WindowSize::get();
+        let font = get_font();
+
+        let last_note = manager.end_time;
+        let first_note = (last_note - map.duration).max(0.0);
+
+        let mut scroll = ScrollableArea::new(
+            TIMELINE_POS + TIMELINE_SIZE.y_portion() + Vector2::with_y(50.0),
+            Vector2::new(ITEM_SIZE.x, ITEM_SIZE.y * 2.0 + 10.0),
+            true
+        );
+        scroll.set_item_margin(5.0);
+
+        let mut loops = Slider::<Font2, Text>::new(Vector2::ZERO, ITEM_SIZE, "Loops (0 = forever)", 5.0, Some(0.0..20.0), None, font.clone());
+        loops.set_tag("loops");
+        scroll.add_item(Box::new(loops));
+
+        let mut rate_step = Slider::<Font2, Text>::new(Vector2::ZERO, ITEM_SIZE, "Speed up per clean loop (%)", 0.0, Some(0.0..20.0), None, font.clone());
+        rate_step.set_tag("rate_step");
+        scroll.add_item(Box::new(rate_step));
+
+        let start_button = MenuButton::<Font2, Text>::new(
+            Vector2::new(BASE_SIZE.x - (BUTTON_SIZE.x + 20.0) * 2.0, BASE_SIZE.y - BUTTON_SIZE.y - 10.0),
+            BUTTON_SIZE,
+            "Start",
+            font.clone()
+        );
+        let cancel_button = MenuButton::<Font2, Text>::new(
+            Vector2::new(BASE_SIZE.x - (BUTTON_SIZE.x + 20.0), BASE_SIZE.y - BUTTON_SIZE.y - 10.0),
+            BUTTON_SIZE,
+            "Cancel",
+            font.clone()
+        );
+
+        Ok(Self {
+            pos: (window_size.0 - BASE_SIZE) / 2.0,
+            should_close: false,
+
+            manager: Some(manager),
+            map_name: map.version_string(),
+
+            first_note,
+            last_note,
+            start: first_note,
+            end: last_note,
+            mouse_pos: Vector2::ZERO,
+
+            scroll,
+            start_button,
+            cancel_button,
+        })
+    }
+
+    fn timeline_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(self.pos + TIMELINE_POS, TIMELINE_SIZE)
+    }
+
+    /// convert a position on the timeline to a map time
+    fn time_at(&self, pos: Vector2) -> f32 {
+        let bounds = self.timeline_bounds();
+        let ratio = ((pos.x - bounds.pos.x) / bounds.size.x).clamp(0.0, 1.0) as f32;
+        self.first_note + (self.last_note - self.first_note) * ratio
+    }
+    /// convert a map time to an x position on the timeline
+    fn x_at(&self, time: f32) -> f64 {
+        let bounds = self.timeline_bounds();
+        let ratio = ((time - self.first_note) / (self.last_note - self.first_note)).clamp(0.0, 1.0) as f64;
+        bounds.pos.x + bounds.size.x * ratio
+    }
+
+    fn get_slider_value(&self, tag: &str) -> f64 {
+        self.scroll
+            .get_tagged(tag.to_owned())
+            .first()
+            .and_then(|i| i.get_value().downcast_ref::<f64>().cloned())
+            .unwrap_or_default()
+    }
+
+    fn start_practice(&mut self, game: &mut Game) {
+        let Some(mut manager) = std::mem::take(&mut self.manager) else { return };
+
+        manager.set_practice(PracticeSettings {
+            start: self.start.min(self.end),
+            end: self.end.max(self.start),
+            loop_count: self.get_slider_value("loops").round() as u32,
+            rate_step: self.get_slider_value("rate_step") as f32 / 100.0,
+        });
+
+        game.queue_state_change(GameState::Ingame(manager));
+        self.should_close = true;
+    }
+}
+
+fn format_time(time: f32) -> String {
+    let secs = (time / 1000.0).max(0.0);
+    let mins = (secs / 60.0).floor();
+    format!("{:02}:{:06.3}", mins, secs - mins * 60.0)
+}
+
+#[async_trait]
+impl Dialog<Game> for PracticeDialog {
+    fn name(&self) -> &'static str { "practice" }
+    fn should_close(&self) -> bool { self.should_close }
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(self.pos, BASE_SIZE)
+    }
+
+    async fn update(&mut self, _g:&mut Game) {
+        self.scroll.update();
+        self.start_button.update();
+        self.cancel_button.update();
+    }
+
+    async fn draw(&mut self, args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
+        let font = get_font();
+        self.draw_background(*depth + 10.0, Color::BLACK, list);
+
+        // title
+        list.push(Text::new(Color::WHITE, *depth, self.pos + Vector2::new(20.0, 10.0), 25, format!("Practice: {}", self.map_name), font.clone()));
+        list.push(Text::new(Color::WHITE, *depth, self.pos + Vector2::new(20.0, 45.0), 18, "Left click to set A, right click to set B".to_owned(), font.clone()));
+
+        // timeline
+        let bounds = self.timeline_bounds();
+        list.push(Rectangle::new(Color::WHITE.alpha(0.3), *depth, bounds.pos, bounds.size, Some(Border::new(Color::WHITE, 1.5))));
+
+        // selected section
+        let a = self.x_at(self.start.min(self.end));
+        let b = self.x_at(self.end.max(self.start));
+        list.push(Rectangle::new(Color::BLUE.alpha(0.5), *depth - 0.001, Vector2::new(a, bounds.pos.y), Vector2::new(b - a, bounds.size.y), None));
+
+        for (x, label) in [(a, "A"), (b, "B")] {
+            list.push(Line::new(Vector2::new(x, bounds.pos.y), Vector2::new(x, bounds.pos.y + bounds.size.y), 2.0, *depth - 0.002, Color::RED));
+            list.push(Text::new(Color::WHITE, *depth - 0.002, Vector2::new(x - 5.0, bounds.pos.y + bounds.size.y + 2.0), 18, label.to_owned(), font.clone()));
+        }
+
+        // hover time
+        if bounds.contains(self.mouse_pos) {
+            let x = self.mouse_pos.x;
+            list.push(Line::new(Vector2::new(x, bounds.pos.y), Vector2::new(x, bounds.pos.y + bounds.size.y), 1.0, *depth - 0.003, Color::WHITE));
+            list.push(Text::new(Color::WHITE, *depth - 0.003, Vector2::new(x, bounds.pos.y - 20.0), 15, format_time(self.time_at(self.mouse_pos)), font.clone()));
+        }
+
+        // section info
+        list.push(Text::new(
+            Color::WHITE,
+            *depth,
+            bounds.pos + bounds.size.y_portion() + Vector2::with_y(25.0),
+            18,
+            format!("A: {}  B: {}", format_time(self.start.min(self.end)), format_time(self.end.max(self.start))),
+            font.clone()
+        ));
+
+        self.scroll.draw(*args, self.pos, *depth, list);
+        self.start_button.draw(*args, self.pos, *depth, list);
+        self.cancel_button.draw(*args, self.pos, *depth, list);
+    }
+
+    async fn on_mouse_move(&mut self, pos:&Vector2, _g:&mut Game) {
+        self.mouse_pos = *pos;
+
+        let pos = *pos - self.pos;
+        self.scroll.on_mouse_move(pos);
+        self.start_button.on_mouse_move(pos);
+        self.cancel_button.on_mouse_move(pos);
+    }
+
+    async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, game:&mut Game) -> bool {
+        if self.timeline_bounds().contains(*pos) {
+            match button {
+                MouseButton::Left => self.start = self.time_at(*pos),
+                MouseButton::Right => self.end = self.time_at(*pos),
+                _ => {}
+            }
+            return true;
+        }
+
+        let pos = *pos - self.pos;
+        self.scroll.on_click(pos, *button, *mods);
+
+        if self.start_button.on_click(pos, *button, *mods) {
+            self.start_practice(game);
+        }
+        if self.cancel_button.on_click(pos, *button, *mods) {
+            self.should_close = true;
+        }
+
+        true
+    }
+
+    async fn on_mouse_up(&mut self, pos:&Vector2, button:&MouseButton, _mods:&KeyModifiers, _g:&mut Game) -> bool {
+        let pos = *pos - self.pos;
+        self.scroll.on_click_release(pos, *button);
+        self.start_button.on_click_release(pos, *button);
+        self.cancel_button.on_click_release(pos, *button);
+        true
+    }
+
+    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, game:&mut Game) -> bool {
+        match key {
+            Key::Escape => self.should_close = true,
+            Key::Return => self.start_practice(game),
+            _ => { self.scroll.on_key_press(*key, *mods); }
+        }
+
+        true
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.pos = (window_size.0 - BASE_SIZE) / 2.0;
+    }
+}
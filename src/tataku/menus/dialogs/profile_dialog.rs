@@ -0,0 +1,221 @@
+use crate::prelude::*;
+
+const PADDING:Vector2 = Vector2::new(20.0, 20.0);
+const TITLE_SIZE:u32 = 30;
+const ITEM_SIZE:u32 = 20;
+const ITEM_HEIGHT:f64 = 30.0;
+
+/// a top play, with everything needed to display it
+struct TopPlay {
+    map_name: String,
+    performance: f32,
+    weight: f32,
+    accuracy: f64,
+    mods: String,
+}
+
+/// shows the local rating and top plays for a playmode
+pub struct ProfileDialog {
+    should_close: bool,
+    window_size: Arc<WindowSize>,
+
+    playmode: PlayMode,
+    top_plays: Vec<TopPlay>,
+    loader: Option<AsyncLoader<Vec<TopPlay>>>,
+
+    /// how many scores are (pending, rejected)
+    submissions: (usize, usize, usize),
+    submissions_loader: Option<AsyncLoader<(usize, usize, usize)>>,
+
+    scroll_offset: f64,
+}
+impl ProfileDialog {
+    pub fn new(playmode: PlayMode) -> Self {
+        let mut s = Self {
+            should_close: false,
+            window_size: WindowSize::get(),
+
+            playmode,
+            top_plays: Vec::new(),
+            loader: None,
+
+            submissions: (0, 0, 0),
+            submissions_loader: None,
+
+            scroll_offset: 0.0,
+        };
+        s.load_top_plays();
+        s.load_submissions();
+        s
+    }
+
+    fn load_top_plays(&mut self) {
+        self.top_plays.clear();
+        self.scroll_offset = 0.0;
+
+        let playmode = self.playmode.clone();
+        self.loader = Some(AsyncLoader::new(async move {
+            let Some(info) = get_gamemode_info(&playmode) else { return Vec::new() };
+
+            let scores:HashMap<String, Score> = Database::get_scores_for_playmode(&playmode)
+                .await
+                .into_iter()
+                .map(|s| (s.hash(), s))
+                .collect();
+
+            let manager = BEATMAP_MANAGER.read().await;
+            Database::get_best_performances(&playmode)
+                .await
+                .into_iter()
+                .enumerate()
+                .map(|(i, best)| {
+                    let score = scores.get(&best.score_hash);
+                    TopPlay {
+                        map_name: manager.get_by_hash(&best.map_hash).map(|m| m.version_string()).unwrap_or_else(|| "Unknown map".to_owned()),
+                        performance: best.performance,
+                        weight: info.rating_weight(i),
+                        accuracy: score.map(calc_acc).unwrap_or_default(),
+                        mods: score.map(|s| ModManager::short_mods_string(s.mods(), false, &playmode)).unwrap_or_default(),
+                    }
+                })
+                .collect()
+        }));
+    }
+
+    fn load_submissions(&mut self) {
+        self.submissions_loader = Some(AsyncLoader::new(Database::get_submission_counts()));
+    }
+
+    fn resubmit_all(&mut self) {
+        self.submissions_loader = Some(AsyncLoader::new(async {
+            ScoreSubmitHelper::resubmit_all().await;
+            Database::get_submission_counts().await
+        }));
+    }
+
+    fn change_playmode(&mut self, offset: isize) {
+        let count = AVAILABLE_PLAYMODES.len() as isize;
+        let current = AVAILABLE_PLAYMODES.iter().position(|m| *m == self.playmode).unwrap_or_default() as isize;
+        let index = (current + offset).rem_euclid(count) as usize;
+
+        self.playmode = AVAILABLE_PLAYMODES[index].to_owned();
+        self.load_top_plays();
+    }
+}
+
+#[async_trait]
+impl Dialog<Game> for ProfileDialog {
+    fn name(&self) -> &'static str { "profile" }
+    fn should_close(&self) -> bool { self.should_close }
+    fn get_bounds(&self) -> Rectangle {
+        Rectangle::bounds_only(Vector2::ZERO, self.window_size.0)
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.window_size = window_size;
+    }
+
+    async fn update(&mut self, _g:&mut Game) {
+        if let Some(loader) = &self.loader {
+            if let Some(top_plays) = loader.check().await {
+                self.top_plays = top_plays;
+                self.loader = None;
+            }
+        }
+        if let Some(loader) = &self.submissions_loader {
+            if let Some(submissions) = loader.check().await {
+                self.submissions = submissions;
+                self.submissions_loader = None;
+            }
+        }
+    }
+
+    async fn draw(&mut self, _args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
+        let font = get_font();
+        self.draw_background(*depth + 10.0, Color::BLACK, list);
+
+        let rating = RatingHelper::get_rating(&self.playmode)
+            .map(|r| format!("{}pr", format_float(r, 2)))
+            .unwrap_or_else(|| "...".to_owned());
+
+        list.push(Text::new(
+            Color::WHITE,
+            *depth,
+            PADDING,
+            TITLE_SIZE,
+            format!("{} ({}) - Rating: {rating}", get_settings!().username, gamemode_display_name(&self.playmode)),
+            font.clone()
+        ));
+        list.push(Text::new(
+            Color::WHITE,
+            *depth,
+            PADDING + Vector2::with_y(TITLE_SIZE as f64 + 5.0),
+            15,
+            "Left/Right to change mode, R to resubmit scores, Escape to close".to_owned(),
+            font.clone()
+        ));
+
+        let (pending, rejected, failed) = self.submissions;
+        if pending + rejected + failed > 0 {
+            list.push(Text::new(
+                Color::WHITE,
+                *depth,
+                PADDING + Vector2::with_y(TITLE_SIZE as f64 + 25.0),
+                15,
+                format!("{pending} score(s) waiting to be submitted, {rejected} rejected, {failed} couldn't be sent"),
+                font.clone()
+            ));
+        }
+
+        let list_top = PADDING.y + TITLE_SIZE as f64 + 50.0;
+        if self.loader.is_some() {
+            list.push(Text::new(Color::WHITE, *depth, Vector2::new(PADDING.x, list_top), ITEM_SIZE, "Loading...".to_owned(), font.clone()));
+            return;
+        }
+        if self.top_plays.is_empty() {
+            list.push(Text::new(Color::WHITE, *depth, Vector2::new(PADDING.x, list_top), ITEM_SIZE, "No plays yet".to_owned(), font.clone()));
+            return;
+        }
+
+        for (i, play) in self.top_plays.iter().enumerate() {
+            let y = list_top + i as f64 * ITEM_HEIGHT - self.scroll_offset;
+            if y < list_top - ITEM_HEIGHT { continue }
+            if y > self.window_size.0.y { break }
+
+            let mods = if play.mods.is_empty() { String::new() } else { format!(" +{}", play.mods) };
+            list.push(Text::new(
+                Color::WHITE,
+                *depth,
+                Vector2::new(PADDING.x, y),
+                ITEM_SIZE,
+                format!(
+                    "#{} {}{mods} | {:.2}% | {:.2}pr (weighted {:.2}pr, {:.0}%)",
+                    i + 1,
+                    play.map_name,
+                    play.accuracy * 100.0,
+                    play.performance,
+                    play.performance * play.weight,
+                    play.weight * 100.0
+                ),
+                font.clone()
+            ));
+        }
+    }
+
+    async fn on_mouse_scroll(&mut self, delta:&f64, _g:&mut Game) -> bool {
+        let max = (self.top_plays.len() as f64 - 1.0).max(0.0) * ITEM_HEIGHT;
+        self.scroll_offset = (self.scroll_offset - delta * ITEM_HEIGHT).clamp(0.0, max);
+        true
+    }
+
+    async fn on_key_press(&mut self, key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
+        match key {
+            Key::Escape => self.should_close = true,
+            Key::Left => self.change_playmode(-1),
+            Key::Right => self.change_playmode(1),
+            Key::R => self.resubmit_all(),
+            _ => {}
+        }
+        true
+    }
+}
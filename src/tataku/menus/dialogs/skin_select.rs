@@ -14,25 +14,45 @@ lazy_static::lazy_static! {
 pub struct SkinSelect {
     should_close: bool,
     dropdown: Dropdown<SkinDropdownable, Font2, Text>,
-    current_skin: String
+    current_skin: String,
+    /// only the dropdown can be focused, this just shows that the keyboard/controller is being used
+    focus: FocusRing,
 }
 impl SkinSelect {
     pub async fn new() -> Self {
         let current_skin = get_settings!().current_skin.clone();
         Self {
-            dropdown: Dropdown::new(
-                Vector2::new(300.0, 200.0),
-                500.0,
-                FontSize::new(20.0).unwrap(),
-                "Skin",
-                Some(SkinDropdownable::Skin(current_skin.clone())),
-                get_font()
-            ),
+            dropdown: Self::make_dropdown(&current_skin),
             current_skin,
             should_close: false,
+            focus: FocusRing::new(),
         }
     }
 
+    fn make_dropdown(skin: &String) -> Dropdown<SkinDropdownable, Font2, Text> {
+        Dropdown::new(
+            Vector2::new(300.0, 200.0),
+            500.0,
+            FontSize::new(20.0).unwrap(),
+            "Skin",
+            Some(SkinDropdownable::Skin(skin.clone())),
+            get_font()
+        )
+    }
+
+    /// switch to the skin `delta` away from the current one in the list
+    async fn cycle_skin(&mut self, delta: isize) {
+        let current = SKINS.iter().position(|s| s == &self.current_skin).unwrap_or_default() as isize;
+        let next = &SKINS[(current + delta).rem_euclid(SKINS.len() as isize) as usize];
+
+        self.dropdown = Self::make_dropdown(next);
+        self.check_skin_change().await;
+    }
+
+    fn dropdown_bounds(&self) -> [Rectangle; 1] {
+        [Rectangle::bounds_only(self.dropdown.get_pos(), self.dropdown.size())]
+    }
+
     async fn check_skin_change(&mut self) {
         let selected = self.dropdown.get_value().downcast::<Option<SkinDropdownable>>();
         if let Ok(s) = selected {
@@ -58,7 +78,8 @@ impl Dialog<Game> for SkinSelect {
     
     async fn draw(&mut self, args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
         self.draw_background(*depth, Color::WHITE, list);
-        self.dropdown.draw(*args, Vector2::ZERO, *depth, list)
+        self.dropdown.draw(*args, Vector2::ZERO, *depth, list);
+        self.focus.draw(&self.dropdown_bounds(), Vector2::ZERO, *depth, list);
     }
 
     async fn update(&mut self, _g:&mut Game) {
@@ -66,6 +87,7 @@ impl Dialog<Game> for SkinSelect {
     }
 
     async fn on_mouse_move(&mut self, p:&Vector2, _g:&mut Game) {
+        self.focus.hide();
         self.dropdown.on_mouse_move(*p)
     }
 
@@ -88,9 +110,17 @@ impl Dialog<Game> for SkinSelect {
         true
     }
 
-    async fn on_key_press(&mut self, key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
-        if key == &Key::Escape {self.should_close = true}
-        
+    async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, _g:&mut Game) -> bool {
+        let Some(input) = NavigationInput::from_key(*key, mods) else { return true };
+
+        match input {
+            NavigationInput::Back | NavigationInput::Confirm => self.should_close = true,
+            // the first input just shows where focus is
+            _ if self.focus.focused().is_none() => self.focus.focus(0),
+            NavigationInput::Up | NavigationInput::Left | NavigationInput::Previous => self.cycle_skin(-1).await,
+            NavigationInput::Down | NavigationInput::Right | NavigationInput::Next => self.cycle_skin(1).await,
+        }
+
         true
     }
     async fn on_key_release(&mut self, _key:&Key, _mods:&KeyModifiers, _g:&mut Game) -> bool {
@@ -0,0 +1,130 @@
+use crate::prelude::*;
+
+const LINK_START:&str = "[tataku://beatmap/";
+
+/// a link to a beatmap in a chat message, written as `[tataku://beatmap/<hash>/<playmode> <label>]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeatmapLink {
+    pub hash: String,
+    /// artist - title [version]
+    pub label: String,
+    pub playmode: PlayMode,
+}
+impl BeatmapLink {
+    pub fn new(map: &BeatmapMeta) -> Self {
+        Self {
+            hash: map.beatmap_hash.clone(),
+            label: map.version_string(),
+            playmode: map.mode.clone(),
+        }
+    }
+
+    /// find the first beatmap link in some text, and where it is
+    pub fn find(text: &str) -> Option<(Self, std::ops::Range<usize>)> {
+        let start = text.find(LINK_START)?;
+        let rest = &text[start + LINK_START.len()..];
+
+        // the label can have brackets in it (the difficulty name), so find the bracket that actually closes the link
+        let mut depth = 1;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+
+        let (target, label) = rest[..end].split_once(' ')?;
+        // the playmode is optional, since it isnt needed to find the map
+        let (hash, playmode) = target.split_once('/').unwrap_or((target, "osu"));
+        if hash.is_empty() { return None }
+
+        let link = Self {
+            hash: hash.to_owned(),
+            label: label.to_owned(),
+            playmode: playmode.to_owned(),
+        };
+        Some((link, start..start + LINK_START.len() + end + 1))
+    }
+
+    /// replace the link in some text with just its label
+    pub fn display_text(text: &str) -> String {
+        match Self::find(text) {
+            Some((link, range)) => format!("{}{}{}", &text[..range.start], link.label, &text[range.end..]),
+            None => text.to_owned(),
+        }
+    }
+
+    /// open the map in song select if we have it, otherwise search for it in direct
+    pub async fn open(&self, game: &mut Game) {
+        let map = BEATMAP_MANAGER.read().await.get_by_hash(&self.hash);
+        match map {
+            Some(map) => {
+                BEATMAP_MANAGER.write().await.set_current_beatmap(game, &map, true).await;
+                let menu = game.menus.get("beatmap").unwrap().clone();
+                game.queue_state_change(GameState::InMenu(menu));
+            }
+            None => {
+                // direct searches sets, so leave out the difficulty name
+                let search = self.label.rsplit_once(" [").map(|(s, _)| s).unwrap_or(&self.label).to_owned();
+                let menu = DirectMenu::with_search(self.playmode.clone(), search).await;
+                game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(menu))));
+            }
+        }
+    }
+}
+impl Display for BeatmapLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{LINK_START}{}/{} {}]", self.hash, self.playmode, self.label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_a_link() {
+        let text = "playing [tataku://beatmap/abc/taiko Artist - Title] now";
+        let (link, range) = BeatmapLink::find(text).unwrap();
+
+        assert_eq!(link, BeatmapLink { hash: "abc".to_owned(), label: "Artist - Title".to_owned(), playmode: "taiko".to_owned() });
+        assert_eq!(&text[range], "[tataku://beatmap/abc/taiko Artist - Title]");
+    }
+
+    #[test]
+    fn nested_brackets() {
+        let text = "[tataku://beatmap/abc/osu Artist - Title [Insane [v2]]] and [something else]";
+        let (link, range) = BeatmapLink::find(text).unwrap();
+
+        assert_eq!(link.label, "Artist - Title [Insane [v2]]");
+        assert_eq!(range, 0..55);
+        assert_eq!(BeatmapLink::display_text(text), "Artist - Title [Insane [v2]] and [something else]");
+    }
+
+    #[test]
+    fn playmode_is_optional() {
+        let (link, _) = BeatmapLink::find("[tataku://beatmap/abc Artist - Title]").unwrap();
+        assert_eq!(link.hash, "abc");
+        assert_eq!(link.playmode, "osu");
+    }
+
+    #[test]
+    fn broken_links() {
+        assert!(BeatmapLink::find("no link here").is_none());
+        // never closed
+        assert!(BeatmapLink::find("[tataku://beatmap/abc/osu Artist - Title [Insane]").is_none());
+        // no label
+        assert!(BeatmapLink::find("[tataku://beatmap/abc/osu]").is_none());
+        // no hash
+        assert!(BeatmapLink::find("[tataku://beatmap/ Artist - Title]").is_none());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let link = BeatmapLink { hash: "abc".to_owned(), label: "Artist - Title [Hard]".to_owned(), playmode: "mania".to_owned() };
+        let (found, _) = BeatmapLink::find(&format!("look at {link}!")).unwrap();
+        assert_eq!(found, link);
+    }
+}
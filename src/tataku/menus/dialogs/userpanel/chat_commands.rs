@@ -0,0 +1,100 @@
+/// what was typed into the chat box
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatCommand {
+    /// a normal message
+    Message(String),
+    /// /join #channel
+    Join(String),
+    /// /part [#channel], leaves the current channel if none is given
+    Part(Option<String>),
+    /// /me does something
+    Me(String),
+    /// /np, sends the current map
+    NowPlaying,
+    /// /msg user message
+    Msg { username: String, message: String },
+    /// /ignore user, or unignore if they're already ignored
+    Ignore(String),
+}
+impl ChatCommand {
+    pub const HELP: &'static str = "Commands: /join #channel, /part [#channel], /me <action>, /np, /msg <user> <message>, /ignore <user>";
+
+    /// parse what was typed. errors are meant to be shown to the user
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        // "//" to send something starting with a slash
+        if let Some(text) = text.strip_prefix("//") {
+            return Ok(Self::Message(format!("/{text}")))
+        }
+        let Some(command) = text.strip_prefix('/') else { return Ok(Self::Message(text.to_owned())) };
+
+        let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+        let args = args.trim();
+
+        match &*name.to_lowercase() {
+            "join" | "j" if !args.is_empty() => Ok(Self::Join(Self::channel_name(args))),
+            "part" | "leave" | "close" => Ok(Self::Part((!args.is_empty()).then(|| Self::channel_name(args)))),
+            "me" if !args.is_empty() => Ok(Self::Me(args.to_owned())),
+            "np" => Ok(Self::NowPlaying),
+            "msg" | "query" => match args.split_once(' ') {
+                Some((username, message)) => Ok(Self::Msg { username: username.to_owned(), message: message.trim().to_owned() }),
+                None if !args.is_empty() => Ok(Self::Msg { username: args.to_owned(), message: String::new() }),
+                None => Err("Usage: /msg <user> <message>".to_owned()),
+            },
+            "ignore" if !args.is_empty() => Ok(Self::Ignore(args.to_owned())),
+
+            "join" | "j" => Err("Usage: /join #channel".to_owned()),
+            "me" => Err("Usage: /me <action>".to_owned()),
+            "ignore" => Err("Usage: /ignore <user>".to_owned()),
+            other => Err(format!("Unknown command /{other}. {}", Self::HELP)),
+        }
+    }
+
+    /// channels always start with #
+    fn channel_name(name: &str) -> String {
+        format!("#{}", name.trim_start_matches('#'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        assert_eq!(ChatCommand::parse("  hello  "), Ok(ChatCommand::Message("hello".to_owned())));
+        assert_eq!(ChatCommand::parse("//not a command"), Ok(ChatCommand::Message("/not a command".to_owned())));
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(ChatCommand::parse("/join osu"), Ok(ChatCommand::Join("#osu".to_owned())));
+        assert_eq!(ChatCommand::parse("/J #osu"), Ok(ChatCommand::Join("#osu".to_owned())));
+        assert!(ChatCommand::parse("/join").is_err());
+
+        assert_eq!(ChatCommand::parse("/part"), Ok(ChatCommand::Part(None)));
+        assert_eq!(ChatCommand::parse("/leave osu"), Ok(ChatCommand::Part(Some("#osu".to_owned()))));
+    }
+
+    #[test]
+    fn actions() {
+        assert_eq!(ChatCommand::parse("/me waves"), Ok(ChatCommand::Me("waves".to_owned())));
+        assert!(ChatCommand::parse("/me").is_err());
+        assert_eq!(ChatCommand::parse("/np"), Ok(ChatCommand::NowPlaying));
+    }
+
+    #[test]
+    fn users() {
+        assert_eq!(ChatCommand::parse("/msg someone hi there"), Ok(ChatCommand::Msg { username: "someone".to_owned(), message: "hi there".to_owned() }));
+        assert_eq!(ChatCommand::parse("/query someone"), Ok(ChatCommand::Msg { username: "someone".to_owned(), message: String::new() }));
+        assert!(ChatCommand::parse("/msg").is_err());
+
+        assert_eq!(ChatCommand::parse("/ignore someone"), Ok(ChatCommand::Ignore("someone".to_owned())));
+        assert!(ChatCommand::parse("/ignore").is_err());
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert!(ChatCommand::parse("/dance").is_err());
+    }
+}
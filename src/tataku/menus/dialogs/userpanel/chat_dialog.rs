@@ -10,10 +10,15 @@ const INPUT_HEIGHT:f64 = 45.0;
 /// how many pixels away from the thing can it be to resize?
 const RESIZE_LENIENCE: f64 = 3.0;
 
+/// is the chat open? if not, mentions and direct messages show a notification
+pub static CHAT_OPEN: AtomicBool = AtomicBool::new(false);
+
 
 pub struct Chat {
-    // messages
-    messages: HashMap<ChatChannel, Vec<ChatMessage>>,
+    /// messages in the list for the selected channel
+    shown_messages: Vec<ChatMessage>,
+    /// channels in the list, with their unread count and if we were mentioned
+    channels: Vec<(ChatChannel, usize, bool)>,
     // if the chat is visible or not
     should_close: bool,
 
@@ -24,8 +29,6 @@ pub struct Chat {
 
     pub selected_channel: Option<ChatChannel>,
 
-    // TODO: unread messages
-
     // sizes
     pub chat_height: f64,
     pub channel_list_width: f64,
@@ -58,9 +61,12 @@ impl Chat {
             get_font(),
         );
         
+        CHAT_OPEN.store(true, SeqCst);
+        OnlineManager::load_chat_history();
+
         Self {
-            // [channels][messages]
-            messages:HashMap::new(),
+            shown_messages: Vec::new(),
+            channels: Vec::new(),
             selected_channel: None,
             should_close: false,
 
@@ -87,6 +93,100 @@ impl Chat {
         // do a negative max scroll
         self.message_scroll.on_scroll(-f64::MAX);
     }
+
+    /// is there a message being typed? if so, enter sends it instead of doing anything else
+    pub fn is_typing(&self) -> bool {
+        !self.input.get_text().is_empty()
+    }
+
+    pub fn select_channel(&mut self, channel: ChatChannel) {
+        self.selected_channel = Some(channel);
+        // the messages get filled in on the next update
+        self.shown_messages.clear();
+        self.message_scroll.clear();
+    }
+
+    fn add_message(&mut self, message: ChatMessage) {
+        let username = get_settings!().username.clone();
+        self.message_scroll.add_item(Box::new(MessageScroll::new(
+            message.clone(),
+            message.mentions(&username),
+            self.window_size.x - self.channel_list_width,
+            30
+        )));
+        self.shown_messages.push(message);
+    }
+
+    /// run whatever was typed into the chat box
+    async fn run_command(&mut self, command: Result<ChatCommand, String>) {
+        let current = self.selected_channel.clone();
+        let command = match command {
+            Ok(command) => command,
+            Err(e) => {
+                if let Some(channel) = current { OnlineManager::system_message(channel, e) }
+                return;
+            }
+        };
+
+        match command {
+            ChatCommand::Message(text) => if let Some(channel) = current {
+                OnlineManager::send_message(channel, text);
+            }
+
+            ChatCommand::Join(name) => {
+                OnlineManager::join_channel(name.clone());
+                self.select_channel(ChatChannel::from_name(name));
+            }
+            ChatCommand::Part(name) => {
+                let Some(channel) = name.map(ChatChannel::from_name).or(current) else { return };
+                if self.selected_channel.as_ref() == Some(&channel) {
+                    self.selected_channel = None;
+                    self.shown_messages.clear();
+                    self.message_scroll.clear();
+                }
+                OnlineManager::leave_channel(channel);
+            }
+
+            ChatCommand::Me(action) => if let Some(channel) = current {
+                OnlineManager::send_message(channel, ChatMessage::action(&action));
+            }
+            ChatCommand::NowPlaying => {
+                let Some(channel) = current else { return };
+                match BEATMAP_MANAGER.read().await.current_beatmap.clone() {
+                    Some(map) => OnlineManager::send_message(channel, ChatMessage::action(&format!("is listening to {}", BeatmapLink::new(&map)))),
+                    None => OnlineManager::system_message(channel, "No map selected".to_owned()),
+                }
+            }
+
+            ChatCommand::Msg { username, message } => {
+                let channel = ChatChannel::User { username };
+                if !message.is_empty() {
+                    OnlineManager::send_message(channel.clone(), message);
+                }
+                self.select_channel(channel);
+            }
+
+            ChatCommand::Ignore(username) => {
+                let ignored = {
+                    let mut settings = get_settings_mut!();
+                    let list = &mut settings.ignored_users;
+                    match list.iter().position(|u| u.eq_ignore_ascii_case(&username)) {
+                        Some(i) => { list.remove(i); false }
+                        None => { list.push(username.clone()); true }
+                    }
+                };
+
+                let text = if ignored { format!("Ignoring {username}. /ignore them again to stop") } else { format!("No longer ignoring {username}") };
+                let channel = current.unwrap_or(ChatChannel::User { username });
+                OnlineManager::system_message(channel, text);
+            }
+        }
+    }
+}
+impl Drop for Chat {
+    fn drop(&mut self) {
+        CHAT_OPEN.store(false, SeqCst);
+    }
 }
 
 #[async_trait]
@@ -117,15 +217,8 @@ impl Dialog<Game> for Chat {
             let send_text = self.input.get_text();
             self.input.set_text(String::new());
 
-            if let Some(channel) = self.selected_channel.clone() {
-                let clone = ONLINE_MANAGER.clone();
-                tokio::spawn(async move {
-                    let man = clone.read().await;
-                    send_packet!(man.writer, create_packet!(PacketId::Client_SendMessage {
-                        channel: channel.get_name(),
-                        message: send_text
-                    }));
-                });
+            if !send_text.trim().is_empty() {
+                self.run_command(ChatCommand::parse(&send_text)).await;
             }
             return true;
         }
@@ -143,37 +236,28 @@ impl Dialog<Game> for Chat {
         true
     }
 
-    async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, _g:&mut Game) -> bool {
+    async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, game:&mut Game) -> bool {
         // check if a channel was clicked
         if let Some(channel_name) = self.channel_scroll.on_click_tagged(*pos, *button, *mods) {
-
-            // find the channel name in the list
-            for (channel, message_list) in self.messages.iter() {
-                if channel.get_name() != channel_name {continue}
-
-                // set our current channel
-                self.selected_channel = Some(channel.clone());
-
-                // clear old messages
-                self.message_scroll.clear();
-
-                for m in message_list.iter() {
-                    self.message_scroll.add_item(Box::new(MessageScroll::new(
-                        m.clone(),
-                        self.window_size.x - self.channel_list_width,
-                        30
-                    )));
-                }
+            let channel = self.channels.iter().find(|(c, ..)| c.get_name() == channel_name).map(|(c, ..)| c.clone());
+            if let Some(channel) = channel {
+                self.select_channel(channel);
             }
 
-            // scroll to the bottom
-            self.scroll_to_new_message();
-
             return true;
         }
 
+        // messages are tagged with the hash of the map they link to
+        if let Some(hash) = self.message_scroll.on_click_tagged(*pos, *button, *mods) {
+            let link = self.shown_messages.iter().find_map(|m| m.link().filter(|l| l.hash == hash));
+            if let Some(link) = link {
+                link.open(game).await;
+                self.should_close = true;
+                return true;
+            }
+        }
+
         self.input.on_click(*pos, *button, *mods);
-        //TODO: check messages click?
 
         if self.height_resize_hover {
             self.height_resize = true;
@@ -253,67 +337,64 @@ impl Dialog<Game> for Chat {
 
     async fn update(&mut self, _g:&mut Game) {
         if let Ok(mut online_manager) = ONLINE_MANAGER.try_write() {
-            let mut scroll_pending = false;
-
             if let Some(selected_channel) = &self.selected_channel {
-                if !online_manager.chat_messages.contains_key(selected_channel) {
-                    online_manager.chat_messages.insert(selected_channel.clone(), Vec::new());
-                }
-
-                // ensure the selected channel is actually selected
-                let selected_name = selected_channel.get_name();
-                for i in self.channel_scroll.items.iter_mut() {
-                    if i.get_selected() && i.get_tag() != selected_name {
-                        i.set_selected(false)
-                    }
-
-                    if !i.get_selected() && i.get_tag() == selected_name {
-                        i.set_selected(true)
-                    }
-                }
+                online_manager.chat_messages.entry(selected_channel.clone()).or_default();
 
+                // its being looked at, so its been read
+                online_manager.chat_unread.remove(selected_channel);
+                online_manager.chat_mentions.remove(selected_channel);
             }
 
-            // get chat messages
-            for (channel, messages) in online_manager.chat_messages.iter() {
-                if !self.messages.contains_key(channel) {
-                    self.messages.insert(channel.clone(), messages.clone());
+            // rebuild the channel list if anything changed
+            let mut channels = online_manager.chat_messages
+                .keys()
+                .map(|c| (c.clone(), online_manager.chat_unread.get(c).copied().unwrap_or_default(), online_manager.chat_mentions.contains(c)))
+                .collect::<Vec<_>>();
+            channels.sort_by_key(|(c, ..)| c.get_name());
+
+            if channels != self.channels {
+                self.channel_scroll.clear();
+                for (channel, unread, mentioned) in channels.iter() {
                     self.channel_scroll.add_item(Box::new(ChannelScroll::new(
-                        channel.clone(), 
-                        self.channel_list_width, 
+                        channel.clone(),
+                        *unread,
+                        *mentioned,
+                        self.channel_list_width,
                         30
                     )));
-                    continue;
                 }
+                self.channels = channels;
+            }
 
-                // update the messages list if there was a new message in the currently selected channel
-                if let Some(current_channel) = &self.selected_channel {
-                    if channel.get_name() == current_channel.get_name() {
-                        let cached_messages = self.messages.get_mut(channel).unwrap();
-
-                        let window_size = self.window_size.0;
-                        for message in online_manager.chat_messages.get(channel).unwrap() {
-                            if !cached_messages.contains(message) {
-                                // cached_messages.push(message.clone())
-                                self.message_scroll.add_item(Box::new(MessageScroll::new(
-                                    message.clone(),
-                                    window_size.x - self.channel_list_width,
-                                    30
-                                )));
-                                scroll_pending = true;
-                            }
-                        }
-                    }
+            // ensure the selected channel is actually selected
+            let selected_name = self.selected_channel.as_ref().map(|c| c.get_name());
+            for i in self.channel_scroll.items.iter_mut() {
+                let selected = Some(i.get_tag()) == selected_name;
+                if i.get_selected() != selected {
+                    i.set_selected(selected)
                 }
             }
 
+            // add any new messages in the selected channel
+            if let Some(selected_channel) = self.selected_channel.clone() {
+                let messages = online_manager.chat_messages.get(&selected_channel).cloned().unwrap_or_default();
+                drop(online_manager);
 
-            // scroll to the bottom
-            if scroll_pending {
-                self.scroll_to_new_message();
-            }
+                // history might have been loaded in before what we have, so start over if so
+                if !messages.starts_with(&self.shown_messages) {
+                    self.shown_messages.clear();
+                    self.message_scroll.clear();
+                }
 
-            self.messages = online_manager.chat_messages.clone();
+                if messages.len() > self.shown_messages.len() {
+                    for message in messages[self.shown_messages.len()..].iter() {
+                        self.add_message(message.clone());
+                    }
+
+                    // scroll to the bottom
+                    self.scroll_to_new_message();
+                }
+            }
         }
 
         // ensure input is always accepting input
@@ -404,13 +485,52 @@ impl ChatMessage {
     pub fn get_formatted_text(&self) -> String {
         let timestamp = self.format_time();
 
+        if self.is_action() {
+            return format!("{} * {} {}", timestamp, self.sender, self.display_text())
+        }
+
         format!(
             "{} {}: {}",
             timestamp,
             self.sender,
-            self.text
+            self.display_text()
         )
     }
+
+    /// make the text for a /me message
+    pub fn action(text: &str) -> String {
+        format!("\u{1}ACTION {text}\u{1}")
+    }
+    pub fn is_action(&self) -> bool {
+        self.text.starts_with("\u{1}ACTION ")
+    }
+
+    /// the text without any formatting, ie what the action was, or links replaced with their label
+    pub fn display_text(&self) -> String {
+        let text = self.text
+            .strip_prefix("\u{1}ACTION ")
+            .map(|t| t.trim_end_matches('\u{1}'))
+            .unwrap_or(&self.text);
+        BeatmapLink::display_text(text)
+    }
+
+    pub fn link(&self) -> Option<BeatmapLink> {
+        BeatmapLink::find(&self.text).map(|(link, _)| link)
+    }
+
+    /// does this message mention `username`? (not counting our own messages)
+    pub fn mentions(&self, username: &String) -> bool {
+        if username.is_empty() || &self.sender == username { return false }
+
+        let username = username.to_lowercase();
+        let text = self.display_text().to_lowercase();
+        let is_boundary = |c: Option<char>| c.map(|c| !c.is_alphanumeric() && c != '_').unwrap_or(true);
+
+        // make sure its the whole name, and not part of another word
+        text.match_indices(&username).any(|(i, _)| {
+            is_boundary(text[..i].chars().next_back()) && is_boundary(text[i + username.len()..].chars().next())
+        })
+    }
 }
 
 // some kind of identifier
@@ -422,7 +542,7 @@ pub enum ChatChannel {
 impl ChatChannel {
     pub fn from_name(name:String) -> ChatChannel {
         if name.starts_with("#") {
-            ChatChannel::Channel{name}
+            ChatChannel::Channel{name: name.trim_start_matches('#').to_owned()}
         } else {
             ChatChannel::User{username: name}
         }
@@ -446,14 +566,19 @@ struct ChannelScroll {
     tag: String,
 
     channel: ChatChannel,
+    unread: usize,
+    /// is there an unread message mentioning us
+    mentioned: bool,
     font_size: FontSize,
     font: Font2,
 }
 impl ChannelScroll {
-    fn new(channel: ChatChannel, width: f64, font_size: u32) -> Self {
+    fn new(channel: ChatChannel, unread: usize, mentioned: bool, width: f64, font_size: u32) -> Self {
         Self {
             tag: channel.get_name(),
             channel,
+            unread,
+            mentioned,
             font_size: FontSize::new(font_size as f32).unwrap(),
 
             hover: false,
@@ -467,12 +592,19 @@ impl ChannelScroll {
 impl ScrollableItem for ChannelScroll {
     fn draw(&mut self, args:RenderArgs, pos_offset:Vector2, parent_depth:f64, list: &mut RenderableCollection) {
 
+        let color = if self.hover {Color::RED} else if self.selected {Color::BLUE} else if self.mentioned {color_from_byte(230, 120, 0)} else {Color::BLACK};
+        let name = if self.unread > 0 {
+            format!("{} ({})", self.channel.get_name(), self.unread)
+        } else {
+            self.channel.get_name()
+        };
+
         let text = Text::new(
-            if self.hover {Color::RED} else if self.selected {Color::BLUE} else {Color::BLACK},
+            color,
             parent_depth,
             self.pos + pos_offset,
             self.font_size.0 as u32,
-            self.channel.get_name(),
+            name,
             self.font.clone()
         );
         list.push(text);
@@ -485,15 +617,21 @@ struct MessageScroll {
     pos: Vector2,
     size: Vector2,
     hover: bool,
+    /// the hash of the linked map, if there is one
+    tag: String,
 
     message: ChatMessage,
+    /// does this message mention us
+    highlight: bool,
     font_size: FontSize,
     font: Font2,
 }
 impl MessageScroll {
-    fn new(message: ChatMessage, width: f64, font_size: u32) -> Self {
+    fn new(message: ChatMessage, highlight: bool, width: f64, font_size: u32) -> Self {
         Self {
+            tag: message.link().map(|l| l.hash).unwrap_or_default(),
             message,
+            highlight,
             font_size: FontSize::new(font_size as f32).unwrap(),
 
             hover: false,
@@ -505,8 +643,25 @@ impl MessageScroll {
 }
 impl ScrollableItem for MessageScroll {
     fn draw(&mut self, args:RenderArgs, pos_offset:Vector2, parent_depth:f64, list: &mut RenderableCollection) {
+        if self.highlight {
+            list.push(Rectangle::new(
+                Color::YELLOW.alpha(0.4),
+                parent_depth + 0.001,
+                self.pos + pos_offset,
+                self.size,
+                None
+            ));
+        }
+
+        // links are blue, and red when they can be clicked
+        let color = match (self.tag.is_empty(), self.hover) {
+            (true, _) => Color::BLACK,
+            (false, false) => Color::BLUE,
+            (false, true) => Color::RED,
+        };
+
         let text = Text::new(
-            Color::BLACK,
+            color,
             parent_depth,
             self.pos + pos_offset,
             self.font_size.0 as u32,
@@ -516,3 +671,31 @@ impl ScrollableItem for MessageScroll {
         list.push(text);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(sender: &str, text: &str) -> ChatMessage {
+        ChatMessage::new(sender.to_owned(), ChatChannel::Channel { name: "general".to_owned() }, 1, text.to_owned())
+    }
+
+    #[test]
+    fn mentions_the_whole_name() {
+        let username = "User_1".to_owned();
+        assert!(message("other", "hi user_1").mentions(&username));
+        assert!(message("other", "User_1: hello").mentions(&username));
+        assert!(message("other", "(user_1)").mentions(&username));
+
+        assert!(!message("other", "user_12 is here").mentions(&username));
+        assert!(!message("other", "someuser_1").mentions(&username));
+        assert!(!message("other", "nobody").mentions(&username));
+    }
+
+    #[test]
+    fn own_messages_dont_mention() {
+        let username = "user".to_owned();
+        assert!(!message("user", "i am user").mentions(&username));
+        assert!(!message("other", "hi").mentions(&String::new()));
+    }
+}
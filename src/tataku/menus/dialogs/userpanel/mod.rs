@@ -1,7 +1,11 @@
 mod panel_user;
 mod chat_dialog;
+mod beatmap_link;
+mod chat_commands;
 mod userpanel_dialog;
 
 pub use panel_user::*;
 pub use chat_dialog::*;
-pub use userpanel_dialog::*;
\ No newline at end of file
+pub use beatmap_link::*;
+pub use chat_commands::*;
+pub use userpanel_dialog::*;
@@ -10,29 +10,89 @@ lazy_static::lazy_static! {
     };
 }
 
+const PROFILE_BUTTON_SIZE:Vector2 = Vector2::new(150.0, 40.0);
+
 pub struct UserPanel {
     chat: Chat,
 
     /// user_id, user
     users: HashMap<u32, PanelUser>,
 
+    /// opens the local profile
+    profile_button: MenuButton<Font2, Text>,
+    connection_indicator: ConnectionIndicator,
+    /// the profile button, then the users in the order they're shown
+    focus: FocusRing,
+
     should_close: bool,
     window_size: Arc<WindowSize>
 }
 impl UserPanel {
     pub fn new() -> Self {
+        let window_size = WindowSize::get();
+
         Self {
             chat: Chat::new(),
             users: HashMap::new(),
+            profile_button: MenuButton::new(Self::profile_button_pos(&window_size), PROFILE_BUTTON_SIZE, "Profile", get_font()),
+            connection_indicator: ConnectionIndicator::new(),
+            focus: FocusRing::new(),
             should_close: false,
-            window_size: WindowSize::get(),
+            window_size,
         }
     }
+
+    fn profile_button_pos(window_size: &WindowSize) -> Vector2 {
+        Vector2::new(window_size.0.x - PROFILE_BUTTON_SIZE.x - 10.0, 10.0)
+    }
+
+    /// what can be focused, as the user id (none for the profile button) and its bounds
+    fn focus_items(&self) -> Vec<(Option<u32>, Rectangle)> {
+        let mut users = self.users
+            .values()
+            .map(|u| (Some(u.user.user_id), Rectangle::bounds_only(u.get_pos(), u.size())))
+            .collect::<Vec<_>>();
+        users.sort_by(|(_, a), (_, b)| (a.pos.y, a.pos.x).partial_cmp(&(b.pos.y, b.pos.x)).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut list = vec![(None, Rectangle::bounds_only(self.profile_button.get_pos(), self.profile_button.size()))];
+        list.extend(users);
+        list
+    }
+
+    fn user_menu(user: &OnlineUser) -> NormalDialog {
+        let user_id = user.user_id;
+        let username = user.username.clone();
+
+        let mut user_menu_dialog = NormalDialog::new("User Options");
+
+        if user.game.starts_with("Tataku") {
+            user_menu_dialog.add_button("Spectate", Box::new(move |dialog, _game| {
+                OnlineManager::start_spectating(user_id);
+                dialog.should_close = true;
+            }));
+        }
+
+        user_menu_dialog.add_button("Send Message", Box::new(move |dialog, _game| {
+            PANEL_QUEUE.0.lock().ignite(UserPanelEvent::OpenChat(username.clone()));
+            dialog.should_close = true;
+        }));
+
+        user_menu_dialog.add_button("Close", Box::new(|dialog, _game| {
+            dialog.should_close = true;
+        }));
+
+        user_menu_dialog
+    }
+
+    fn current_playmode() -> PlayMode {
+        GlobalValueManager::get::<CurrentPlaymode>().map(|p| p.0.clone()).unwrap_or("osu".to_owned())
+    }
 }
 
 #[async_trait]
 impl Dialog<Game> for UserPanel {
     async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.profile_button.set_pos(Self::profile_button_pos(&window_size));
         self.window_size = window_size;
     }
 
@@ -46,6 +106,27 @@ impl Dialog<Game> for UserPanel {
     }
     
     async fn on_key_press(&mut self, key:&Key, mods:&KeyModifiers, game:&mut Game) -> bool {
+        let items = self.focus_items();
+        match NavigationInput::from_key(*key, mods) {
+            // enter only activates the focused item if theres no message to send
+            Some(NavigationInput::Confirm) if !self.chat.is_typing() => {
+                match self.focus.focused().and_then(|i| items.get(i)) {
+                    Some((None, _)) => game.add_dialog(Box::new(ProfileDialog::new(Self::current_playmode()))),
+                    Some((Some(user_id), _)) => if let Some(u) = self.users.get(user_id) {
+                        game.add_dialog(Box::new(Self::user_menu(&u.user)));
+                    }
+                    None => {}
+                }
+                return true;
+            }
+            Some(input @ (NavigationInput::Up | NavigationInput::Down | NavigationInput::Next | NavigationInput::Previous)) => {
+                let bounds = items.iter().map(|(_, b)| *b).collect::<Vec<_>>();
+                self.focus.navigate(input, &bounds);
+                return true;
+            }
+            _ => {}
+        }
+
         self.chat.on_key_press(key, mods, game).await;
 
         if key == &Key::Escape {
@@ -64,31 +145,15 @@ impl Dialog<Game> for UserPanel {
 
     async fn on_mouse_down(&mut self, pos:&Vector2, button:&MouseButton, mods:&KeyModifiers, game:&mut Game) -> bool {
         self.chat.on_mouse_down(pos, button, mods, game).await;
-        for (_, i) in self.users.iter_mut() {
-            if i.on_click(*pos, *button, *mods) {
-                let user_id = i.user.user_id;
-                let username = i.user.username.clone();
-
-                // user menu dialog
-                let mut user_menu_dialog = NormalDialog::new("User Options");
-
-                if i.user.game.starts_with("Tataku") {
-                    user_menu_dialog.add_button("Spectate", Box::new(move |dialog, _game| {
-                        OnlineManager::start_spectating(user_id);
-                        dialog.should_close = true;
-                    }));
-                }
-
-                user_menu_dialog.add_button("Send Message", Box::new(move |dialog, _game| {
-                    PANEL_QUEUE.0.lock().ignite(UserPanelEvent::OpenChat(username.clone()));
-                    dialog.should_close = true;
-                }));
 
-                user_menu_dialog.add_button("Close", Box::new(|dialog, _game| {
-                    dialog.should_close = true;
-                }));
+        if self.profile_button.on_click(*pos, *button, *mods) {
+            game.add_dialog(Box::new(ProfileDialog::new(Self::current_playmode())));
+            return true;
+        }
 
-                game.add_dialog(Box::new(user_menu_dialog));
+        for (_, i) in self.users.iter_mut() {
+            if i.on_click(*pos, *button, *mods) {
+                game.add_dialog(Box::new(Self::user_menu(&i.user)));
             }
         }
         true
@@ -102,7 +167,9 @@ impl Dialog<Game> for UserPanel {
     }
 
     async fn on_mouse_move(&mut self, pos:&Vector2, game:&mut Game) {
+        self.focus.hide();
         self.chat.on_mouse_move(pos, game).await;
+        self.profile_button.on_mouse_move(*pos);
 
         for (_, i) in self.users.iter_mut() {
             i.on_mouse_move(*pos)
@@ -111,11 +178,17 @@ impl Dialog<Game> for UserPanel {
 
     async fn update(&mut self, game:&mut Game) {
         self.chat.update(game).await;
+        // ie a map link was clicked
+        if self.chat.should_close() {
+            self.should_close = true;
+        }
+        self.profile_button.update();
+        self.connection_indicator.update();
 
         let mut bomb = PANEL_QUEUE.1.lock().await;
         while let Some(event) = bomb.exploded() {
             match event {
-                UserPanelEvent::OpenChat(username) => self.chat.selected_channel = Some(ChatChannel::from_name(username)),
+                UserPanelEvent::OpenChat(username) => self.chat.select_channel(ChatChannel::from_name(username)),
             }
         }
 
@@ -136,6 +209,24 @@ impl Dialog<Game> for UserPanel {
 
     async fn draw(&mut self, args:&RenderArgs, depth: &f64, list: &mut RenderableCollection) {
         self.chat.draw(args, depth, list).await;
+
+        // local rating
+        let playmode = Self::current_playmode();
+        if let Some(rating) = RatingHelper::get_rating(&playmode) {
+            let mut text = Text::new(
+                Color::WHITE,
+                *depth,
+                Vector2::ZERO,
+                20,
+                format!("{} Rating: {}pr", gamemode_display_name(&playmode), format_float(rating, 2)),
+                get_font()
+            );
+            let button_pos = self.profile_button.get_pos();
+            text.pos = Vector2::new(button_pos.x - text.measure_text().x - 10.0, button_pos.y + 10.0);
+            list.push(text);
+        }
+        self.profile_button.draw(*args, Vector2::ZERO, *depth, list);
+        self.connection_indicator.draw(self.profile_button.get_pos() + Vector2::with_y(PROFILE_BUTTON_SIZE.y + 10.0), *depth, list);
         //TODO: move the set_pos code to update or smth
         let mut counter = 0;
         
@@ -148,6 +239,9 @@ impl Dialog<Game> for UserPanel {
             counter += 1;
             u.draw(*args, Vector2::ZERO, *depth, list);
         }
+
+        let bounds = self.focus_items().into_iter().map(|(_, b)| b).collect::<Vec<_>>();
+        self.focus.draw(&bounds, Vector2::ZERO, *depth, list);
         
     }
 
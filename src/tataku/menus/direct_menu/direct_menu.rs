@@ -49,6 +49,11 @@ pub struct DirectMenu {
 }
 impl DirectMenu {
     pub async fn new(mode: PlayMode) -> DirectMenu {
+        Self::with_search(mode, String::new()).await
+    }
+
+    /// open direct with something already searched for
+    pub async fn with_search(mode: PlayMode, search: String) -> DirectMenu {
         let window_size = WindowSize::get();
 
         let mut x = DirectMenu {
@@ -63,7 +68,7 @@ impl DirectMenu {
             selected: None,
             old_audio: None,
 
-            search_bar: TextInput::new(Vector2::ZERO, Vector2::new(window_size.x , SEARCH_BAR_HEIGHT), "Search", "",get_font()),
+            search_bar: TextInput::new(Vector2::ZERO, Vector2::new(window_size.x , SEARCH_BAR_HEIGHT), "Search", &search, get_font()),
             current_api: Box::new(OsuDirect::new()),
 
             mode,
@@ -136,7 +141,7 @@ impl DirectMenu {
         }
     }
 
-    /// go back to the main menu
+    /// go back to the main menu, or the lobby if we're in one
     async fn back(&mut self, game:&mut Game) {
 
         if let Some(old_audio) = &self.old_audio {
@@ -149,6 +154,8 @@ impl DirectMenu {
             }
         }
 
+        if LobbyMenu::return_to_lobby(game).await { return }
+
         let menu = game.menus.get("main").unwrap().clone();
         game.queue_state_change(GameState::InMenu(menu));
     }
@@ -31,6 +31,9 @@ impl LoadingMenu {
             // load beatmaps
             Self::load_beatmaps(status.clone()).await;
 
+            // load ratings (needs beatmaps). this might need to recalc, so dont wait for it
+            tokio::spawn(RatingHelper::check_all_playmodes());
+
             status.lock().await.stage = LoadingStage::Done;
         });
     }
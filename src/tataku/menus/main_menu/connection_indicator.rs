@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+const DOT_RADIUS:f64 = 5.0;
+const FONT_SIZE:u32 = 16;
+
+/// a dot and some text showing how the connection to the server is doing
+pub struct ConnectionIndicator {
+    state: ConnectionState,
+}
+impl ConnectionIndicator {
+    pub fn new() -> Self {
+        Self {
+            state: ConnectionState::Disconnected,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if let Ok(manager) = ONLINE_MANAGER.try_read() {
+            self.state = manager.connection;
+        }
+    }
+
+    /// `pos` is the top left
+    pub fn draw(&self, pos: Vector2, depth: f64, list: &mut RenderableCollection) {
+        let color = self.state.color();
+        list.push(Circle::new(color, depth, pos + Vector2::new(DOT_RADIUS, FONT_SIZE as f64 / 2.0), DOT_RADIUS, None));
+        list.push(Text::new(color, depth, pos + Vector2::with_x(DOT_RADIUS * 3.0), FONT_SIZE, self.state.to_string(), get_font()));
+    }
+}
@@ -7,17 +7,24 @@ const Y_MARGIN: f64 = 20.0;
 const Y_OFFSET: f64 = 10.0;
 
 const MENU_HIDE_TIMER:f32 = 5_000.0;
+/// lobbies use packets only our own servers know about (see [`LOBBY_PACKET_MAGIC`]),
+/// so theyre hidden unless the build opts in with the `multiplayer_lobbies` feature
+const SHOW_MULTIPLAYER: bool = cfg!(feature = "multiplayer_lobbies");
+/// how many buttons there are to pick from
+const BUTTON_COUNT: usize = if SHOW_MULTIPLAYER { 5 } else { 4 };
 // const COOKIE_HIDE_TIMER:f32 = 10_000.0;
 // const COOKIE_FADE_TIME:f32 = 10_000.0;
 
 pub struct MainMenu {
     // index 0
     pub play_button: MainMenuButton,
-    // // index 1
-    // pub direct_button: MainMenuButton,
+    // index 1
+    pub direct_button: MainMenuButton,
     // index 2
-    pub settings_button: MainMenuButton,
+    pub multiplayer_button: MainMenuButton,
     // index 3
+    pub settings_button: MainMenuButton,
+    // index 4
     pub exit_button: MainMenuButton,
 
     visualization: MenuVisualizationNew,
@@ -31,6 +38,7 @@ pub struct MainMenu {
     settings: SettingsHelper,
     window_size: Arc<WindowSize>,
     song_display: CurrentSongDisplay,
+    connection_indicator: ConnectionIndicator,
     new_map_helper: LatestBeatmapHelper,
     current_skin: CurrentSkinHelper,
 }
@@ -41,15 +49,18 @@ impl MainMenu {
         let mut counter = 1.0;
         
         let mut play_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "Play", "menu-button-play").await;
-        // counter += 1.0;
-        // let mut direct_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "osu!Direct").await;
+        counter += 1.0;
+        let mut direct_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "Direct", "menu-button-direct").await;
+        counter += 1.0;
+        let mut multiplayer_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "Multiplayer", "menu-button-multiplayer").await;
         counter += 1.0;
         let mut settings_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "Settings", "menu-button-options").await;
         counter += 1.0;
         let mut exit_button = MainMenuButton::new(Vector2::new(middle, (BUTTON_SIZE.y + Y_MARGIN) * counter + Y_OFFSET), BUTTON_SIZE, "Exit", "menu-button-exit").await;
 
         play_button.visible = false;
-        // direct_button.visible = false;
+        direct_button.visible = false;
+        multiplayer_button.visible = false;
         settings_button.visible = false;
         exit_button.visible = false;
 
@@ -57,7 +68,8 @@ impl MainMenu {
 
         MainMenu {
             play_button,
-            // direct_button,
+            direct_button,
+            multiplayer_button,
             settings_button,
             exit_button,
 
@@ -71,6 +83,7 @@ impl MainMenu {
             window_size,
             last_input: Instant::now(),
             song_display: CurrentSongDisplay::new(),
+            connection_indicator: ConnectionIndicator::new(),
             new_map_helper: LatestBeatmapHelper::new(),
             current_skin: CurrentSkinHelper::new()
         }
@@ -98,15 +111,21 @@ impl MainMenu {
 
         // ensure they have the latest window size
         self.play_button.window_size = self.window_size.0;
-        // self.direct_button.window_size = self.window_size.0;
+        self.direct_button.window_size = self.window_size.0;
+        self.multiplayer_button.window_size = self.window_size.0;
         self.settings_button.window_size = self.window_size.0;
         self.exit_button.window_size = self.window_size.0;
 
         // show
-        let count = 3;
+        let count = BUTTON_COUNT;
         let mut counter = 0;
         self.play_button.show(counter, count, true); counter += 1;
-        // self.direct_button.show(counter, count, true); counter += 1;
+        self.direct_button.show(counter, count, true); counter += 1;
+        if SHOW_MULTIPLAYER {
+            self.multiplayer_button.show(counter, count, true); counter += 1;
+        } else {
+            self.multiplayer_button.visible = false;
+        }
         self.settings_button.show(counter, count, true); counter += 1;
         self.exit_button.show(counter, count, true); // counter += 1;
     }
@@ -116,15 +135,21 @@ impl MainMenu {
 
         // ensure they have the latest window size
         self.play_button.window_size = self.window_size.0;
-        // self.direct_button.window_size = self.window_size.0;
+        self.direct_button.window_size = self.window_size.0;
+        self.multiplayer_button.window_size = self.window_size.0;
         self.settings_button.window_size = self.window_size.0;
         self.exit_button.window_size = self.window_size.0;
 
         // hide
-        let count = 3;
+        let count = BUTTON_COUNT;
         let mut counter = 0;
         self.play_button.hide(counter, count, true); counter += 1;
-        // self.direct_button.hide(1, 4, true); counter += 1;
+        self.direct_button.hide(counter, count, true); counter += 1;
+        if SHOW_MULTIPLAYER {
+            self.multiplayer_button.hide(counter, count, true); counter += 1;
+        } else {
+            self.multiplayer_button.visible = false;
+        }
         self.settings_button.hide(counter, count, true); counter += 1;
         self.exit_button.hide(counter, count, true); // counter += 1;
     }
@@ -135,7 +160,8 @@ impl MainMenu {
             vec![
                 &mut self.music_box,
                 &mut self.play_button,
-                // &mut self.direct_button,
+                &mut self.direct_button,
+                &mut self.multiplayer_button,
                 &mut self.settings_button,
                 &mut self.exit_button,
             ]
@@ -222,12 +248,14 @@ impl AsyncMenu<Game> for MainMenu {
     async fn update(&mut self, g:&mut Game) {
         self.settings.update();
         self.song_display.update();
+        self.connection_indicator.update();
 
         if self.current_skin.update() {
             self.visualization.reload_skin().await;
 
             self.play_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Play", "menu-button-play").await;
-            // self.direct_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "osu!Direct").await;
+            self.direct_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Direct", "menu-button-direct").await;
+            self.multiplayer_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Multiplayer", "menu-button-multiplayer").await;
             self.settings_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Settings", "menu-button-options").await;
             self.exit_button = MainMenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Exit", "menu-button-exit").await;
 
@@ -306,7 +334,8 @@ impl AsyncMenu<Game> for MainMenu {
         self.menu_game.draw(args, list).await;
 
         self.song_display.draw(list);
-        
+        self.connection_indicator.draw(Vector2::new(10.0, self.window_size.y - 30.0), depth, list);
+
         // draw dim
         list.push(Rectangle::new(
             Color::BLACK.alpha(0.5),
@@ -330,13 +359,20 @@ impl AsyncMenu<Game> for MainMenu {
             return;
         }
 
-        // // open direct menu
-        // if self.direct_button.on_click(pos, button, mods) {
-        //     let mode = self.settings.background_game_settings.mode.clone();
-        //     let menu:Arc<tokio::sync::Mutex<dyn ControllerInputMenu<Game>>> = Arc::new(Mutex::new(DirectMenu::new(mode).await));
-        //     game.queue_state_change(GameState::InMenu(menu));
-        //     return;
-        // }
+        // open direct menu
+        if self.direct_button.on_click(pos, button, mods) {
+            let mode = self.settings.background_game_settings.mode.clone();
+            let menu:Arc<tokio::sync::Mutex<dyn ControllerInputMenu<Game>>> = Arc::new(Mutex::new(DirectMenu::new(mode).await));
+            game.queue_state_change(GameState::InMenu(menu));
+            return;
+        }
+
+        // open multiplayer lobbies
+        if self.multiplayer_button.on_click(pos, button, mods) {
+            let menu = Arc::new(Mutex::new(LobbySelectMenu::new().await));
+            game.queue_state_change(GameState::InMenu(menu));
+            return;
+        }
 
         // open settings menu
         if self.settings_button.on_click(pos, button, mods) {
@@ -399,6 +435,10 @@ impl AsyncMenu<Game> for MainMenu {
         // check offset keys
         self.menu_game.key_down(key, mods).await;
 
+        if let Some(input) = NavigationInput::from_key(key, &mods) {
+            if self.navigate(game, input).await { return }
+        }
+
         if !mods.alt {
             match key {
                 Key::Left => needs_manager_setup |= self.previous(game).await,
@@ -435,7 +475,8 @@ impl AsyncMenu<Game> for MainMenu {
     
     async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
         self.play_button.window_size_changed(&window_size);
-        // self.direct_button.window_size_changed(&window_size);
+        self.direct_button.window_size_changed(&window_size);
+        self.multiplayer_button.window_size_changed(&window_size);
         self.settings_button.window_size_changed(&window_size);
         self.exit_button.window_size_changed(&window_size);
 
@@ -445,12 +486,12 @@ impl AsyncMenu<Game> for MainMenu {
         self.menu_game.window_size_changed(window_size).await;
     }
 }
-#[async_trait]
-impl ControllerInputMenu<Game> for MainMenu {
-    async fn controller_down(&mut self, game:&mut Game, controller: &Box<dyn Controller>, button: u8) -> bool {
-        self.reset_timer();
+impl ControllerInputMenu<Game> for MainMenu {}
+impl MainMenu {
+    /// move between and activate the menu buttons. returns true if the input was used
+    async fn navigate(&mut self, game:&mut Game, input: NavigationInput) -> bool {
         if !self.menu_visible {
-            if let Some(ControllerButton::A) = controller.map_button(button) {
+            if input == NavigationInput::Confirm {
                 self.show_menu();
                 return true;
             }
@@ -458,22 +499,28 @@ impl ControllerInputMenu<Game> for MainMenu {
         }
 
         let mut changed = false;
-        if let Some(ControllerButton::DPad_Down) = controller.map_button(button) {
+        if let NavigationInput::Down | NavigationInput::Next = input {
             self.selected_index += 1;
-            if self.selected_index >= 4 {
+            if self.selected_index == 2 && !SHOW_MULTIPLAYER {
+                self.selected_index += 1;
+            }
+            if self.selected_index >= 5 {
                 self.selected_index = 0;
             }
 
             changed = true;
         }
 
-        if let Some(ControllerButton::DPad_Up) = controller.map_button(button) {
+        if let NavigationInput::Up | NavigationInput::Previous = input {
             if self.selected_index == 0 {
-                self.selected_index = 3;
-            } else if self.selected_index >= 4 { // original value is 99
+                self.selected_index = 4;
+            } else if self.selected_index >= 5 { // original value is 99
                 self.selected_index = 0;
             } else {
                 self.selected_index -= 1;
+                if self.selected_index == 2 && !SHOW_MULTIPLAYER {
+                    self.selected_index -= 1;
+                }
             }
 
             changed = true;
@@ -481,12 +528,13 @@ impl ControllerInputMenu<Game> for MainMenu {
 
         if changed {
             self.play_button.set_selected(self.selected_index == 0);
-            // self.direct_button.set_selected(self.selected_index == 1);
-            self.settings_button.set_selected(self.selected_index == 2);
-            self.exit_button.set_selected(self.selected_index == 3);
+            self.direct_button.set_selected(self.selected_index == 1);
+            self.multiplayer_button.set_selected(self.selected_index == 2);
+            self.settings_button.set_selected(self.selected_index == 3);
+            self.exit_button.set_selected(self.selected_index == 4);
         }
 
-        if let Some(ControllerButton::A) = controller.map_button(button) {
+        if input == NavigationInput::Confirm {
             match self.selected_index {
                 0 => {
                     let menu = game.menus.get("beatmap").unwrap().clone();
@@ -498,15 +546,20 @@ impl ControllerInputMenu<Game> for MainMenu {
                     game.queue_state_change(GameState::InMenu(menu));
                 },
                 2 => {
+                    let menu = Arc::new(tokio::sync::Mutex::new(LobbySelectMenu::new().await));
+                    game.queue_state_change(GameState::InMenu(menu));
+                },
+                3 => {
                     let menu = Arc::new(tokio::sync::Mutex::new(SettingsMenu::new().await));
                     game.queue_state_change(GameState::InMenu(menu));
                 },
-                3 => game.queue_state_change(GameState::Closing),
+                4 => game.queue_state_change(GameState::Closing),
                 _ => {}
             }
+            return true;
         }
 
-        true
+        changed
     }
 }
 
@@ -2,7 +2,9 @@ mod main_menu;
 mod music_box;
 mod menu_button;
 mod current_song_display;
+mod connection_indicator;
 
 pub use main_menu::*;
 pub use music_box::*;
-pub use current_song_display::*;
\ No newline at end of file
+pub use current_song_display::*;
+pub use connection_indicator::*;
\ No newline at end of file
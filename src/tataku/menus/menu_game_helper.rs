@@ -163,7 +163,7 @@ impl MenuGameHelper {
 
     pub async fn key_down(&mut self, key:Key, mods:KeyModifiers) {
         if let Some(manager) = &mut self.manager {
-            manager.key_down(key, mods).await
+            manager.key_down(key, mods, Instant::now()).await
         }
     }
 
@@ -3,6 +3,7 @@ mod dialogs;
 mod main_menu;
 mod pause_menu;
 mod score_menu;
+mod multiplayer;
 mod direct_menu;
 mod loading_menu;
 mod settings_menu;
@@ -14,6 +15,7 @@ pub use dialogs::*;
 pub use main_menu::*;
 pub use pause_menu::*;
 pub use score_menu::*;
+pub use multiplayer::*;
 pub use direct_menu::*;
 pub use loading_menu::*;
 pub use settings_menu::*;
@@ -0,0 +1,326 @@
+use crate::prelude::*;
+
+const BUTTON_SIZE:Vector2 = Vector2::new(150.0, 50.0);
+const BUTTON_MARGIN:f64 = 10.0;
+const PADDING:f64 = 20.0;
+const LINE_HEIGHT:f64 = 30.0;
+const PLAYER_LIST_Y:f64 = 150.0;
+
+/// the room for the lobby we're in. shows the players and the map, and starts the match when the server says to
+pub struct LobbyMenu {
+    /// the lobby as of the last update
+    lobby: Lobby,
+    user_id: u32,
+
+    ready_button: MenuButton<Font2, Text>,
+    map_button: MenuButton<Font2, Text>,
+    mods_button: MenuButton<Font2, Text>,
+    start_button: MenuButton<Font2, Text>,
+    leave_button: MenuButton<Font2, Text>,
+    focus: FocusRing,
+
+    /// hash of the map that was last checked for, so the map is only checked once when it changes
+    checked_map: String,
+    /// set by the missing map dialog, since direct has to be opened from here
+    direct_pending: Arc<AtomicBool>,
+
+    mods: ModManagerHelper,
+    new_map_helper: LatestBeatmapHelper,
+    window_size: Arc<WindowSize>,
+    /// already switching to another menu
+    leaving: bool,
+}
+impl LobbyMenu {
+    pub async fn new() -> Self {
+        let window_size = WindowSize::get();
+        let font = get_font();
+        let button = |text| MenuButton::new(Vector2::ZERO, BUTTON_SIZE, text, font.clone());
+
+        let mut menu = Self {
+            lobby: Lobby::default(),
+            user_id: 0,
+
+            ready_button: button("Toggle Ready"),
+            map_button: button("Change Map"),
+            mods_button: button("Mods"),
+            start_button: button("Start"),
+            leave_button: button("Leave"),
+            focus: FocusRing::new(),
+
+            checked_map: String::new(),
+            direct_pending: Arc::new(AtomicBool::new(false)),
+
+            mods: ModManagerHelper::new(),
+            new_map_helper: LatestBeatmapHelper::new(),
+            window_size: window_size.clone(),
+            leaving: false,
+        };
+        menu.window_size_changed(window_size).await;
+        menu
+    }
+
+    /// go back to the lobby menu if we're in a lobby. returns true if we are
+    pub async fn return_to_lobby(game: &mut Game) -> bool {
+        if ONLINE_MANAGER.read().await.lobby.is_none() { return false }
+
+        game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(LobbyMenu::new().await))));
+        true
+    }
+
+    fn is_host(&self) -> bool {
+        self.lobby.host_id == self.user_id
+    }
+    fn our_state(&self) -> LobbyPlayerState {
+        self.lobby.player(self.user_id).map(|p| p.state).unwrap_or_default()
+    }
+
+    fn buttons(&mut self) -> Vec<&mut MenuButton<Font2, Text>> {
+        let mut list = vec![&mut self.ready_button];
+        if self.lobby.host_id == self.user_id {
+            list.push(&mut self.map_button);
+            list.push(&mut self.mods_button);
+            list.push(&mut self.start_button);
+        }
+        list.push(&mut self.leave_button);
+        list
+    }
+    fn button_bounds(&mut self) -> Vec<Rectangle> {
+        self.buttons()
+            .into_iter()
+            .map(|b| Rectangle::bounds_only(b.get_pos(), b.size()))
+            .collect()
+    }
+
+    /// the lobby's map changed, see if we have it
+    async fn map_changed(&mut self, game: &mut Game) {
+        let beatmap = self.lobby.beatmap.clone();
+        if !beatmap.is_set() { return }
+
+        let map = BEATMAP_MANAGER.read().await.get_by_hash(&beatmap.hash);
+        match map {
+            Some(map) => {
+                BEATMAP_MANAGER.write().await.set_current_beatmap(game, &map, true).await;
+                if self.our_state() == LobbyPlayerState::NoMap {
+                    OnlineManager::lobby_set_state(LobbyPlayerState::NotReady);
+                }
+            }
+            None => {
+                OnlineManager::lobby_set_state(LobbyPlayerState::NoMap);
+                NotificationManager::add_text_notification(&format!("You don't have the lobby's map: {}", beatmap.title), 5000.0, Color::YELLOW).await;
+                game.add_dialog(Box::new(self.missing_map_dialog()));
+            }
+        }
+    }
+
+    fn missing_map_dialog(&self) -> NormalDialog {
+        let mut dialog = NormalDialog::new("Missing Map");
+        let direct_pending = self.direct_pending.clone();
+        dialog.add_button("Download", Box::new(move |dialog, _game| {
+            direct_pending.store(true, SeqCst);
+            dialog.should_close = true;
+        }));
+        dialog.add_button("Cancel", Box::new(|dialog, _game| dialog.should_close = true));
+        dialog
+    }
+
+    /// search direct for the lobby's map
+    async fn open_direct(&mut self, game: &mut Game) {
+        // search without the difficulty name, since direct searches sets
+        let title = &self.lobby.beatmap.title;
+        let search = title.rsplit_once(" [").map(|(s, _)| s).unwrap_or(title).to_owned();
+
+        let menu = DirectMenu::with_search(self.lobby.beatmap.playmode.clone(), search).await;
+        self.leaving = true;
+        game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(menu))));
+    }
+
+    async fn start_match(&mut self, game: &mut Game) {
+        let Some(map) = BEATMAP_MANAGER.read().await.get_by_hash(&self.lobby.beatmap.hash) else {
+            NotificationManager::add_text_notification("The match started, but you don't have the map", 3000.0, Color::YELLOW).await;
+            return;
+        };
+
+        *ModManager::get_mut() = self.lobby.mod_manager();
+        match manager_from_playmode(self.lobby.beatmap.playmode.clone(), &map).await {
+            Ok(mut manager) => {
+                manager.multiplayer = true;
+                OnlineManager::lobby_set_state(LobbyPlayerState::Playing);
+
+                self.leaving = true;
+                game.queue_state_change(GameState::Ingame(manager));
+            }
+            Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await,
+        }
+    }
+
+    fn toggle_ready(&self) {
+        match self.our_state() {
+            LobbyPlayerState::NotReady => OnlineManager::lobby_set_state(LobbyPlayerState::Ready),
+            LobbyPlayerState::Ready => OnlineManager::lobby_set_state(LobbyPlayerState::NotReady),
+            _ => {}
+        }
+    }
+
+    async fn open_mods(&self, game: &mut Game) {
+        let groups = get_gamemode_info(&self.lobby.beatmap.playmode).map(|i| i.get_mods()).unwrap_or_default();
+        game.add_dialog(Box::new(ModDialog::new(groups).await));
+    }
+
+    async fn start(&self) {
+        if self.lobby.can_start() {
+            OnlineManager::lobby_start();
+        } else {
+            NotificationManager::add_text_notification("Not everyone is ready", 2000.0, Color::YELLOW).await;
+        }
+    }
+
+    async fn leave(&mut self, game: &mut Game) {
+        OnlineManager::leave_lobby().await;
+        self.leaving = true;
+        game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(LobbySelectMenu::new().await))));
+    }
+
+    /// put the buttons that are showing along the bottom
+    fn layout_buttons(&mut self) {
+        let y = self.window_size.y - BUTTON_SIZE.y - PADDING;
+        for (i, button) in self.buttons().into_iter().enumerate() {
+            button.set_pos(Vector2::new(PADDING + (BUTTON_SIZE.x + BUTTON_MARGIN) * i as f64, y));
+        }
+    }
+
+    /// do whatever button was clicked at `pos`
+    async fn click_buttons(&mut self, pos: Vector2, button: MouseButton, mods: KeyModifiers, game: &mut Game) {
+        let is_host = self.is_host();
+
+        if self.ready_button.on_click(pos, button, mods) {
+            self.toggle_ready();
+        } else if is_host && self.map_button.on_click(pos, button, mods) {
+            self.leaving = true;
+            let menu = game.menus.get("beatmap").unwrap().clone();
+            game.queue_state_change(GameState::InMenu(menu));
+        } else if is_host && self.mods_button.on_click(pos, button, mods) {
+            self.open_mods(game).await;
+        } else if is_host && self.start_button.on_click(pos, button, mods) {
+            self.start().await;
+        } else if self.leave_button.on_click(pos, button, mods) {
+            self.leave(game).await;
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncMenu<Game> for LobbyMenu {
+    fn get_name(&self) -> &str { "lobby" }
+
+    async fn on_change(&mut self, into:bool) {
+        if !into { return }
+        self.leaving = false;
+        self.checked_map.clear();
+        self.mods.update();
+        self.new_map_helper.update();
+    }
+
+    async fn update(&mut self, game:&mut Game) {
+        if self.leaving { return }
+
+        let (lobby, start) = {
+            let mut online = ONLINE_MANAGER.write().await;
+            self.user_id = online.user_id;
+            (online.lobby.clone(), online.take_lobby_start())
+        };
+
+        // left, or the lobby was closed
+        let Some(lobby) = lobby else {
+            self.leaving = true;
+            game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(LobbySelectMenu::new().await))));
+            return;
+        };
+        self.lobby = lobby;
+        self.layout_buttons();
+
+        // a new map might be the lobby's map
+        let new_map = self.new_map_helper.update() && self.our_state() == LobbyPlayerState::NoMap;
+        if self.lobby.beatmap.hash != self.checked_map || new_map {
+            self.checked_map = self.lobby.beatmap.hash.clone();
+            self.map_changed(game).await;
+        }
+
+        // the host picks the mods, everyone else uses them
+        if self.is_host() {
+            if self.mods.update() {
+                OnlineManager::lobby_set_mods(&self.mods);
+            }
+        } else {
+            let lobby_mods = self.lobby.mod_manager();
+            if *ModManager::get() != lobby_mods {
+                *ModManager::get_mut() = lobby_mods;
+            }
+        }
+
+        if self.direct_pending.swap(false, SeqCst) {
+            return self.open_direct(game).await;
+        }
+
+        if start {
+            self.start_match(game).await;
+        }
+    }
+
+    async fn draw(&mut self, args:RenderArgs, list: &mut RenderableCollection) {
+        let font = get_font();
+        let depth = 0.0;
+
+        list.push(Text::new(Color::WHITE, depth, Vector2::new(PADDING, PADDING), 32, self.lobby.name.clone(), font.clone()));
+
+        // map and mods
+        let map = if self.lobby.beatmap.is_set() { self.lobby.beatmap.title.clone() } else { "No map selected".to_owned() };
+        list.push(Text::new(Color::WHITE, depth, Vector2::new(PADDING, PADDING + 45.0), 20, format!("Map: {map}"), font.clone()));
+
+        let mods = ModManager::short_mods_string(self.lobby.mods.iter().cloned().collect(), true, &self.lobby.beatmap.playmode);
+        list.push(Text::new(Color::WHITE, depth, Vector2::new(PADDING, PADDING + 75.0), 20, format!("Mods: {mods} ({:.2}x)", self.lobby.speed as f32 / 100.0), font.clone()));
+
+        // players
+        for (i, player) in self.lobby.players.iter().enumerate() {
+            let host = if player.user_id == self.lobby.host_id { " (host)" } else { "" };
+            let pos = Vector2::new(PADDING, PLAYER_LIST_Y + LINE_HEIGHT * i as f64);
+            list.push(Text::new(player.state.color(), depth, pos, 20, format!("{}{host}: {}", player.username, player.state), font.clone()));
+        }
+
+        let bounds = self.button_bounds();
+        for button in self.buttons() {
+            button.draw(args, Vector2::ZERO, depth, list);
+        }
+        self.focus.draw(&bounds, Vector2::ZERO, depth, list);
+    }
+
+    async fn on_click(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
+        self.click_buttons(pos, button, mods, game).await;
+    }
+
+    async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {
+        self.focus.hide();
+        for button in self.buttons() {
+            button.on_mouse_move(pos);
+        }
+    }
+
+    async fn on_key_press(&mut self, key:Key, game:&mut Game, mods:KeyModifiers) {
+        let Some(input) = NavigationInput::from_key(key, &mods) else { return };
+        let bounds = self.button_bounds();
+
+        match input {
+            NavigationInput::Back => self.leave(game).await,
+            NavigationInput::Confirm => {
+                let Some(pos) = self.focus.focused_center(&bounds) else { return };
+                self.click_buttons(pos, MouseButton::Left, KeyModifiers::default(), game).await;
+            }
+            input => { self.focus.navigate(input, &bounds); }
+        }
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.window_size = window_size;
+        self.layout_buttons();
+    }
+}
+impl ControllerInputMenu<Game> for LobbyMenu {}
@@ -0,0 +1,114 @@
+use crate::prelude::*;
+
+const PADDING:f64 = 20.0;
+const LINE_HEIGHT:f64 = 50.0;
+const RESULTS_Y:f64 = 100.0;
+
+/// everyone's scores from a multiplayer match.
+/// scores keep updating until everyone has finished
+pub struct LobbyResultsMenu {
+    beatmap: Arc<BeatmapMeta>,
+    /// username, score and state for each player, best score first
+    results: Vec<(String, Option<Score>, LobbyPlayerState)>,
+    complete: bool,
+
+    back_button: MenuButton<Font2, Text>,
+    leaving: bool,
+}
+impl LobbyResultsMenu {
+    pub async fn new(beatmap: Arc<BeatmapMeta>) -> Self {
+        let window_size = WindowSize::get();
+
+        Self {
+            beatmap,
+            results: Vec::new(),
+            complete: false,
+
+            back_button: MenuButton::back_button(window_size.0, get_font()),
+            leaving: false,
+        }
+    }
+
+    async fn back(&mut self, game: &mut Game) {
+        self.leaving = true;
+        if LobbyMenu::return_to_lobby(game).await { return }
+
+        game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(LobbySelectMenu::new().await))));
+    }
+}
+
+#[async_trait]
+impl AsyncMenu<Game> for LobbyResultsMenu {
+    fn get_name(&self) -> &str { "lobby_results" }
+
+    async fn update(&mut self, game:&mut Game) {
+        if self.leaving { return }
+
+        let Ok(online) = ONLINE_MANAGER.try_read() else { return };
+        let Some(lobby) = online.lobby.clone() else {
+            drop(online);
+            return self.back(game).await;
+        };
+
+        self.complete = online.lobby_complete;
+        self.results = lobby.players
+            .iter()
+            .map(|p| (p.username.clone(), online.lobby_scores.get(&p.user_id).cloned(), p.state))
+            .collect();
+
+        // players without a score go at the bottom
+        self.results.sort_by_key(|(_, score, _)| std::cmp::Reverse(score.as_ref().map(|s| s.score)));
+    }
+
+    async fn draw(&mut self, args:RenderArgs, list: &mut RenderableCollection) {
+        let font = get_font();
+        let depth = 0.0;
+
+        let BeatmapMeta { artist, title, version, .. } = &*self.beatmap;
+        list.push(Text::new(Color::WHITE, depth, Vector2::new(PADDING, PADDING), 32, format!("{artist} - {title} [{version}]"), font.clone()));
+
+        let status = if self.complete { "Final Results" } else { "Waiting for everyone to finish..." };
+        list.push(Text::new(Color::WHITE.alpha(0.7), depth, Vector2::new(PADDING, PADDING + 40.0), 20, status.to_owned(), font.clone()));
+
+        for (i, (username, score, state)) in self.results.iter().enumerate() {
+            let pos = Vector2::new(PADDING, RESULTS_Y + LINE_HEIGHT * i as f64);
+            let playing = *state == LobbyPlayerState::Playing;
+            let color = if playing { Color::WHITE.alpha(0.7) } else { Color::WHITE };
+
+            let (score_text, info) = match score {
+                Some(score) => (
+                    crate::format_number(score.score),
+                    format!("{}x, {:.2}%", crate::format_number(score.max_combo), calc_acc(score) * 100.0)
+                ),
+                None => ("-".to_owned(), String::new()),
+            };
+            let playing = if playing { " (playing)" } else { "" };
+
+            list.push(Text::new(color, depth, pos, 22, format!("#{} {username}: {score_text}{playing}", i + 1), font.clone()));
+            list.push(Text::new(color.alpha(0.7), depth, pos + Vector2::new(0.0, 24.0), 16, info, font.clone()));
+        }
+
+        self.back_button.draw(args, Vector2::ZERO, depth, list);
+    }
+
+    async fn on_click(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
+        if self.back_button.on_click(pos, button, mods) {
+            self.back(game).await;
+        }
+    }
+
+    async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {
+        self.back_button.on_mouse_move(pos);
+    }
+
+    async fn on_key_press(&mut self, key:Key, game:&mut Game, mods:KeyModifiers) {
+        if let Some(NavigationInput::Back | NavigationInput::Confirm) = NavigationInput::from_key(key, &mods) {
+            self.back(game).await;
+        }
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        self.back_button = MenuButton::back_button(window_size.0, get_font());
+    }
+}
+impl ControllerInputMenu<Game> for LobbyResultsMenu {}
@@ -0,0 +1,215 @@
+use crate::prelude::*;
+
+const LOBBY_ITEM_SIZE:Vector2 = Vector2::new(600.0, 50.0);
+const LOBBY_LIST_POS:Vector2 = Vector2::new(20.0, 80.0);
+const LOBBY_ITEM_MARGIN:f64 = 5.0;
+
+const INPUT_SIZE:Vector2 = Vector2::new(300.0, 40.0);
+const BUTTON_SIZE:Vector2 = Vector2::new(100.0, 40.0);
+const PADDING:f64 = 20.0;
+
+/// ms between lobby list refreshes
+const REFRESH_INTERVAL:f32 = 5_000.0;
+
+/// lists the server's lobbies, and lets the user join or make one
+pub struct LobbySelectMenu {
+    lobbies: Vec<LobbyInfo>,
+    focus: FocusRing,
+    mouse_pos: Vector2,
+
+    name_input: TextInput<Font2, Text>,
+    /// used when creating a lobby, and when joining one with a password
+    password_input: TextInput<Font2, Text>,
+    create_button: MenuButton<Font2, Text>,
+    back_button: MenuButton<Font2, Text>,
+
+    last_refresh: Instant,
+    /// already switching to the lobby menu
+    joined: bool,
+}
+impl LobbySelectMenu {
+    pub async fn new() -> Self {
+        let window_size = WindowSize::get();
+        let font = get_font();
+
+        let mut menu = Self {
+            lobbies: Vec::new(),
+            focus: FocusRing::new(),
+            mouse_pos: Vector2::ZERO,
+
+            name_input: TextInput::new(Vector2::ZERO, INPUT_SIZE, "Lobby Name", "", font.clone()),
+            password_input: TextInput::new(Vector2::ZERO, INPUT_SIZE, "Password (optional)", "", font.clone()),
+            create_button: MenuButton::new(Vector2::ZERO, BUTTON_SIZE, "Create", font.clone()),
+            back_button: MenuButton::back_button(window_size.0, font.clone()),
+
+            last_refresh: Instant::now(),
+            joined: false,
+        };
+        menu.window_size_changed(window_size).await;
+        menu
+    }
+
+    fn lobby_bounds(&self) -> Vec<Rectangle> {
+        (0..self.lobbies.len())
+            .map(|i| Rectangle::bounds_only(
+                LOBBY_LIST_POS + Vector2::new(0.0, (LOBBY_ITEM_SIZE.y + LOBBY_ITEM_MARGIN) * i as f64),
+                LOBBY_ITEM_SIZE
+            ))
+            .collect()
+    }
+
+    fn refresh(&mut self) {
+        self.last_refresh = Instant::now();
+        OnlineManager::request_lobbies();
+    }
+
+    fn join(&self, index: usize) {
+        let Some(lobby) = self.lobbies.get(index) else { return };
+        OnlineManager::join_lobby(lobby.id, self.password_input.get_text());
+    }
+
+    fn create(&mut self) {
+        let name = self.name_input.get_text().trim().to_owned();
+        if name.is_empty() { return }
+        OnlineManager::create_lobby(name, self.password_input.get_text());
+    }
+
+    fn back(&self, game: &mut Game) {
+        let menu = game.menus.get("main").unwrap().clone();
+        game.queue_state_change(GameState::InMenu(menu));
+    }
+}
+
+#[async_trait]
+impl AsyncMenu<Game> for LobbySelectMenu {
+    fn get_name(&self) -> &str { "lobby_select" }
+
+    async fn on_change(&mut self, into:bool) {
+        if !into { return }
+        self.joined = false;
+        self.refresh();
+    }
+
+    async fn update(&mut self, game:&mut Game) {
+        if self.last_refresh.as_millis() >= REFRESH_INTERVAL {
+            self.refresh();
+        }
+
+        let Ok(online) = ONLINE_MANAGER.try_read() else { return };
+        self.lobbies = online.lobbies.clone();
+
+        // joined or made a lobby
+        if online.lobby.is_some() && !self.joined {
+            self.joined = true;
+            drop(online);
+            game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(LobbyMenu::new().await))));
+        }
+    }
+
+    async fn draw(&mut self, args:RenderArgs, list: &mut RenderableCollection) {
+        let font = get_font();
+        let depth = 0.0;
+
+        list.push(Text::new(Color::WHITE, depth, Vector2::new(PADDING, PADDING), 32, "Multiplayer Lobbies".to_owned(), font.clone()));
+
+        let (connected, supported) = ONLINE_MANAGER.try_read().map(|m| (m.connection.is_connected(), m.lobbies_supported)).unwrap_or((true, true));
+        if !connected {
+            list.push(Text::new(Color::RED, depth, LOBBY_LIST_POS, 20, "Not connected to the server".to_owned(), font.clone()));
+        } else if !supported {
+            list.push(Text::new(Color::RED, depth, LOBBY_LIST_POS, 20, "This server doesn't support multiplayer lobbies".to_owned(), font.clone()));
+        } else if self.lobbies.is_empty() {
+            list.push(Text::new(Color::WHITE, depth, LOBBY_LIST_POS, 20, "No lobbies, make one!".to_owned(), font.clone()));
+        }
+
+        let bounds = self.lobby_bounds();
+        for (lobby, bounds) in self.lobbies.iter().zip(bounds.iter()) {
+            let hover = bounds.contains(self.mouse_pos);
+            list.push(Rectangle::new(
+                Color::BLACK.alpha(if hover { 0.8 } else { 0.5 }),
+                depth + 1.0,
+                bounds.pos,
+                bounds.size,
+                Some(Border::new(Color::WHITE, 1.0))
+            ));
+
+            let lock = if lobby.has_password { " (locked)" } else { "" };
+            let status = if lobby.playing { "Playing" } else { "Waiting" };
+            list.push(Text::new(Color::WHITE, depth, bounds.pos + Vector2::new(10.0, 5.0), 20, format!("{}{lock}", lobby.name), font.clone()));
+            list.push(Text::new(Color::WHITE.alpha(0.7), depth, bounds.pos + Vector2::new(10.0, 28.0), 15, format!("Host: {}, {} players, {status}", lobby.host, lobby.players), font.clone()));
+        }
+        self.focus.draw(&bounds, Vector2::ZERO, depth, list);
+
+        self.name_input.draw(args, Vector2::ZERO, depth, list);
+        self.password_input.draw(args, Vector2::ZERO, depth, list);
+        self.create_button.draw(args, Vector2::ZERO, depth, list);
+        self.back_button.draw(args, Vector2::ZERO, depth, list);
+    }
+
+    async fn on_click(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
+        if self.back_button.on_click(pos, button, mods) {
+            return self.back(game);
+        }
+        if self.create_button.on_click(pos, button, mods) {
+            return self.create();
+        }
+
+        self.name_input.on_click(pos, button, mods);
+        self.password_input.on_click(pos, button, mods);
+
+        if let Some(index) = self.lobby_bounds().iter().position(|b| b.contains(pos)) {
+            self.join(index);
+        }
+    }
+
+    async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {
+        self.mouse_pos = pos;
+        self.focus.hide();
+
+        self.name_input.on_mouse_move(pos);
+        self.password_input.on_mouse_move(pos);
+        self.create_button.on_mouse_move(pos);
+        self.back_button.on_mouse_move(pos);
+    }
+
+    async fn on_key_press(&mut self, key:Key, game:&mut Game, mods:KeyModifiers) {
+        match NavigationInput::from_key(key, &mods) {
+            Some(NavigationInput::Back) => return self.back(game),
+            Some(NavigationInput::Confirm) => {
+                match self.focus.focused() {
+                    Some(index) => self.join(index),
+                    None => self.create(),
+                }
+                return;
+            }
+            Some(input @ (NavigationInput::Up | NavigationInput::Down)) => {
+                if self.focus.navigate(input, &self.lobby_bounds()) { return }
+            }
+            _ => {}
+        }
+
+        if key == Key::F5 {
+            self.refresh();
+        }
+
+        self.name_input.on_key_press(key, mods);
+        self.password_input.on_key_press(key, mods);
+    }
+    async fn on_key_release(&mut self, key:Key, _game:&mut Game) {
+        self.name_input.on_key_release(key);
+        self.password_input.on_key_release(key);
+    }
+
+    async fn on_text(&mut self, text:String) {
+        self.name_input.on_text(text.clone());
+        self.password_input.on_text(text);
+    }
+
+    async fn window_size_changed(&mut self, window_size: Arc<WindowSize>) {
+        let x = window_size.x - INPUT_SIZE.x - PADDING;
+        self.name_input.set_pos(Vector2::new(x, LOBBY_LIST_POS.y));
+        self.password_input.set_pos(Vector2::new(x, LOBBY_LIST_POS.y + INPUT_SIZE.y + LOBBY_ITEM_MARGIN));
+        self.create_button.set_pos(Vector2::new(x, LOBBY_LIST_POS.y + (INPUT_SIZE.y + LOBBY_ITEM_MARGIN) * 2.0));
+        self.back_button = MenuButton::back_button(window_size.0, get_font());
+    }
+}
+impl ControllerInputMenu<Game> for LobbySelectMenu {}
@@ -0,0 +1,7 @@
+mod lobby_menu;
+mod lobby_select;
+mod lobby_results;
+
+pub use lobby_menu::*;
+pub use lobby_select::*;
+pub use lobby_results::*;
@@ -62,11 +62,24 @@ impl PauseMenu {
     }
 
     async fn retry(&mut self, game:&mut Game) {
+        if self.manager.multiplayer {
+            NotificationManager::add_text_notification("You can't retry during a multiplayer match", 2000.0, Color::RED).await;
+            return;
+        }
+
         self.manager.reset().await;
         self.unpause(game);
     }
 
-    fn exit(&mut self, game:&mut Game) {
+    async fn exit(&mut self, game:&mut Game) {
+        // leaving a multiplayer match counts as finishing it
+        if self.manager.multiplayer {
+            OnlineManager::lobby_score(self.manager.score.score.clone(), true);
+            let menu = LobbyResultsMenu::new(self.manager.metadata.clone()).await;
+            game.queue_state_change(GameState::InMenu(Arc::new(Mutex::new(menu))));
+            return;
+        }
+
         let menu = game.menus.get("beatmap").unwrap().to_owned();
         game.queue_state_change(GameState::InMenu(menu));
     }
@@ -113,7 +126,7 @@ impl AsyncMenu<Game> for PauseMenu {
         }
 
         // return to song select
-        if self.exit_button.on_click(pos, button, mods) {self.exit(game)}
+        if self.exit_button.on_click(pos, button, mods) {self.exit(game).await}
     }
 
     async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {
@@ -122,23 +135,28 @@ impl AsyncMenu<Game> for PauseMenu {
         self.exit_button.on_mouse_move(pos);
     }
 
-    async fn on_key_press(&mut self, key:piston::Key, game:&mut Game, _mods:KeyModifiers) {
+    async fn on_key_press(&mut self, key:piston::Key, game:&mut Game, mods:KeyModifiers) {
+        if let Some(input) = NavigationInput::from_key(key, &mods) {
+            if self.navigate(game, input).await { return }
+        }
+
         if key == piston::Key::Escape {
             if self.is_fail_menu {
-                self.exit(game);
+                self.exit(game).await;
             } else {
                 self.unpause(game);
             }
         }
     }
 }
-#[async_trait]
-impl ControllerInputMenu<Game> for PauseMenu {
-    async fn controller_down(&mut self, game:&mut Game, controller: &Box<dyn Controller>, button: u8) -> bool {
+impl ControllerInputMenu<Game> for PauseMenu {}
+impl PauseMenu {
+    /// move between and activate the buttons. returns true if the input was used
+    async fn navigate(&mut self, game:&mut Game, input: NavigationInput) -> bool {
         let max = if self.is_fail_menu {2} else {3};
 
         let mut changed = false;
-        if let Some(ControllerButton::DPad_Down) = controller.map_button(button) {
+        if let NavigationInput::Down | NavigationInput::Next = input {
             self.selected_index += 1;
             if self.selected_index >= max {
                 self.selected_index = 0;
@@ -148,7 +166,7 @@ impl ControllerInputMenu<Game> for PauseMenu {
         }
 
 
-        if let Some(ControllerButton::DPad_Up) = controller.map_button(button) {
+        if let NavigationInput::Up | NavigationInput::Previous = input {
             if self.selected_index == 0 {
                 self.selected_index = max;
             } else if self.selected_index >= max { // original value is 99
@@ -170,7 +188,7 @@ impl ControllerInputMenu<Game> for PauseMenu {
             self.exit_button.set_selected(self.selected_index == continue_index + 2);
         }
 
-        if let Some(ControllerButton::A) = controller.map_button(button) {
+        if input == NavigationInput::Confirm {
             match (self.selected_index, self.is_fail_menu) {
                 (0, false) => { // continue
                     self.unpause(game)
@@ -179,12 +197,13 @@ impl ControllerInputMenu<Game> for PauseMenu {
                     self.retry(game).await;
                 },
                 (2, false) | (1, true) => { // close
-                    self.exit(game);
+                    self.exit(game).await;
                 },
                 _ => {}
             }
+            return true;
         }
 
-        true
+        changed
     }
 }
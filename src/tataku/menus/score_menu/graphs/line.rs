@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+pub struct LineGraph {
+    min: f32,
+    max: f32,
+    data: Arc<Vec<MenuStatsEntry>>
+}
+impl LineGraph {
+    pub fn new(data: Arc<Vec<MenuStatsEntry>>) -> Self {
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+
+        for i in data.iter() {
+            match &i.value {
+                MenuStatsValue::Single(val) => {
+                    min = min.min(*val);
+                    max = max.max(*val);
+                }
+                MenuStatsValue::List(list) => {
+                    for val in list {
+                        min = min.min(*val);
+                        max = max.max(*val);
+                    }
+                }
+            }
+        }
+
+        // avoid dividing by 0 if every value is the same
+        if min == max { min -= 1.0; max += 1.0; }
+
+        Self {
+            min, 
+            max, 
+            data
+        }
+    }
+
+    fn map_point(&self, point: f32, size: Vector2) -> f64 {
+        (self.max - point.clamp(self.min, self.max)) as f64 * size.y / (self.max - self.min).abs() as f64
+    }
+}
+
+
+impl StatsGraph for LineGraph {
+    fn draw(&self, bounds: &Rectangle, depth: f64, list: &mut RenderableCollection) {
+        let pos = bounds.pos;
+        let size = bounds.size;
+
+        // background
+        list.push(Rectangle::new(
+            Color::new(0.2, 0.2, 0.2, 0.7),
+            depth,
+            pos,
+            size,
+            Some(Border::new(Color::RED, 1.5))
+        ));
+
+        for i in self.data.iter() {
+            if !i.show_in_graph { continue }
+
+            match &i.value {
+                MenuStatsValue::Single(v) => {
+                    let v = self.map_point(*v, size);
+
+                    list.push(Line::new(
+                        pos + Vector2::with_y(v),
+                        pos + size.x_portion() + Vector2::with_y(v),
+                        1.5,
+                        depth,
+                        i.color,
+                    ))
+                }
+                MenuStatsValue::List(points) => {
+                    if points.len() < 2 { continue }
+                    let x_step = size.x / (points.len() - 1) as f64;
+
+                    for (n, pair) in points.windows(2).enumerate() {
+                        list.push(Line::new(
+                            pos + Vector2::new(x_step * n as f64, self.map_point(pair[0], size)),
+                            pos + Vector2::new(x_step * (n + 1) as f64, self.map_point(pair[1], size)),
+                            1.5,
+                            depth,
+                            i.color,
+                        ));
+                    }
+                }
+            }
+        }
+
+    }
+
+}
@@ -1,9 +1,11 @@
 mod pie;
 mod bar;
+mod line;
 mod scatter;
 
 pub use pie::*;
 pub use bar::*;
+pub use line::*;
 pub use scatter::*;
 
 
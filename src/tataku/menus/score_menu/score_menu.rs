@@ -11,12 +11,15 @@ use chrono::{
 // const GRAPH_SIZE:Vector2 = Vector2::new(400.0, 200.0);
 // const GRAPH_PADDING:Vector2 = Vector2::new(10.0,10.0);
 
-const MENU_ITEM_COUNT:usize = 2;
-
 pub struct ScoreMenu {
     score: IngameScore,
     pub replay: Option<Replay>,
+    /// path to the raw input recorded during the play, exported with the replay
+    pub input_recording: Option<String>,
     score_mods: String,
+    judgment_preset: String,
+    /// the score recalculated with another scoring system (F3 to cycle)
+    rescored: Option<(ScoringSystemKind, u64, f64)>,
 
     beatmap: Arc<BeatmapMeta>,
     buttons: Vec<MenuButton<Font2, Text>>,
@@ -24,7 +27,10 @@ pub struct ScoreMenu {
     // graph: Graph<Font2, Text>,
 
     // cached
-    hit_error: HitError,
+    /// None if the score has no hit timings
+    hit_stats: Option<HitTimingStats>,
+    /// timings being simulated for a score which didnt have any, see [`Self::simulate_missing_timings`]
+    simulated_timings: Option<Arc<RwLock<Option<HitTimings>>>>,
     hit_counts: Vec<(String, u32, Color)>,
 
     pub dont_do_menu: bool,
@@ -38,12 +44,24 @@ pub struct ScoreMenu {
 
 
     selected_stat: usize,
-    stats: Vec<MenuStatsInfo>
+    stats: Vec<MenuStatsInfo>,
+
+    /// local perf calc for the score, None if the map's diff hasnt been calculated
+    performance: Option<PerformanceInfo>,
 }
 impl ScoreMenu {
+    /// shortcuts checked while looking at a score
+    pub fn keybind_context() -> KeybindContext {
+        KeybindContext::new("score_menu", "Score Menu", vec![
+            KeybindAction::new("save_replay", "Export Replay", vec![InputTarget::Key(Key::F2)]),
+            KeybindAction::new("export_osu", "Export as osu Replay", vec![InputTarget::shift(Key::F2)]),
+            KeybindAction::new("rescore", "Rescore", vec![InputTarget::Key(Key::F3)]),
+        ])
+    }
+
     pub fn new(score:&IngameScore, beatmap: Arc<BeatmapMeta>, allow_retry: bool) -> ScoreMenu {
         let window_size = WindowSize::get();
-        let hit_error = score.hit_error();
+        let hit_stats = score.hit_stats();
         let font = get_font();
 
         // let graph = Graph::new(
@@ -73,7 +91,12 @@ impl ScoreMenu {
 
         // extract mods
         let mut score_mods = ModManager::short_mods_string(score.mods(), false, &score.playmode);
+        if score.scoring_system != ScoringSystemKind::Default {
+            let scoring = score.scoring_system.short_name();
+            score_mods = if score_mods.is_empty() { scoring.to_owned() } else { format!("{score_mods} {scoring}") };
+        }
         if score_mods.len() > 0 { score_mods = format!("Mods: {score_mods}"); }
+        let judgment_preset = format!("Judgment: {}", score.get_judgment_preset().0.display_text());
 
         let mut buttons = Vec::new();
 
@@ -89,6 +112,12 @@ impl ScoreMenu {
             buttons.push(retry_button);
         }
 
+        // race against this score, ie from a replay file someone sent
+        let race_offset = if allow_retry { 3.0 } else { 2.0 };
+        let mut race_button = MenuButton::new(back_button.get_pos() - Vector2::new(0.0, back_button.size().y+5.0)*race_offset, back_button.size(), "Race", font.clone());
+        race_button.set_tag("race");
+
+        buttons.push(race_button);
         buttons.push(replay_button);
         buttons.push(back_button);
 
@@ -103,12 +132,18 @@ impl ScoreMenu {
             stats.extend(gamemode_info.stats_from_groups(&data));
         }
 
+        let performance = calc_perf(score, &beatmap);
+
         ScoreMenu {
             score: score.clone(),
             score_mods,
+            judgment_preset,
+            rescored: None,
             replay: None,
+            input_recording: None,
             beatmap,
-            hit_error,
+            hit_stats,
+            simulated_timings: None,
             // graph,
             buttons,
 
@@ -122,10 +157,41 @@ impl ScoreMenu {
             score_submit_response: None,
 
             selected_stat: 0,
-            stats
+            stats,
+            performance,
         }
     }
 
+    /// replays from other games only have key presses, so simulate `replay` in the background to get the score's hit timings
+    pub fn simulate_missing_timings(&mut self, replay: Replay, extras: ReplayExtras) {
+        if !self.score.timings.is_empty() { return }
+
+        let simulated = Arc::new(RwLock::new(None));
+        self.simulated_timings = Some(simulated.clone());
+
+        let map = self.beatmap.clone();
+        tokio::spawn(async move {
+            match ReplaySimulator::simulate(&replay, &extras, &map).await {
+                Ok(score) => *simulated.write().await = Some(score.timings),
+                Err(e) => warn!("error simulating replay for hit timings: {e}"),
+            }
+        });
+    }
+
+    /// add a summary of each practice loop to the stats
+    pub fn add_practice_results(&mut self, results: &Vec<PracticeLoopResult>) {
+        if results.is_empty() { return }
+
+        let list = results.iter().map(|r| {
+            let color = if r.is_clean() { Color::GREEN } else { Color::RED };
+            MenuStatsEntry::new_f32(format!("Loop {} ({:.2}x, {} misses)", r.loop_num, r.speed, r.misses), r.accuracy as f32 * 100.0, color, true, true)
+        }).collect();
+
+        // put it first, since its most relevant
+        self.stats.insert(0, MenuStatsInfo::new("Practice Loops", GraphType::Bar, list));
+        self.selected_stat = 0;
+    }
+
     fn close(&mut self, game: &mut Game) {
         if self.dont_do_menu {
             self.should_close = true;
@@ -136,29 +202,107 @@ impl ScoreMenu {
         game.queue_state_change(GameState::InMenu(menu));
     }
 
+    /// this score's replay and its extras, either from the file it was opened from or from wherever the score is
+    async fn get_replay(&self) -> Option<(Replay, ReplayExtras)> {
+        Self::load_replay(self.replay.clone(), &self.score).await
+    }
+    async fn load_replay(replay: Option<Replay>, score: &IngameScore) -> Option<(Replay, ReplayExtras)> {
+        match replay {
+            Some(replay) => Some((replay, ReplayExtras::from_score(score))),
+            None => score.get_replay().await,
+        }
+    }
+
     async fn replay(&mut self, game: &mut Game) {
-        if let Some(replay) = self.replay.clone() {
-            self.do_replay(game, replay).await;
-        } else if let Some(replay) = self.score.get_replay().await {
-            self.do_replay(game, replay).await;
+        if let Some((replay, extras)) = self.get_replay().await {
+            self.do_replay(game, replay, extras).await;
         } else {
             warn!("no replay")
         }
     }
 
-    async fn do_replay(&mut self, game: &mut Game, mut replay: Replay) {
+    async fn button_pressed(&mut self, tag: &str, game: &mut Game) {
+        match tag {
+            "back" => self.close(game),
+            "replay" => self.replay(game).await,
+            "retry" => self.retry(game).await,
+            "race" => self.race(game).await,
+            _ => {}
+        }
+    }
+
+    /// play the map with this score's replay as a ghost
+    async fn race(&mut self, game: &mut Game) {
+        let Some((mut replay, extras)) = self.get_replay().await else {
+            return NotificationManager::add_text_notification("No replay to race against", 5000.0, Color::RED).await;
+        };
+        if replay.score_data.is_none() {
+            replay.score_data = Some(self.score.score.clone());
+        }
+
+        match manager_from_playmode(self.score.playmode.clone(), &self.beatmap).await {
+            Ok(mut manager) => {
+                manager.set_ghost(replay, extras).await;
+                game.queue_state_change(GameState::Ingame(manager));
+            }
+            Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await
+        }
+    }
+
+    async fn do_replay(&mut self, game: &mut Game, mut replay: Replay, extras: ReplayExtras) {
         match manager_from_playmode(self.score.playmode.clone(), &self.beatmap).await {
             Ok(mut manager) => {
                 if replay.score_data.is_none() {
                     replay.score_data = Some(self.score.score.clone());
                 }
-                manager.set_replay(replay);
+                manager.set_replay(replay, &extras);
                 game.queue_state_change(GameState::Ingame(manager));
             },
             Err(e) => NotificationManager::add_error_notification("Error loading beatmap", e).await
         }
     }
 
+    /// where to export this score's replay to
+    fn export_path(&self, extension: &str) -> String {
+        let BeatmapMeta { artist, title, version, .. } = &*self.beatmap;
+        let Score { playmode, username, time, .. } = &self.score.score;
+        let playmode = gamemode_display_name(playmode);
+
+        let mut date = String::new();
+        if let Some(datetime) = NaiveDateTime::from_timestamp_opt(*time as i64, 0) {
+            let score_time = DateTime::<Utc>::from_utc(datetime, Utc).with_timezone(&Local);
+            date = score_time.date_naive().format("%d-%m-%Y").to_string();
+        }
+
+        format!("{REPLAY_EXPORTS_DIR}/") + &sanitize_filename(format!("{username}[{playmode}] - {artist} - {title} [{version}] ({date}).{extension}"))
+    }
+
+    /// export the replay as an osu .osr.
+    /// this plays the whole replay back to get the life bar, so its done in the background
+    fn export_osu_replay(&self) {
+        let replay = self.replay.clone();
+        let score = self.score.clone();
+        let beatmap = self.beatmap.clone();
+        let export_path = self.export_path("osr");
+
+        tokio::spawn(async move {
+            let Some((replay, extras)) = Self::load_replay(replay, &score).await else {
+                return NotificationManager::add_text_notification("No replay to export", 5000.0, Color::RED).await;
+            };
+
+            let bytes = match export_osu_replay(&replay, &extras, &score.score, &beatmap).await {
+                Ok(bytes) => bytes,
+                Err(e) => return NotificationManager::add_error_notification("Error converting replay", e).await,
+            };
+
+            if let Err(e) = std::fs::create_dir_all(REPLAY_EXPORTS_DIR).and_then(|_| std::fs::write(&export_path, bytes)) {
+                NotificationManager::add_error_notification("Error exporting replay", e).await;
+            } else {
+                NotificationManager::add_text_notification("Replay exported as .osr!", 5000.0, Color::BLUE).await;
+            }
+        });
+    }
+
     async fn retry(&mut self, game: &mut Game) {
         match manager_from_playmode(self.score.playmode.clone(), &self.beatmap).await {
             Ok(manager) => game.queue_state_change(GameState::Ingame(manager)),
@@ -172,6 +316,16 @@ impl ScoreMenu {
 impl AsyncMenu<Game> for ScoreMenu {
 
     async fn update(&mut self, _game:&mut Game) {
+        if let Some(simulated) = &self.simulated_timings {
+            let timings = simulated.write().await.take();
+            if let Some(timings) = timings {
+                self.simulated_timings = None;
+                if self.score.fill_missing_timings(timings) {
+                    self.hit_stats = self.score.hit_stats();
+                }
+            }
+        }
+
         if self.score_submit_response.is_none() {
             if let Some(t) = &self.score_submit {
                 if let Some(r) = t.response.read().await.as_ref() {
@@ -227,14 +381,18 @@ impl AsyncMenu<Game> for ScoreMenu {
 
         current_pos += size / 2.0;
         for str in [
-            format!("Combo: {}x, {:.2}%", format_number(self.score.max_combo), calc_acc(&self.score) * 100.0),
+            format!("Combo: {}x, {:.2}%", format_number(self.score.max_combo), self.score.calc_acc() * 100.0),
             String::new(),
-            format!("Mean: {:.2}ms", self.hit_error.mean),
-            format!("Error: {:.2}ms - {:.2}ms avg", self.hit_error.early, self.hit_error.late),
-            format!("Deviance: {:.2}ms", self.hit_error.deviance),
+            self.hit_stats.map(|h| format!("Unstable Rate: {:.2}", h.unstable_rate)).unwrap_or_default(),
+            self.hit_stats.map(|h| format!("Mean: {:.2}ms", h.mean)).unwrap_or_default(),
+            self.hit_stats.map(|h| format!("Early: {} ({:.2}ms avg), Late: {} ({:.2}ms avg)", h.early_count, h.early_mean, h.late_count, h.late_mean)).unwrap_or_default(),
             if self.score.speed != 1.0 {format!("Speed: {:.2}x", self.score.speed)} else { String::new() },
-            // format!("Expected Performance: {:.2}pr", self.score.score.performance),
             self.score_mods.clone(),
+            self.judgment_preset.clone(),
+            self.rescored.map(|(kind, score, acc)| format!("{}: {}, {:.2}%", kind.display_text(), format_number(score), acc * 100.0)).unwrap_or_default(),
+            String::new(),
+            self.performance.as_ref().map(|p| format!("Local Performance: {:.2}pr", p.total)).unwrap_or_default(),
+            self.performance.as_ref().map(|p| p.breakdown_string()).unwrap_or_default(),
         ] {
             if !str.is_empty() {
                 if !str.contains("NaN") {
@@ -286,6 +444,21 @@ impl AsyncMenu<Game> for ScoreMenu {
                     }
                 }
             }
+        } else if self.score_submit.as_ref().filter(|s| s.queued.load(std::sync::atomic::Ordering::Acquire)).is_some() {
+            current_pos += size / 2.0;
+            let text = if ScoreSubmitHelper::paused() {
+                "Score queued, it will be submitted once you log in"
+            } else {
+                "Score queued, it will be submitted once the server is reachable"
+            };
+            list.push(Text::new(
+                Color::BLACK,
+                depth + 1.0,
+                current_pos,
+                30,
+                text.to_owned(),
+                font.clone()
+            ));
         }
 
 
@@ -318,17 +491,9 @@ impl AsyncMenu<Game> for ScoreMenu {
 
     async fn on_click(&mut self, pos:Vector2, button:MouseButton, mods:KeyModifiers, game:&mut Game) {
 
-        for b in self.buttons.iter_mut() {
-            if b.on_click(pos, button, mods) {
-                match &*b.get_tag() {
-                    "back" => self.close(game),
-                    "replay" => self.replay(game).await,
-                    "retry" => self.retry(game).await,
-                    _ => {}
-                }
-
-                break;
-            }
+        let clicked = self.buttons.iter_mut().find(|b| b.on_click(pos, button, mods)).map(|b| b.get_tag());
+        if let Some(tag) = clicked {
+            self.button_pressed(&tag, game).await;
         }
     }
 
@@ -338,29 +503,25 @@ impl AsyncMenu<Game> for ScoreMenu {
         }
     }
 
-    async fn on_key_press(&mut self, key:Key, game: &mut Game, _mods:KeyModifiers) {
+    async fn on_key_press(&mut self, key:Key, game: &mut Game, mods:KeyModifiers) {
+        if let Some(input) = NavigationInput::from_key(key, &mods) {
+            if self.navigate(game, input).await { return }
+        }
+
         if key == Key::Escape {
             self.close(game)
         }
 
-        if key == Key::F2 {
+        let keybinds = get_settings!().keybinds.get_bindings("score_menu");
+        if keybinds.key_matches("export_osu", key, &mods) {
+            self.export_osu_replay();
+        }
+        if keybinds.key_matches("save_replay", key, &mods) {
             if let Some(replay) = &self.replay {
                 // save the replay
                 match save_replay(replay, &self.score) {
                     Ok(saved_path) => {
-                        let saved_path = Path::new(&saved_path);
-
-                        let BeatmapMeta { artist, title, version, .. } = &*self.beatmap;
-                        let Score { playmode, username, time, .. } = &self.score.score;
-                        let playmode = gamemode_display_name(playmode);
-
-                        let mut date = String::new();
-                        if let Some(datetime) = NaiveDateTime::from_timestamp_opt(*time as i64, 0) {
-                            let score_time = DateTime::<Utc>::from_utc(datetime, Utc).with_timezone(&Local);
-                            date = score_time.date_naive().format("%d-%m-%Y").to_string();
-                        }
-
-                        let export_path = format!("{REPLAY_EXPORTS_DIR}/") + &sanitize_filename(format!("{username}[{playmode}] - {artist} - {title} [{version}] ({date}).ttkr"));
+                        let export_path = self.export_path("ttkr");
                         let export_path = Path::new(&export_path);
 
                         // ensure export dir exists
@@ -372,6 +533,13 @@ impl AsyncMenu<Game> for ScoreMenu {
                                 } else {
                                     NotificationManager::add_text_notification("Replay exported!", 5000.0, Color::BLUE).await;
                                 }
+
+                                // export the input recording alongside, so both can be attached to a bug report
+                                if let Some(recording) = &self.input_recording {
+                                    if let Err(e) = std::fs::copy(recording, self.export_path(INPUT_RECORDING_EXTENSION)) {
+                                        NotificationManager::add_error_notification("Error exporting input recording", e).await;
+                                    }
+                                }
                             }
                             Err(e) => NotificationManager::add_error_notification("Error creating exports directory", e).await,
                         }
@@ -381,6 +549,17 @@ impl AsyncMenu<Game> for ScoreMenu {
             }
         }
     
+        // rescore with the next scoring system this playmode supports
+        if keybinds.key_matches("rescore", key, &mods) {
+            let variants = ScoringSystemKind::variants();
+            let current = self.rescored.map(|r| r.0).unwrap_or(self.score.scoring_system);
+            let start = variants.iter().position(|k| *k == current).unwrap_or_default();
+
+            self.rescored = (1..variants.len())
+                .map(|i| variants[(start + i) % variants.len()])
+                .find_map(|kind| kind.rescore(&self.score).map(|(score, acc)| (kind, score, acc)));
+        }
+
         if key == Key::Left && self.stats.len() > 0 {
             if self.selected_stat == 0 { self.selected_stat = self.stats.len() - 1 }
             else { self.selected_stat -= 1 }
@@ -397,24 +576,24 @@ impl AsyncMenu<Game> for ScoreMenu {
     }
 }
 
-#[async_trait]
-impl ControllerInputMenu<Game> for ScoreMenu {
-    async fn controller_down(&mut self, game:&mut Game, controller: &Box<dyn Controller>, button: u8) -> bool {
-
+impl ControllerInputMenu<Game> for ScoreMenu {}
+impl ScoreMenu {
+    /// move between and activate the buttons. returns true if the input was used
+    async fn navigate(&mut self, game:&mut Game, input: NavigationInput) -> bool {
         let mut changed = false;
-        if let Some(ControllerButton::DPad_Down) = controller.map_button(button) {
+        if let NavigationInput::Down | NavigationInput::Next = input {
             self.selected_index += 1;
-            if self.selected_index >= MENU_ITEM_COUNT {
+            if self.selected_index >= self.buttons.len() {
                 self.selected_index = 0;
             }
 
             changed = true;
         }
 
-        if let Some(ControllerButton::DPad_Up) = controller.map_button(button) {
+        if let NavigationInput::Up | NavigationInput::Previous = input {
             if self.selected_index == 0 {
-                self.selected_index = 3;
-            } else if self.selected_index >= MENU_ITEM_COUNT { // original value is 99
+                self.selected_index = self.buttons.len() - 1;
+            } else if self.selected_index >= self.buttons.len() { // original value is 99
                 self.selected_index = 0;
             } else {
                 self.selected_index -= 1;
@@ -429,24 +608,12 @@ impl ControllerInputMenu<Game> for ScoreMenu {
             }
         }
 
-        if let Some(ControllerButton::A) = controller.map_button(button) {
-            match self.selected_index {
-                0 => {
-                    // replay
-                    self.replay(game).await;
-                },
-                1 => {
-                    // back
-                    self.close(game);
-                },
-                2 => {
-                    // retry
-                    self.retry(game).await;
-                },
-                _ => {}
-            }
+        if input == NavigationInput::Confirm {
+            let tag = self.buttons.get(self.selected_index).map(|b| b.get_tag()).unwrap_or_default();
+            self.button_pressed(&tag, game).await;
+            return true;
         }
 
-        true
+        changed
     }
 }
@@ -14,6 +14,7 @@ impl MenuStatsInfo {
         let graph:Box<dyn StatsGraph> = match graph_type {
             GraphType::Pie => Box::new(PieGraph::new(data.clone())),
             GraphType::Bar => Box::new(BarGraph::new(data.clone())),
+            GraphType::Line => Box::new(LineGraph::new(data.clone())),
             GraphType::Scatter => Box::new(ScatterGraph::new(data.clone())),
         };
 
@@ -63,6 +64,7 @@ impl MenuStatsInfo {
 pub enum GraphType {
     Pie,
     Bar,
+    Line,
     Scatter,
 }
 
@@ -7,6 +7,7 @@ const WIDTH:f64 = 600.0;
 
 pub struct SettingsMenu {
     scroll_area: ScrollableArea,
+    focus: FocusRing,
     old_settings: Settings,
 
     window_size: Arc<WindowSize>,
@@ -25,12 +26,24 @@ impl SettingsMenu {
         // setup items
         let mut scroll_area = ScrollableArea::new(Vector2::new(10.0, SCROLLABLE_YOFFSET), Vector2::new(window_size.x - 20.0, window_size.y - SCROLLABLE_YOFFSET*2.0), true);
         
-        let items = settings.get_menu_items(p, Arc::new(sender));
+        let items = settings.get_menu_items(p, String::new(), Arc::new(sender));
         for i in items {
             scroll_area.add_item(i);
         }
         let font = get_font();
 
+        // keybind and controller setup buttons
+        for (tag, text) in [
+            ("keybinds", "Keybinds"),
+            ("calibrate_osu", "Set Up Osu Controller"),
+            ("calibrate_taiko", "Set Up Taiko Controller"),
+            ("calibrate_mania", "Set Up Mania Controller"),
+        ] {
+            let mut button = MenuButton::<Font2, Text>::new(p, Vector2::new(WIDTH - SECTION_XOFFSET, BUTTON_SIZE.y), text, font.clone());
+            button.set_tag(tag);
+            scroll_area.add_item(Box::new(button));
+        }
+
         //TODO: make these not part of the scrollable?!?!
 
         // revert button
@@ -47,6 +60,7 @@ impl SettingsMenu {
 
         SettingsMenu {
             scroll_area,
+            focus: FocusRing::new(),
             old_settings: settings.as_ref().clone(),
             window_size,
             change_receiver: Mutex::new(change_receiver),
@@ -57,7 +71,7 @@ impl SettingsMenu {
     pub async fn update_settings(&mut self) {
         // write settings to settings
         let mut settings = get_settings_mut!();
-        settings.from_menu(&self.scroll_area);
+        settings.from_menu(String::new(), &self.scroll_area);
 
         settings.check_hashes();
         // drop to make sure changes propogate correctly
@@ -83,6 +97,52 @@ impl SettingsMenu {
         game.queue_state_change(GameState::InMenu(menu));
     }
 
+    /// move focus between the settings, or activate the focused one. returns true if the input was used
+    async fn navigate(&mut self, game:&mut Game, input: NavigationInput) -> bool {
+        let items = FocusRing::item_bounds(&self.scroll_area.items);
+
+        match input {
+            NavigationInput::Confirm => {
+                let Some(pos) = self.focus.focused_center(&items) else { return false };
+                // items check hover when clicked
+                self.scroll_area.on_mouse_move(pos);
+                self.on_click(pos, MouseButton::Left, KeyModifiers::default(), game).await;
+                self.on_click_release(pos, MouseButton::Left, game).await;
+                true
+            }
+            // left and right are left to the items, for sliders and text inputs
+            NavigationInput::Up | NavigationInput::Down | NavigationInput::Next | NavigationInput::Previous => {
+                let used = self.focus.navigate(input, &items);
+                self.focus.scroll_into_view(&mut self.scroll_area);
+                used
+            }
+            _ => false,
+        }
+    }
+
+    fn osu_calibration() -> ControllerCalibrationDialog {
+        let inputs = vec!["Key 1".to_owned(), "Key 2".to_owned()];
+        ControllerCalibrationDialog::new("Osu Controller Setup", inputs, 2, Box::new(|name, inputs| {
+            get_settings_mut!().keybinds.set_controller_bindings("osu", name, &["left", "right"], &inputs);
+        }))
+    }
+
+    fn taiko_calibration() -> ControllerCalibrationDialog {
+        let inputs = ["Left Kat", "Left Don", "Right Don", "Right Kat"].map(|s| s.to_owned()).to_vec();
+        ControllerCalibrationDialog::new("Taiko Controller Setup", inputs, 4, Box::new(|name, inputs| {
+            get_settings_mut!().keybinds.set_controller_bindings("taiko", name, &["left_kat", "left_don", "right_don", "right_kat"], &inputs);
+        }))
+    }
+
+    /// the number of columns set up is the key count the bindings are for
+    fn mania_calibration() -> ControllerCalibrationDialog {
+        let inputs = (1..=9).map(|i| format!("Column {i}")).collect();
+        ControllerCalibrationDialog::new("Mania Controller Setup (Enter to finish)", inputs, 1, Box::new(|name, inputs| {
+            let key_count = inputs.len();
+            let columns = (1..=key_count).map(|col| format!("column_{col}")).collect::<Vec<_>>();
+            get_settings_mut!().keybinds.set_controller_bindings(&format!("mania_{key_count}k"), name, &columns, &inputs);
+        }))
+    }
 }
 
 #[async_trait]
@@ -128,6 +188,7 @@ impl AsyncMenu<Game> for SettingsMenu {
     
     async fn draw(&mut self, args:RenderArgs, list: &mut RenderableCollection) {
         self.scroll_area.draw(args, Vector2::ZERO, 0.0, list);
+        self.focus.draw(&FocusRing::item_bounds(&self.scroll_area.items), Vector2::ZERO, 0.0, list);
 
         // background
         list.push(visibility_bg(
@@ -144,6 +205,10 @@ impl AsyncMenu<Game> for SettingsMenu {
             match tag.as_str() {
                 "done" => self.finalize(game).await,
                 "revert" => self.revert(game).await,
+                "keybinds" => game.add_dialog(Box::new(KeybindsDialog::new())),
+                "calibrate_osu" => game.add_dialog(Box::new(Self::osu_calibration().with_axis_rest(game.controller_axis_values()))),
+                "calibrate_taiko" => game.add_dialog(Box::new(Self::taiko_calibration().with_axis_rest(game.controller_axis_values()))),
+                "calibrate_mania" => game.add_dialog(Box::new(Self::mania_calibration().with_axis_rest(game.controller_axis_values()))),
                 _ => {}
             }
         }
@@ -154,7 +219,17 @@ impl AsyncMenu<Game> for SettingsMenu {
     }
 
     async fn on_key_press(&mut self, key:piston::Key, game:&mut Game, mods:KeyModifiers) {
-        self.scroll_area.on_key_press(key, mods);
+        // a focused item (ie a text input) gets keys first, so up/down and tab go to it instead of moving focus
+        let item_focused = self.scroll_area.items.iter().any(|i| i.get_selected());
+        if item_focused && self.scroll_area.on_key_press(key, mods) && key != piston::Key::Escape { return }
+
+        if let Some(input) = NavigationInput::from_key(key, &mods) {
+            if self.navigate(game, input).await { return }
+        }
+
+        if !item_focused {
+            self.scroll_area.on_key_press(key, mods);
+        }
 
         if key == piston::Key::Escape {
             self.finalize(game).await;
@@ -195,7 +270,10 @@ impl AsyncMenu<Game> for SettingsMenu {
 
         self.scroll_area.update()
     }
-    async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {self.scroll_area.on_mouse_move(pos)}
+    async fn on_mouse_move(&mut self, pos:Vector2, _game:&mut Game) {
+        self.focus.hide();
+        self.scroll_area.on_mouse_move(pos)
+    }
     async fn on_scroll(&mut self, delta:f64, _game:&mut Game) {self.scroll_area.on_scroll(delta);}
     async fn on_text(&mut self, text:String) {self.scroll_area.on_text(text)}
 }